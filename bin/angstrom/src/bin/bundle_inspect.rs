@@ -0,0 +1,500 @@
+use std::fmt;
+
+use alloy::{
+    consensus::Transaction,
+    eips::BlockNumberOrTag,
+    primitives::{Address, Bytes, TxHash, B256, I256, U256},
+    providers::{Provider, ProviderBuilder},
+    rpc::types::BlockTransactionsKind
+};
+use angstrom_types::contract_payloads::{
+    angstrom::{AngstromBundle, TopOfBlockOrder, UserOrder},
+    rewards::{PoolUpdate, RewardsUpdate},
+    Asset, Pair
+};
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+
+/// Decodes the angstrom bundles a node submitted and prints them in a human
+/// readable form or as json
+#[derive(Parser, Debug)]
+struct Args {
+    #[command(subcommand)]
+    source: Source,
+    /// Print the decoded bundles as json
+    #[arg(long, global = true)]
+    json:   bool,
+    /// Check that the user orders of every bundle net out to zero, exits with
+    /// an error if one of them doesn't
+    #[arg(long, global = true)]
+    verify: bool
+}
+
+#[derive(Subcommand, Debug)]
+enum Source {
+    /// Decode raw `execute` calldata or a bare pade encoded bundle
+    Calldata {
+        calldata: Bytes,
+        /// Block the bundle was or is going to be executed in, order hashes
+        /// commit to it
+        #[arg(long, default_value_t = 0)]
+        block:    u64
+    },
+    /// Fetch and decode the bundle of a transaction
+    Tx {
+        hash:             TxHash,
+        /// Address of the angstrom contract
+        #[arg(long)]
+        angstrom_address: Address,
+        /// Http, ws or ipc endpoint of the node to fetch the transaction from
+        #[arg(long, default_value = "http://localhost:8545")]
+        rpc_url:          String
+    },
+    /// Decode every bundle that landed in the given (inclusive) block range
+    Blocks {
+        from:             u64,
+        to:               u64,
+        /// Address of the angstrom contract
+        #[arg(long)]
+        angstrom_address: Address,
+        /// Http, ws or ipc endpoint of the node to fetch the blocks from
+        #[arg(long, default_value = "http://localhost:8545")]
+        rpc_url:          String
+    }
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let args = Args::parse();
+    let bundles = fetch_bundles(args.source).await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&bundles)?);
+    } else {
+        for bundle in &bundles {
+            println!("{bundle}");
+        }
+    }
+
+    if args.verify {
+        let unbalanced = bundles
+            .iter()
+            .filter(|bundle| !bundle.unbalanced_assets.is_empty())
+            .count();
+        if unbalanced != 0 {
+            eyre::bail!("{unbalanced} of {} bundles don't net out", bundles.len());
+        }
+        println!("all {} bundles net out", bundles.len());
+    }
+
+    Ok(())
+}
+
+async fn fetch_bundles(source: Source) -> eyre::Result<Vec<DecodedBundle>> {
+    match source {
+        Source::Calldata { calldata, block } => {
+            let bundle = AngstromBundle::from_calldata(&calldata)?;
+            Ok(vec![DecodedBundle::new(block, None, &bundle)?])
+        }
+        Source::Tx { hash, angstrom_address, rpc_url } => {
+            let provider = ProviderBuilder::new().on_builtin(&rpc_url).await?;
+            let tx = provider
+                .get_transaction_by_hash(hash)
+                .await?
+                .ok_or_else(|| eyre::eyre!("transaction {hash} not found"))?;
+            let Some(block) = tx.block_number else {
+                eyre::bail!("transaction {hash} is still pending")
+            };
+            if tx.to() != Some(angstrom_address) {
+                eyre::bail!("transaction {hash} isn't sent to the angstrom contract")
+            }
+
+            let bundle = AngstromBundle::from_calldata(tx.input())?;
+            Ok(vec![DecodedBundle::new(block, Some(hash), &bundle)?])
+        }
+        Source::Blocks { from, to, angstrom_address, rpc_url } => {
+            let provider = ProviderBuilder::new().on_builtin(&rpc_url).await?;
+            let mut bundles = Vec::new();
+            for number in from..=to {
+                let block = provider
+                    .get_block_by_number(
+                        BlockNumberOrTag::Number(number),
+                        BlockTransactionsKind::Full
+                    )
+                    .await?
+                    .ok_or_else(|| eyre::eyre!("block {number} not found"))?;
+
+                for tx in block.transactions.txns() {
+                    if tx.to() != Some(angstrom_address) {
+                        continue
+                    }
+                    let hash = *tx.inner.tx_hash();
+                    match AngstromBundle::from_calldata(tx.input())
+                        .and_then(|bundle| DecodedBundle::new(number, Some(hash), &bundle))
+                    {
+                        Ok(bundle) => bundles.push(bundle),
+                        Err(e) => eprintln!("skipping {hash} in block {number}: {e}")
+                    }
+                }
+            }
+
+            Ok(bundles)
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DecodedBundle {
+    block_number:        u64,
+    tx_hash:             Option<TxHash>,
+    assets:              Vec<DecodedAsset>,
+    pairs:               Vec<DecodedPair>,
+    pool_updates:        Vec<DecodedPoolUpdate>,
+    top_of_block_orders: Vec<DecodedTopOfBlock>,
+    user_orders:         Vec<DecodedUserOrder>,
+    /// assets the user orders don't net out on, see
+    /// [`AngstromBundle::user_order_deltas`]
+    unbalanced_assets:   Vec<(Address, I256)>
+}
+
+impl DecodedBundle {
+    /// fails if the bundle references pairs or assets it doesn't hold
+    fn new(
+        block_number: u64,
+        tx_hash: Option<TxHash>,
+        bundle: &AngstromBundle
+    ) -> eyre::Result<Self> {
+        let pairs = bundle
+            .pairs
+            .iter()
+            .map(|pair| DecodedPair::new(pair, &bundle.assets))
+            .collect::<eyre::Result<Vec<_>>>()?;
+        // every pair resolved, so hashing an order only has to check its pair index
+        let check_pair = |pair_index: u16| {
+            if pair_index as usize >= bundle.pairs.len() {
+                eyre::bail!("order references missing pair {pair_index}");
+            }
+            Ok(())
+        };
+
+        let top_of_block_orders = bundle
+            .top_of_block_orders
+            .iter()
+            .map(|order| {
+                check_pair(order.pairs_index)?;
+                Ok(DecodedTopOfBlock::new(order, bundle, block_number))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+        let user_orders = bundle
+            .user_orders
+            .iter()
+            .map(|order| {
+                check_pair(order.pair_index)?;
+                Ok(DecodedUserOrder::new(order, bundle, block_number))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        let mut unbalanced_assets = bundle
+            .user_order_deltas()?
+            .into_iter()
+            .filter(|(_, delta)| !delta.is_zero())
+            .collect::<Vec<_>>();
+        unbalanced_assets.sort();
+
+        Ok(Self {
+            block_number,
+            tx_hash,
+            assets: bundle.assets.iter().map(DecodedAsset::from).collect(),
+            pairs,
+            pool_updates: bundle
+                .pool_updates
+                .iter()
+                .map(DecodedPoolUpdate::from)
+                .collect(),
+            top_of_block_orders,
+            user_orders,
+            unbalanced_assets
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DecodedAsset {
+    address: Address,
+    save:    u128,
+    take:    u128,
+    settle:  u128
+}
+
+impl From<&Asset> for DecodedAsset {
+    fn from(asset: &Asset) -> Self {
+        Self {
+            address: asset.addr,
+            save:    asset.save,
+            take:    asset.take,
+            settle:  asset.settle
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DecodedPair {
+    token0:         Address,
+    token1:         Address,
+    store_index:    u16,
+    price_1_over_0: U256
+}
+
+impl DecodedPair {
+    fn new(pair: &Pair, assets: &[Asset]) -> eyre::Result<Self> {
+        let asset = |index: u16| {
+            assets
+                .get(index as usize)
+                .map(|asset| asset.addr)
+                .ok_or_else(|| eyre::eyre!("pair references missing asset {index}"))
+        };
+
+        Ok(Self {
+            token0:         asset(pair.index0)?,
+            token1:         asset(pair.index1)?,
+            store_index:    pair.store_index,
+            price_1_over_0: pair.price_1over0
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DecodedPoolUpdate {
+    pair_index:       u16,
+    zero_for_one:     bool,
+    swap_in_quantity: u128,
+    /// total rewards paid to the pool, in token0
+    rewards:          u128,
+    /// number of ticks the rewards are spread over, 0 if only the current
+    /// tick is rewarded
+    reward_ticks:     usize
+}
+
+impl From<&PoolUpdate> for DecodedPoolUpdate {
+    fn from(update: &PoolUpdate) -> Self {
+        let (rewards, reward_ticks) = match &update.rewards_update {
+            RewardsUpdate::MultiTick { quantities, .. } => {
+                (quantities.iter().sum(), quantities.len())
+            }
+            RewardsUpdate::CurrentOnly { amount } => (*amount, 0)
+        };
+
+        Self {
+            pair_index: update.pair_index,
+            zero_for_one: update.zero_for_one,
+            swap_in_quantity: update.swap_in_quantity,
+            rewards,
+            reward_ticks
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DecodedTopOfBlock {
+    order_hash:       B256,
+    signer:           Address,
+    pair_index:       u16,
+    zero_for_one:     bool,
+    quantity_in:      u128,
+    quantity_out:     u128,
+    gas_used_asset_0: u128,
+    recipient:        Option<Address>
+}
+
+impl DecodedTopOfBlock {
+    fn new(order: &TopOfBlockOrder, bundle: &AngstromBundle, block_number: u64) -> Self {
+        let order_hash = order.order_hash(&bundle.pairs, &bundle.assets, block_number);
+        Self {
+            order_hash,
+            signer: order.signature.recover_signer(order_hash),
+            pair_index: order.pairs_index,
+            zero_for_one: order.zero_for_1,
+            quantity_in: order.quantity_in,
+            quantity_out: order.quantity_out,
+            gas_used_asset_0: order.gas_used_asset_0,
+            recipient: order.recipient
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DecodedUserOrder {
+    order_hash:       B256,
+    signer:           Address,
+    pair_index:       u16,
+    zero_for_one:     bool,
+    exact_in:         bool,
+    standing:         bool,
+    max_quantity:     u128,
+    min_price:        U256,
+    extra_fee_asset0: u128,
+    recipient:        Option<Address>
+}
+
+impl DecodedUserOrder {
+    fn new(order: &UserOrder, bundle: &AngstromBundle, block_number: u64) -> Self {
+        let order_hash = order.order_hash(&bundle.pairs, &bundle.assets, block_number);
+        Self {
+            order_hash,
+            signer: order.signature.recover_signer(order_hash),
+            pair_index: order.pair_index,
+            zero_for_one: order.zero_for_one,
+            exact_in: order.exact_in,
+            standing: order.standing_validation.is_some(),
+            max_quantity: order.order_quantities.fetch_max_amount(),
+            min_price: order.min_price,
+            extra_fee_asset0: order.extra_fee_asset0,
+            recipient: order.recipient
+        }
+    }
+}
+
+impl fmt::Display for DecodedBundle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bundle in block {}", self.block_number)?;
+        if let Some(hash) = self.tx_hash {
+            write!(f, " (tx {hash})")?;
+        }
+        writeln!(f)?;
+
+        writeln!(f, "  assets:")?;
+        for (i, asset) in self.assets.iter().enumerate() {
+            writeln!(
+                f,
+                "    [{i}] {} save {} take {} settle {}",
+                asset.address, asset.save, asset.take, asset.settle
+            )?;
+        }
+
+        writeln!(f, "  pairs:")?;
+        for (i, pair) in self.pairs.iter().enumerate() {
+            writeln!(
+                f,
+                "    [{i}] {} / {} store index {} price 1/0 {}",
+                pair.token0, pair.token1, pair.store_index, pair.price_1_over_0
+            )?;
+        }
+
+        writeln!(f, "  pool updates:")?;
+        for update in &self.pool_updates {
+            writeln!(
+                f,
+                "    pair {} zero for one {} swap in {} rewards {} over {} ticks",
+                update.pair_index,
+                update.zero_for_one,
+                update.swap_in_quantity,
+                update.rewards,
+                update.reward_ticks
+            )?;
+        }
+
+        writeln!(f, "  top of block orders:")?;
+        for order in &self.top_of_block_orders {
+            writeln!(
+                f,
+                "    {} from {} pair {} zero for one {} in {} out {} gas {}",
+                order.order_hash,
+                order.signer,
+                order.pair_index,
+                order.zero_for_one,
+                order.quantity_in,
+                order.quantity_out,
+                order.gas_used_asset_0
+            )?;
+        }
+
+        writeln!(f, "  user orders:")?;
+        for order in &self.user_orders {
+            writeln!(
+                f,
+                "    {} from {} pair {} zero for one {} {} {} {} min price {} fee {}",
+                order.order_hash,
+                order.signer,
+                order.pair_index,
+                order.zero_for_one,
+                if order.standing { "standing" } else { "flash" },
+                if order.exact_in { "exact in" } else { "exact out" },
+                order.max_quantity,
+                order.min_price,
+                order.extra_fee_asset0
+            )?;
+        }
+
+        for (asset, delta) in &self.unbalanced_assets {
+            writeln!(f, "  user orders don't net out on {asset}: {delta}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::sol_types::SolCall;
+    use angstrom_types::contract_bindings::angstrom::Angstrom::executeCall;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_decode_calldata() {
+        let (token0, token1) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let bundle = AngstromBundle::new(
+            vec![
+                Asset { addr: token0, ..Default::default() },
+                Asset { addr: token1, ..Default::default() },
+            ],
+            vec![Pair {
+                index0:       0,
+                index1:       1,
+                store_index:  3,
+                price_1over0: U256::from(7)
+            }],
+            vec![PoolUpdate {
+                zero_for_one:     true,
+                pair_index:       0,
+                swap_in_quantity: 100,
+                rewards_update:   RewardsUpdate::CurrentOnly { amount: 5 }
+            }],
+            vec![],
+            vec![]
+        );
+        let calldata =
+            executeCall::new((pade::PadeEncode::pade_encode(&bundle).into(),)).abi_encode();
+
+        let decoded = fetch_bundles(Source::Calldata { calldata: calldata.into(), block: 9 })
+            .await
+            .unwrap();
+
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].block_number, 9);
+        assert_eq!(decoded[0].pairs[0].token1, token1);
+        assert_eq!(decoded[0].pairs[0].price_1_over_0, U256::from(7));
+        assert_eq!(decoded[0].pool_updates[0].rewards, 5);
+        assert!(decoded[0].unbalanced_assets.is_empty());
+        assert!(decoded[0].to_string().contains("store index 3"));
+    }
+
+    #[test]
+    fn test_bundle_with_missing_asset_is_rejected() {
+        let bundle = AngstromBundle::new(
+            vec![Asset { addr: Address::with_last_byte(1), ..Default::default() }],
+            vec![Pair {
+                index0:       0,
+                index1:       1,
+                store_index:  0,
+                price_1over0: U256::from(7)
+            }],
+            vec![],
+            vec![],
+            vec![]
+        );
+
+        let err = DecodedBundle::new(9, None, &bundle).unwrap_err();
+        assert!(err.to_string().contains("missing asset 1"));
+    }
+}
@@ -1,11 +1,20 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
+use alloy_chains::Chain;
 use alloy_primitives::Address;
 use angstrom_metrics::initialize_prometheus_metrics;
-use angstrom_types::contract_bindings::angstrom::Angstrom::PoolKey;
+use angstrom_types::{contract_bindings::angstrom::Angstrom::PoolKey, primitive::PoolId};
+use consensus::ConsensusTimingConfig;
 use eyre::Context;
+use matching_engine::{strategy::StrategyKind, DEFAULT_INITIAL_TICKS_PER_SIDE};
+use order_pool::{
+    LimitSubPoolLimit, PoolConfig, SearcherSubPoolLimit, LIMIT_SUBPOOL_MAX_ORDERS_DEFAULT,
+    LIMIT_SUBPOOL_MAX_SIZE_MB_DEFAULT, ORDER_POOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+    SEARCHER_SUBPOOL_MAX_ORDERS_DEFAULT, SEARCHER_SUBPOOL_MAX_SIZE_MB_DEFAULT
+};
 use serde::Deserialize;
 use url::Url;
+use validation::common::wrapped_native_for_chain;
 
 #[derive(Debug, Clone, Default, clap::Args)]
 pub struct AngstromConfig {
@@ -27,7 +36,15 @@ pub struct AngstromConfig {
     #[clap(long, default_value = "6969", global = true)]
     pub metrics_port:        u16,
     #[clap(short, long, default_value = "https://rpc.flashbots.net")]
-    pub mev_boost_endpoints: Vec<Url>
+    pub mev_boost_endpoints: Vec<Url>,
+    /// block builders that our bundle is submitted to through
+    /// `eth_sendBundle`, targeting the next block
+    #[clap(long)]
+    pub builder_endpoints:   Vec<Url>,
+    /// file that evidence of validator misbehaviour is appended to. If not set,
+    /// evidence is only kept in memory
+    #[clap(long)]
+    pub evidence_path:       Option<PathBuf>
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -36,11 +53,183 @@ pub struct NodeConfig {
     pub angstrom_address:     Address,
     pub periphery_addr:       Address,
     pub pool_manager_address: Address,
-    pub pools:                Vec<PoolKey>
+    pub pools:                Vec<PoolKey>,
+    #[serde(default)]
+    pub order_pool:           OrderPoolLimits,
+    #[serde(default)]
+    pub consensus:            ConsensusTiming,
+    #[serde(default)]
+    pub uniswap:              UniswapConfig,
+    #[serde(default)]
+    pub matching:             MatchingConfig,
+    #[serde(default)]
+    pub chain:                ChainConfig
+}
+
+/// The `[order_pool]` section of the node config
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OrderPoolLimits {
+    pub max_limit_orders:     usize,
+    pub max_limit_size_mb:    usize,
+    pub max_searcher_orders:  usize,
+    pub max_searcher_size_mb: usize,
+    /// the most orders a single account can have in the pool
+    pub max_account_slots:    usize
+}
+
+impl Default for OrderPoolLimits {
+    fn default() -> Self {
+        Self {
+            max_limit_orders:     LIMIT_SUBPOOL_MAX_ORDERS_DEFAULT,
+            max_limit_size_mb:    LIMIT_SUBPOOL_MAX_SIZE_MB_DEFAULT,
+            max_searcher_orders:  SEARCHER_SUBPOOL_MAX_ORDERS_DEFAULT,
+            max_searcher_size_mb: SEARCHER_SUBPOOL_MAX_SIZE_MB_DEFAULT,
+            max_account_slots:    ORDER_POOL_MAX_ACCOUNT_SLOTS_PER_SENDER
+        }
+    }
+}
+
+impl OrderPoolLimits {
+    pub fn validate(&self) -> eyre::Result<()> {
+        for (name, value) in [
+            ("max_limit_orders", self.max_limit_orders),
+            ("max_limit_size_mb", self.max_limit_size_mb),
+            ("max_searcher_orders", self.max_searcher_orders),
+            ("max_searcher_size_mb", self.max_searcher_size_mb),
+            ("max_account_slots", self.max_account_slots)
+        ] {
+            if value == 0 {
+                eyre::bail!("order_pool.{name} must be greater than zero");
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn pool_config(&self, ids: Vec<PoolId>, block_time: Duration) -> PoolConfig {
+        let limit = |max_orders, max_size_mb| LimitSubPoolLimit {
+            max_orders,
+            max_size: max_size_mb * 1024 * 1024
+        };
+
+        PoolConfig {
+            ids,
+            lo_pending_limit: limit(self.max_limit_orders, self.max_limit_size_mb),
+            lo_queued_limit: limit(self.max_limit_orders, self.max_limit_size_mb),
+            lo_parked_limit: limit(self.max_limit_orders, self.max_limit_size_mb),
+            cl_pending_limit: limit(self.max_limit_orders, self.max_limit_size_mb),
+            s_pending_limit: SearcherSubPoolLimit {
+                max_orders: self.max_searcher_orders,
+                max_size:   self.max_searcher_size_mb * 1024 * 1024
+            },
+            max_account_slots: self.max_account_slots,
+            block_time
+        }
+    }
+}
+
+/// The `[consensus]` section of the node config. All values are in
+/// milliseconds
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsensusTiming {
+    /// base time after a new block before we send our pre-proposal
+    pub pre_proposal_wait_ms:          u64,
+    /// how long before the next block we want our bundle submitted
+    pub target_submission_time_rem_ms: u64,
+    /// how long to wait on a silent round leader before handing the round to
+    /// the next backup proposer
+    pub proposal_deadline_ms:          u64
+}
+
+impl Default for ConsensusTiming {
+    fn default() -> Self {
+        let defaults = ConsensusTimingConfig::default();
+        Self {
+            pre_proposal_wait_ms:          defaults.pre_proposal_wait.as_millis() as u64,
+            target_submission_time_rem_ms: defaults.target_submission_time_rem.as_millis() as u64,
+            proposal_deadline_ms:          defaults.proposal_deadline.as_millis() as u64
+        }
+    }
+}
+
+impl ConsensusTiming {
+    pub fn timing_config(&self, block_time: Duration) -> ConsensusTimingConfig {
+        ConsensusTimingConfig {
+            pre_proposal_wait: Duration::from_millis(self.pre_proposal_wait_ms),
+            target_submission_time_rem: Duration::from_millis(self.target_submission_time_rem_ms),
+            proposal_deadline: Duration::from_millis(self.proposal_deadline_ms),
+            block_time
+        }
+    }
+}
+
+/// The `[uniswap]` section of the node config
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UniswapConfig {
+    /// how many initialized ticks to load on each side of the current tick
+    /// when a pool is first synced
+    pub initial_ticks_per_side: u16
+}
+
+impl Default for UniswapConfig {
+    fn default() -> Self {
+        Self { initial_ticks_per_side: DEFAULT_INITIAL_TICKS_PER_SIDE }
+    }
+}
+
+/// The `[matching]` section of the node config
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MatchingConfig {
+    /// the strategy the matching engine solves books with. This has to be the
+    /// same across all nodes or proposals will be rejected
+    pub strategy: StrategyKind
+}
+
+/// The `[chain]` section of the node config. These are derived from the chain
+/// the node runs on and only need to be set for chains we don't know about
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChainConfig {
+    /// how long a block takes, in milliseconds
+    pub block_time_ms:  Option<u64>,
+    /// the wrapped native token that gas is priced in
+    pub wrapped_native: Option<Address>
+}
+
+impl ChainConfig {
+    pub fn block_time(&self, chain_id: u64) -> eyre::Result<Duration> {
+        if let Some(block_time_ms) = self.block_time_ms {
+            if block_time_ms == 0 {
+                eyre::bail!("chain.block_time_ms must be greater than zero");
+            }
+            return Ok(Duration::from_millis(block_time_ms))
+        }
+
+        Chain::from_id(chain_id)
+            .named()
+            .and_then(|chain| chain.average_blocktime_hint())
+            .ok_or_else(|| {
+                eyre::eyre!("no known block time for chain {chain_id}, set chain.block_time_ms")
+            })
+    }
+
+    pub fn wrapped_native(&self, chain_id: u64) -> eyre::Result<Address> {
+        self.wrapped_native
+            .or_else(|| wrapped_native_for_chain(chain_id))
+            .ok_or_else(|| {
+                eyre::eyre!(
+                    "no known wrapped native token for chain {chain_id}, set chain.wrapped_native"
+                )
+            })
+    }
 }
 
 impl NodeConfig {
-    pub fn load_from_config(config: Option<PathBuf>) -> Result<Self, eyre::Report> {
+    pub fn load_from_config(config: Option<PathBuf>, chain_id: u64) -> Result<Self, eyre::Report> {
         let config_path = config.ok_or_else(|| eyre::eyre!("Config path not provided"))?;
 
         if !config_path.exists() {
@@ -52,9 +241,27 @@ impl NodeConfig {
 
         let node_config: NodeConfig = toml::from_str(&toml_content)
             .wrap_err_with(|| format!("Could not deserialize config file {:?}", config_path))?;
+        node_config
+            .validate(chain_id)
+            .wrap_err_with(|| format!("Invalid config file {:?}", config_path))?;
 
         Ok(node_config)
     }
+
+    /// validates the config for the chain the node runs on
+    pub fn validate(&self, chain_id: u64) -> eyre::Result<()> {
+        self.order_pool.validate()?;
+        self.chain.wrapped_native(chain_id)?;
+        self.consensus
+            .timing_config(self.chain.block_time(chain_id)?)
+            .validate()
+            .wrap_err("invalid consensus section")?;
+        if self.uniswap.initial_ticks_per_side == 0 {
+            eyre::bail!("uniswap.initial_ticks_per_side must be greater than zero");
+        }
+
+        Ok(())
+    }
 }
 
 pub async fn init_metrics(metrics_port: u16) {
@@ -62,3 +269,145 @@ pub async fn init_metrics(metrics_port: u16) {
         .await
         .inspect_err(|e| eprintln!("failed to start metrics endpoint - {:?}", e));
 }
+
+#[cfg(test)]
+mod tests {
+    use consensus::ETH_BLOCK_TIME;
+    use validation::common::{SEPOLIA_WETH_ADDRESS, WETH_ADDRESS};
+
+    use super::*;
+
+    const MAINNET: u64 = 1;
+    /// a local chain nothing is known about
+    const LOCAL_CHAIN: u64 = 424242;
+
+    const BASE_CONFIG: &str = r#"
+        secret_key = "0x0000000000000000000000000000000000000000000000000000000000000001"
+        angstrom_address = "0x0000000000000000000000000000000000000001"
+        periphery_addr = "0x0000000000000000000000000000000000000002"
+        pool_manager_address = "0x0000000000000000000000000000000000000003"
+        pools = []
+    "#;
+
+    #[test]
+    fn test_node_config_sections_default() {
+        let config: NodeConfig = toml::from_str(BASE_CONFIG).unwrap();
+        assert!(config.validate(MAINNET).is_ok());
+        assert_eq!(config.order_pool, OrderPoolLimits::default());
+        assert_eq!(
+            config.consensus.timing_config(ETH_BLOCK_TIME),
+            ConsensusTimingConfig::default()
+        );
+        assert_eq!(config.chain, ChainConfig::default());
+        assert_eq!(config.uniswap.initial_ticks_per_side, DEFAULT_INITIAL_TICKS_PER_SIDE);
+        assert_eq!(config.matching.strategy, StrategyKind::MaxVolume);
+    }
+
+    #[test]
+    fn test_node_config_sections() {
+        let config: NodeConfig = toml::from_str(&format!(
+            r#"{BASE_CONFIG}
+            [order_pool]
+            max_limit_orders = 5000
+            max_account_slots = 4
+
+            [consensus]
+            proposal_deadline_ms = 750
+
+            [uniswap]
+            initial_ticks_per_side = 50
+
+            [matching]
+            strategy = "max-surplus"
+            "#
+        ))
+        .unwrap();
+        assert!(config.validate(MAINNET).is_ok());
+
+        let pool_config = config
+            .order_pool
+            .pool_config(vec![PoolId::default()], ETH_BLOCK_TIME);
+        assert_eq!(pool_config.ids, vec![PoolId::default()]);
+        assert_eq!(pool_config.block_time, ETH_BLOCK_TIME);
+        assert_eq!(pool_config.lo_pending_limit.max_orders, 5000);
+        assert_eq!(
+            pool_config.s_pending_limit.max_size,
+            SEARCHER_SUBPOOL_MAX_SIZE_MB_DEFAULT * 1024 * 1024
+        );
+        assert_eq!(pool_config.max_account_slots, 4);
+        assert_eq!(
+            config
+                .consensus
+                .timing_config(ETH_BLOCK_TIME)
+                .proposal_deadline,
+            Duration::from_millis(750)
+        );
+        assert_eq!(config.uniswap.initial_ticks_per_side, 50);
+        assert_eq!(config.matching.strategy, StrategyKind::MaxSurplus);
+    }
+
+    #[test]
+    fn test_node_config_rejects_bad_sections() {
+        let parse =
+            |section: &str| toml::from_str::<NodeConfig>(&format!("{BASE_CONFIG}\n{section}"));
+
+        let config = parse("[order_pool]\nmax_account_slots = 0").unwrap();
+        assert!(config.validate(MAINNET).is_err());
+
+        // the pre-proposal has to go out before the submission cutoff
+        let config = parse("[consensus]\npre_proposal_wait_ms = 11500").unwrap();
+        assert!(config.validate(MAINNET).is_err());
+
+        let config = parse("[uniswap]\ninitial_ticks_per_side = 0").unwrap();
+        assert!(config.validate(MAINNET).is_err());
+
+        // typos shouldn't silently fall back to the defaults
+        assert!(parse("[order_pool]\nmax_acount_slots = 4").is_err());
+        assert!(parse("[matching]\nstrategy = \"max-profit\"").is_err());
+        assert!(parse("[chain]\nblock_time = 2000").is_err());
+    }
+
+    #[test]
+    fn test_chain_section_defaults_from_chain_id() {
+        let config: NodeConfig = toml::from_str(BASE_CONFIG).unwrap();
+        assert_eq!(config.chain.block_time(MAINNET).unwrap(), ETH_BLOCK_TIME);
+        assert_eq!(config.chain.wrapped_native(MAINNET).unwrap(), WETH_ADDRESS);
+        assert_eq!(config.chain.wrapped_native(11155111).unwrap(), SEPOLIA_WETH_ADDRESS);
+
+        // nothing to derive these from on a chain we don't know
+        assert!(config.chain.block_time(LOCAL_CHAIN).is_err());
+        assert!(config.chain.wrapped_native(LOCAL_CHAIN).is_err());
+        assert!(config.validate(LOCAL_CHAIN).is_err());
+    }
+
+    #[test]
+    fn test_chain_section_overrides() {
+        let config: NodeConfig = toml::from_str(&format!(
+            r#"{BASE_CONFIG}
+            [chain]
+            block_time_ms = 2000
+            wrapped_native = "0x0000000000000000000000000000000000000004"
+
+            [consensus]
+            pre_proposal_wait_ms = 900
+            target_submission_time_rem_ms = 300
+            proposal_deadline_ms = 200
+            "#
+        ))
+        .unwrap();
+        assert!(config.validate(LOCAL_CHAIN).is_ok());
+        assert_eq!(config.chain.block_time(LOCAL_CHAIN).unwrap(), Duration::from_secs(2));
+        assert_eq!(config.chain.wrapped_native(LOCAL_CHAIN).unwrap(), Address::with_last_byte(4));
+        // the override wins over what we know about the chain
+        assert_eq!(config.chain.block_time(MAINNET).unwrap(), Duration::from_secs(2));
+
+        // the default consensus timings don't fit into 2s blocks
+        let config: NodeConfig =
+            toml::from_str(&format!("{BASE_CONFIG}\n[chain]\nblock_time_ms = 2000")).unwrap();
+        assert!(config.validate(MAINNET).is_err());
+
+        let config: NodeConfig =
+            toml::from_str(&format!("{BASE_CONFIG}\n[chain]\nblock_time_ms = 0")).unwrap();
+        assert!(config.validate(MAINNET).is_err());
+    }
+}
@@ -5,30 +5,35 @@ use std::{collections::HashSet, sync::Arc};
 use alloy::{
     self,
     eips::{BlockId, BlockNumberOrTag},
+    primitives::BlockNumber,
     providers::{network::Ethereum, Provider, ProviderBuilder}
 };
-use alloy_chains::Chain;
 use angstrom_eth::{
     handle::{Eth, EthCommand},
+    indexer::BundleIndex,
     manager::{EthDataCleanser, EthEvent}
 };
 use angstrom_network::{
     manager::StromConsensusEvent,
     pool_manager::{OrderCommand, PoolHandle},
     NetworkBuilder as StromNetworkBuilder, NetworkOrderEvent, PoolManagerBuilder, StatusState,
-    VerificationSidecar
+    StromVersion, VerificationSidecar
 };
 use angstrom_types::{
     block_sync::{BlockSyncProducer, GlobalBlockSync},
     contract_bindings::controller_v_1::ControllerV1,
     contract_payloads::angstrom::{AngstromPoolConfigStore, UniswapAngstromRegistry},
+    matching::match_estimate_response::BundleEstimate,
     mev_boost::MevBoostProvider,
-    primitive::{AngstromSigner, PeerId, UniswapPoolRegistry},
+    primitive::{AngstromSigner, UniswapPoolRegistry},
     reth_db_wrapper::RethDbWrapper
 };
-use consensus::{AngstromValidator, ConsensusManager, ManagerNetworkDeps};
+use consensus::{
+    rounds::ConsensusMessage, AngstromValidator, ConsensusClient, ConsensusCommand,
+    ConsensusManager, EvidenceStore, ManagerNetworkDeps, ManagerRpcDeps, DEFAULT_VOTING_POWER
+};
 use matching_engine::{configure_uniswap_manager, manager::MatcherCommand, MatchingManager};
-use order_pool::{order_storage::OrderStorage, PoolConfig, PoolManagerUpdate};
+use order_pool::{order_storage::OrderStorage, OrderJournal, PoolManagerUpdate};
 use reth::{
     api::NodeAddOns,
     builder::FullNodeComponents,
@@ -44,23 +49,31 @@ use tokio::sync::mpsc::{
     channel, unbounded_channel, Receiver, Sender, UnboundedReceiver, UnboundedSender
 };
 use validation::{
-    common::TokenPriceGenerator,
+    common::{sync_configured_pool_prices, TokenPriceGenerator},
     init_validation,
-    order::state::pools::AngstromPoolsTracker,
+    order::state::{db_state_utils::layouts::TokenLayoutStore, pools::AngstromPoolsTracker},
     validator::{ValidationClient, ValidationRequest}
 };
 
 use crate::{cli::NodeConfig, AngstromConfig};
 
+/// the order pool journal, kept in the reth datadir
+const ORDER_JOURNAL_FILE_NAME: &str = "angstrom-orders.jsonl";
+/// the index of landed bundles, kept in the reth datadir
+const BUNDLE_INDEX_FILE_NAME: &str = "angstrom-bundles.jsonl";
+/// where tokens keep their balances and approvals, kept in the reth datadir
+const TOKEN_LAYOUTS_FILE_NAME: &str = "angstrom-token-layouts.jsonl";
+
 pub fn init_network_builder(
     secret_key: AngstromSigner,
-    eth_handle: UnboundedReceiver<EthEvent>
+    eth_handle: UnboundedReceiver<EthEvent>,
+    chain_id: u64
 ) -> eyre::Result<StromNetworkBuilder> {
     let public_key = secret_key.id();
 
     let state = StatusState {
-        version:   0,
-        chain:     Chain::mainnet().id(),
+        version:   StromVersion::LATEST.into(),
+        chain:     chain_id,
         peer:      public_key,
         timestamp: 0
     };
@@ -92,13 +105,21 @@ pub struct StromHandles {
     pub eth_handle_rx: Option<UnboundedReceiver<EthEvent>>,
 
     pub pool_manager_tx: tokio::sync::broadcast::Sender<PoolManagerUpdate>,
+    pub gas_estimate_tx: tokio::sync::broadcast::Sender<(BlockNumber, BundleEstimate)>,
+
+    pub consensus_command_tx: UnboundedSender<ConsensusCommand>,
+    pub consensus_command_rx: UnboundedReceiver<ConsensusCommand>,
+    pub consensus_events_tx:  tokio::sync::broadcast::Sender<ConsensusMessage>,
 
     pub consensus_tx_op: UnboundedMeteredSender<StromConsensusEvent>,
     pub consensus_rx_op: UnboundedMeteredReceiver<StromConsensusEvent>,
 
     // only 1 set cur
     pub matching_tx: Sender<MatcherCommand>,
-    pub matching_rx: Receiver<MatcherCommand>
+    pub matching_rx: Receiver<MatcherCommand>,
+
+    // shared with the rpc, filled once the node is up
+    pub bundle_index: BundleIndex
 }
 
 impl StromHandles {
@@ -108,12 +129,22 @@ impl StromHandles {
             pool_manager_tx: self.pool_manager_tx.clone()
         }
     }
+
+    pub fn get_consensus_handle(&self) -> ConsensusClient {
+        ConsensusClient {
+            sender:    self.consensus_command_tx.clone(),
+            events_tx: self.consensus_events_tx.clone()
+        }
+    }
 }
 
 pub fn initialize_strom_handles() -> StromHandles {
     let (eth_tx, eth_rx) = channel(100);
     let (matching_tx, matching_rx) = channel(100);
     let (pool_manager_tx, _) = tokio::sync::broadcast::channel(100);
+    let (gas_estimate_tx, _) = tokio::sync::broadcast::channel(10);
+    let (consensus_events_tx, _) = tokio::sync::broadcast::channel(100);
+    let (consensus_command_tx, consensus_command_rx) = unbounded_channel();
     let (pool_tx, pool_rx) = reth_metrics::common::mpsc::metered_unbounded_channel("orderpool");
     let (orderpool_tx, orderpool_rx) = unbounded_channel();
     let (validator_tx, validator_rx) = unbounded_channel();
@@ -131,12 +162,17 @@ pub fn initialize_strom_handles() -> StromHandles {
         validator_tx,
         validator_rx,
         pool_manager_tx,
+        gas_estimate_tx,
+        consensus_command_tx,
+        consensus_command_rx,
+        consensus_events_tx,
         consensus_tx_op,
         consensus_rx_op,
         matching_tx,
         matching_rx,
         eth_handle_tx: Some(eth_handle_tx),
-        eth_handle_rx: Some(eth_handle_rx)
+        eth_handle_rx: Some(eth_handle_rx),
+        bundle_index: BundleIndex::default()
     }
 }
 
@@ -157,7 +193,8 @@ pub async fn initialize_strom_components<Node, AddOns>(
     >,
     AddOns: NodeAddOns<Node> + RethRpcAddOns<Node>
 {
-    let node_config = NodeConfig::load_from_config(Some(config.node_config)).unwrap();
+    let chain_id = node.chain_spec().chain.id();
+    let node_config = NodeConfig::load_from_config(Some(config.node_config), chain_id).unwrap();
     let node_address = signer.address();
 
     // NOTE:
@@ -172,8 +209,14 @@ pub async fn initialize_strom_components<Node, AddOns>(
         .unwrap()
         .into();
 
-    let mev_boost_provider =
-        MevBoostProvider::new_from_urls(querying_provider.clone(), &config.mev_boost_endpoints);
+    let block_time = node_config.chain.block_time(chain_id).unwrap();
+    let mev_boost_provider = MevBoostProvider::new_from_urls(
+        querying_provider.clone(),
+        &config.mev_boost_endpoints,
+        &config.builder_endpoints,
+        chain_id
+    )
+    .with_submission_timeout(block_time);
 
     tracing::info!(target: "angstrom::startup-sequence", "waiting for the next block to continue startup sequence. \
         this is done to ensure all modules start on the same state and we don't hit the rare  \
@@ -203,6 +246,7 @@ pub async fn initialize_strom_components<Node, AddOns>(
     );
 
     let uniswap_registry: UniswapPoolRegistry = node_config.pools.into();
+    let pool_ids = uniswap_registry.pools().keys().cloned().collect::<Vec<_>>();
     let uni_ang_registry =
         UniswapAngstromRegistry::new(uniswap_registry.clone(), pool_config_store.clone());
 
@@ -216,6 +260,11 @@ pub async fn initialize_strom_components<Node, AddOns>(
         .into_iter()
         .collect::<HashSet<_>>();
 
+    let validators = node_set
+        .iter()
+        .map(|node| AngstromValidator::new(*node, DEFAULT_VOTING_POWER))
+        .collect::<Vec<_>>();
+
     // Build our PoolManager using the PoolConfig and OrderStorage we've already
     // created
     let eth_handle = EthDataCleanser::spawn(
@@ -232,6 +281,23 @@ pub async fn initialize_strom_components<Node, AddOns>(
         vec![handles.eth_handle_tx.take().unwrap()]
     )
     .unwrap();
+    // subscribe before anything else can happen so that we don't miss any node set
+    // changes
+    let consensus_eth_events = eth_handle.subscribe_network();
+    let uniswap_pool_events = eth_handle.subscribe_network();
+    let pool_price_events = eth_handle.subscribe_network();
+
+    handles
+        .bundle_index
+        .attach_file(node.data_dir.data_dir().join(BUNDLE_INDEX_FILE_NAME))
+        .expect("failed to open bundle index");
+    handles.bundle_index.spawn_indexer(
+        executor.clone(),
+        node.provider.clone(),
+        node_config.angstrom_address,
+        pool_config_store.clone(),
+        eth_handle.subscribe_cannon_state_notifications().await
+    );
 
     let uniswap_pool_manager = configure_uniswap_manager(
         querying_provider.clone(),
@@ -239,16 +305,23 @@ pub async fn initialize_strom_components<Node, AddOns>(
         uniswap_registry,
         block_id,
         global_block_sync.clone(),
-        node_config.pool_manager_address
+        node_config.pool_manager_address,
+        node_config.uniswap.initial_ticks_per_side,
+        uniswap_pool_events
     )
     .await;
 
     let uniswap_pools = uniswap_pool_manager.pools();
     executor.spawn(Box::pin(uniswap_pool_manager));
-    let price_generator =
-        TokenPriceGenerator::new(querying_provider.clone(), block_id, uniswap_pools.clone(), None)
-            .await
-            .expect("failed to start token price generator");
+    let price_generator = TokenPriceGenerator::new(
+        querying_provider.clone(),
+        block_id,
+        uniswap_pools.clone(),
+        None,
+        node_config.chain.wrapped_native(chain_id).unwrap()
+    )
+    .await
+    .expect("failed to start token price generator");
 
     let block_height = node.provider.best_block_number().unwrap();
 
@@ -263,20 +336,31 @@ pub async fn initialize_strom_components<Node, AddOns>(
         uniswap_pools.clone(),
         price_generator,
         pool_config_store.clone(),
+        TokenLayoutStore::open(node.data_dir.data_dir().join(TOKEN_LAYOUTS_FILE_NAME))
+            .expect("failed to open token layouts"),
         handles.validator_rx
     );
 
     let validation_handle = ValidationClient(handles.validator_tx.clone());
+    executor.spawn(Box::pin(sync_configured_pool_prices(
+        querying_provider.clone(),
+        node_config.pool_manager_address,
+        None,
+        pool_price_events,
+        validation_handle.clone()
+    )));
 
     let network_handle = network_builder
         .with_pool_manager(handles.pool_tx)
         .with_consensus_manager(handles.consensus_tx_op)
         .build_handle(executor.clone(), node.provider.clone());
 
-    let pool_config = PoolConfig::default();
+    let pool_config = node_config.order_pool.pool_config(pool_ids, block_time);
     let order_storage = Arc::new(OrderStorage::new(&pool_config));
     let angstrom_pool_tracker =
         AngstromPoolsTracker::new(node_config.angstrom_address, pool_config_store.clone());
+    let order_journal = OrderJournal::open(node.data_dir.data_dir().join(ORDER_JOURNAL_FILE_NAME))
+        .expect("failed to open order journal");
 
     let _pool_handle = PoolManagerBuilder::new(
         validation_handle.clone(),
@@ -287,6 +371,7 @@ pub async fn initialize_strom_components<Node, AddOns>(
         global_block_sync.clone()
     )
     .with_config(pool_config)
+    .with_journal(order_journal)
     .build_with_channels(
         executor.clone(),
         handles.orderpool_tx,
@@ -295,21 +380,26 @@ pub async fn initialize_strom_components<Node, AddOns>(
         handles.pool_manager_tx
     );
 
-    // TODO load the stakes from Eigen using node.provider
-    let validators = vec![
-        AngstromValidator::new(PeerId::default(), 100),
-        AngstromValidator::new(PeerId::default(), 200),
-        AngstromValidator::new(PeerId::default(), 300),
-    ];
+    let evidence = config
+        .evidence_path
+        .map(EvidenceStore::load)
+        .transpose()
+        .expect("failed to load evidence")
+        .unwrap_or_else(EvidenceStore::in_memory);
 
     // spinup matching engine
-    let matching_handle = MatchingManager::spawn(executor.clone(), validation_handle.clone());
+    let matching_handle = MatchingManager::spawn(
+        executor.clone(),
+        validation_handle.clone(),
+        node_config.matching.strategy
+    );
 
     let manager = ConsensusManager::new(
         ManagerNetworkDeps::new(
             network_handle.clone(),
             eth_handle.subscribe_cannon_state_notifications().await,
-            handles.consensus_rx_op
+            handles.consensus_rx_op,
+            consensus_eth_events
         ),
         signer,
         validators,
@@ -320,7 +410,14 @@ pub async fn initialize_strom_components<Node, AddOns>(
         uniswap_pools.clone(),
         mev_boost_provider,
         matching_handle,
-        global_block_sync.clone()
+        global_block_sync.clone(),
+        evidence,
+        node_config.consensus.timing_config(block_time),
+        ManagerRpcDeps::new(
+            handles.gas_estimate_tx,
+            handles.consensus_events_tx,
+            handles.consensus_command_rx
+        )
     );
 
     let _consensus_handle = executor.spawn_critical("consensus", Box::pin(manager));
@@ -7,10 +7,16 @@ use std::path::PathBuf;
 use alloy::signers::local::PrivateKeySigner;
 use angstrom_metrics::METRICS_ENABLED;
 use angstrom_network::AngstromNetworkBuilder;
-use angstrom_rpc::{api::OrderApiServer, OrderApi};
-use angstrom_types::primitive::AngstromSigner;
+use angstrom_rpc::{
+    api::{
+        ConsensusApiServer, FillsApiServer, HistoryApiServer, OrderApiServer,
+        PrivateOrderApiServer, QuotingApiServer
+    },
+    ConsensusApi, FillsApi, HistoryApi, OrderApi, PrivateOrderApi, QuotesApi
+};
+use angstrom_types::primitive::{init_angstrom_domain, AngstromSigner};
 use clap::Parser;
-use cli::AngstromConfig;
+use cli::{AngstromConfig, NodeConfig};
 use reth::{chainspec::EthereumChainSpecParser, cli::Cli};
 use reth_node_builder::{Node, NodeHandle};
 use reth_node_ethereum::{node::EthereumAddOns, EthereumNode};
@@ -39,15 +45,26 @@ pub fn run() -> eyre::Result<()> {
 
         let secret_key = get_secret_key(&args.secret_key_location)?;
 
+        let chain_id = builder.config().chain.chain.id();
+        // orders are signed and simulated for the chain and contract we run against
+        let node_config = NodeConfig::load_from_config(Some(args.node_config.clone()), chain_id)?;
+        init_angstrom_domain(chain_id, node_config.angstrom_address)?;
+
         let mut channels = initialize_strom_handles();
-        let mut network =
-            init_network_builder(secret_key.clone(), channels.eth_handle_rx.take().unwrap())?;
+        let mut network = init_network_builder(
+            secret_key.clone(),
+            channels.eth_handle_rx.take().unwrap(),
+            chain_id
+        )?;
         let protocol_handle = network.build_protocol_handler();
 
         // for rpc
         let pool = channels.get_pool_handle();
         let executor_clone = executor.clone();
         let validation_client = ValidationClient(channels.validator_tx.clone());
+        let gas_estimate_tx = channels.gas_estimate_tx.clone();
+        let consensus_client = channels.get_consensus_handle();
+        let bundle_index = channels.bundle_index.clone();
         let NodeHandle { node, node_exit_future } = builder
             .with_types::<EthereumNode>()
             .with_components(
@@ -57,9 +74,34 @@ pub fn run() -> eyre::Result<()> {
             )
             .with_add_ons::<EthereumAddOns<_>>(Default::default())
             .extend_rpc_modules(move |rpc_context| {
-                let order_api = OrderApi::new(pool.clone(), executor_clone, validation_client);
+                let order_api =
+                    OrderApi::new(pool.clone(), executor_clone.clone(), validation_client);
                 rpc_context.modules.merge_configured(order_api.into_rpc())?;
 
+                // private order flow is only accepted on the jwt authenticated server
+                let private_order_api = PrivateOrderApi::new(pool.clone());
+                rpc_context
+                    .auth_module
+                    .merge_auth_methods(private_order_api.into_rpc())?;
+
+                let quotes_api = QuotesApi::new(gas_estimate_tx, executor_clone.clone());
+                rpc_context
+                    .modules
+                    .merge_configured(quotes_api.into_rpc())?;
+
+                let fills_api = FillsApi::new(consensus_client.clone());
+                rpc_context.modules.merge_configured(fills_api.into_rpc())?;
+
+                let history_api = HistoryApi::new(bundle_index);
+                rpc_context
+                    .modules
+                    .merge_configured(history_api.into_rpc())?;
+
+                let consensus_api = ConsensusApi::new(consensus_client, executor_clone);
+                rpc_context
+                    .modules
+                    .merge_configured(consensus_api.into_rpc())?;
+
                 Ok(())
             })
             .launch()
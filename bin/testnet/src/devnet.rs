@@ -29,3 +29,32 @@ pub(crate) async fn run_devnet(executor: TaskExecutor, cli: DevnetCli) -> eyre::
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use angstrom_types::matching::SqrtPriceX96;
+    use testing_tools::types::initial_state::PartialConfigPoolKey;
+
+    use super::*;
+    use crate::cli::init_tracing;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn devnet_trades_on_pool_configured_at_runtime() {
+        init_tracing(4);
+        let config = DevnetConfig::new(3, 42000, None, None);
+        let mut testnet = AngstromTestnet::spawn_devnet(NoopProvider::default(), config)
+            .await
+            .unwrap()
+            .as_state_machine();
+
+        testnet.configure_pool(PartialConfigPoolKey::new(
+            0,
+            60,
+            1_000_000_000_000_000_000_000,
+            SqrtPriceX96::at_tick(100_020).unwrap()
+        ));
+        testnet.clear_order_on_new_pool(5);
+
+        testnet.run().await;
+    }
+}
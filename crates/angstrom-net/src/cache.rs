@@ -55,6 +55,15 @@ impl<T: Hash + Eq> LruCache<T> {
         self.inner.contains(value)
     }
 
+    /// Removes the value from the set, returns false if it wasn't present
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq
+    {
+        self.inner.remove(value)
+    }
+
     /// Returns an iterator over all cached entries
     pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
         self.inner.iter()
@@ -12,7 +12,18 @@ pub enum StromStreamError {
     MessageTooBig(usize),
     #[error("message id is invalid")]
     /// Flags an unrecognized message ID for a given protocol version.
-    InvalidMessageError
+    InvalidMessageError,
+    #[error("failed to decode message payload: {0}")]
+    /// The payload couldn't be decoded into a message.
+    InvalidPayload(#[from] bincode::Error),
+    #[error("message encoded with a different protocol version: {0}")]
+    /// The payload was encoded with a version other than the one negotiated
+    /// for the session.
+    MismatchedVersion(GotExpected<u8>),
+    #[error("message id {id} is not part of protocol version {version}")]
+    /// The message was added in a newer version than the one negotiated for
+    /// the session.
+    UnsupportedMessage { id: u8, version: u8 }
 }
 
 /// Error  that can occur during the `eth` sub-protocol handshake.
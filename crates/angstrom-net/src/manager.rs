@@ -8,7 +8,7 @@ use std::{
 use alloy::primitives::BlockNumber;
 use angstrom_eth::manager::EthEvent;
 use angstrom_types::{
-    consensus::{PreProposal, PreProposalAggregation, Proposal},
+    consensus::{PreProposal, PreProposalAggregation, Proposal, ProposalTimeout},
     primitive::PeerId
 };
 use futures::StreamExt;
@@ -206,6 +206,11 @@ impl<DB: Unpin> Future for StromNetworkManager<DB> {
                                 let _ = tx.send(StromConsensusEvent::Proposal(peer_id, a));
                             });
                         }
+                        StromMessage::ProposalTimeout(t) => {
+                            self.to_consensus_manager.as_ref().inspect(|tx| {
+                                let _ = tx.send(StromConsensusEvent::ProposalTimeout(peer_id, t));
+                            });
+                        }
                         StromMessage::PropagatePooledOrders(a) => {
                             self.to_pool_manager.as_ref().inspect(|tx| {
                                 let _ = tx
@@ -218,6 +223,33 @@ impl<DB: Unpin> Future for StromNetworkManager<DB> {
                                     tx.send(NetworkOrderEvent::CancelOrder { peer_id, request: a });
                             });
                         }
+                        StromMessage::GetPooledOrderHashes(pools) => {
+                            self.to_pool_manager.as_ref().inspect(|tx| {
+                                let _ = tx.send(NetworkOrderEvent::GetPooledOrderHashes {
+                                    peer_id,
+                                    pools
+                                });
+                            });
+                        }
+                        StromMessage::PooledOrderHashes(hashes) => {
+                            self.to_pool_manager.as_ref().inspect(|tx| {
+                                let _ = tx
+                                    .send(NetworkOrderEvent::PooledOrderHashes { peer_id, hashes });
+                            });
+                        }
+                        StromMessage::GetPooledOrders(hashes) => {
+                            self.to_pool_manager.as_ref().inspect(|tx| {
+                                let _ =
+                                    tx.send(NetworkOrderEvent::GetPooledOrders { peer_id, hashes });
+                            });
+                        }
+                        // requested orders go through the same validation as gossiped ones
+                        StromMessage::PooledOrders(orders) => {
+                            self.to_pool_manager.as_ref().inspect(|tx| {
+                                let _ =
+                                    tx.send(NetworkOrderEvent::IncomingOrders { peer_id, orders });
+                            });
+                        }
                         StromMessage::Status(_) => {}
                     },
                     SwarmEvent::Disconnected { peer_id } => {
@@ -231,6 +263,24 @@ impl<DB: Unpin> Future for StromNetworkManager<DB> {
                             .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
                         self.notify_listeners(StromNetworkEvent::SessionEstablished { peer_id })
                     }
+                    SwarmEvent::SessionCongested { peer_id } => {
+                        self.notify_listeners(StromNetworkEvent::SessionCongested { peer_id })
+                    }
+                    SwarmEvent::SessionDrained { peer_id } => {
+                        self.notify_listeners(StromNetworkEvent::SessionDrained { peer_id })
+                    }
+                    // gossip is queued again, everything else is best effort
+                    SwarmEvent::Unsent { peer_id, msg } => match msg {
+                        StromMessage::PropagatePooledOrders(orders) => {
+                            self.to_pool_manager.as_ref().inspect(|tx| {
+                                let _ =
+                                    tx.send(NetworkOrderEvent::UnsentOrders { peer_id, orders });
+                            });
+                        }
+                        msg => {
+                            tracing::debug!(?peer_id, id=?msg.message_id(), "dropped message to congested peer")
+                        }
+                    }
                 }
             }
         }
@@ -262,14 +312,19 @@ pub enum StromNetworkEvent {
     /// Event emitted when a new peer is added
     PeerAdded(PeerId),
     /// Event emitted when a new peer is removed
-    PeerRemoved(PeerId)
+    PeerRemoved(PeerId),
+    /// The peer's session can't keep up with the messages we are sending it
+    SessionCongested { peer_id: PeerId },
+    /// The peer's session has worked through its backlog
+    SessionDrained { peer_id: PeerId }
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub enum StromConsensusEvent {
     PreProposal(PeerId, PreProposal),
     PreProposalAgg(PeerId, PreProposalAggregation),
-    Proposal(PeerId, Proposal)
+    Proposal(PeerId, Proposal),
+    ProposalTimeout(PeerId, ProposalTimeout)
 }
 
 impl StromConsensusEvent {
@@ -277,7 +332,8 @@ impl StromConsensusEvent {
         match self {
             StromConsensusEvent::PreProposal(..) => "PreProposal",
             StromConsensusEvent::PreProposalAgg(..) => "PreProposalAggregation",
-            StromConsensusEvent::Proposal(..) => "Proposal"
+            StromConsensusEvent::Proposal(..) => "Proposal",
+            StromConsensusEvent::ProposalTimeout(..) => "ProposalTimeout"
         }
     }
 
@@ -285,7 +341,8 @@ impl StromConsensusEvent {
         match self {
             StromConsensusEvent::PreProposal(peer_id, _)
             | StromConsensusEvent::Proposal(peer_id, _)
-            | StromConsensusEvent::PreProposalAgg(peer_id, _) => *peer_id
+            | StromConsensusEvent::PreProposalAgg(peer_id, _)
+            | StromConsensusEvent::ProposalTimeout(peer_id, _) => *peer_id
         }
     }
 
@@ -293,7 +350,8 @@ impl StromConsensusEvent {
         match self {
             StromConsensusEvent::PreProposal(_, pre_proposal) => pre_proposal.source,
             StromConsensusEvent::PreProposalAgg(_, pre_proposal) => pre_proposal.source,
-            StromConsensusEvent::Proposal(_, proposal) => proposal.source
+            StromConsensusEvent::Proposal(_, proposal) => proposal.source,
+            StromConsensusEvent::ProposalTimeout(_, timeout) => timeout.source
         }
     }
 
@@ -301,7 +359,8 @@ impl StromConsensusEvent {
         match self {
             StromConsensusEvent::PreProposal(_, PreProposal { block_height, .. }) => *block_height,
             StromConsensusEvent::PreProposalAgg(_, p) => p.block_height,
-            StromConsensusEvent::Proposal(_, Proposal { block_height, .. }) => *block_height
+            StromConsensusEvent::Proposal(_, Proposal { block_height, .. }) => *block_height,
+            StromConsensusEvent::ProposalTimeout(_, timeout) => timeout.block_height
         }
     }
 }
@@ -314,7 +373,10 @@ impl From<StromConsensusEvent> for StromMessage {
             }
             StromConsensusEvent::PreProposalAgg(_, agg) => StromMessage::PreProposeAgg(agg),
 
-            StromConsensusEvent::Proposal(_, proposal) => StromMessage::Propose(proposal)
+            StromConsensusEvent::Proposal(_, proposal) => StromMessage::Propose(proposal),
+            StromConsensusEvent::ProposalTimeout(_, timeout) => {
+                StromMessage::ProposalTimeout(timeout)
+            }
         }
     }
 }
@@ -1,7 +1,10 @@
 use std::sync::{atomic::AtomicUsize, Arc};
 
+use alloy::primitives::B256;
 use angstrom_types::{
-    orders::CancelOrderRequest, primitive::PeerId, sol_bindings::grouped_orders::AllOrders
+    orders::CancelOrderRequest,
+    primitive::{PeerId, PoolId},
+    sol_bindings::grouped_orders::AllOrders
 };
 use reth_metrics::common::mpsc::UnboundedMeteredSender;
 use reth_network::DisconnectReason;
@@ -91,8 +94,34 @@ struct StromNetworkInner {
 /// All events related to orders emitted by the network.
 #[derive(Debug, Clone, PartialEq)]
 pub enum NetworkOrderEvent {
-    IncomingOrders { peer_id: PeerId, orders: Vec<AllOrders> },
-    CancelOrder { peer_id: PeerId, request: CancelOrderRequest }
+    IncomingOrders {
+        peer_id: PeerId,
+        orders:  Vec<AllOrders>
+    },
+    CancelOrder {
+        peer_id: PeerId,
+        request: CancelOrderRequest
+    },
+    /// the peer wants the hashes of all our orders in the given pools
+    GetPooledOrderHashes {
+        peer_id: PeerId,
+        pools:   Vec<PoolId>
+    },
+    /// the order hashes the peer has, in response to our request
+    PooledOrderHashes {
+        peer_id: PeerId,
+        hashes:  Vec<B256>
+    },
+    /// the peer wants the orders with the given hashes
+    GetPooledOrders {
+        peer_id: PeerId,
+        hashes:  Vec<B256>
+    },
+    /// orders we gossiped that didn't fit into the peer's session buffer
+    UnsentOrders {
+        peer_id: PeerId,
+        orders:  Vec<AllOrders>
+    }
 }
 
 #[derive(Debug)]
@@ -1,41 +1,63 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet, VecDeque},
     num::NonZeroUsize,
     pin::Pin,
     sync::Arc,
-    task::{Context, Poll, Waker}
+    task::{Context, Poll, Waker},
+    time::{Duration, Instant}
 };
 
 use alloy::primitives::{Address, FixedBytes, B256};
 use angstrom_eth::manager::EthEvent;
 use angstrom_types::{
     block_sync::BlockSyncConsumer,
-    orders::{CancelOrderRequest, OrderLocation, OrderOrigin, OrderStatus},
+    orders::{CancelOrderRequest, OrderHistoryEntry, OrderLocation, OrderOrigin, OrderStatus},
     primitive::{NewInitializedPool, OrderPoolNewOrderResult, PeerId, PoolId},
     sol_bindings::grouped_orders::AllOrders
 };
 use futures::{Future, FutureExt, StreamExt};
 use order_pool::{
-    order_storage::OrderStorage, OrderIndexer, OrderPoolHandle, PoolConfig, PoolInnerEvent,
-    PoolManagerUpdate
+    order_storage::OrderStorage, OrderIndexer, OrderJournal, OrderPoolHandle, PoolConfig,
+    PoolInnerEvent, PoolManagerUpdate
 };
 use reth_metrics::common::mpsc::UnboundedMeteredReceiver;
 use reth_tasks::TaskSpawner;
-use tokio::sync::{
-    broadcast,
-    mpsc::{error::SendError, unbounded_channel, UnboundedReceiver, UnboundedSender}
+use tokio::{
+    sync::{
+        broadcast,
+        mpsc::{error::SendError, unbounded_channel, UnboundedReceiver, UnboundedSender}
+    },
+    time::{interval, Interval}
 };
 use tokio_stream::wrappers::{BroadcastStream, UnboundedReceiverStream};
 use validation::order::{
     state::pools::AngstromPoolsTracker, OrderValidationResults, OrderValidatorHandle
 };
 
-use crate::{LruCache, NetworkOrderEvent, StromMessage, StromNetworkEvent, StromNetworkHandle};
+use crate::{
+    LruCache, NetworkOrderEvent, ReputationChangeKind, StromMessage, StromNetworkEvent,
+    StromNetworkHandle, MAX_MESSAGE_SIZE
+};
 
 const MODULE_NAME: &str = "Order Pool";
 
 /// Cache limit of transactions to keep track of for a single peer.
 const PEER_ORDER_CACHE_LIMIT: usize = 1024 * 10;
+/// Max amount of order hashes in a single order sync message.
+const MAX_ORDER_SYNC_HASHES: usize = 4096;
+/// How often a peer can make each kind of order sync request.
+const ORDER_SYNC_REQUEST_INTERVAL: Duration = Duration::from_secs(12);
+/// How often newly validated orders are batched up and sent to peers.
+const ORDER_GOSSIP_INTERVAL: Duration = Duration::from_millis(100);
+/// Max amount of orders queued for a single peer. When a congested peer falls
+/// this far behind, the oldest queued orders are dropped.
+const MAX_PENDING_PEER_ORDERS: usize = 4096;
+/// Max amount of propagation messages sent to a single peer per flush, so that
+/// a burst doesn't fill up the session's command buffer by itself.
+const MAX_PEER_BATCHES_PER_FLUSH: usize = 4;
+/// Size budget of the orders in a single propagation message. Leaves room for
+/// the message id and encoding overhead.
+const MAX_ORDER_BATCH_SIZE: usize = MAX_MESSAGE_SIZE - 1024;
 
 /// Api to interact with [`PoolManager`] task.
 #[derive(Debug, Clone)]
@@ -51,7 +73,8 @@ pub enum OrderCommand {
     CancelOrder(CancelOrderRequest, tokio::sync::oneshot::Sender<bool>),
     PendingOrders(Address, tokio::sync::oneshot::Sender<Vec<AllOrders>>),
     OrdersByPool(FixedBytes<32>, OrderLocation, tokio::sync::oneshot::Sender<Vec<AllOrders>>),
-    OrderStatus(B256, tokio::sync::oneshot::Sender<Option<OrderStatus>>)
+    OrderStatus(B256, tokio::sync::oneshot::Sender<Option<OrderStatus>>),
+    OrderHistory(B256, tokio::sync::oneshot::Sender<Vec<OrderHistoryEntry>>)
 }
 
 impl PoolHandle {
@@ -101,6 +124,18 @@ impl OrderPoolHandle for PoolHandle {
         rx.map(|v| v.ok().flatten())
     }
 
+    fn fetch_order_history(
+        &self,
+        order_hash: B256
+    ) -> impl Future<Output = Vec<OrderHistoryEntry>> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let _ = self
+            .manager_tx
+            .send(OrderCommand::OrderHistory(order_hash, tx));
+
+        rx.map(|v| v.unwrap_or_default())
+    }
+
     fn pending_orders(&self, sender: Address) -> impl Future<Output = Vec<AllOrders>> + Send {
         let (tx, rx) = tokio::sync::oneshot::channel();
         let _ = self.send(OrderCommand::PendingOrders(sender, tx)).is_ok();
@@ -126,7 +161,8 @@ where
     strom_network_events: UnboundedReceiverStream<StromNetworkEvent>,
     eth_network_events:   UnboundedReceiverStream<EthEvent>,
     order_events:         UnboundedMeteredReceiver<NetworkOrderEvent>,
-    config:               PoolConfig
+    config:               PoolConfig,
+    journal:              Option<OrderJournal>
 }
 
 impl<V, GlobalSync> PoolManagerBuilder<V, GlobalSync>
@@ -150,7 +186,8 @@ where
             network_handle,
             validator,
             order_storage,
-            config: Default::default()
+            config: Default::default(),
+            journal: None
         }
     }
 
@@ -164,6 +201,13 @@ where
         self
     }
 
+    /// persists the pool to the journal and replays the orders that were in it
+    /// from the last run
+    pub fn with_journal(mut self, journal: OrderJournal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
     pub fn build_with_channels<TP: TaskSpawner>(
         self,
         task_spawner: TP,
@@ -184,7 +228,13 @@ where
             0,
             pool_manager_tx.clone(),
             pool_storage
-        );
+        )
+        .with_max_account_slots(self.config.max_account_slots)
+        .with_block_time(self.config.block_time);
+        let inner = match self.journal {
+            Some(journal) => inner.with_journal(journal),
+            None => inner
+        };
         self.global_sync.register(MODULE_NAME);
 
         task_spawner.spawn_critical(
@@ -197,7 +247,8 @@ where
                 order_indexer:        inner,
                 network:              self.network_handle,
                 command_rx:           rx,
-                global_sync:          self.global_sync
+                global_sync:          self.global_sync,
+                gossip_interval:      interval(ORDER_GOSSIP_INTERVAL)
             })
         );
 
@@ -223,7 +274,13 @@ where
             0,
             pool_manager_tx.clone(),
             pool_storage
-        );
+        )
+        .with_max_account_slots(self.config.max_account_slots)
+        .with_block_time(self.config.block_time);
+        let inner = match self.journal {
+            Some(journal) => inner.with_journal(journal),
+            None => inner
+        };
 
         task_spawner.spawn_critical(
             "transaction manager",
@@ -235,7 +292,8 @@ where
                 order_indexer:        inner,
                 network:              self.network_handle,
                 command_rx:           rx,
-                global_sync:          self.global_sync
+                global_sync:          self.global_sync,
+                gossip_interval:      interval(ORDER_GOSSIP_INTERVAL)
             })
         );
 
@@ -265,7 +323,9 @@ where
     /// Incoming events from the ProtocolManager.
     order_events:         UnboundedMeteredReceiver<NetworkOrderEvent>,
     /// All the connected peers.
-    peer_to_info:         HashMap<PeerId, StromPeer>
+    peer_to_info:         HashMap<PeerId, StromPeer>,
+    /// when the queued order propagations get flushed to the peers
+    gossip_interval:      Interval
 }
 
 impl<V, GlobalSync> PoolManager<V, GlobalSync>
@@ -275,9 +335,9 @@ where
 {
     fn on_command(&mut self, cmd: OrderCommand) {
         match cmd {
-            OrderCommand::NewOrder(_, order, validation_response) => self
+            OrderCommand::NewOrder(origin, order, validation_response) => self
                 .order_indexer
-                .new_rpc_order(OrderOrigin::External, order, validation_response),
+                .new_rpc_order(origin, order, validation_response),
             OrderCommand::CancelOrder(req, receiver) => {
                 let res = self.order_indexer.cancel_order(&req);
                 if res {
@@ -293,6 +353,10 @@ where
                 let res = self.order_indexer.order_status(order_hash);
                 let _ = tx.send(res);
             }
+            OrderCommand::OrderHistory(order_hash, tx) => {
+                let res = self.order_indexer.order_history(order_hash);
+                let _ = tx.send(res);
+            }
 
             OrderCommand::OrdersByPool(pool_id, location, tx) => {
                 let res = self.order_indexer.orders_by_pool(pool_id, location);
@@ -358,23 +422,110 @@ where
                     self.broadcast_cancel_to_peers(request);
                 }
             }
+            NetworkOrderEvent::GetPooledOrderHashes { peer_id, pools } => {
+                self.on_get_pooled_order_hashes(peer_id, pools)
+            }
+            NetworkOrderEvent::PooledOrderHashes { peer_id, hashes } => {
+                self.on_pooled_order_hashes(peer_id, hashes)
+            }
+            NetworkOrderEvent::GetPooledOrders { peer_id, hashes } => {
+                self.on_get_pooled_orders(peer_id, hashes)
+            }
+            NetworkOrderEvent::UnsentOrders { peer_id, orders } => {
+                self.on_unsent_orders(peer_id, orders)
+            }
+        }
+    }
+
+    /// the peer's session couldn't take the batch, so the orders weren't seen
+    /// after all and go back to the front of its queue until it drains. Being
+    /// the oldest, they are the first to be dropped if the queue is full
+    fn on_unsent_orders(&mut self, peer_id: PeerId, orders: Vec<AllOrders>) {
+        let Some(peer) = self.peer_to_info.get_mut(&peer_id) else { return };
+        peer.congested = true;
+
+        for order in orders.into_iter().rev() {
+            let order_hash = order.order_hash();
+            peer.orders.remove(&order_hash);
+            if peer.queued.insert(order_hash) {
+                peer.pending_orders.push_front(order);
+            }
         }
+        peer.evict_oldest_orders(peer_id);
+    }
+
+    fn on_get_pooled_order_hashes(&mut self, peer_id: PeerId, pools: Vec<PoolId>) {
+        let Some(peer) = self.peer_to_info.get_mut(&peer_id) else { return };
+        if !StromPeer::allow_request(&mut peer.last_hashes_request) {
+            tracing::debug!(?peer_id, "peer is requesting order hashes too often");
+            self.network
+                .peer_reputation_change(peer_id, ReputationChangeKind::BadMessage);
+            return
+        }
+
+        let mut hashes = self.order_indexer.order_hashes_by_pool(&pools);
+        hashes.truncate(MAX_ORDER_SYNC_HASHES);
+        self.network
+            .send_message(peer_id, StromMessage::PooledOrderHashes(hashes));
+    }
+
+    fn on_pooled_order_hashes(&mut self, peer_id: PeerId, hashes: Vec<B256>) {
+        let Some(peer) = self.peer_to_info.get_mut(&peer_id) else { return };
+        if !std::mem::take(&mut peer.awaiting_order_hashes) || hashes.len() > MAX_ORDER_SYNC_HASHES
+        {
+            tracing::debug!(?peer_id, "got unrequested or oversized order hashes");
+            self.network
+                .peer_reputation_change(peer_id, ReputationChangeKind::BadMessage);
+            return
+        }
+
+        // the peer already has these, no need to gossip them back
+        hashes.iter().for_each(|hash| {
+            peer.orders.insert(*hash);
+        });
+
+        let missing = self.order_indexer.missing_orders(hashes);
+        if missing.is_empty() {
+            return
+        }
+
+        tracing::debug!(?peer_id, missing = missing.len(), "requesting missing orders from peer");
+        self.network
+            .send_message(peer_id, StromMessage::GetPooledOrders(missing));
+    }
+
+    fn on_get_pooled_orders(&mut self, peer_id: PeerId, hashes: Vec<B256>) {
+        let Some(peer) = self.peer_to_info.get_mut(&peer_id) else { return };
+        if hashes.len() > MAX_ORDER_SYNC_HASHES
+            || !StromPeer::allow_request(&mut peer.last_orders_request)
+        {
+            tracing::debug!(?peer_id, "peer is requesting orders too often");
+            self.network
+                .peer_reputation_change(peer_id, ReputationChangeKind::BadMessage);
+            return
+        }
+
+        let orders = self.order_indexer.orders_by_hash(&hashes);
+        orders.iter().for_each(|order| {
+            peer.orders.insert(order.order_hash());
+        });
+        self.network
+            .send_message(peer_id, StromMessage::PooledOrders(orders));
     }
 
     fn on_network_event(&mut self, event: StromNetworkEvent) {
         match event {
             StromNetworkEvent::SessionEstablished { peer_id } => {
                 // insert a new peer into the peerset
-                self.peer_to_info.insert(
+                let mut peer = StromPeer::new();
+                // we only get orders that are gossiped after this point, so we sync the
+                // rest from the peer
+                peer.awaiting_order_hashes = true;
+                self.peer_to_info.insert(peer_id, peer);
+
+                self.network.send_message(
                     peer_id,
-                    StromPeer {
-                        orders:        LruCache::new(
-                            NonZeroUsize::new(PEER_ORDER_CACHE_LIMIT).unwrap()
-                        ),
-                        cancellations: LruCache::new(
-                            NonZeroUsize::new(PEER_ORDER_CACHE_LIMIT).unwrap()
-                        )
-                    }
+                    StromMessage::GetPooledOrderHashes(self.order_indexer.pool_ids())
                 );
             }
             StromNetworkEvent::SessionClosed { peer_id, .. } => {
@@ -385,17 +536,17 @@ where
                 self.peer_to_info.remove(&peer_id);
             }
             StromNetworkEvent::PeerAdded(peer_id) => {
-                self.peer_to_info.insert(
-                    peer_id,
-                    StromPeer {
-                        orders:        LruCache::new(
-                            NonZeroUsize::new(PEER_ORDER_CACHE_LIMIT).unwrap()
-                        ),
-                        cancellations: LruCache::new(
-                            NonZeroUsize::new(PEER_ORDER_CACHE_LIMIT).unwrap()
-                        )
-                    }
-                );
+                self.peer_to_info.insert(peer_id, StromPeer::new());
+            }
+            StromNetworkEvent::SessionCongested { peer_id } => {
+                if let Some(peer) = self.peer_to_info.get_mut(&peer_id) {
+                    peer.congested = true;
+                }
+            }
+            StromNetworkEvent::SessionDrained { peer_id } => {
+                if let Some(peer) = self.peer_to_info.get_mut(&peer_id) {
+                    peer.congested = false;
+                }
             }
         }
     }
@@ -407,10 +558,15 @@ where
                 PoolInnerEvent::Propagation(order) => Some(order),
                 PoolInnerEvent::BadOrderMessages(o) => {
                     o.into_iter().for_each(|peer| {
-                        self.network.peer_reputation_change(
-                            peer,
-                            crate::ReputationChangeKind::InvalidOrder
-                        );
+                        self.network
+                            .peer_reputation_change(peer, ReputationChangeKind::InvalidOrder);
+                    });
+                    None
+                }
+                PoolInnerEvent::BadComposableOrderMessages(o) => {
+                    o.into_iter().for_each(|peer| {
+                        self.network
+                            .peer_reputation_change(peer, ReputationChangeKind::BadComposableOrder);
                     });
                     None
                 }
@@ -438,22 +594,83 @@ where
         }
     }
 
+    /// queues the orders for every peer that hasn't seen them yet. They get
+    /// sent out in batches on the next gossip flush
     fn broadcast_orders_to_peers(&mut self, valid_orders: Vec<AllOrders>) {
         for order in valid_orders.iter() {
+            let order_hash = order.order_hash();
             for (peer_id, info) in self.peer_to_info.iter_mut() {
-                let order_hash = order.order_hash();
-                if !info.orders.contains(&order_hash) {
-                    self.network.send_message(
-                        *peer_id,
-                        StromMessage::PropagatePooledOrders(vec![order.clone()])
-                    );
+                if info.orders.contains(&order_hash) || !info.queued.insert(order_hash) {
+                    continue
+                }
+
+                info.pending_orders.push_back(order.clone());
+                info.evict_oldest_orders(*peer_id);
+            }
+        }
+    }
+
+    /// sends the queued orders to every peer that isn't congested. The orders
+    /// only count as seen once they are handed to the session, which gives
+    /// them back if its buffer is full
+    fn flush_order_gossip(&mut self) {
+        for (peer_id, info) in self.peer_to_info.iter_mut() {
+            if info.congested || info.pending_orders.is_empty() {
+                continue
+            }
+
+            for _ in 0..MAX_PEER_BATCHES_PER_FLUSH {
+                let batch = next_order_batch(
+                    &mut info.pending_orders,
+                    &mut info.queued,
+                    MAX_ORDER_BATCH_SIZE
+                );
+                if batch.is_empty() {
+                    break
+                }
+                for order_hash in batch.iter().map(|order| order.order_hash()) {
                     info.orders.insert(order_hash);
                 }
+                self.network
+                    .send_message(*peer_id, StromMessage::PropagatePooledOrders(batch));
             }
         }
     }
 }
 
+/// Takes orders from the front of the queue until the next one would push the
+/// batch over `max_size` encoded bytes. An order that doesn't fit into a
+/// message by itself is dropped. Every order taken off the queue is also
+/// removed from `queued`.
+fn next_order_batch(
+    pending: &mut VecDeque<AllOrders>,
+    queued: &mut HashSet<B256>,
+    max_size: usize
+) -> Vec<AllOrders> {
+    let mut batch = Vec::new();
+    let mut batch_size = 0;
+
+    while let Some(order) = pending.front() {
+        let size = bincode::serialized_size(order).map_or(usize::MAX, |size| size as usize);
+        if size > max_size {
+            let order_hash = order.order_hash();
+            tracing::warn!(?order_hash, size, "order is too large to gossip");
+            queued.remove(&order_hash);
+            pending.pop_front();
+            continue
+        }
+        if batch_size + size > max_size {
+            break
+        }
+
+        batch_size += size;
+        queued.remove(&order.order_hash());
+        batch.extend(pending.pop_front());
+    }
+
+    batch
+}
+
 impl<V, GlobalSync> Future for PoolManager<V, GlobalSync>
 where
     V: OrderValidatorHandle<Order = AllOrders> + Unpin,
@@ -486,6 +703,10 @@ where
                 this.on_pool_events(orders, || cx.waker().clone());
             }
 
+            while this.gossip_interval.poll_tick(cx).is_ready() {
+                this.flush_order_gossip();
+            }
+
             // halt dealing with these till we have synced
             if this.global_sync.can_operate() {
                 // drain commands
@@ -520,6 +741,143 @@ pub enum NetworkTransactionEvent {
 #[derive(Debug)]
 struct StromPeer {
     /// Keeps track of transactions that we know the peer has seen.
-    orders:        LruCache<B256>,
-    cancellations: LruCache<B256>
+    orders:                LruCache<B256>,
+    cancellations:         LruCache<B256>,
+    /// orders waiting for the next gossip flush
+    pending_orders:        VecDeque<AllOrders>,
+    /// the hashes of the pending orders
+    queued:                HashSet<B256>,
+    /// the peer's session buffer is full, so we hold back gossip until it
+    /// drains
+    congested:             bool,
+    /// we requested the peer's order hashes and haven't gotten them yet
+    awaiting_order_hashes: bool,
+    /// when the peer last requested our order hashes
+    last_hashes_request:   Option<Instant>,
+    /// when the peer last requested orders from us
+    last_orders_request:   Option<Instant>
+}
+
+impl StromPeer {
+    fn new() -> Self {
+        Self {
+            orders:                LruCache::new(
+                NonZeroUsize::new(PEER_ORDER_CACHE_LIMIT).unwrap()
+            ),
+            cancellations:         LruCache::new(
+                NonZeroUsize::new(PEER_ORDER_CACHE_LIMIT).unwrap()
+            ),
+            pending_orders:        VecDeque::new(),
+            queued:                HashSet::new(),
+            congested:             false,
+            awaiting_order_hashes: false,
+            last_hashes_request:   None,
+            last_orders_request:   None
+        }
+    }
+
+    /// drops the oldest queued orders until the queue is back within
+    /// [`MAX_PENDING_PEER_ORDERS`]
+    fn evict_oldest_orders(&mut self, peer_id: PeerId) {
+        while self.pending_orders.len() > MAX_PENDING_PEER_ORDERS {
+            let Some(dropped) = self.pending_orders.pop_front() else { break };
+            let order_hash = dropped.order_hash();
+            tracing::debug!(
+                ?peer_id,
+                ?order_hash,
+                "peer order queue is full, dropping oldest order"
+            );
+            self.queued.remove(&order_hash);
+        }
+    }
+
+    /// returns false if the peer has already made this request within the
+    /// rate limit interval
+    fn allow_request(last_request: &mut Option<Instant>) -> bool {
+        let now = Instant::now();
+        if last_request.is_some_and(|last| now.duration_since(last) < ORDER_SYNC_REQUEST_INTERVAL) {
+            return false
+        }
+        *last_request = Some(now);
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use angstrom_types::sol_bindings::{
+        grouped_orders::StandingVariants, rpc_orders::PartialStandingOrder
+    };
+
+    use super::*;
+
+    fn order() -> AllOrders {
+        AllOrders::Standing(StandingVariants::Partial(Default::default()))
+    }
+
+    fn order_with_nonce(nonce: u64) -> AllOrders {
+        AllOrders::Standing(StandingVariants::Partial(PartialStandingOrder {
+            nonce,
+            ..Default::default()
+        }))
+    }
+
+    fn queue(orders: impl IntoIterator<Item = AllOrders>) -> (VecDeque<AllOrders>, HashSet<B256>) {
+        let pending = orders.into_iter().collect::<VecDeque<_>>();
+        let queued = pending.iter().map(|order| order.order_hash()).collect();
+        (pending, queued)
+    }
+
+    #[test]
+    fn test_order_batches_respect_size_limit() {
+        let order_size = bincode::serialized_size(&order()).unwrap() as usize;
+        let (mut pending, mut queued) = queue((0..5).map(order_with_nonce));
+
+        let batch = next_order_batch(&mut pending, &mut queued, order_size * 2);
+        assert_eq!(batch.len(), 2);
+        assert_eq!(pending.len(), 3);
+        assert_eq!(queued.len(), 3);
+
+        // everything fits
+        let batch = next_order_batch(&mut pending, &mut queued, MAX_ORDER_BATCH_SIZE);
+        assert_eq!(batch.len(), 3);
+        assert!(pending.is_empty());
+        assert!(queued.is_empty());
+        assert!(next_order_batch(&mut pending, &mut queued, MAX_ORDER_BATCH_SIZE).is_empty());
+
+        // orders that can never fit are dropped instead of blocking the queue, and can
+        // be queued again afterwards
+        let (mut pending, mut queued) = queue([order()]);
+        assert!(next_order_batch(&mut pending, &mut queued, order_size - 1).is_empty());
+        assert!(pending.is_empty());
+        assert!(queued.is_empty());
+    }
+
+    #[test]
+    fn test_full_peer_queue_evicts_oldest_orders() {
+        let mut peer = StromPeer::new();
+        (peer.pending_orders, peer.queued) =
+            queue((0..MAX_PENDING_PEER_ORDERS as u64).map(order_with_nonce));
+
+        // a new order pushes out the oldest one
+        let newest = order_with_nonce(MAX_PENDING_PEER_ORDERS as u64);
+        peer.queued.insert(newest.order_hash());
+        peer.pending_orders.push_back(newest.clone());
+        peer.evict_oldest_orders(PeerId::default());
+
+        assert_eq!(peer.pending_orders.len(), MAX_PENDING_PEER_ORDERS);
+        assert_eq!(peer.queued.len(), MAX_PENDING_PEER_ORDERS);
+        assert!(!peer.queued.contains(&order_with_nonce(0).order_hash()));
+        assert_eq!(peer.pending_orders.back(), Some(&newest));
+
+        // orders handed back by the session are older than everything queued since
+        let unsent = order_with_nonce(0);
+        peer.queued.insert(unsent.order_hash());
+        peer.pending_orders.push_front(unsent.clone());
+        peer.evict_oldest_orders(PeerId::default());
+
+        assert!(!peer.queued.contains(&unsent.order_hash()));
+        assert_eq!(peer.pending_orders.back(), Some(&newest));
+    }
 }
@@ -1,10 +1,7 @@
 use std::{collections::HashSet, net::SocketAddr, pin::Pin};
 
-use alloy::{
-    primitives::{keccak256, Address},
-    rlp::BytesMut
-};
-use angstrom_types::primitive::PeerId;
+use alloy::{primitives::Address, rlp::BytesMut};
+use angstrom_types::primitive::{AngstromSigner, PeerId};
 use futures::{stream::Empty, Stream, StreamExt};
 use reth_eth_wire::{
     capability::SharedCapabilities, multiplex::ProtocolConnection, protocol::Protocol,
@@ -81,8 +78,7 @@ impl ConnectionHandler for StromConnectionHandler {
         peer_id: PeerId,
         conn: ProtocolConnection
     ) -> Self::Connection {
-        let hash = keccak256(peer_id);
-        let validator_address = Address::from_slice(&hash[12..]);
+        let validator_address = AngstromSigner::peer_id_to_address(peer_id);
         if !self.validator_set.contains(&validator_address) {
             return PossibleStromSession::Invalid(futures::stream::empty())
         }
@@ -93,7 +89,8 @@ impl ConnectionHandler for StromConnectionHandler {
             direction,
             remote_id: peer_id,
             established: Instant::now(),
-            commands_to_session: tx
+            commands_to_session: tx,
+            drained: Default::default()
         };
 
         PossibleStromSession::Session(StromSession::new(
@@ -1,4 +1,7 @@
+use std::sync::Arc;
+
 use angstrom_types::primitive::PeerId;
+use futures::task::AtomicWaker;
 use reth_network::Direction;
 use tokio::{sync::mpsc, time::Instant};
 
@@ -29,7 +32,10 @@ pub struct StromSessionHandle {
     pub(crate) established:         Instant,
     /// Sender half of the command channel used send commands _to_ the spawned
     /// session
-    pub(crate) commands_to_session: mpsc::Sender<SessionCommand>
+    pub(crate) commands_to_session: mpsc::Sender<SessionCommand>,
+    /// Woken by the session whenever it takes a command off its buffer, so
+    /// that the manager notices when a congested session drains
+    pub(crate) drained:             Arc<AtomicWaker>
 }
 
 impl StromSessionHandle {
@@ -14,7 +14,7 @@ pub use config::*;
 use futures::task::Context;
 pub mod connection_handler;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet, VecDeque},
     fmt::Debug,
     net::SocketAddr,
     pin::Pin,
@@ -26,6 +26,7 @@ pub use connection_handler::*;
 use futures::task::Poll;
 use reth_eth_wire::DisconnectReason;
 use reth_network::Direction;
+use tokio::sync::mpsc::error::TrySendError;
 use tracing::warn;
 
 use crate::{errors::StromStreamError, StromMessage, StromProtocolMessage};
@@ -33,7 +34,12 @@ use crate::{errors::StromStreamError, StromMessage, StromProtocolMessage};
 #[derive(Debug)]
 pub struct StromSessionManager {
     // All active sessions that are ready to exchange messages.
-    active_sessions: HashMap<PeerId, StromSessionHandle>,
+    active_sessions:    HashMap<PeerId, StromSessionHandle>,
+    /// Sessions whose command buffer filled up. Messages to these are handed
+    /// back until the session has worked through its backlog
+    congested_sessions: HashSet<PeerId>,
+    /// congestion events that still need to be yielded
+    queued_events:      VecDeque<SessionEvent>,
 
     /// Channel to receive the session handle upon initialization from the
     /// connection handler This channel is also used to receive messages
@@ -43,28 +49,85 @@ pub struct StromSessionManager {
 
 impl StromSessionManager {
     pub fn new(from_sessions: mpsc::Receiver<StromSessionMessage>) -> Self {
-        Self { from_sessions, active_sessions: HashMap::default() }
+        Self {
+            from_sessions,
+            active_sessions: HashMap::default(),
+            congested_sessions: HashSet::default(),
+            queued_events: VecDeque::default()
+        }
     }
 
     /// Sends a message to the peer's session
     pub fn send_message(&mut self, peer_id: &PeerId, msg: StromMessage) {
         if let Some(session) = self.active_sessions.get_mut(peer_id) {
-            let _ = session
+            let res = session
                 .commands_to_session
                 .try_send(SessionCommand::Message(msg));
+            self.on_send_result(*peer_id, res);
         }
     }
 
     pub fn broadcast_message(&mut self, msg: StromMessage) {
-        self.active_sessions.values_mut().for_each(|cmd| {
-            let _ = cmd
-                .commands_to_session
-                .try_send(SessionCommand::Message(msg.clone()));
-        })
+        let results = self
+            .active_sessions
+            .iter()
+            .map(|(peer_id, cmd)| {
+                (
+                    *peer_id,
+                    cmd.commands_to_session
+                        .try_send(SessionCommand::Message(msg.clone()))
+                )
+            })
+            .collect::<Vec<_>>();
+
+        results
+            .into_iter()
+            .for_each(|(peer_id, res)| self.on_send_result(peer_id, res));
+    }
+
+    /// Messages that didn't fit into the session's command buffer are handed
+    /// back, so that their sender can queue them again once it drains
+    fn on_send_result(&mut self, peer_id: PeerId, res: Result<(), TrySendError<SessionCommand>>) {
+        let Err(TrySendError::Full(command)) = res else { return };
+        if self.congested_sessions.insert(peer_id) {
+            warn!(?peer_id, "session command buffer is full, holding back messages");
+            self.queued_events
+                .push_back(SessionEvent::Congested { peer_id });
+        }
+        if let SessionCommand::Message(message) = command {
+            self.queued_events
+                .push_back(SessionEvent::Unsent { peer_id, message });
+        }
+    }
+
+    /// A congested session is drained once half of its command buffer is free
+    /// again. Until then, we get woken every time the session takes a command
+    fn poll_drained_sessions(&mut self, cx: &mut Context<'_>) {
+        let drained = self
+            .congested_sessions
+            .iter()
+            .filter(|peer_id| {
+                self.active_sessions.get(peer_id).map_or(true, |session| {
+                    session.drained.register(cx.waker());
+                    let tx = &session.commands_to_session;
+                    tx.capacity() * 2 >= tx.max_capacity()
+                })
+            })
+            .copied()
+            .collect::<Vec<_>>();
+
+        for peer_id in drained {
+            self.congested_sessions.remove(&peer_id);
+            if self.active_sessions.contains_key(&peer_id) {
+                self.queued_events
+                    .push_back(SessionEvent::Drained { peer_id });
+            }
+        }
     }
 
     // Removes the Session handle if it exists.
     fn remove_session(&mut self, id: &PeerId) -> Option<StromSessionHandle> {
+        self.congested_sessions.remove(id);
         let session = self.active_sessions.remove(id)?;
         Some(session)
     }
@@ -129,6 +192,11 @@ impl Stream for StromSessionManager {
     type Item = SessionEvent;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<SessionEvent>> {
+        self.poll_drained_sessions(cx);
+        if let Some(event) = self.queued_events.pop_front() {
+            return Poll::Ready(Some(event))
+        }
+
         self.poll_session_msg(cx)
     }
 }
@@ -195,5 +263,93 @@ pub enum SessionEvent {
     Disconnected {
         /// The remote node's public key
         peer_id: PeerId
+    },
+    /// The session's command buffer is full, so messages to it are handed back
+    Congested {
+        /// The remote node's public key
+        peer_id: PeerId
+    },
+    /// A message that didn't fit into the session's command buffer
+    Unsent {
+        /// The remote node's public key
+        peer_id: PeerId,
+        /// The message that wasn't sent
+        message: StromMessage
+    },
+    /// A congested session has room for messages again
+    Drained {
+        /// The remote node's public key
+        peer_id: PeerId
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use futures::{
+        task::{waker, ArcWake, AtomicWaker},
+        StreamExt
+    };
+    use tokio::time::Instant;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct WakeFlag(AtomicBool);
+
+    impl ArcWake for WakeFlag {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_full_session_hands_back_messages_and_wakes_on_drain() {
+        let (_tx, from_sessions) = mpsc::channel(1);
+        let mut manager = StromSessionManager::new(from_sessions);
+        let (commands_to_session, mut commands_rx) = mpsc::channel(2);
+        let peer_id = PeerId::random();
+        let drained = Arc::new(AtomicWaker::new());
+        manager.active_sessions.insert(
+            peer_id,
+            StromSessionHandle {
+                direction: Direction::Incoming,
+                remote_id: peer_id,
+                established: Instant::now(),
+                commands_to_session,
+                drained: drained.clone()
+            }
+        );
+
+        let message = StromMessage::PooledOrderHashes(vec![]);
+        for _ in 0..3 {
+            manager.send_message(&peer_id, message.clone());
+        }
+
+        let flag = Arc::new(WakeFlag::default());
+        let waker = waker(flag.clone());
+        let mut cx = Context::from_waker(&waker);
+        assert!(matches!(
+            manager.poll_next_unpin(&mut cx),
+            Poll::Ready(Some(SessionEvent::Congested { .. }))
+        ));
+        match manager.poll_next_unpin(&mut cx) {
+            Poll::Ready(Some(SessionEvent::Unsent { peer_id: unsent_to, message: unsent })) => {
+                assert_eq!(unsent_to, peer_id);
+                assert_eq!(unsent, message);
+            }
+            _ => panic!("expected the message that didn't fit to be handed back")
+        }
+        assert!(manager.poll_next_unpin(&mut cx).is_pending());
+
+        // the session taking a command off its buffer wakes us up
+        commands_rx.try_recv().unwrap();
+        drained.wake();
+        assert!(flag.0.load(Ordering::SeqCst));
+        assert!(matches!(
+            manager.poll_next_unpin(&mut cx),
+            Poll::Ready(Some(SessionEvent::Drained { .. }))
+        ));
     }
 }
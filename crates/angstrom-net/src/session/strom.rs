@@ -3,14 +3,15 @@ use std::{
     fmt::Debug,
     ops::Deref,
     pin::Pin,
+    sync::Arc,
     time::{SystemTime, UNIX_EPOCH}
 };
 
-use alloy::rlp::{BytesMut, Encodable};
+use alloy::rlp::BytesMut;
 use angstrom_types::primitive::{AngstromSigner, PeerId};
 use angstrom_utils::{GenericExt, PollFlatten};
 use futures::{
-    task::{Context, Poll},
+    task::{AtomicWaker, Context, Poll},
     Stream, StreamExt
 };
 use reth_eth_wire::multiplex::ProtocolConnection;
@@ -21,11 +22,12 @@ use tokio_util::sync::PollSender;
 
 use super::handle::SessionCommand;
 use crate::{
+    errors::StromStreamError,
     types::{
         message::StromProtocolMessage,
         status::{Status, StatusState}
     },
-    StatusBuilder, StromMessage, StromSessionHandle, StromSessionMessage
+    StatusBuilder, StromMessage, StromSessionHandle, StromSessionMessage, StromVersion
 };
 
 const STATUS_TIMESTAMP_TIMEOUT_MS: u128 = 1500;
@@ -51,6 +53,36 @@ impl VerificationSidecar {
     pub fn is_verified(&self) -> bool {
         self.has_sent && self.has_received
     }
+
+    /// checks the status the remote peer sent us, returning the protocol
+    /// version we agree on if the peer is who it claims to be and is on our
+    /// chain
+    pub fn verify_status(&self, remote_peer_id: PeerId, status: Status) -> Option<StromVersion> {
+        let current_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+
+        let status_time = status.state.timestamp + STATUS_TIMESTAMP_TIMEOUT_MS;
+        let state = status.state;
+        let Ok(signer) = status.verify() else { return None };
+
+        if current_time > status_time || signer != remote_peer_id {
+            return None
+        }
+
+        if state.chain != self.status.chain {
+            tracing::debug!(peer=?remote_peer_id, chain=state.chain, "peer is on a different chain");
+            return None
+        }
+
+        let version = StromVersion::negotiate(self.status.version, state.version);
+        if version.is_none() {
+            tracing::debug!(peer=?remote_peer_id, version=state.version, "no common protocol version");
+        }
+
+        version
+    }
 }
 
 impl Debug for VerificationSidecar {
@@ -62,13 +94,15 @@ impl Debug for VerificationSidecar {
 #[allow(dead_code)]
 pub struct StromSession {
     /// The underlying connection.
-    pub(crate) conn:               ProtocolConnection,
+    pub(crate) conn: ProtocolConnection,
     /// Identifier of the node we're connected to.
-    pub(crate) remote_peer_id:     PeerId,
+    pub(crate) remote_peer_id: PeerId,
     /// Incoming commands from the manager
-    pub(crate) commands_rx:        ReceiverStream<SessionCommand>,
+    pub(crate) commands_rx: ReceiverStream<SessionCommand>,
     /// Sink to send messages to the [`SessionManager`](super::SessionManager).
     pub(crate) to_session_manager: MeteredPollSender<StromSessionMessage>,
+    /// Lets the manager know that our command buffer has room again
+    drained: Arc<AtomicWaker>,
 
     /// If an [ActiveSession] does not receive a response at all within this
     /// duration then it is considered a protocol violation and the session
@@ -82,7 +116,9 @@ pub struct StromSession {
     /// has sent the handle to the receiver
     pending_handle: Option<StromSessionHandle>,
     /// buffer for pending messages
-    outbound_buffer: VecDeque<StromSessionMessage>
+    outbound_buffer: VecDeque<StromSessionMessage>,
+    /// the wire version negotiated during the status handshake
+    version: StromVersion
 }
 
 impl StromSession {
@@ -101,10 +137,12 @@ impl StromSession {
             remote_peer_id: peer_id,
             commands_rx,
             to_session_manager,
+            drained: handle.drained.clone(),
             protocol_breach_request_timeout,
             terminate_message: None,
             pending_handle: Some(handle),
-            outbound_buffer: VecDeque::default()
+            outbound_buffer: VecDeque::default(),
+            version: StromVersion::HANDSHAKE
         }
     }
 
@@ -164,17 +202,38 @@ impl StromSession {
             .map(|inner| {
                 inner.map_or_else(
                     || Poll::Ready(None),
-                    |msg| match msg {
-                        SessionCommand::Disconnect { .. } => self.emit_disconnect(cx),
-                        SessionCommand::Message(msg) => {
-                            let msg = StromProtocolMessage {
-                                message_id: msg.message_id(),
-                                message:    msg
-                            };
-                            let mut buf = BytesMut::new();
-
-                            msg.encode(&mut buf);
-                            Poll::Ready(Some(buf))
+                    |msg| {
+                        // the manager waits on this to notice a congested session drain
+                        self.drained.wake();
+                        match msg {
+                            SessionCommand::Disconnect { .. } => self.emit_disconnect(cx),
+                            SessionCommand::Message(msg) => {
+                                let message_id = msg.message_id();
+                                let msg = StromProtocolMessage { message_id, message: msg };
+                                let mut buf = BytesMut::new();
+
+                                match msg.encode_versioned(self.version, &mut buf) {
+                                    Ok(()) => Poll::Ready(Some(buf)),
+                                    Err(e @ StromStreamError::UnsupportedMessage { .. }) => {
+                                        // the peer runs an older version that doesn't know this
+                                        // message
+                                        tracing::trace!(%e, peer=?self.remote_peer_id, "skipping message");
+                                        cx.waker().wake_by_ref();
+                                        Poll::Pending
+                                    }
+                                    Err(e) => {
+                                        tracing::error!(
+                                            %e,
+                                            ?message_id,
+                                            peer=?self.remote_peer_id,
+                                            "dropping message that can't be encoded"
+                                        );
+                                        // more commands can be queued behind the dropped one
+                                        cx.waker().wake_by_ref();
+                                        Poll::Pending
+                                    }
+                                }
+                            }
                         }
                     }
                 )
@@ -187,7 +246,10 @@ impl StromSession {
         // processes incoming messages until there are none left or the stream closes
         while let Poll::Ready(msg) = self.conn.poll_next_unpin(cx).map(|data| {
             data.map(|bytes| {
-                let msg = StromProtocolMessage::decode_message(&mut bytes.deref());
+                let msg = StromProtocolMessage::decode_message(self.version, &mut bytes.deref());
+                if let Err(e) = &msg {
+                    tracing::debug!(%e, peer=?self.remote_peer_id, "failed to decode message");
+                }
 
                 let msg = msg
                     .map(|m| StromSessionMessage::ValidMessage {
@@ -222,7 +284,10 @@ impl StromSession {
             let msg = StromProtocolMessage { message_id: msg.message_id(), message: msg };
 
             let mut buf = BytesMut::new();
-            msg.encode(&mut buf);
+            if let Err(e) = msg.encode_versioned(StromVersion::HANDSHAKE, &mut buf) {
+                tracing::error!(%e, peer=?self.remote_peer_id, "failed to encode status message");
+                return self.emit_disconnect(cx)
+            }
 
             return Poll::Ready(Some(buf))
         }
@@ -235,7 +300,10 @@ impl StromSession {
                 self.verification_sidecar.has_received = true;
 
                 msg.map(|bytes| {
-                    let msg = StromProtocolMessage::decode_message(&mut bytes.deref());
+                    let msg = StromProtocolMessage::decode_message(
+                        StromVersion::HANDSHAKE,
+                        &mut bytes.deref()
+                    );
 
                     msg.map_or(false, |msg| {
                         // first message has to be status
@@ -272,19 +340,17 @@ impl StromSession {
         }
     }
 
-    fn verify_incoming_status(&self, status: Status) -> bool {
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_millis();
-
-        let status_time = status.state.timestamp + STATUS_TIMESTAMP_TIMEOUT_MS;
-        let verification = status.verify();
-        if verification.is_err() {
+    fn verify_incoming_status(&mut self, status: Status) -> bool {
+        let Some(version) = self
+            .verification_sidecar
+            .verify_status(self.remote_peer_id, status)
+        else {
             return false
-        }
+        };
+        tracing::debug!(peer=?self.remote_peer_id, ?version, "negotiated protocol version");
+        self.version = version;
 
-        current_time <= status_time && verification.unwrap() == self.remote_peer_id
+        true
     }
 }
 
@@ -321,3 +387,45 @@ impl Stream for StromSession {
         Poll::Pending
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAINNET: u64 = 1;
+
+    fn sidecar(chain: u64) -> VerificationSidecar {
+        let secret_key = AngstromSigner::random();
+        VerificationSidecar {
+            status: StatusState {
+                version: StromVersion::LATEST.into(),
+                chain,
+                peer: secret_key.id(),
+                timestamp: 0
+            },
+            has_sent: false,
+            has_received: false,
+            secret_key
+        }
+    }
+
+    #[test]
+    fn test_rejects_peer_from_another_chain() {
+        let mut ours = sidecar(MAINNET);
+        let mut same_chain = sidecar(MAINNET);
+        let mut other_chain = sidecar(MAINNET + 1);
+
+        let status = same_chain.make_status_message(ours.secret_key.id());
+        assert_eq!(
+            ours.verify_status(same_chain.secret_key.id(), status),
+            Some(StromVersion::LATEST)
+        );
+
+        let status = other_chain.make_status_message(ours.secret_key.id());
+        assert_eq!(ours.verify_status(other_chain.secret_key.id(), status), None);
+
+        // the check is symmetric
+        let status = ours.make_status_message(other_chain.secret_key.id());
+        assert_eq!(other_chain.verify_status(ours.secret_key.id(), status), None);
+    }
+}
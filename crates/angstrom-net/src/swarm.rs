@@ -63,6 +63,11 @@ impl<DB: Unpin> Swarm<DB> {
             SessionEvent::SessionEstablished { peer_id, .. } => {
                 Some(SwarmEvent::SessionEstablished { peer_id })
             }
+            SessionEvent::Congested { peer_id } => Some(SwarmEvent::SessionCongested { peer_id }),
+            SessionEvent::Drained { peer_id } => Some(SwarmEvent::SessionDrained { peer_id }),
+            SessionEvent::Unsent { peer_id, message } => {
+                Some(SwarmEvent::Unsent { peer_id, msg: message })
+            }
             _ => None
         }
     }
@@ -96,5 +101,8 @@ impl<DB: Unpin> Stream for Swarm<DB> {
 pub enum SwarmEvent {
     SessionEstablished { peer_id: PeerId },
     ValidMessage { peer_id: PeerId, msg: StromMessage },
-    Disconnected { peer_id: PeerId }
+    Disconnected { peer_id: PeerId },
+    SessionCongested { peer_id: PeerId },
+    SessionDrained { peer_id: PeerId },
+    Unsent { peer_id: PeerId, msg: StromMessage }
 }
@@ -1,17 +1,23 @@
 #![allow(missing_docs)]
 use std::{fmt::Debug, sync::Arc};
 
-use alloy::rlp::{Buf, BufMut, Decodable, Encodable};
+use alloy::{
+    primitives::B256,
+    rlp::{Buf, BufMut, Decodable, Encodable}
+};
 use angstrom_types::{
-    consensus::{PreProposal, PreProposalAggregation, Proposal},
+    consensus::{PreProposal, PreProposalAggregation, Proposal, ProposalTimeout},
     orders::CancelOrderRequest,
+    primitive::PoolId,
     sol_bindings::grouped_orders::AllOrders
 };
+use bincode::Options;
 use reth_eth_wire::{protocol::Protocol, Capability};
 use reth_network_p2p::error::RequestError;
+use reth_primitives::GotExpected;
 use serde::{Deserialize, Serialize};
 
-use crate::errors::StromStreamError;
+use crate::{errors::StromStreamError, StromVersion};
 /// Result alias for result of a request.
 pub type RequestResult<T> = Result<T, RequestError>;
 use crate::Status;
@@ -21,19 +27,26 @@ use crate::Status;
 pub const MAX_MESSAGE_SIZE: usize = 10 * 1024 * 1024;
 
 const STROM_CAPABILITY: Capability = Capability::new_static("strom", 1);
-const STROM_PROTOCOL: Protocol = Protocol::new(STROM_CAPABILITY, 5);
+const STROM_PROTOCOL: Protocol = Protocol::new(STROM_CAPABILITY, 11);
 /// Represents message IDs for eth protocol messages.
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum StromMessageID {
-    Status            = 0,
+    Status               = 0,
     /// Consensus
-    PrePropose        = 1,
-    PreProposeAgg     = 2,
-    Propose           = 3,
+    PrePropose           = 1,
+    PreProposeAgg        = 2,
+    Propose              = 3,
     /// Propagation messages that broadcast new orders to all peers
     PropagatePooledOrders = 4,
-    OrderCancellation = 5
+    OrderCancellation    = 5,
+    /// Order pool sync with newly connected peers
+    GetPooledOrderHashes = 6,
+    PooledOrderHashes    = 7,
+    GetPooledOrders      = 8,
+    PooledOrders         = 9,
+    /// Consensus vote to move past a leader that didn't deliver
+    ProposalTimeout      = 10
 }
 
 impl Encodable for StromMessageID {
@@ -53,9 +66,14 @@ impl Decodable for StromMessageID {
             0 => StromMessageID::Status,
             1 => StromMessageID::PrePropose,
             2 => StromMessageID::PreProposeAgg,
-            3 => StromMessageID::PrePropose,
+            3 => StromMessageID::Propose,
             4 => StromMessageID::PropagatePooledOrders,
             5 => StromMessageID::OrderCancellation,
+            6 => StromMessageID::GetPooledOrderHashes,
+            7 => StromMessageID::PooledOrderHashes,
+            8 => StromMessageID::GetPooledOrders,
+            9 => StromMessageID::PooledOrders,
+            10 => StromMessageID::ProposalTimeout,
             _ => return Err(alloy::rlp::Error::Custom("Invalid message ID"))
         };
         buf.advance(1);
@@ -71,23 +89,91 @@ pub struct StromProtocolMessage {
 }
 
 impl StromProtocolMessage {
-    pub fn decode_message(buf: &mut &[u8]) -> Result<Self, StromStreamError> {
+    /// Decodes a message that was encoded with the given protocol version
+    pub fn decode_message(
+        version: StromVersion,
+        buf: &mut &[u8]
+    ) -> Result<Self, StromStreamError> {
         let message_id: StromMessageID = Decodable::decode(buf)?;
+        Self::check_supported(message_id, version)?;
         let data: Vec<u8> = Decodable::decode(buf)?;
-        let message: StromMessage = bincode::deserialize(&data).unwrap();
+        if data.len() > MAX_MESSAGE_SIZE {
+            return Err(StromStreamError::MessageTooBig(data.len()))
+        }
+
+        let message: StromMessage = match version {
+            StromVersion::Strom0 => bincode::deserialize(&data)?,
+            StromVersion::Strom1 | StromVersion::Strom2 => {
+                let (&got, payload) = data
+                    .split_first()
+                    .ok_or(StromStreamError::InvalidMessageError)?;
+                if got != u8::from(version) {
+                    return Err(StromStreamError::MismatchedVersion(GotExpected {
+                        got,
+                        expected: version.into()
+                    }))
+                }
+                versioned_payload_options().deserialize(payload)?
+            }
+        };
+
+        // the id is redundant with the payload, so the two have to agree
+        if message.message_id() != message_id {
+            return Err(StromStreamError::InvalidMessageError)
+        }
 
         Ok(StromProtocolMessage { message_id, message })
     }
-}
 
-impl Encodable for StromProtocolMessage {
-    fn encode(&self, out: &mut dyn BufMut) {
+    /// Encodes the message with the given protocol version. Fails without
+    /// writing anything if the message is bigger than our peers would accept
+    pub fn encode_versioned(
+        &self,
+        version: StromVersion,
+        out: &mut dyn BufMut
+    ) -> Result<(), StromStreamError> {
+        Self::check_supported(self.message_id, version)?;
+        let buf = match version {
+            StromVersion::Strom0 => bincode::serialize(&self.message)?,
+            StromVersion::Strom1 | StromVersion::Strom2 => {
+                let mut buf = vec![version.into()];
+                versioned_payload_options().serialize_into(&mut buf, &self.message)?;
+                buf
+            }
+        };
+        if buf.len() > MAX_MESSAGE_SIZE {
+            return Err(StromStreamError::MessageTooBig(buf.len()))
+        }
+
         Encodable::encode(&self.message_id, out);
-        let buf = bincode::serialize(&self.message).unwrap();
         Encodable::encode(&buf, out);
+
+        Ok(())
+    }
+
+    fn check_supported(
+        message_id: StromMessageID,
+        version: StromVersion
+    ) -> Result<(), StromStreamError> {
+        if message_id as u8 >= version.total_messages() {
+            return Err(StromStreamError::UnsupportedMessage {
+                id:      message_id as u8,
+                version: version.into()
+            })
+        }
+
+        Ok(())
     }
 }
 
+/// Same layout as [`bincode::serialize`], but bounded by [`MAX_MESSAGE_SIZE`]
+/// so that a malicious length prefix can't make us allocate unbounded memory
+fn versioned_payload_options() -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_limit(MAX_MESSAGE_SIZE as u64)
+}
+
 impl StromProtocolMessage {
     /// Returns the protocol for the `Strom` protocol.
     pub const fn protocol() -> Protocol {
@@ -121,7 +207,17 @@ pub enum StromMessage {
 
     /// Propagation messages that broadcast new orders to all peers
     PropagatePooledOrders(Vec<AllOrders>),
-    OrderCancellation(CancelOrderRequest)
+    OrderCancellation(CancelOrderRequest),
+
+    /// Order pool sync. Requests the hashes of all orders in the given pools
+    GetPooledOrderHashes(Vec<PoolId>),
+    PooledOrderHashes(Vec<B256>),
+    /// Requests the full orders for the given hashes
+    GetPooledOrders(Vec<B256>),
+    PooledOrders(Vec<AllOrders>),
+
+    /// Vote to hand the round to the next backup leader
+    ProposalTimeout(ProposalTimeout)
 }
 impl StromMessage {
     /// Returns the message's ID.
@@ -132,7 +228,12 @@ impl StromMessage {
             StromMessage::PreProposeAgg(_) => StromMessageID::PreProposeAgg,
             StromMessage::Propose(_) => StromMessageID::Propose,
             StromMessage::PropagatePooledOrders(_) => StromMessageID::PropagatePooledOrders,
-            StromMessage::OrderCancellation(_) => StromMessageID::OrderCancellation
+            StromMessage::OrderCancellation(_) => StromMessageID::OrderCancellation,
+            StromMessage::GetPooledOrderHashes(_) => StromMessageID::GetPooledOrderHashes,
+            StromMessage::PooledOrderHashes(_) => StromMessageID::PooledOrderHashes,
+            StromMessage::GetPooledOrders(_) => StromMessageID::GetPooledOrders,
+            StromMessage::PooledOrders(_) => StromMessageID::PooledOrders,
+            StromMessage::ProposalTimeout(_) => StromMessageID::ProposalTimeout
         }
     }
 }
@@ -170,3 +271,211 @@ impl StromBroadcastMessage {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, PrimitiveSignature};
+    use angstrom_types::{primitive::AngstromSigner, sol_bindings::grouped_orders::AllOrders};
+    use proptest::prelude::*;
+    use testing_tools::type_generator::{
+        consensus::{
+            pre_proposal_agg::PreProposalAggregationBuilder, preproposal::PreproposalBuilder,
+            proposal::ProposalBuilder
+        },
+        orders::UserOrderBuilder
+    };
+
+    use super::*;
+    use crate::StatusBuilder;
+
+    const VERSIONS: [StromVersion; 3] =
+        [StromVersion::Strom0, StromVersion::Strom1, StromVersion::Strom2];
+
+    fn orders() -> Vec<AllOrders> {
+        vec![
+            UserOrderBuilder::new()
+                .standing()
+                .amount(100)
+                .build()
+                .into(),
+            UserOrderBuilder::new()
+                .kill_or_fill()
+                .amount(200)
+                .build()
+                .into(),
+        ]
+    }
+
+    /// one message of every variant
+    fn all_messages() -> Vec<StromMessage> {
+        let sk = AngstromSigner::random();
+        let status = StatusBuilder::new(sk.id()).build(&sk);
+
+        vec![
+            StromMessage::Status(status),
+            StromMessage::PrePropose(
+                PreproposalBuilder::new()
+                    .order_count(4)
+                    .for_random_pools(1)
+                    .build()
+            ),
+            StromMessage::PreProposeAgg(
+                PreProposalAggregationBuilder::new()
+                    .order_count(4)
+                    .for_random_pools(1)
+                    .build()
+            ),
+            StromMessage::Propose(
+                ProposalBuilder::new()
+                    .order_count(4)
+                    .preproposal_count(1)
+                    .for_random_pools(1)
+                    .build()
+            ),
+            StromMessage::PropagatePooledOrders(orders()),
+            StromMessage::OrderCancellation(CancelOrderRequest {
+                signature:    PrimitiveSignature::test_signature(),
+                user_address: sk.address(),
+                order_id:     B256::random()
+            }),
+            StromMessage::GetPooledOrderHashes(vec![PoolId::random()]),
+            StromMessage::PooledOrderHashes(vec![B256::random(), B256::random()]),
+            StromMessage::GetPooledOrders(vec![B256::random()]),
+            StromMessage::PooledOrders(orders()),
+            StromMessage::ProposalTimeout(ProposalTimeout::new(
+                1,
+                sk.address(),
+                1,
+                Address::ZERO,
+                &sk
+            )),
+        ]
+    }
+
+    fn encode(message: StromMessage, version: StromVersion) -> Vec<u8> {
+        let message = StromProtocolMessage { message_id: message.message_id(), message };
+        let mut buf = Vec::new();
+        message.encode_versioned(version, &mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_every_message_round_trips() {
+        let messages = all_messages();
+        assert_eq!(messages.len(), StromVersion::LATEST.total_messages() as usize);
+
+        for version in VERSIONS {
+            for message in messages.clone() {
+                if message.message_id() as u8 >= version.total_messages() {
+                    continue
+                }
+                let buf = encode(message.clone(), version);
+                let decoded = StromProtocolMessage::decode_message(version, &mut buf.as_slice())
+                    .unwrap_or_else(|e| panic!("{:?} failed to decode: {e}", message.message_id()));
+
+                assert_eq!(decoded.message_id, message.message_id());
+                assert_eq!(decoded.message, message);
+            }
+        }
+    }
+
+    #[test]
+    fn test_message_ids_round_trip() {
+        for message in all_messages() {
+            let mut buf = Vec::new();
+            message.message_id().encode(&mut buf);
+            assert_eq!(StromMessageID::decode(&mut buf.as_slice()).unwrap(), message.message_id());
+        }
+        assert!(StromMessageID::decode(&mut [11u8].as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_wrong_version() {
+        let message = StromMessage::GetPooledOrders(vec![B256::random()]);
+
+        let buf = encode(message.clone(), StromVersion::Strom0);
+        assert!(StromProtocolMessage::decode_message(StromVersion::Strom1, &mut buf.as_slice())
+            .is_err());
+
+        let buf = encode(message, StromVersion::Strom1);
+        assert!(StromProtocolMessage::decode_message(StromVersion::Strom0, &mut buf.as_slice())
+            .is_err());
+    }
+
+    #[test]
+    fn test_newer_messages_need_newer_version() {
+        let sk = AngstromSigner::random();
+        let message = StromMessage::ProposalTimeout(ProposalTimeout::new(
+            1,
+            sk.address(),
+            1,
+            Address::ZERO,
+            &sk
+        ));
+        let message = StromProtocolMessage { message_id: message.message_id(), message };
+
+        let mut buf = Vec::new();
+        assert!(matches!(
+            message.encode_versioned(StromVersion::Strom1, &mut buf),
+            Err(StromStreamError::UnsupportedMessage { id: 10, version: 1 })
+        ));
+        assert!(buf.is_empty());
+
+        message
+            .encode_versioned(StromVersion::Strom2, &mut buf)
+            .unwrap();
+        assert!(StromProtocolMessage::decode_message(StromVersion::Strom1, &mut buf.as_slice())
+            .is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_mismatched_id() {
+        let message = StromMessage::GetPooledOrders(vec![B256::random()]);
+        let message = StromProtocolMessage { message_id: StromMessageID::PooledOrders, message };
+        let mut buf = Vec::new();
+        message
+            .encode_versioned(StromVersion::LATEST, &mut buf)
+            .unwrap();
+
+        assert!(matches!(
+            StromProtocolMessage::decode_message(StromVersion::LATEST, &mut buf.as_slice()),
+            Err(StromStreamError::InvalidMessageError)
+        ));
+    }
+
+    #[test]
+    fn test_oversized_messages_are_not_encoded() {
+        let hashes = vec![B256::ZERO; MAX_MESSAGE_SIZE / 32 + 1];
+        for version in VERSIONS {
+            let message = StromMessage::PooledOrderHashes(hashes.clone());
+            let message = StromProtocolMessage { message_id: message.message_id(), message };
+            let mut buf = Vec::new();
+
+            assert!(message.encode_versioned(version, &mut buf).is_err());
+            assert!(buf.is_empty());
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn decoding_arbitrary_bytes_never_panics(
+            bytes in proptest::collection::vec(any::<u8>(), 0..512)
+        ) {
+            for version in VERSIONS {
+                let _ = StromProtocolMessage::decode_message(version, &mut bytes.as_slice());
+            }
+        }
+
+        #[test]
+        fn truncated_messages_fail_to_decode(len in 0usize..64, seed in any::<u8>()) {
+            let hashes = (0..4).map(|i| B256::repeat_byte(seed.wrapping_add(i))).collect();
+            for version in VERSIONS {
+                let buf = encode(StromMessage::PooledOrderHashes(hashes.clone()), version);
+                let len = len.min(buf.len() - 1);
+                prop_assert!(
+                    StromProtocolMessage::decode_message(version, &mut &buf[..len]).is_err()
+                );
+            }
+        }
+    }
+}
@@ -13,17 +13,35 @@ pub struct ParseVersionError(String);
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum StromVersion {
-    /// The `strom` protocol version 0
-    Strom0 = 0
+    /// The `strom` protocol version 0. Unversioned bincode payloads
+    Strom0 = 0,
+    /// The `strom` protocol version 1. Payloads are prefixed with the version
+    /// and decoded with a size limit
+    Strom1 = 1,
+    /// The `strom` protocol version 2. Same encoding as version 1, adds
+    /// proposal timeouts
+    Strom2 = 2
 }
 
 impl StromVersion {
+    /// The status handshake is always encoded with this version, as the
+    /// session version is only known once both status messages are exchanged
+    pub const HANDSHAKE: StromVersion = StromVersion::Strom0;
     /// The latest known eth version
-    pub const LATEST: StromVersion = StromVersion::Strom0;
+    pub const LATEST: StromVersion = StromVersion::Strom2;
 
     /// Returns the total number of messages the protocol version supports.
     pub const fn total_messages(&self) -> u8 {
-        7
+        match self {
+            StromVersion::Strom0 | StromVersion::Strom1 => 10,
+            StromVersion::Strom2 => 11
+        }
+    }
+
+    /// The newest version that both sides of a session support, given the
+    /// versions in the status messages
+    pub fn negotiate(ours: u8, theirs: u8) -> Option<StromVersion> {
+        StromVersion::try_from(ours.min(theirs)).ok()
     }
 }
 
@@ -35,6 +53,8 @@ impl TryFrom<&str> for StromVersion {
     fn try_from(s: &str) -> Result<Self, Self::Error> {
         match s {
             "0" => Ok(StromVersion::Strom0),
+            "1" => Ok(StromVersion::Strom1),
+            "2" => Ok(StromVersion::Strom2),
             _ => Err(ParseVersionError(s.to_string()))
         }
     }
@@ -48,6 +68,8 @@ impl TryFrom<u8> for StromVersion {
     fn try_from(u: u8) -> Result<Self, Self::Error> {
         match u {
             0 => Ok(StromVersion::Strom0),
+            1 => Ok(StromVersion::Strom1),
+            2 => Ok(StromVersion::Strom2),
             _ => Err(ParseVersionError(u.to_string()))
         }
     }
@@ -73,7 +95,9 @@ impl From<StromVersion> for &'static str {
     #[inline]
     fn from(v: StromVersion) -> &'static str {
         match v {
-            StromVersion::Strom0 => "0"
+            StromVersion::Strom0 => "0",
+            StromVersion::Strom1 => "1",
+            StromVersion::Strom2 => "2"
         }
     }
 }
@@ -93,6 +117,18 @@ mod test {
     #[test]
     fn test_eth_version_from_str() {
         assert_eq!(StromVersion::Strom0, "0".parse().unwrap());
+        assert_eq!(StromVersion::Strom1, "1".parse().unwrap());
+        assert_eq!(StromVersion::Strom2, "2".parse().unwrap());
         assert_eq!(Err(ParseVersionError("69".to_string())), "69".parse::<StromVersion>());
     }
+
+    #[test]
+    fn test_version_negotiation() {
+        assert_eq!(StromVersion::negotiate(2, 2), Some(StromVersion::Strom2));
+        assert_eq!(StromVersion::negotiate(2, 1), Some(StromVersion::Strom1));
+        assert_eq!(StromVersion::negotiate(1, 0), Some(StromVersion::Strom0));
+        // a newer peer falls back to our version
+        assert_eq!(StromVersion::negotiate(2, 69), Some(StromVersion::Strom2));
+        assert_eq!(StromVersion::negotiate(69, 70), None);
+    }
 }
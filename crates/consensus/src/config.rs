@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+/// How long we wait for the round leader after starting pre-proposal
+/// aggregation before voting to hand the round to a backup
+pub const DEFAULT_PROPOSAL_DEADLINE: Duration = Duration::from_millis(500);
+/// How soon we send our pre-proposal
+pub const DEFAULT_PRE_PROPOSAL_WAIT: Duration = Duration::from_secs(9);
+/// How close we want to be to the creation of the ethereum block
+pub const DEFAULT_TARGET_SUBMISSION_TIME_REM: Duration = Duration::from_millis(800);
+/// Eth block time, used unless the chain we run on says otherwise
+pub const ETH_BLOCK_TIME: Duration = Duration::from_secs(12);
+
+/// The timings that drive a consensus round
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConsensusTimingConfig {
+    /// the base time after a new block that we wait before sending our
+    /// pre-proposal. This gets scaled down by the amount of orders we hold
+    pub pre_proposal_wait:          Duration,
+    /// how long before the next block we want our bundle to be submitted
+    pub target_submission_time_rem: Duration,
+    /// how long we wait on a silent round leader before voting to time it out
+    pub proposal_deadline:          Duration,
+    /// how long a block on the chain we run on takes
+    pub block_time:                 Duration
+}
+
+impl Default for ConsensusTimingConfig {
+    fn default() -> Self {
+        Self {
+            pre_proposal_wait:          DEFAULT_PRE_PROPOSAL_WAIT,
+            target_submission_time_rem: DEFAULT_TARGET_SUBMISSION_TIME_REM,
+            proposal_deadline:          DEFAULT_PROPOSAL_DEADLINE,
+            block_time:                 ETH_BLOCK_TIME
+        }
+    }
+}
+
+impl ConsensusTimingConfig {
+    /// checks that a round still fits inside of a single block
+    pub fn validate(&self) -> eyre::Result<()> {
+        if self.target_submission_time_rem >= self.block_time {
+            eyre::bail!(
+                "target submission time remainder {:?} must be less than the block time {:?}",
+                self.target_submission_time_rem,
+                self.block_time
+            );
+        }
+
+        if self.pre_proposal_wait.is_zero() || self.proposal_deadline.is_zero() {
+            eyre::bail!(
+                "pre-proposal wait {:?} and proposal deadline {:?} must be non zero",
+                self.pre_proposal_wait,
+                self.proposal_deadline
+            );
+        }
+
+        // a backup leader only takes over once the deadline passed, it still has to
+        // make the submission cutoff
+        let submission_cutoff = self.block_time - self.target_submission_time_rem;
+        if self.pre_proposal_wait + self.proposal_deadline >= submission_cutoff {
+            eyre::bail!(
+                "pre-proposal wait {:?} plus proposal deadline {:?} must be less than {:?}",
+                self.pre_proposal_wait,
+                self.proposal_deadline,
+                submission_cutoff
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timing_validation() {
+        assert!(ConsensusTimingConfig::default().validate().is_ok());
+
+        let config = ConsensusTimingConfig {
+            pre_proposal_wait: Duration::from_secs(11),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = ConsensusTimingConfig {
+            target_submission_time_rem: ETH_BLOCK_TIME,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config =
+            ConsensusTimingConfig { proposal_deadline: Duration::ZERO, ..Default::default() };
+        assert!(config.validate().is_err());
+
+        // each fits on its own, but the backup leader would miss the cutoff
+        let config = ConsensusTimingConfig {
+            pre_proposal_wait: Duration::from_millis(10_900),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        // the default waits don't fit into the blocks of a fast chain
+        let config =
+            ConsensusTimingConfig { block_time: Duration::from_secs(2), ..Default::default() };
+        assert!(config.validate().is_err());
+
+        let config = ConsensusTimingConfig {
+            block_time:                 Duration::from_secs(2),
+            pre_proposal_wait:          Duration::from_millis(900),
+            target_submission_time_rem: Duration::from_millis(300),
+            proposal_deadline:          Duration::from_millis(200)
+        };
+        assert!(config.validate().is_ok());
+    }
+}
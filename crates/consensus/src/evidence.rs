@@ -0,0 +1,114 @@
+use std::{
+    fs::OpenOptions,
+    io::{BufRead, BufReader, Write},
+    path::PathBuf
+};
+
+use angstrom_types::consensus::Evidence;
+
+/// Holds all of the misbehaviour evidence we have collected. If a path is set,
+/// every new piece of evidence is appended to it as a json line so that it
+/// survives restarts and can be picked up by a slashing submitter.
+#[derive(Debug, Default)]
+pub struct EvidenceStore {
+    path:     Option<PathBuf>,
+    evidence: Vec<Evidence>
+}
+
+impl EvidenceStore {
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// loads all of the evidence that has already been written to the given
+    /// path, creating the file if it doesn't exist
+    pub fn load(path: PathBuf) -> eyre::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .read(true)
+            .open(&path)?;
+
+        let evidence = BufReader::new(file)
+            .lines()
+            .map(|line| Ok(serde_json::from_str(&line?)?))
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        Ok(Self { path: Some(path), evidence })
+    }
+
+    /// returns false if we already had this evidence
+    pub fn insert(&mut self, evidence: Evidence) -> bool {
+        if self.evidence.contains(&evidence) {
+            return false
+        }
+
+        tracing::warn!(
+            offender=?evidence.offender(),
+            block=evidence.block_height(),
+            "collected misbehaviour evidence"
+        );
+
+        if let Some(path) = self.path.as_ref() {
+            if let Err(e) = Self::append(path, &evidence) {
+                tracing::error!(%e, ?path, "failed to persist evidence");
+            }
+        }
+        self.evidence.push(evidence);
+
+        true
+    }
+
+    pub fn all(&self) -> &[Evidence] {
+        &self.evidence
+    }
+
+    fn append(path: &PathBuf, evidence: &Evidence) -> eyre::Result<()> {
+        let mut file = OpenOptions::new().append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(evidence)?)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::B256;
+    use angstrom_types::{
+        consensus::{DuplicateVoteEvidence, PreProposal},
+        primitive::AngstromSigner,
+        sol_bindings::grouped_orders::OrderWithStorageData
+    };
+
+    use super::*;
+
+    fn duplicate_pre_proposal() -> Evidence {
+        let signer = AngstromSigner::random();
+        let vote_a = PreProposal::generate_pre_proposal(1, &signer, vec![], vec![]);
+        let vote_b = PreProposal::generate_pre_proposal(
+            1,
+            &signer,
+            vec![OrderWithStorageData::default()],
+            vec![]
+        );
+
+        DuplicateVoteEvidence::new(vote_a, vote_b, 300, 100)
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn test_evidence_survives_restart() {
+        let path = std::env::temp_dir().join(format!("evidence-{}.jsonl", B256::random()));
+        let evidence = duplicate_pre_proposal();
+
+        let mut store = EvidenceStore::load(path.clone()).unwrap();
+        assert!(store.insert(evidence.clone()));
+        assert!(!store.insert(evidence.clone()));
+
+        let store = EvidenceStore::load(path.clone()).unwrap();
+        assert_eq!(store.all(), &[evidence]);
+
+        let _ = std::fs::remove_file(path);
+    }
+}
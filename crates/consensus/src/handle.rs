@@ -0,0 +1,94 @@
+use alloy::primitives::{Address, BlockNumber};
+use angstrom_types::{consensus::Evidence, matching::match_estimate_response::BundleEstimate};
+use futures::{Future, FutureExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_stream::wrappers::BroadcastStream;
+
+use crate::rounds::ConsensusMessage;
+
+/// Api to interact with the [`ConsensusManager`](crate::ConsensusManager) from
+/// other modules.
+pub trait ConsensusHandle: Send + Sync + Clone + Unpin + 'static {
+    /// all valid pre-proposals, aggregations and proposals the round state
+    /// machine sees
+    fn subscribe_consensus_events(&self) -> BroadcastStream<ConsensusMessage>;
+
+    fn fetch_round_state(&self) -> impl Future<Output = Option<ConsensusRoundInfo>> + Send;
+
+    /// all evidence of validator misbehaviour that has been collected
+    fn fetch_evidence(&self) -> impl Future<Output = Option<Vec<Evidence>>> + Send;
+
+    /// the fills and gas usage of the bundle that would be built from the
+    /// orders that are currently pending. None if the bundle couldn't be
+    /// simulated
+    fn fetch_fill_estimate(
+        &self
+    ) -> impl Future<Output = Option<(BlockNumber, BundleEstimate)>> + Send;
+}
+
+pub enum ConsensusCommand {
+    RoundState(tokio::sync::oneshot::Sender<ConsensusRoundInfo>),
+    Evidence(tokio::sync::oneshot::Sender<Vec<Evidence>>),
+    FillEstimate(tokio::sync::oneshot::Sender<Option<(BlockNumber, BundleEstimate)>>)
+}
+
+#[derive(Debug, Clone)]
+pub struct ConsensusClient {
+    pub sender:    UnboundedSender<ConsensusCommand>,
+    pub events_tx: tokio::sync::broadcast::Sender<ConsensusMessage>
+}
+
+impl ConsensusHandle for ConsensusClient {
+    fn subscribe_consensus_events(&self) -> BroadcastStream<ConsensusMessage> {
+        BroadcastStream::new(self.events_tx.subscribe())
+    }
+
+    fn fetch_round_state(&self) -> impl Future<Output = Option<ConsensusRoundInfo>> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let _ = self.sender.send(ConsensusCommand::RoundState(tx));
+        rx.map(Result::ok)
+    }
+
+    fn fetch_evidence(&self) -> impl Future<Output = Option<Vec<Evidence>>> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let _ = self.sender.send(ConsensusCommand::Evidence(tx));
+        rx.map(Result::ok)
+    }
+
+    fn fetch_fill_estimate(
+        &self
+    ) -> impl Future<Output = Option<(BlockNumber, BundleEstimate)>> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let _ = self.sender.send(ConsensusCommand::FillEstimate(tx));
+        rx.map(|res| res.ok().flatten())
+    }
+}
+
+/// The step of the round state machine we are currently in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConsensusRoundName {
+    BidAggregation,
+    PreProposal,
+    PreProposalAggregation,
+    Proposal,
+    Finalization
+}
+
+/// Snapshot of the current consensus round.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsensusRoundInfo {
+    pub block_height: BlockNumber,
+    pub leader: Address,
+    pub is_leader: bool,
+    pub state: ConsensusRoundName,
+    pub pre_proposals_seen: usize,
+    pub pre_proposal_aggregations_seen: usize,
+    /// every leader still benched at `block_height`, i.e. a quorum agreed it
+    /// missed a round within the last 32 blocks (not only this round), ordered
+    /// by the block it missed. They are skipped when they would lead until the
+    /// cooldown ends
+    pub missed_leaders: Vec<Address>
+}
@@ -1,28 +1,30 @@
 use std::{cmp::Ordering, collections::HashSet};
 
-use alloy::primitives::BlockNumber;
-use angstrom_types::primitive::PeerId;
+use alloy::primitives::{Address, BlockNumber};
 
 // https://github.com/tendermint/tendermint/pull/2785#discussion_r235038971
 // 1.125
 const PENALTY_FACTOR: u64 = 1125;
 /// do the math with fixed here to avoid floats
 const ONE_E3: u64 = 1000;
+/// the node set in the controller doesn't carry any stake, so every node has
+/// the same voting power
+pub const DEFAULT_VOTING_POWER: u64 = 100;
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct AngstromValidator {
-    pub peer_id:  PeerId,
+    pub address:  Address,
     voting_power: u64,
     priority:     i64
 }
 
 impl AngstromValidator {
-    pub fn new(name: PeerId, voting_power: u64) -> Self {
-        AngstromValidator {
-            peer_id:      name,
-            voting_power: voting_power * ONE_E3,
-            priority:     0
-        }
+    pub fn new(address: Address, voting_power: u64) -> Self {
+        AngstromValidator { address, voting_power: voting_power * ONE_E3, priority: 0 }
+    }
+
+    pub fn voting_power(&self) -> u64 {
+        self.voting_power
     }
 }
 
@@ -31,7 +33,7 @@ pub struct WeightedRoundRobin {
     validators:                HashSet<AngstromValidator>,
     new_joiner_penalty_factor: u64,
     block_number:              BlockNumber,
-    last_proposer:             Option<PeerId>
+    last_proposer:             Option<Address>
 }
 
 impl WeightedRoundRobin {
@@ -44,7 +46,7 @@ impl WeightedRoundRobin {
         }
     }
 
-    fn proposer_selection(&mut self) -> PeerId {
+    fn proposer_selection(&mut self) -> Address {
         let total_voting_power: u64 = self.validators.iter().map(|v| v.voting_power).sum();
 
         //  apply all priorities.
@@ -65,7 +67,7 @@ impl WeightedRoundRobin {
             .unwrap()
             .clone();
         proposer.priority -= total_voting_power as i64;
-        let proposer_name = proposer.peer_id;
+        let proposer_name = proposer.address;
 
         self.validators.replace(proposer);
 
@@ -75,10 +77,10 @@ impl WeightedRoundRobin {
     fn priority(a: &&AngstromValidator, b: &&AngstromValidator) -> Ordering {
         let out = a.priority.partial_cmp(&b.priority);
         if out == Some(Ordering::Equal) {
-            // TODO: not the best because it encourages mining lower peer ids
+            // TODO: not the best because it encourages mining lower addresses
             // however we need a way for this to be uniform across nodes and
             // this is the easiest
-            return a.peer_id.cmp(&b.peer_id)
+            return a.address.cmp(&b.address)
         }
         out.unwrap()
     }
@@ -128,7 +130,7 @@ impl WeightedRoundRobin {
         }
     }
 
-    pub fn choose_proposer(&mut self, block_number: BlockNumber) -> Option<PeerId> {
+    pub fn choose_proposer(&mut self, block_number: BlockNumber) -> Option<Address> {
         // 1. this is not ideal, since on multi-block reorgs the same proposer will be
         //    chosen for the length of the reorg
         // 2. reverting the block number (self.block_number = block_number) is also not
@@ -154,15 +156,42 @@ impl WeightedRoundRobin {
         leader
     }
 
-    #[allow(dead_code)]
-    fn remove_validator(&mut self, peer_id: &PeerId) {
-        let validator = AngstromValidator::new(*peer_id, 0);
+    /// the order in which the other validators take over if the current
+    /// proposer is silent. This is the order the next selection would pick them
+    /// in, without actually advancing the priorities
+    pub fn backup_proposers(&self) -> Vec<Address> {
+        let mut validators = self
+            .validators
+            .iter()
+            .filter(|v| Some(v.address) != self.last_proposer)
+            .cloned()
+            .map(|mut validator| {
+                validator.priority += validator.voting_power as i64;
+                validator
+            })
+            .collect::<Vec<_>>();
+        validators.sort_by(|a, b| Self::priority(&b, &a));
+
+        validators.into_iter().map(|v| v.address).collect()
+    }
+
+    pub fn validators(&self) -> Vec<AngstromValidator> {
+        self.validators.iter().cloned().collect()
+    }
+
+    pub fn remove_validator(&mut self, address: &Address) {
+        let validator = AngstromValidator::new(*address, 0);
         self.validators.remove(&validator);
     }
 
-    #[allow(dead_code)]
-    fn add_validator(&mut self, peer_id: PeerId, voting_power: u64) {
-        let mut new_validator = AngstromValidator::new(peer_id, voting_power);
+    /// adds the validator with a penalty to its priority so that it can't
+    /// instantly become the leader. Is a noop if the validator already exists
+    pub fn add_validator(&mut self, address: Address, voting_power: u64) {
+        if self.validators.iter().any(|v| v.address == address) {
+            return
+        }
+
+        let mut new_validator = AngstromValidator::new(address, voting_power);
         let total_voting_power: u64 = self.validators.iter().map(|v| v.voting_power).sum();
         new_validator.priority -=
             ((self.new_joiner_penalty_factor * total_voting_power) / ONE_E3) as i64;
@@ -172,7 +201,7 @@ impl WeightedRoundRobin {
 
 impl PartialEq for AngstromValidator {
     fn eq(&self, other: &Self) -> bool {
-        self.peer_id == other.peer_id
+        self.address == other.address
     }
 }
 
@@ -180,7 +209,7 @@ impl Eq for AngstromValidator {}
 
 impl std::hash::Hash for AngstromValidator {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.peer_id.hash(state);
+        self.address.hash(state);
     }
 }
 
@@ -188,19 +217,19 @@ impl std::hash::Hash for AngstromValidator {
 mod tests {
     use std::collections::HashMap;
 
-    use alloy::primitives::BlockNumber;
+    use alloy::primitives::{Address, BlockNumber};
 
     use super::*;
 
     #[test]
     fn test_validator_equality() {
-        let peer_id = PeerId::random();
-        let v1 = AngstromValidator::new(peer_id, 100);
-        let v2 = AngstromValidator::new(peer_id, 200);
-        let v3 = AngstromValidator::new(PeerId::random(), 100);
+        let address = Address::random();
+        let v1 = AngstromValidator::new(address, 100);
+        let v2 = AngstromValidator::new(address, 200);
+        let v3 = AngstromValidator::new(Address::random(), 100);
 
-        assert_eq!(v1, v2, "Validators with same peer_id should be equal");
-        assert_ne!(v1, v3, "Validators with different peer_id should not be equal");
+        assert_eq!(v1, v2, "Validators with same address should be equal");
+        assert_ne!(v1, v3, "Validators with different address should not be equal");
     }
 
     #[test]
@@ -210,16 +239,16 @@ mod tests {
             hash::{Hash, Hasher}
         };
 
-        let peer_id = PeerId::random();
-        let v1 = AngstromValidator::new(peer_id, 100);
-        let v2 = AngstromValidator::new(peer_id, 200);
+        let address = Address::random();
+        let v1 = AngstromValidator::new(address, 100);
+        let v2 = AngstromValidator::new(address, 200);
 
         let mut hasher1 = DefaultHasher::new();
         let mut hasher2 = DefaultHasher::new();
         v1.hash(&mut hasher1);
         v2.hash(&mut hasher2);
 
-        assert_eq!(hasher1.finish(), hasher2.finish(), "Hash should only depend on peer_id");
+        assert_eq!(hasher1.finish(), hasher2.finish(), "Hash should only depend on address");
     }
 
     #[test]
@@ -228,44 +257,48 @@ mod tests {
         let mut algo = WeightedRoundRobin::new(validators, BlockNumber::default());
 
         // Test adding new validator
-        let new_peer = PeerId::random();
+        let new_peer = Address::random();
         let initial_count = algo.validators.len();
         algo.add_validator(new_peer, 150);
         assert_eq!(algo.validators.len(), initial_count + 1);
 
+        // Adding the same validator again shouldn't reset its priority
+        algo.add_validator(new_peer, 150);
+        assert_eq!(algo.validators.len(), initial_count + 1);
+
         // Verify penalty was applied
         let new_validator = algo
             .validators
             .iter()
-            .find(|v| v.peer_id == new_peer)
+            .find(|v| v.address == new_peer)
             .unwrap();
         assert!(new_validator.priority < 0, "New validator should have negative priority");
 
         // Test removing validator
         algo.remove_validator(&new_peer);
         assert_eq!(algo.validators.len(), initial_count);
-        assert!(algo.validators.iter().all(|v| v.peer_id != new_peer));
+        assert!(algo.validators.iter().all(|v| v.address != new_peer));
     }
 
     #[test]
     fn test_priority_comparison() {
-        let peer1 = PeerId::random();
-        let peer2 = PeerId::random();
+        let peer1 = Address::random();
+        let peer2 = Address::random();
 
         let v1 =
-            AngstromValidator { peer_id: peer1, voting_power: 100 * ONE_E3, priority: 10 };
+            AngstromValidator { address: peer1, voting_power: 100 * ONE_E3, priority: 10 };
 
         let v2 =
-            AngstromValidator { peer_id: peer2, voting_power: 100 * ONE_E3, priority: 10 };
+            AngstromValidator { address: peer2, voting_power: 100 * ONE_E3, priority: 10 };
 
         let v3 =
-            AngstromValidator { peer_id: peer2, voting_power: 100 * ONE_E3, priority: 20 };
+            AngstromValidator { address: peer2, voting_power: 100 * ONE_E3, priority: 20 };
 
         // Test equal priorities
         assert_eq!(
             WeightedRoundRobin::priority(&&v1, &&v2),
             peer1.cmp(&peer2),
-            "Equal priorities should compare peer_ids"
+            "Equal priorities should compare addresses"
         );
 
         // Test different priorities
@@ -294,18 +327,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_backup_proposers() {
+        let validators = (0..4)
+            .map(|_| AngstromValidator::new(Address::random(), DEFAULT_VOTING_POWER))
+            .collect::<Vec<_>>();
+        let mut algo = WeightedRoundRobin::new(validators, 5);
+
+        let leader = algo.choose_proposer(6).unwrap();
+        let backups = algo.backup_proposers();
+        assert_eq!(backups.len(), 3);
+        assert!(!backups.contains(&leader), "the leader can't be its own backup");
+
+        // looking up the backups shouldn't advance the rotation
+        assert_eq!(algo.choose_proposer(6).unwrap(), leader);
+        assert_eq!(algo.backup_proposers(), backups);
+
+        // with equal stake the first backup is who would have been next anyway
+        assert_eq!(algo.choose_proposer(7).unwrap(), backups[0]);
+    }
+
     #[test]
     fn test_voting_power_scaling() {
-        let peer_id = PeerId::random();
-        let validator = AngstromValidator::new(peer_id, 100);
+        let address = Address::random();
+        let validator = AngstromValidator::new(address, 100);
         assert_eq!(validator.voting_power, 100 * ONE_E3, "Voting power should be scaled by ONE_E3");
     }
 
-    fn create_test_validators() -> (HashMap<String, PeerId>, Vec<AngstromValidator>) {
+    fn create_test_validators() -> (HashMap<String, Address>, Vec<AngstromValidator>) {
         let peers = HashMap::from([
-            ("Alice".to_string(), PeerId::random()),
-            ("Bob".to_string(), PeerId::random()),
-            ("Charlie".to_string(), PeerId::random())
+            ("Alice".to_string(), Address::random()),
+            ("Bob".to_string(), Address::random()),
+            ("Charlie".to_string(), Address::random())
         ]);
         let validators = vec![
             AngstromValidator::new(peers["Alice"], 100),
@@ -447,9 +500,9 @@ mod tests {
     #[test]
     fn test_round_robin_simulation() {
         let peers = HashMap::from([
-            ("Alice".to_string(), PeerId::random()),
-            ("Bob".to_string(), PeerId::random()),
-            ("Charlie".to_string(), PeerId::random())
+            ("Alice".to_string(), Address::random()),
+            ("Bob".to_string(), Address::random()),
+            ("Charlie".to_string(), Address::random())
         ]);
         let validators = vec![
             AngstromValidator::new(peers["Alice"], 100),
@@ -458,7 +511,10 @@ mod tests {
         ];
         let mut algo = WeightedRoundRobin::new(validators, BlockNumber::default());
 
-        fn simulate_rounds(algo: &mut WeightedRoundRobin, rounds: usize) -> HashMap<PeerId, usize> {
+        fn simulate_rounds(
+            algo: &mut WeightedRoundRobin,
+            rounds: usize
+        ) -> HashMap<Address, usize> {
             let mut stats = HashMap::new();
             for i in 1..=rounds {
                 let proposer = algo.choose_proposer(BlockNumber::from(i as u64)).unwrap();
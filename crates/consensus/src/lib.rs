@@ -1,6 +1,13 @@
+mod config;
+mod evidence;
+mod handle;
 mod leader_selection;
 mod manager;
+mod missed_leaders;
 
+pub use config::*;
+pub use evidence::*;
+pub use handle::*;
 pub use manager::*;
 pub mod rounds;
 
@@ -8,7 +15,7 @@ use std::pin::Pin;
 
 use angstrom_types::consensus::{PreProposal, Proposal};
 use futures::Stream;
-pub use leader_selection::AngstromValidator;
+pub use leader_selection::{AngstromValidator, DEFAULT_VOTING_POWER};
 
 #[derive(Debug, Clone)]
 pub enum ConsensusMessage {
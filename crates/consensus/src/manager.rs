@@ -7,39 +7,62 @@ use std::{
 };
 
 use alloy::{
+    consensus::Transaction,
     primitives::{Address, BlockNumber},
     providers::Provider
 };
+use angstrom_eth::manager::EthEvent;
 use angstrom_metrics::ConsensusMetricsWrapper;
 use angstrom_network::{manager::StromConsensusEvent, StromMessage, StromNetworkHandle};
 use angstrom_types::{
-    block_sync::BlockSyncConsumer, contract_payloads::angstrom::UniswapAngstromRegistry,
-    mev_boost::MevBoostProvider, primitive::AngstromSigner
+    block_sync::BlockSyncConsumer,
+    contract_payloads::angstrom::UniswapAngstromRegistry,
+    matching::match_estimate_response::BundleEstimate,
+    mev_boost::MevBoostProvider,
+    primitive::{AngstromSigner, PoolId}
 };
-use futures::StreamExt;
+use futures::{future::BoxFuture, FutureExt, StreamExt};
 use matching_engine::MatchingEngineHandle;
 use order_pool::order_storage::OrderStorage;
 use reth_metrics::common::mpsc::UnboundedMeteredReceiver;
 use reth_provider::{CanonStateNotification, CanonStateNotifications};
-use tokio_stream::wrappers::BroadcastStream;
+use tokio::sync::{broadcast, mpsc::UnboundedReceiver, oneshot};
+use tokio_stream::wrappers::{BroadcastStream, UnboundedReceiverStream};
 use uniswap_v4::uniswap::pool_manager::SyncedUniswapPools;
 
 use crate::{
-    leader_selection::WeightedRoundRobin,
+    leader_selection::{WeightedRoundRobin, DEFAULT_VOTING_POWER},
     rounds::{ConsensusMessage, RoundStateMachine, SharedRoundState},
-    AngstromValidator
+    AngstromValidator, ConsensusCommand, ConsensusTimingConfig, EvidenceStore
 };
 
 const MODULE_NAME: &str = "Consensus";
 
 pub struct ConsensusManager<P, Matching, BlockSync> {
     current_height:         BlockNumber,
+    angstrom_address:       Address,
     leader_selection:       WeightedRoundRobin,
     consensus_round_state:  RoundStateMachine<P, Matching>,
     canonical_block_stream: BroadcastStream<CanonStateNotification>,
     strom_consensus_event:  UnboundedMeteredReceiver<StromConsensusEvent>,
+    eth_events:             UnboundedReceiverStream<EthEvent>,
+    /// node set changes from the controller that get applied on the next block
+    validator_updates:      Vec<EthEvent>,
+    /// shares its pools with the registry of the round state machine
+    pool_registry:          UniswapAngstromRegistry,
     network:                StromNetworkHandle,
     block_sync:             BlockSync,
+    /// all misbehaviour we have seen from other validators
+    evidence:               EvidenceStore,
+    /// subscribers to the per block gas estimates
+    gas_estimate_tx:        broadcast::Sender<(BlockNumber, BundleEstimate)>,
+    /// subscribers to the messages produced by the round state machine
+    events_tx:              broadcast::Sender<ConsensusMessage>,
+    command_rx:             UnboundedReceiverStream<ConsensusCommand>,
+    /// the gas estimate that is being computed for the current block
+    pending_gas_estimate:   Option<(BlockNumber, BoxFuture<'static, eyre::Result<BundleEstimate>>)>,
+    /// rpc requests waiting on the pending gas estimate
+    fill_estimate_requests: Vec<oneshot::Sender<Option<(BlockNumber, BundleEstimate)>>>,
 
     /// Track broadcasted messages to avoid rebroadcasting
     broadcasted_messages: HashSet<StromConsensusEvent>
@@ -63,18 +86,32 @@ where
         uniswap_pools: SyncedUniswapPools,
         provider: MevBoostProvider<P>,
         matching_engine: Matching,
-        block_sync: BlockSync
+        block_sync: BlockSync,
+        evidence: EvidenceStore,
+        timing: ConsensusTimingConfig,
+        rpcdeps: ManagerRpcDeps
     ) -> Self {
-        let ManagerNetworkDeps { network, canonical_block_stream, strom_consensus_event } = netdeps;
+        let ManagerNetworkDeps {
+            network,
+            canonical_block_stream,
+            strom_consensus_event,
+            eth_events
+        } = netdeps;
+        let ManagerRpcDeps { gas_estimate_tx, events_tx, command_rx } = rpcdeps;
         let wrapped_broadcast_stream = BroadcastStream::new(canonical_block_stream);
         tracing::info!(?validators, "setting up with validators");
         let mut leader_selection = WeightedRoundRobin::new(validators.clone(), current_height);
         let leader = leader_selection.choose_proposer(current_height).unwrap();
+        let backup_leaders = leader_selection.backup_proposers();
         block_sync.register(MODULE_NAME);
 
         Self {
             strom_consensus_event,
+            eth_events,
+            validator_updates: Vec::new(),
+            pool_registry: pool_registry.clone(),
             current_height,
+            angstrom_address,
             leader_selection,
             consensus_round_state: RoundStateMachine::new(SharedRoundState::new(
                 current_height,
@@ -82,15 +119,23 @@ where
                 order_storage,
                 signer,
                 leader,
+                backup_leaders,
                 validators.clone(),
                 ConsensusMetricsWrapper::new(),
                 pool_registry,
                 uniswap_pools,
                 provider,
-                matching_engine
+                matching_engine,
+                timing
             )),
             block_sync,
+            evidence,
             network,
+            gas_estimate_tx,
+            events_tx,
+            command_rx: UnboundedReceiverStream::new(command_rx),
+            pending_gas_estimate: None,
+            fill_estimate_requests: Vec::new(),
             canonical_block_stream: wrapped_broadcast_stream,
             broadcasted_messages: HashSet::new()
         }
@@ -100,22 +145,89 @@ where
         tracing::info!("got new block_chain state");
         let new_block = notification.tip();
         self.current_height = new_block.block.number;
+        self.apply_validator_updates();
+
         let round_leader = self
             .leader_selection
             .choose_proposer(self.current_height)
             .unwrap();
-        tracing::info!(?round_leader, "selected new round leader");
+        let backup_leaders = self.leader_selection.backup_proposers();
+        tracing::info!(?round_leader, ?backup_leaders, "selected new round leader");
 
-        self.consensus_round_state
-            .reset_round(self.current_height, round_leader);
+        // tells the round whether the last leader got its bundle on chain
+        let bundle_senders = new_block
+            .transactions_with_sender()
+            .filter(|(_, tx)| tx.transaction.to() == Some(self.angstrom_address))
+            .map(|(sender, _)| *sender)
+            .collect::<Vec<_>>();
+
+        self.consensus_round_state.reset_round(
+            self.current_height,
+            round_leader,
+            backup_leaders,
+            &bundle_senders
+        );
         self.broadcasted_messages.clear();
 
+        // no point in simulating a bundle if nobody is listening
+        if self.gas_estimate_tx.receiver_count() > 0 {
+            self.pending_gas_estimate =
+                Some((self.current_height, self.consensus_round_state.estimate_gas_per_pool()));
+        }
+
         self.block_sync
             .sign_off_on_block(MODULE_NAME, self.current_height, Some(waker));
     }
 
+    fn on_eth_event(&mut self, event: EthEvent) {
+        match event {
+            EthEvent::AddedNode(_) | EthEvent::RemovedNode(_) => {
+                self.validator_updates.push(event);
+            }
+            EthEvent::NewPool { pool } => {
+                let pool_id = self.pool_registry.add_pool(pool);
+                tracing::info!(?pool_id, "added pool to registry");
+            }
+            EthEvent::RemovedPool { pool } => {
+                let pool_id = PoolId::from(pool);
+                self.pool_registry.remove_pool(&pool_id);
+                tracing::info!(?pool_id, "removed pool from registry");
+            }
+            _ => {}
+        }
+    }
+
+    /// applies the node set changes that have been seen since the last block so
+    /// that all nodes agree on the validator set for the round
+    fn apply_validator_updates(&mut self) {
+        if self.validator_updates.is_empty() {
+            return
+        }
+
+        for update in self.validator_updates.drain(..) {
+            match update {
+                EthEvent::AddedNode(address) => {
+                    tracing::info!(?address, "adding validator");
+                    self.leader_selection
+                        .add_validator(address, DEFAULT_VOTING_POWER);
+                }
+                EthEvent::RemovedNode(address) => {
+                    tracing::info!(?address, "removing validator");
+                    self.leader_selection.remove_validator(&address);
+                }
+                _ => {}
+            }
+        }
+
+        self.consensus_round_state
+            .set_validators(self.leader_selection.validators());
+    }
+
     fn on_network_event(&mut self, event: StromConsensusEvent) {
-        if self.current_height != event.block_height() {
+        // votes against a bundle that never landed are sent after the next block
+        let is_last_rounds_timeout = matches!(event, StromConsensusEvent::ProposalTimeout(..))
+            && event.block_height() + 1 == self.current_height;
+        if self.current_height != event.block_height() && !is_last_rounds_timeout {
             tracing::warn!(
                 event_block_height=%event.block_height(),
                 msg_sender=%event.sender(),
@@ -129,6 +241,10 @@ where
     }
 
     fn on_round_event(&mut self, event: ConsensusMessage) {
+        if self.events_tx.receiver_count() > 0 {
+            let _ = self.events_tx.send(event.clone());
+        }
+
         match event {
             ConsensusMessage::PropagateProposal(p) => {
                 self.network.broadcast_message(StromMessage::Propose(p))
@@ -138,7 +254,48 @@ where
             }
             ConsensusMessage::PropagatePreProposalAgg(p) => self
                 .network
-                .broadcast_message(StromMessage::PreProposeAgg(p))
+                .broadcast_message(StromMessage::PreProposeAgg(p)),
+            ConsensusMessage::PropagateProposalTimeout(t) => self
+                .network
+                .broadcast_message(StromMessage::ProposalTimeout(t))
+        }
+    }
+
+    fn on_command(&mut self, command: ConsensusCommand) {
+        match command {
+            ConsensusCommand::RoundState(tx) => {
+                let _ = tx.send(self.consensus_round_state.round_info());
+            }
+            ConsensusCommand::Evidence(tx) => {
+                let _ = tx.send(self.evidence.all().to_vec());
+            }
+            ConsensusCommand::FillEstimate(tx) => {
+                // requests that come in while a simulation is running share its result
+                if self.pending_gas_estimate.is_none() {
+                    self.pending_gas_estimate = Some((
+                        self.current_height,
+                        self.consensus_round_state.estimate_gas_per_pool()
+                    ));
+                }
+                self.fill_estimate_requests.push(tx);
+            }
+        }
+    }
+
+    fn poll_gas_estimate(&mut self, cx: &mut Context<'_>) {
+        let Some((block, estimate)) = self.pending_gas_estimate.as_mut() else { return };
+        let Poll::Ready(res) = estimate.poll_unpin(cx) else { return };
+        let block = *block;
+        self.pending_gas_estimate = None;
+
+        let estimate = res
+            .inspect_err(|e| tracing::debug!(%e, block, "failed to estimate gas for block"))
+            .ok();
+        for tx in self.fill_estimate_requests.drain(..) {
+            let _ = tx.send(estimate.clone().map(|estimate| (block, estimate)));
+        }
+        if let Some(estimate) = estimate {
+            let _ = self.gas_estimate_tx.send((block, estimate));
         }
     }
 }
@@ -154,6 +311,12 @@ where
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
 
+        // node set changes are sent before the block they are in, so we need to
+        // process them first
+        while let Poll::Ready(Some(event)) = this.eth_events.poll_next_unpin(cx) {
+            this.on_eth_event(event);
+        }
+
         while let Poll::Ready(Some(msg)) = this.canonical_block_stream.poll_next_unpin(cx) {
             match msg {
                 Ok(notification) => this.on_blockchain_state(notification, cx.waker().clone()),
@@ -169,8 +332,18 @@ where
             while let Poll::Ready(Some(msg)) = this.consensus_round_state.poll_next_unpin(cx) {
                 this.on_round_event(msg);
             }
+
+            for evidence in this.consensus_round_state.take_evidence() {
+                this.evidence.insert(evidence);
+            }
+        }
+
+        while let Poll::Ready(Some(command)) = this.command_rx.poll_next_unpin(cx) {
+            this.on_command(command);
         }
 
+        this.poll_gas_estimate(cx);
+
         Poll::Pending
     }
 }
@@ -178,15 +351,34 @@ where
 pub struct ManagerNetworkDeps {
     network:                StromNetworkHandle,
     canonical_block_stream: CanonStateNotifications,
-    strom_consensus_event:  UnboundedMeteredReceiver<StromConsensusEvent>
+    strom_consensus_event:  UnboundedMeteredReceiver<StromConsensusEvent>,
+    eth_events:             UnboundedReceiverStream<EthEvent>
 }
 
 impl ManagerNetworkDeps {
     pub fn new(
         network: StromNetworkHandle,
         canonical_block_stream: CanonStateNotifications,
-        strom_consensus_event: UnboundedMeteredReceiver<StromConsensusEvent>
+        strom_consensus_event: UnboundedMeteredReceiver<StromConsensusEvent>,
+        eth_events: UnboundedReceiverStream<EthEvent>
+    ) -> Self {
+        Self { network, canonical_block_stream, strom_consensus_event, eth_events }
+    }
+}
+
+/// Channels used to serve the consensus and quoting rpc namespaces
+pub struct ManagerRpcDeps {
+    gas_estimate_tx: broadcast::Sender<(BlockNumber, BundleEstimate)>,
+    events_tx:       broadcast::Sender<ConsensusMessage>,
+    command_rx:      UnboundedReceiver<ConsensusCommand>
+}
+
+impl ManagerRpcDeps {
+    pub fn new(
+        gas_estimate_tx: broadcast::Sender<(BlockNumber, BundleEstimate)>,
+        events_tx: broadcast::Sender<ConsensusMessage>,
+        command_rx: UnboundedReceiver<ConsensusCommand>
     ) -> Self {
-        Self { network, canonical_block_stream, strom_consensus_event }
+        Self { gas_estimate_tx, events_tx, command_rx }
     }
 }
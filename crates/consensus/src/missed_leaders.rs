@@ -0,0 +1,129 @@
+use std::collections::{HashMap, HashSet};
+
+use alloy::primitives::{Address, BlockNumber};
+use angstrom_types::{consensus::ProposalTimeout, primitive::PeerId};
+
+/// How many blocks a leader that a quorum agreed missed its round is skipped
+/// for when it would lead again
+pub const MISSED_LEADER_COOLDOWN: u64 = 32;
+
+/// The timeout votes against round leaders and the leaders they agreed on.
+/// Outlives the rounds so that a leader that is down is skipped by every node
+/// instead of costing a block each time its turn comes around.
+#[derive(Debug, Default)]
+pub struct MissedLeaders {
+    /// who voted that the leader of the given block didn't deliver
+    votes:  HashMap<(BlockNumber, Address), HashSet<PeerId>>,
+    /// the last block each leader was agreed to have missed
+    missed: HashMap<Address, BlockNumber>
+}
+
+impl MissedLeaders {
+    /// returns false if we already had this vote
+    pub fn insert_vote(&mut self, timeout: &ProposalTimeout) -> bool {
+        self.votes
+            .entry((timeout.block_height, timeout.leader))
+            .or_default()
+            .insert(timeout.source)
+    }
+
+    pub fn voters(&self, block_height: BlockNumber, leader: Address) -> Vec<PeerId> {
+        self.votes
+            .get(&(block_height, leader))
+            .map(|voters| voters.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// returns false if the leader was already recorded for this block
+    pub fn record_missed(&mut self, leader: Address, block_height: BlockNumber) -> bool {
+        let last = self.missed.entry(leader).or_default();
+        if *last >= block_height {
+            return false
+        }
+        *last = block_height;
+
+        true
+    }
+
+    fn is_benched(&self, leader: &Address, block_height: BlockNumber) -> bool {
+        self.missed
+            .get(leader)
+            .is_some_and(|missed| block_height <= missed + MISSED_LEADER_COOLDOWN)
+    }
+
+    /// the leaders that are currently skipped, in the order they missed
+    pub fn benched(&self, block_height: BlockNumber) -> Vec<Address> {
+        let mut benched = self
+            .missed
+            .iter()
+            .filter(|(leader, _)| self.is_benched(leader, block_height))
+            .map(|(leader, missed)| (*missed, *leader))
+            .collect::<Vec<_>>();
+        benched.sort();
+
+        benched.into_iter().map(|(_, leader)| leader).collect()
+    }
+
+    /// moves the benched leaders to the back of the order the round's leaders
+    /// take over in. If everyone is benched, the order is left untouched
+    pub fn reorder(&self, block_height: BlockNumber, leaders: Vec<Address>) -> Vec<Address> {
+        let (mut active, benched): (Vec<_>, Vec<_>) = leaders
+            .into_iter()
+            .partition(|leader| !self.is_benched(leader, block_height));
+        active.extend(benched);
+
+        active
+    }
+
+    /// votes are only taken for the current and the last block
+    pub fn prune(&mut self, block_height: BlockNumber) {
+        self.votes
+            .retain(|(height, _), _| *height + 1 >= block_height);
+        self.missed
+            .retain(|_, missed| block_height <= *missed + MISSED_LEADER_COOLDOWN);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use angstrom_types::primitive::AngstromSigner;
+
+    use super::*;
+
+    #[test]
+    fn test_missed_leaders_are_skipped_until_cooldown() {
+        let (a, b, c) = (Address::random(), Address::random(), Address::random());
+        let mut missed = MissedLeaders::default();
+
+        assert!(missed.record_missed(a, 10));
+        assert!(!missed.record_missed(a, 10));
+        assert_eq!(missed.reorder(11, vec![a, b, c]), vec![b, c, a]);
+        assert_eq!(missed.benched(11), vec![a]);
+
+        // the order is kept when there is nobody else
+        missed.record_missed(b, 11);
+        missed.record_missed(c, 11);
+        assert_eq!(missed.reorder(12, vec![a, b, c]), vec![a, b, c]);
+
+        let after_cooldown = 11 + MISSED_LEADER_COOLDOWN + 1;
+        missed.prune(after_cooldown);
+        assert_eq!(missed.reorder(after_cooldown, vec![a, b, c]), vec![a, b, c]);
+        assert!(missed.benched(after_cooldown).is_empty());
+    }
+
+    #[test]
+    fn test_votes_are_counted_once_and_pruned() {
+        let leader = Address::random();
+        let voter = AngstromSigner::random();
+        let mut missed = MissedLeaders::default();
+
+        assert!(missed.insert_vote(&ProposalTimeout::new(5, leader, 1, Address::ZERO, &voter)));
+        assert!(!missed.insert_vote(&ProposalTimeout::new(5, leader, 1, Address::ZERO, &voter)));
+        assert_eq!(missed.voters(5, leader), vec![voter.id()]);
+
+        missed.prune(6);
+        assert_eq!(missed.voters(5, leader).len(), 1);
+        missed.prune(7);
+        assert!(missed.voters(5, leader).is_empty());
+    }
+}
@@ -14,6 +14,7 @@ use super::{
     finalization::FinalizationState, pre_proposal::PreProposalState,
     preproposal_wait_trigger::PreProposalWaitTrigger, ConsensusState, SharedRoundState
 };
+use crate::ConsensusRoundName;
 
 /// BidAggregationState
 ///
@@ -82,9 +83,15 @@ where
                     self.waker.as_ref().inspect(|w| w.wake_by_ref());
                 }
             }
+            // handled by the round state machine
+            StromConsensusEvent::ProposalTimeout(..) => {}
         }
     }
 
+    fn name(&self) -> ConsensusRoundName {
+        ConsensusRoundName::BidAggregation
+    }
+
     fn poll_transition(
         &mut self,
         handles: &mut SharedRoundState<P, Matching>,
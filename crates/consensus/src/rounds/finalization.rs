@@ -1,16 +1,21 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     pin::Pin,
     task::{Context, Poll, Waker}
 };
 
 use alloy::providers::Provider;
 use angstrom_network::manager::StromConsensusEvent;
-use angstrom_types::consensus::Proposal;
+use angstrom_types::{
+    consensus::{EvidenceError, InvalidProposalEvidence, Proposal},
+    orders::PoolSolution,
+    primitive::PoolId
+};
 use futures::{Future, FutureExt};
 use matching_engine::MatchingEngineHandle;
 
 use super::{ConsensusState, SharedRoundState};
+use crate::ConsensusRoundName;
 
 /// The finalization state.
 ///
@@ -20,7 +25,8 @@ use super::{ConsensusState, SharedRoundState};
 /// have a day max). in which they will be verified and the round will
 /// officially close.
 pub struct FinalizationState {
-    verification_future: Pin<Box<dyn Future<Output = bool> + Send>>,
+    /// resolves to the evidence if the proposal doesn't match our own output
+    verification_future: Pin<Box<dyn Future<Output = Option<InvalidProposalEvidence>> + Send>>,
     completed:           bool
 }
 
@@ -45,24 +51,28 @@ impl FinalizationState {
             .map(move |output| {
                 let (solution, _) = output.unwrap();
 
-                let mut proposal_solution = proposal.solutions.clone();
-                proposal_solution.sort();
-
-                let mut verification_solution = solution;
-                verification_solution.sort();
-
-                if !proposal_solution
-                    .into_iter()
-                    .zip(verification_solution)
-                    .all(|(p, v)| p == v)
-                {
+                for pool_id in tob_auction_mismatches(&proposal.solutions, &solution) {
                     tracing::error!(
-                        "Violation DETECTED. in future this will be related to slashing"
+                        leader=?proposal.source,
+                        ?pool_id,
+                        "leader didn't pick the top of block auction winner"
                     );
-                    return false
                 }
 
-                true
+                match InvalidProposalEvidence::new(proposal, solution) {
+                    Ok(evidence) => {
+                        tracing::error!(
+                            leader=?evidence.proposal.source,
+                            "Violation DETECTED. proposal doesn't match our matching output"
+                        );
+                        Some(evidence)
+                    }
+                    Err(EvidenceError::NotConflicting) => None,
+                    Err(e) => {
+                        tracing::error!(%e, "failed to build invalid proposal evidence");
+                        None
+                    }
+                }
             })
             .boxed();
 
@@ -73,6 +83,32 @@ impl FinalizationState {
     }
 }
 
+/// the pools where the proposal's top of block order isn't the winner of our
+/// own run of the auction
+pub(super) fn tob_auction_mismatches(
+    proposed: &[PoolSolution],
+    expected: &[PoolSolution]
+) -> Vec<PoolId> {
+    let winners = |solutions: &[PoolSolution]| {
+        solutions
+            .iter()
+            .map(|s| (s.id, s.searcher.as_ref().map(|order| order.order_id.hash)))
+            .collect::<HashMap<_, _>>()
+    };
+    let (proposed, expected) = (winners(proposed), winners(expected));
+
+    proposed
+        .keys()
+        .chain(expected.keys())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .filter(|pool_id| {
+            proposed.get(pool_id).copied().flatten() != expected.get(pool_id).copied().flatten()
+        })
+        .copied()
+        .collect()
+}
+
 impl<P, Matching> ConsensusState<P, Matching> for FinalizationState
 where
     P: Provider + 'static,
@@ -87,17 +123,25 @@ where
         // to be reset.
     }
 
+    fn name(&self) -> ConsensusRoundName {
+        ConsensusRoundName::Finalization
+    }
+
     fn poll_transition(
         &mut self,
-        _: &mut SharedRoundState<P, Matching>,
+        handles: &mut SharedRoundState<P, Matching>,
         cx: &mut Context<'_>
     ) -> Poll<Option<Box<dyn ConsensusState<P, Matching>>>> {
         if self.completed {
             return Poll::Ready(None)
         }
 
-        if let Poll::Ready(result) = self.verification_future.poll_unpin(cx) {
+        if let Poll::Ready(evidence) = self.verification_future.poll_unpin(cx) {
+            let result = evidence.is_none();
             tracing::info!(%result, "consensus result");
+            if let Some(evidence) = evidence {
+                handles.record_evidence(evidence);
+            }
             self.completed = true;
             return Poll::Ready(None)
         }
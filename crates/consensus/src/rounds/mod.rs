@@ -13,13 +13,20 @@ use alloy::{
 use angstrom_metrics::ConsensusMetricsWrapper;
 use angstrom_network::manager::StromConsensusEvent;
 use angstrom_types::{
-    consensus::{PreProposal, PreProposalAggregation, Proposal},
+    consensus::{
+        ConsensusVote, DuplicateVoteEvidence, Evidence, PreProposal, PreProposalAggregation,
+        Proposal, ProposalTimeout
+    },
     contract_payloads::angstrom::{BundleGasDetails, UniswapAngstromRegistry},
-    matching::uniswap::PoolSnapshot,
+    matching::{match_estimate_response::BundleEstimate, uniswap::PoolSnapshot},
     mev_boost::MevBoostProvider,
     orders::PoolSolution,
     primitive::{AngstromSigner, PeerId},
-    sol_bindings::grouped_orders::OrderWithStorageData
+    sol_bindings::{
+        ext::RawPoolOrder,
+        grouped_orders::{GroupedVanillaOrder, OrderWithStorageData},
+        rpc_orders::TopOfBlockOrder
+    }
 };
 use bid_aggregation::BidAggregationState;
 use futures::{future::BoxFuture, FutureExt, Stream};
@@ -29,7 +36,10 @@ use order_pool::order_storage::OrderStorage;
 use preproposal_wait_trigger::{LastRoundInfo, PreProposalWaitTrigger};
 use uniswap_v4::uniswap::pool_manager::SyncedUniswapPools;
 
-use crate::AngstromValidator;
+use crate::{
+    missed_leaders::MissedLeaders, AngstromValidator, ConsensusRoundInfo, ConsensusRoundName,
+    ConsensusTimingConfig
+};
 
 mod bid_aggregation;
 mod finalization;
@@ -62,6 +72,8 @@ where
     fn last_round_info(&mut self) -> Option<LastRoundInfo> {
         None
     }
+
+    fn name(&self) -> ConsensusRoundName;
 }
 
 /// Holds and progresses the consensus state machine
@@ -80,7 +92,7 @@ where
 {
     pub fn new(shared_state: SharedRoundState<P, Matching>) -> Self {
         let mut consensus_wait_duration =
-            PreProposalWaitTrigger::new(shared_state.order_storage.clone());
+            PreProposalWaitTrigger::new(shared_state.order_storage.clone(), shared_state.timing);
 
         Self {
             current_state: Box::new(BidAggregationState::new(
@@ -91,7 +103,15 @@ where
         }
     }
 
-    pub fn reset_round(&mut self, new_block: u64, new_leader: PeerId) {
+    /// `bundle_senders` are the senders of the angstrom transactions in the
+    /// new block, to check if the last round's bundle landed
+    pub fn reset_round(
+        &mut self,
+        new_block: u64,
+        new_leader: Address,
+        backup_leaders: Vec<Address>,
+        bundle_senders: &[Address]
+    ) {
         // grab the last round info if we were the leader.
         let info = self.current_state.last_round_info();
 
@@ -102,18 +122,78 @@ where
             self.consensus_wait_duration.reset_before_submission();
         }
 
+        let (last_block, last_leader) =
+            (self.shared_state.block_height, self.shared_state.round_leader);
+        let missed_bundle =
+            self.shared_state.expects_bundle && !bundle_senders.contains(&last_leader);
+
         self.shared_state.block_height = new_block;
-        self.shared_state.round_leader = new_leader;
+        self.shared_state.missed_leaders.prune(new_block);
+        // leaders that missed recently take over last
+        let mut leaders = self
+            .shared_state
+            .missed_leaders
+            .reorder(new_block, std::iter::once(new_leader).chain(backup_leaders).collect());
+        self.shared_state.round_leader = leaders.remove(0);
+        self.shared_state.backup_leaders = leaders.into();
+        self.shared_state.expects_bundle = false;
+        self.shared_state.pre_proposals_seen = 0;
+        self.shared_state.pre_proposal_aggs_seen = 0;
 
         self.current_state = Box::new(BidAggregationState::new(
             self.consensus_wait_duration.update_for_new_round(info)
         ));
+
+        // there were orders to fill but the leader never got a bundle on chain
+        if missed_bundle {
+            tracing::warn!(leader=?last_leader, block=last_block, "round leader's bundle never landed");
+            self.shared_state
+                .vote_proposal_timeout(last_block, last_leader);
+        }
+    }
+
+    /// the validator set is only updated at block boundaries, before the round
+    /// is reset
+    pub fn set_validators(&mut self, validators: Vec<AngstromValidator>) {
+        self.shared_state.validators = validators;
     }
 
     pub fn handle_message(&mut self, event: StromConsensusEvent) {
+        // timeouts are about the leader, not the step of the round we are in
+        if let StromConsensusEvent::ProposalTimeout(peer_id, timeout) = event {
+            self.shared_state.handle_proposal_timeout(peer_id, timeout);
+            return
+        }
+
         self.current_state
             .on_consensus_message(&mut self.shared_state, event);
     }
+
+    /// all evidence of misbehaviour that was collected since the last call
+    pub fn take_evidence(&mut self) -> Vec<Evidence> {
+        std::mem::take(&mut self.shared_state.evidence)
+    }
+
+    /// estimates the gas per order of a bundle built from all orders currently
+    /// in our pool.
+    pub fn estimate_gas_per_pool(&self) -> BoxFuture<'static, eyre::Result<BundleEstimate>> {
+        self.shared_state.estimate_gas_per_pool()
+    }
+
+    pub fn round_info(&self) -> ConsensusRoundInfo {
+        ConsensusRoundInfo {
+            block_height: self.shared_state.block_height,
+            leader: self.shared_state.round_leader,
+            is_leader: self.shared_state.i_am_leader(),
+            state: self.current_state.name(),
+            pre_proposals_seen: self.shared_state.pre_proposals_seen,
+            pre_proposal_aggregations_seen: self.shared_state.pre_proposal_aggs_seen,
+            missed_leaders: self
+                .shared_state
+                .missed_leaders
+                .benched(self.shared_state.block_height)
+        }
+    }
 }
 
 impl<P, Matching> Stream for RoundStateMachine<P, Matching>
@@ -130,7 +210,11 @@ where
             .current_state
             .poll_transition(&mut this.shared_state, cx)
         {
-            tracing::info!("transitioning to new round state");
+            tracing::info!(
+                from=?this.current_state.name(),
+                to=?transitioned_state.name(),
+                "transitioning to new round state"
+            );
             this.current_state = transitioned_state;
         }
 
@@ -143,18 +227,33 @@ where
 }
 
 pub struct SharedRoundState<P, Matching> {
-    block_height:     BlockNumber,
-    angstrom_address: Address,
-    matching_engine:  Matching,
-    signer:           AngstromSigner,
-    round_leader:     PeerId,
-    validators:       Vec<AngstromValidator>,
-    order_storage:    Arc<OrderStorage>,
-    _metrics:         ConsensusMetricsWrapper,
-    pool_registry:    UniswapAngstromRegistry,
-    uniswap_pools:    SyncedUniswapPools,
-    provider:         Arc<MevBoostProvider<P>>,
-    messages:         VecDeque<ConsensusMessage>
+    block_height:           BlockNumber,
+    angstrom_address:       Address,
+    matching_engine:        Matching,
+    signer:                 AngstromSigner,
+    round_leader:           Address,
+    /// who takes over, in order, once a quorum agreed the round leader missed
+    backup_leaders:         VecDeque<Address>,
+    /// timeout votes and the leaders that missed recently, kept across rounds
+    missed_leaders:         MissedLeaders,
+    /// if the aggregations we saw held orders, so the leader has to land a
+    /// bundle in the next block
+    expects_bundle:         bool,
+    /// pre-proposal wait, submission and proposal deadline timings
+    timing:                 ConsensusTimingConfig,
+    validators:             Vec<AngstromValidator>,
+    order_storage:          Arc<OrderStorage>,
+    _metrics:               ConsensusMetricsWrapper,
+    pool_registry:          UniswapAngstromRegistry,
+    uniswap_pools:          SyncedUniswapPools,
+    provider:               Arc<MevBoostProvider<P>>,
+    messages:               VecDeque<ConsensusMessage>,
+    /// valid pre-proposals (including our own) seen this round
+    pre_proposals_seen:     usize,
+    /// valid pre-proposal aggregations (including our own) seen this round
+    pre_proposal_aggs_seen: usize,
+    /// evidence that hasn't been picked up by the manager yet
+    evidence:               Vec<Evidence>
 }
 
 // contains shared impls
@@ -169,18 +268,24 @@ where
         angstrom_address: Address,
         order_storage: Arc<OrderStorage>,
         signer: AngstromSigner,
-        round_leader: PeerId,
+        round_leader: Address,
+        backup_leaders: Vec<Address>,
         validators: Vec<AngstromValidator>,
         metrics: ConsensusMetricsWrapper,
         pool_registry: UniswapAngstromRegistry,
         uniswap_pools: SyncedUniswapPools,
         provider: MevBoostProvider<P>,
-        matching_engine: Matching
+        matching_engine: Matching,
+        timing: ConsensusTimingConfig
     ) -> Self {
         Self {
             block_height,
             angstrom_address,
             round_leader,
+            backup_leaders: backup_leaders.into(),
+            missed_leaders: MissedLeaders::default(),
+            expects_bundle: false,
+            timing,
             validators,
             order_storage,
             pool_registry,
@@ -189,20 +294,140 @@ where
             _metrics: metrics,
             matching_engine,
             messages: VecDeque::new(),
-            provider: Arc::new(provider)
+            provider: Arc::new(provider),
+            pre_proposals_seen: 0,
+            pre_proposal_aggs_seen: 0,
+            evidence: Vec::new()
         }
     }
 
     fn propagate_message(&mut self, message: ConsensusMessage) {
+        match &message {
+            ConsensusMessage::PropagatePreProposal(_) => self.pre_proposals_seen += 1,
+            ConsensusMessage::PropagatePreProposalAgg(_) => self.pre_proposal_aggs_seen += 1,
+            ConsensusMessage::PropagateProposal(_)
+            | ConsensusMessage::PropagateProposalTimeout(_) => {}
+        }
         self.messages.push_back(message);
     }
 
+    fn record_evidence(&mut self, evidence: impl Into<Evidence>) {
+        self.evidence.push(evidence.into());
+    }
+
     fn i_am_leader(&self) -> bool {
-        self.round_leader == self.signer.id()
+        self.round_leader == self.signer.address()
+    }
+
+    /// hands the round over to the next backup leader. Returns false if there
+    /// is nobody left to fall back to
+    fn fallback_to_backup_leader(&mut self) -> bool {
+        let Some(backup) = self.backup_leaders.pop_front() else {
+            tracing::warn!(
+                leader=?self.round_leader,
+                "round leader missed and there are no backups left"
+            );
+            return false
+        };
+
+        tracing::warn!(
+            missed_leader=?self.round_leader,
+            new_leader=?backup,
+            block=self.block_height,
+            "round leader missed, falling back"
+        );
+        self.round_leader = backup;
+
+        true
+    }
+
+    /// signs and sends our vote that the leader of the given block didn't
+    /// deliver
+    fn vote_proposal_timeout(&mut self, block_height: BlockNumber, leader: Address) {
+        let timeout = ProposalTimeout::new(
+            block_height,
+            leader,
+            self.provider.chain_id(),
+            self.angstrom_address,
+            &self.signer
+        );
+        if self.missed_leaders.insert_vote(&timeout) {
+            self.propagate_message(timeout.into());
+            self.apply_timeout_quorum(block_height, leader);
+        }
+    }
+
+    fn handle_proposal_timeout(&mut self, peer_id: PeerId, timeout: ProposalTimeout) {
+        if !self.is_validator(peer_id) || !self.is_validator(timeout.source) {
+            tracing::warn!(peer=?peer_id, "got a proposal timeout from a invalid peer");
+            return
+        }
+        // votes against a bundle that never landed come in after the next block
+        let is_recent = timeout.block_height <= self.block_height
+            && timeout.block_height + 1 >= self.block_height;
+        if !is_recent || !timeout.is_valid(self.provider.chain_id(), self.angstrom_address) {
+            tracing::info!(peer=?peer_id, "got a invalid proposal timeout");
+            return
+        }
+
+        if !self.missed_leaders.insert_vote(&timeout) {
+            tracing::trace!(peer=?peer_id, "got a duplicate proposal timeout");
+            return
+        }
+        let (block_height, leader) = (timeout.block_height, timeout.leader);
+        self.propagate_message(timeout.into());
+        self.apply_timeout_quorum(block_height, leader);
+    }
+
+    /// once validators holding 2f+1 of the voting power agree that a leader
+    /// didn't deliver, it is recorded as missed and, if it still leads this
+    /// round, replaced by the next backup
+    fn apply_timeout_quorum(&mut self, block_height: BlockNumber, leader: Address) {
+        if !self.has_quorum(self.missed_leaders.voters(block_height, leader))
+            || !self.missed_leaders.record_missed(leader, block_height)
+        {
+            return
+        }
+        tracing::warn!(?leader, block = block_height, "quorum agreed that the round leader missed");
+
+        if block_height == self.block_height
+            && leader == self.round_leader
+            && self.fallback_to_backup_leader()
+        {
+            // the votes against the backup can reach quorum before ours do
+            self.apply_timeout_quorum(block_height, self.round_leader);
+        }
+    }
+
+    fn total_voting_power(&self) -> u64 {
+        self.validators.iter().map(|v| v.voting_power()).sum()
+    }
+
+    /// the 2f+1 threshold, in voting power, that is needed for quorum
+    fn two_thirds_of_voting_power(&self) -> u64 {
+        (2 * self.total_voting_power()).div_ceil(3)
+    }
+
+    fn voting_power_of(&self, peer_id: PeerId) -> u64 {
+        let address = AngstromSigner::peer_id_to_address(peer_id);
+        self.validators
+            .iter()
+            .find(|v| v.address == address)
+            .map(|v| v.voting_power())
+            .unwrap_or_default()
     }
 
-    fn two_thirds_of_validation_set(&self) -> usize {
-        (2 * self.validators.len()).div_ceil(3)
+    /// checks if the given set of sources holds 2f+1 of the voting power. A
+    /// source is only counted once no matter how many times it shows up
+    fn has_quorum(&self, sources: impl IntoIterator<Item = PeerId>) -> bool {
+        let voting_power: u64 = sources
+            .into_iter()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .map(|source| self.voting_power_of(source))
+            .sum();
+
+        voting_power >= self.two_thirds_of_voting_power()
     }
 
     fn fetch_pool_snapshot(
@@ -210,54 +435,117 @@ where
     ) -> HashMap<FixedBytes<32>, (Address, Address, PoolSnapshot, u16)> {
         self.uniswap_pools
             .iter()
-            .map(|(key, pool)| {
+            .filter_map(|(key, pool)| {
                 tracing::info!(?key, "getting snapshot");
+                // a pool that was just configured or removed can be out of sync
+                // with the registry for a block, we skip it until it isn't
+                let entry = self.pool_registry.get_ang_entry(&key)?;
                 let (token_a, token_b, snapshot) =
-                    pool.read().unwrap().fetch_pool_snapshot().unwrap();
-                let entry = self.pool_registry.get_ang_entry(key).unwrap();
+                    pool.read().unwrap().fetch_pool_snapshot().ok()?;
 
-                (*key, (token_a, token_b, snapshot, entry.store_index as u16))
+                Some((key, (token_a, token_b, snapshot, entry.store_index as u16)))
             })
             .collect::<HashMap<_, _>>()
     }
 
+    /// the orders the matching engine runs over: the public orders that reached
+    /// quorum plus the checked private orders of every validator that sent a
+    /// pre-proposal
+    fn proposal_orders(
+        &self,
+        pre_proposal_aggregation: HashSet<PreProposalAggregation>
+    ) -> (Vec<OrderWithStorageData<GroupedVanillaOrder>>, Vec<OrderWithStorageData<TopOfBlockOrder>>)
+    {
+        // a validators pre-proposal can be in multiple aggregations, we only want to
+        // count it once.
+        let pre_proposals = pre_proposal_aggregation
+            .into_iter()
+            .flat_map(|pre_proposal_agg| pre_proposal_agg.pre_proposals)
+            .fold(HashMap::new(), |mut acc, pre| {
+                acc.entry(pre.source).or_insert(pre);
+                acc
+            })
+            .into_values()
+            .collect::<Vec<_>>();
+
+        let (limit, searcher): (Vec<_>, Vec<_>) = pre_proposals
+            .iter()
+            .map(|pre| ((pre.source, pre.limit.clone()), (pre.source, pre.searcher.clone())))
+            .unzip();
+        let mut limit = self.filter_quorum_orders(limit);
+        let mut searcher = self.filter_quorum_orders(searcher);
+
+        // only the validator the order was submitted to has it, so there is
+        // nobody to reach quorum with
+        for pre in pre_proposals
+            .into_iter()
+            .filter(|pre| self.is_validator(pre.source) && self.private_orders_are_valid(pre))
+        {
+            limit.extend(pre.private.limit);
+            searcher.extend(pre.private.searcher);
+        }
+
+        (limit.into_iter().unique().collect(), searcher.into_iter().unique().collect())
+    }
+
+    /// if the aggregations hold any orders the leader's bundle has to fill
+    fn has_orders_to_propose(&self, aggregations: &HashSet<PreProposalAggregation>) -> bool {
+        let (limit, searcher) = self.proposal_orders(aggregations.clone());
+        !limit.is_empty() || !searcher.is_empty()
+    }
+
     fn matching_engine_output(
         &self,
         pre_proposal_aggregation: HashSet<PreProposalAggregation>
     ) -> BoxFuture<'static, eyre::Result<(Vec<PoolSolution>, BundleGasDetails)>> {
-        // fetch
-        let mut limit = Vec::new();
-        let mut searcher = Vec::new();
+        let (limit, searcher) = self.proposal_orders(pre_proposal_aggregation);
+        let pool_snapshots = self.fetch_pool_snapshot();
 
-        for pre_proposal_agg in pre_proposal_aggregation {
-            pre_proposal_agg.pre_proposals.into_iter().for_each(|pre| {
-                limit.extend(pre.limit);
-                searcher.extend(pre.searcher);
-            });
+        let matcher = self.matching_engine.clone();
+        let uniswap_pools = self.uniswap_pools.clone();
+
+        async move {
+            // only the winner of each pool's top of block auction goes to the matcher
+            let searcher = uniswap_pools.tob_auction(searcher).await;
+            matcher.solve_pools(limit, searcher, pool_snapshots).await
         }
+        .boxed()
+    }
 
-        let limit = self.filter_quorum_orders(limit);
-        let searcher = self.filter_quorum_orders(searcher);
+    fn estimate_gas_per_pool(&self) -> BoxFuture<'static, eyre::Result<BundleEstimate>> {
+        let orders = self.order_storage.get_all_orders();
         let pool_snapshots = self.fetch_pool_snapshot();
 
         let matcher = self.matching_engine.clone();
+        let uniswap_pools = self.uniswap_pools.clone();
 
-        async move { matcher.solve_pools(limit, searcher, pool_snapshots).await }.boxed()
+        async move {
+            let searcher = uniswap_pools.tob_auction(orders.searcher).await;
+            matcher
+                .estimate_gas_per_pool(orders.limit, searcher, pool_snapshots)
+                .await
+        }
+        .boxed()
     }
 
+    /// only keeps the orders that were included by validators holding 2f+1 of
+    /// the voting power
     fn filter_quorum_orders<O: Hash + Eq + Clone>(
         &self,
-        input: Vec<OrderWithStorageData<O>>
+        input: Vec<(PeerId, Vec<OrderWithStorageData<O>>)>
     ) -> Vec<OrderWithStorageData<O>> {
-        let two_thirds = self.two_thirds_of_validation_set();
+        let two_thirds = self.two_thirds_of_voting_power();
         input
             .into_iter()
-            .fold(HashMap::new(), |mut acc, order| {
-                *acc.entry(order).or_insert(0) += 1;
+            .fold(HashMap::new(), |mut acc, (source, orders)| {
+                let voting_power = self.voting_power_of(source);
+                for order in orders.into_iter().collect::<HashSet<_>>() {
+                    *acc.entry(order).or_insert(0) += voting_power;
+                }
                 acc
             })
             .into_iter()
-            .filter(|(_, count)| *count >= two_thirds)
+            .filter(|(_, voting_power)| *voting_power >= two_thirds)
             .map(|(order, _)| order)
             .collect()
     }
@@ -276,18 +564,95 @@ where
         )
     }
 
+    /// Nobody vouches for the private orders of a pre-proposal but its source,
+    /// so we check them again ourselves: each order has to be signed by its
+    /// owner, be filled under its own hash and, for flash and top of block
+    /// orders, be for the block being built. Balances and approvals are left
+    /// to the bundle simulation as we don't track the state of accounts
+    /// outside our pool.
+    fn private_orders_are_valid(&self, pre: &PreProposal) -> bool {
+        let next_block = self.block_height + 1;
+        let invalid = pre
+            .private
+            .limit
+            .iter()
+            .filter(|order| !private_order_is_valid(order, next_block))
+            .map(|order| order.order_id.hash)
+            .chain(
+                pre.private
+                    .searcher
+                    .iter()
+                    .filter(|order| !private_order_is_valid(order, next_block))
+                    .map(|order| order.order_id.hash)
+            )
+            .collect::<Vec<_>>();
+        if !invalid.is_empty() {
+            tracing::warn!(source=?pre.source, ?invalid, "pre-proposal has invalid private orders");
+            return false
+        }
+
+        true
+    }
+
+    fn is_validator(&self, peer_id: PeerId) -> bool {
+        let address = AngstromSigner::peer_id_to_address(peer_id);
+        self.validators.iter().any(|v| v.address == address)
+    }
+
+    /// checks that the private orders of the proposal come from validators and
+    /// that every order it fills was either public with quorum or private
+    fn proposal_orders_are_backed(&self, proposal: &Proposal) -> bool {
+        if let Some(pre) = proposal
+            .flattened_pre_proposals()
+            .into_iter()
+            .find(|pre| !pre.private.is_empty() && !self.is_validator(pre.source))
+        {
+            tracing::warn!(source=?pre.source, "proposal has private orders from a non validator");
+            return false
+        }
+
+        let (limit, searcher) =
+            self.proposal_orders(proposal.preproposals().iter().cloned().collect());
+        let backed = limit
+            .iter()
+            .map(|order| order.order_id.hash)
+            .chain(searcher.iter().map(|order| order.order_id.hash))
+            .collect::<HashSet<_>>();
+
+        let unbacked = proposal
+            .solutions
+            .iter()
+            .flat_map(|solution| {
+                solution
+                    .limit
+                    .iter()
+                    .map(|outcome| outcome.id.hash)
+                    .chain(solution.searcher.iter().map(|order| order.order_id.hash))
+            })
+            .filter(|hash| !backed.contains(hash))
+            .collect::<Vec<_>>();
+        if !unbacked.is_empty() {
+            tracing::warn!(?unbacked, "proposal fills orders that didn't reach quorum");
+            return false
+        }
+
+        true
+    }
+
     fn verify_proposal(&mut self, peer_id: PeerId, proposal: Proposal) -> Option<Proposal> {
-        if self.round_leader != peer_id {
+        if self.round_leader != AngstromSigner::peer_id_to_address(peer_id) {
             tracing::debug!("got invalid proposal");
             return None
         }
 
-        proposal.is_valid(&self.block_height).then(|| {
-            self.messages
-                .push_back(ConsensusMessage::PropagateProposal(proposal.clone()));
+        (proposal.is_valid(&self.block_height) && self.proposal_orders_are_backed(&proposal)).then(
+            || {
+                self.messages
+                    .push_back(ConsensusMessage::PropagateProposal(proposal.clone()));
 
-            proposal
-        })
+                proposal
+            }
+        )
     }
 
     fn handle_pre_proposal(
@@ -296,11 +661,12 @@ where
         pre_proposal: PreProposal,
         pre_proposal_set: &mut HashSet<PreProposal>
     ) {
+        let private_valid = self.private_orders_are_valid(&pre_proposal);
         self.handle_proposal_verification(
             peer_id,
             pre_proposal,
             pre_proposal_set,
-            |proposal, block| proposal.is_valid(block)
+            |proposal, block| private_valid && proposal.is_valid(block)
         )
     }
 
@@ -311,9 +677,10 @@ where
         proposal_set: &mut HashSet<Pro>,
         valid: impl FnOnce(&Pro, &BlockNumber) -> bool
     ) where
-        Pro: Into<ConsensusMessage> + Eq + Hash + Clone
+        Pro: Into<ConsensusMessage> + ConsensusVote + Eq + Hash + Clone,
+        DuplicateVoteEvidence<Pro>: Into<Evidence>
     {
-        if !self.validators.iter().map(|v| v.peer_id).contains(&peer_id) {
+        if !self.is_validator(peer_id) {
             tracing::warn!(peer=?peer_id,"got a consensus message from a invalid peer");
             return
         }
@@ -323,6 +690,30 @@ where
             return
         }
 
+        // a validator signing two different messages for the same round is slashable,
+        // we keep the first one we saw
+        if let Some(existing) = proposal_set
+            .iter()
+            .find(|existing| existing.conflicts_with(&proposal))
+        {
+            tracing::warn!(
+                peer=?peer_id,
+                source=?proposal.source(),
+                "got a conflicting consensus message"
+            );
+            let validator_power = self.voting_power_of(proposal.source());
+            match DuplicateVoteEvidence::new(
+                existing.clone(),
+                proposal,
+                self.total_voting_power(),
+                validator_power
+            ) {
+                Ok(evidence) => self.record_evidence(evidence),
+                Err(e) => tracing::debug!(%e, "failed to build duplicate vote evidence")
+            }
+            return
+        }
+
         // if  we don't have the pre_proposal, propagate it and then store it.
         // else log a message
         if !proposal_set.contains(&proposal) {
@@ -334,13 +725,26 @@ where
     }
 }
 
+fn private_order_is_valid<O: RawPoolOrder>(
+    order: &OrderWithStorageData<O>,
+    next_block: BlockNumber
+) -> bool {
+    order.order.is_valid_signature()
+        && order.order_id.hash == order.order.order_hash()
+        && order
+            .order
+            .flash_block()
+            .is_none_or(|block| block == next_block)
+}
+
 /// These messages will only be broadcasted to the peer network if our consensus
 /// contracts don't currently contain them.
 #[derive(Debug, Clone)]
 pub enum ConsensusMessage {
     PropagatePreProposal(PreProposal),
     PropagatePreProposalAgg(PreProposalAggregation),
-    PropagateProposal(Proposal)
+    PropagateProposal(Proposal),
+    PropagateProposalTimeout(ProposalTimeout)
 }
 
 impl From<PreProposal> for ConsensusMessage {
@@ -355,6 +759,12 @@ impl From<PreProposalAggregation> for ConsensusMessage {
     }
 }
 
+impl From<ProposalTimeout> for ConsensusMessage {
+    fn from(value: ProposalTimeout) -> Self {
+        Self::PropagateProposalTimeout(value)
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use std::{
@@ -365,23 +775,34 @@ pub mod tests {
     };
 
     use alloy::{
-        primitives::Address,
+        primitives::{Address, B256},
         providers::{fillers::*, network::Ethereum, ProviderBuilder, RootProvider, *},
         transports::BoxTransport
     };
     use angstrom_metrics::ConsensusMetricsWrapper;
     use angstrom_network::manager::StromConsensusEvent;
     use angstrom_types::{
+        consensus::{
+            Evidence, PreProposal, PreProposalAggregation, PrivateOrders, Proposal, ProposalTimeout
+        },
         contract_payloads::angstrom::{AngstromPoolConfigStore, UniswapAngstromRegistry},
         mev_boost::MevBoostProvider,
-        primitive::{AngstromSigner, PeerId, UniswapPoolRegistry}
+        orders::{OrderFillState, OrderOutcome, PoolSolution},
+        primitive::{AngstromSigner, PeerId, PoolId, UniswapPoolRegistry},
+        sol_bindings::{
+            grouped_orders::{GroupedVanillaOrder, OrderWithStorageData},
+            rpc_orders::TopOfBlockOrder
+        }
     };
     use futures::{pin_mut, Stream};
     use order_pool::{order_storage::OrderStorage, PoolConfig};
     use testing_tools::{
         mocks::matching_engine::MockMatchingEngine,
-        type_generator::consensus::{
-            pre_proposal_agg::PreProposalAggregationBuilder, preproposal::PreproposalBuilder
+        type_generator::{
+            consensus::{
+                pre_proposal_agg::PreProposalAggregationBuilder, preproposal::PreproposalBuilder
+            },
+            orders::UserOrderBuilder
         }
     };
     use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
@@ -391,8 +812,11 @@ pub mod tests {
         pre_proposal::PreProposalState, ConsensusMessage, RoundStateMachine, SharedRoundState
     };
     use crate::{
-        rounds::{pre_proposal_aggregation::PreProposalAggregationState, ConsensusState},
-        AngstromValidator
+        rounds::{
+            finalization::tob_auction_mismatches,
+            pre_proposal_aggregation::PreProposalAggregationState, ConsensusState
+        },
+        AngstromValidator, ConsensusRoundName, ConsensusTimingConfig
     };
 
     impl RoundStateMachine<ProviderDef, MockMatchingEngine> {
@@ -425,12 +849,12 @@ pub mod tests {
     async fn setup_state_machine() -> RoundStateMachine<ProviderDef, MockMatchingEngine> {
         let order_storage = Arc::new(OrderStorage::new(&PoolConfig::default()));
         let signer = AngstromSigner::random();
-        let leader_id = signer.id();
+        let leader_id = signer.address();
 
         // Initialize test components
         let pool_store = Arc::new(AngstromPoolConfigStore::default());
         let (tx, _rx) = tokio::sync::mpsc::channel(2);
-        let uniswap_pools = SyncedUniswapPools::new(Arc::new(HashMap::new()), tx);
+        let uniswap_pools = SyncedUniswapPools::new(HashMap::new(), tx);
         let reg = UniswapPoolRegistry::default();
 
         let pool_registry = UniswapAngstromRegistry::new(reg, pool_store);
@@ -442,7 +866,7 @@ pub mod tests {
             .unwrap()
             .into();
 
-        let provider = MevBoostProvider::new_from_raw(querying_provider, vec![]);
+        let provider = MevBoostProvider::new_from_raw(querying_provider, vec![], 1);
 
         let shared_state = SharedRoundState::new(
             1, // block height
@@ -450,12 +874,14 @@ pub mod tests {
             order_storage,
             signer,
             leader_id,
+            vec![],
             vec![AngstromValidator::new(leader_id, 100)],
             ConsensusMetricsWrapper::new(),
             pool_registry,
             uniswap_pools,
             provider,
-            MockMatchingEngine {}
+            MockMatchingEngine {},
+            ConsensusTimingConfig::default()
         );
         RoundStateMachine::new(shared_state)
     }
@@ -583,10 +1009,10 @@ pub mod tests {
         init_tracing();
         let mut state_machine = setup_state_machine().await;
         let new_block = 2;
-        let new_leader = PeerId::random();
+        let new_leader = Address::random();
 
         // Reset round with new block and leader
-        state_machine.reset_round(new_block, new_leader);
+        state_machine.reset_round(new_block, new_leader, vec![], &[]);
 
         assert_eq!(state_machine.shared_state.block_height, new_block);
         assert_eq!(state_machine.shared_state.round_leader, new_leader);
@@ -602,6 +1028,126 @@ pub mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn test_silent_leader_falls_back_to_backup() {
+        init_tracing();
+        let mut state_machine = setup_state_machine().await;
+        let me = state_machine.shared_state.signer.address();
+        let silent_leader = Address::random();
+
+        let handles = &mut state_machine.shared_state;
+        handles.round_leader = silent_leader;
+        handles.backup_leaders = [me].into();
+        handles.timing.proposal_deadline = Duration::from_millis(10);
+        handles.validators =
+            vec![AngstromValidator::new(me, 300), AngstromValidator::new(silent_leader, 100)];
+
+        let state = Box::new(PreProposalAggregationState::new(
+            HashSet::default(),
+            HashSet::default(),
+            handles,
+            Instant::now(),
+            futures::task::noop_waker_ref().to_owned()
+        )) as Box<dyn ConsensusState<ProviderDef, MockMatchingEngine>>;
+        handles.messages.clear();
+        state_machine.set_state_machine_at(state);
+
+        pin_mut!(state_machine);
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+
+        // we have quorum but we aren't the leader, so we keep waiting
+        assert!(state_machine.as_mut().poll_next(&mut cx).is_pending());
+        assert_eq!(state_machine.round_info().state, ConsensusRoundName::PreProposalAggregation);
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // the leader never sent its proposal, we vote it out with enough voting
+        // power for quorum and take over the round
+        match state_machine.as_mut().poll_next(&mut cx) {
+            Poll::Ready(Some(ConsensusMessage::PropagateProposalTimeout(timeout))) => {
+                assert_eq!(timeout.leader, silent_leader);
+                let handles = &state_machine.shared_state;
+                assert!(timeout.is_valid(handles.provider.chain_id(), handles.angstrom_address));
+            }
+            _ => panic!("expected our timeout vote")
+        }
+        let info = state_machine.round_info();
+        assert_eq!(info.leader, me);
+        assert_eq!(info.missed_leaders, vec![silent_leader]);
+        assert_eq!(info.state, ConsensusRoundName::Proposal);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_votes_need_quorum() {
+        init_tracing();
+        let mut state_machine = setup_state_machine().await;
+        let me = state_machine.shared_state.signer.address();
+        let leader = Address::random();
+        let voters = [AngstromSigner::random(), AngstromSigner::random()];
+
+        let handles = &mut state_machine.shared_state;
+        handles.round_leader = leader;
+        handles.backup_leaders = [voters[0].address(), me].into();
+        handles.validators = vec![
+            AngstromValidator::new(me, 100),
+            AngstromValidator::new(leader, 100),
+            AngstromValidator::new(voters[0].address(), 100),
+            AngstromValidator::new(voters[1].address(), 100),
+        ];
+
+        let (chain_id, angstrom) = (handles.provider.chain_id(), handles.angstrom_address);
+        for voter in &voters {
+            let timeout = ProposalTimeout::new(1, leader, chain_id, angstrom, voter);
+            state_machine.handle_message(StromConsensusEvent::ProposalTimeout(voter.id(), timeout));
+        }
+        // a vote only counts once
+        let timeout = ProposalTimeout::new(1, leader, chain_id, angstrom, &voters[0]);
+        state_machine.handle_message(StromConsensusEvent::ProposalTimeout(voters[0].id(), timeout));
+        assert_eq!(state_machine.shared_state.messages.len(), 2);
+        assert_eq!(state_machine.round_info().leader, leader);
+
+        // our vote brings the timeout to quorum and every node moves to the same backup
+        state_machine.shared_state.vote_proposal_timeout(1, leader);
+        let info = state_machine.round_info();
+        assert_eq!(info.leader, voters[0].address());
+        assert_eq!(info.missed_leaders, vec![leader]);
+    }
+
+    #[tokio::test]
+    async fn test_leader_whose_bundle_never_landed_is_skipped() {
+        init_tracing();
+        let mut state_machine = setup_state_machine().await;
+        let me = state_machine.shared_state.signer.address();
+        let leader = Address::random();
+
+        let handles = &mut state_machine.shared_state;
+        handles.round_leader = leader;
+        handles.validators =
+            vec![AngstromValidator::new(me, 300), AngstromValidator::new(leader, 100)];
+
+        // a landed bundle is fine
+        state_machine.shared_state.expects_bundle = true;
+        state_machine.reset_round(2, leader, vec![me], &[leader]);
+        assert!(state_machine.shared_state.messages.is_empty());
+        assert_eq!(state_machine.round_info().leader, leader);
+
+        // there were orders to fill but nothing made it on chain
+        state_machine.shared_state.expects_bundle = true;
+        state_machine.reset_round(3, me, vec![leader], &[]);
+        assert!(matches!(
+            state_machine.shared_state.messages.pop_front(),
+            Some(ConsensusMessage::PropagateProposalTimeout(ProposalTimeout {
+                block_height: 2,
+                ..
+            }))
+        ));
+        assert_eq!(state_machine.round_info().missed_leaders, vec![leader]);
+
+        // when its turn comes around again, the backup leads instead
+        state_machine.reset_round(4, leader, vec![me], &[]);
+        assert_eq!(state_machine.round_info().leader, me);
+    }
+
     #[tokio::test]
     async fn test_invalid_messages_in_bid_aggregation_state() {
         init_tracing();
@@ -678,4 +1224,291 @@ pub mod tests {
         ));
         assert!(state_machine.shared_state.messages.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_stake_weighted_quorum() {
+        let mut state_machine = setup_state_machine().await;
+        let whale = AngstromSigner::random();
+        let minnows =
+            [AngstromSigner::random(), AngstromSigner::random(), AngstromSigner::random()];
+
+        // 1000 of the 1300 voting power is held by a single validator
+        state_machine.shared_state.validators = std::iter::once(&whale)
+            .map(|v| AngstromValidator::new(v.address(), 1000))
+            .chain(
+                minnows
+                    .iter()
+                    .map(|v| AngstromValidator::new(v.address(), 100))
+            )
+            .collect();
+        let handles = &state_machine.shared_state;
+
+        assert!(handles.has_quorum([whale.id()]));
+        // a majority by count isn't a quorum
+        assert!(!handles.has_quorum(minnows.iter().map(|v| v.id())));
+        // sources are only counted once
+        assert!(!handles.has_quorum([minnows[0].id(); 10]));
+        // unknown peers carry no voting power
+        assert!(!handles.has_quorum([PeerId::random(), PeerId::random(), PeerId::random()]));
+    }
+
+    #[tokio::test]
+    async fn test_stake_weighted_quorum_boundary() {
+        let mut state_machine = setup_state_machine().await;
+        let validators =
+            [AngstromSigner::random(), AngstromSigner::random(), AngstromSigner::random()];
+
+        // 2f+1 of 300 is exactly 200
+        state_machine.shared_state.validators = validators
+            .iter()
+            .zip([200, 50, 50])
+            .map(|(v, power)| AngstromValidator::new(v.address(), power))
+            .collect();
+        let handles = &state_machine.shared_state;
+
+        assert!(handles.has_quorum([validators[0].id()]));
+        assert!(!handles.has_quorum([validators[1].id(), validators[2].id()]));
+    }
+
+    #[tokio::test]
+    async fn test_filter_quorum_orders_by_stake() {
+        let mut state_machine = setup_state_machine().await;
+        let whale = AngstromSigner::random();
+        let minnows =
+            [AngstromSigner::random(), AngstromSigner::random(), AngstromSigner::random()];
+
+        state_machine.shared_state.validators = std::iter::once(&whale)
+            .map(|v| AngstromValidator::new(v.address(), 1000))
+            .chain(
+                minnows
+                    .iter()
+                    .map(|v| AngstromValidator::new(v.address(), 100))
+            )
+            .collect();
+
+        let whale_order = OrderWithStorageData { order: 1u8, ..Default::default() };
+        let minnow_order = OrderWithStorageData { order: 2u8, ..Default::default() };
+
+        let input: Vec<_> = std::iter::once((whale.id(), vec![whale_order.clone()]))
+            .chain(
+                minnows
+                    .iter()
+                    // duplicates in a single pre-proposal shouldn't add weight
+                    .map(|v| (v.id(), vec![minnow_order.clone(), minnow_order.clone()]))
+            )
+            .collect();
+
+        let filtered = state_machine.shared_state.filter_quorum_orders(input);
+        assert_eq!(filtered, vec![whale_order]);
+    }
+
+    #[tokio::test]
+    async fn test_private_order_on_one_node_is_filled() {
+        init_tracing();
+        let mut state_machine = setup_state_machine().await;
+        let me = state_machine.shared_state.signer.clone();
+        let other = AngstromSigner::random();
+        // neither of us can reach quorum alone
+        state_machine.shared_state.validators = vec![
+            AngstromValidator::new(me.address(), 100),
+            AngstromValidator::new(other.address(), 100),
+        ];
+
+        let limit_order = |hash: B256| {
+            let mut order = OrderWithStorageData::<GroupedVanillaOrder>::default();
+            order.order_id.hash = hash;
+            order
+        };
+        let private_order = signed_limit_order();
+        let (private_hash, public_hash) = (private_order.order_id.hash, B256::random());
+        let private = PrivateOrders { limit: vec![private_order], searcher: vec![] };
+
+        // only we have both orders, the private one in the private section
+        let aggregation = PreProposalAggregation::new(
+            1,
+            &me,
+            vec![
+                PreProposal::generate_with_private(
+                    1,
+                    &me,
+                    vec![limit_order(public_hash)],
+                    vec![],
+                    private
+                ),
+                PreProposal::generate_pre_proposal(1, &other, vec![], vec![]),
+            ]
+        );
+
+        let (limit, _) = state_machine
+            .shared_state
+            .proposal_orders(HashSet::from([aggregation.clone()]));
+        assert_eq!(
+            limit
+                .iter()
+                .map(|order| order.order_id.hash)
+                .collect::<Vec<_>>(),
+            vec![private_hash]
+        );
+
+        let proposal_filling = |hash: B256| {
+            let mut outcome =
+                OrderOutcome { id: Default::default(), outcome: OrderFillState::CompleteFill };
+            outcome.id.hash = hash;
+            let solution = PoolSolution { limit: vec![outcome], ..Default::default() };
+            Proposal::generate_proposal(1, &me, vec![aggregation.clone()], vec![solution])
+        };
+
+        let filled = proposal_filling(private_hash);
+        assert_eq!(
+            state_machine
+                .shared_state
+                .verify_proposal(me.id(), filled.clone()),
+            Some(filled)
+        );
+        // the public order never reached quorum so it can't be filled
+        assert!(state_machine
+            .shared_state
+            .verify_proposal(me.id(), proposal_filling(public_hash))
+            .is_none());
+    }
+
+    fn signed_limit_order() -> OrderWithStorageData<GroupedVanillaOrder> {
+        UserOrderBuilder::new()
+            .standing()
+            .signing_key(Some(AngstromSigner::random()))
+            .with_storage()
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_invalid_private_orders_are_rejected() {
+        init_tracing();
+        let mut state_machine = setup_state_machine().await;
+        let me = state_machine.shared_state.signer.clone();
+
+        // claims to be a different order than the one that was signed
+        let mut forged = signed_limit_order();
+        forged.order_id.hash = B256::random();
+        let private = PrivateOrders { limit: vec![forged], searcher: vec![] };
+        let pre_proposal = PreProposal::generate_with_private(1, &me, vec![], vec![], private);
+
+        let mut pre_proposals = HashSet::new();
+        state_machine.shared_state.handle_pre_proposal(
+            me.id(),
+            pre_proposal.clone(),
+            &mut pre_proposals
+        );
+        assert!(pre_proposals.is_empty());
+
+        // nor does it make it into the proposal through someone else's aggregation
+        let aggregation = PreProposalAggregation::new(1, &me, vec![pre_proposal]);
+        let (limit, _) = state_machine
+            .shared_state
+            .proposal_orders(HashSet::from([aggregation]));
+        assert!(limit.is_empty());
+
+        let private = PrivateOrders { limit: vec![signed_limit_order()], searcher: vec![] };
+        state_machine.shared_state.handle_pre_proposal(
+            me.id(),
+            PreProposal::generate_with_private(1, &me, vec![], vec![], private),
+            &mut pre_proposals
+        );
+        assert_eq!(pre_proposals.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_private_orders_from_non_validators_are_rejected() {
+        init_tracing();
+        let mut state_machine = setup_state_machine().await;
+        let me = state_machine.shared_state.signer.clone();
+        let outsider = AngstromSigner::random();
+
+        let private =
+            PrivateOrders { limit: vec![OrderWithStorageData::default()], searcher: vec![] };
+        let aggregation = PreProposalAggregation::new(
+            1,
+            &me,
+            vec![
+                PreProposal::generate_pre_proposal(1, &me, vec![], vec![]),
+                PreProposal::generate_with_private(1, &outsider, vec![], vec![], private),
+            ]
+        );
+        let proposal = Proposal::generate_proposal(1, &me, vec![aggregation], vec![]);
+
+        assert!(state_machine
+            .shared_state
+            .verify_proposal(me.id(), proposal)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_conflicting_pre_proposals_produce_evidence() {
+        init_tracing();
+        let mut state_machine = setup_state_machine().await;
+        let signer = state_machine.shared_state.signer.clone();
+
+        let pre_proposal = PreProposal::generate_pre_proposal(1, &signer, vec![], vec![]);
+        let conflicting = PreProposal::generate_pre_proposal(
+            1,
+            &signer,
+            vec![OrderWithStorageData::default()],
+            vec![]
+        );
+
+        state_machine.handle_message(StromConsensusEvent::PreProposal(signer.id(), pre_proposal));
+        // the same message again isn't a conflict
+        state_machine.handle_message(StromConsensusEvent::PreProposal(
+            signer.id(),
+            PreProposal::generate_pre_proposal(1, &signer, vec![], vec![])
+        ));
+        state_machine.handle_message(StromConsensusEvent::PreProposal(signer.id(), conflicting));
+
+        // only the first pre-proposal is propagated
+        assert_eq!(state_machine.shared_state.messages.len(), 1);
+
+        let evidence = state_machine.take_evidence();
+        assert_eq!(evidence.len(), 1);
+        assert!(
+            matches!(&evidence[0], Evidence::DuplicatePreProposal(e) if e.vote_a.source == signer.id())
+        );
+        assert!(state_machine.take_evidence().is_empty());
+    }
+
+    #[test]
+    fn test_tob_auction_mismatches() {
+        let tob = |hash: B256| {
+            let mut order = OrderWithStorageData::<TopOfBlockOrder>::default();
+            order.order_id.hash = hash;
+            order
+        };
+        let solution = |id: PoolId, searcher: Option<B256>| PoolSolution {
+            id,
+            searcher: searcher.map(tob),
+            ..Default::default()
+        };
+        let (pool_a, pool_b, pool_c) = (PoolId::random(), PoolId::random(), PoolId::random());
+        let (winner, loser) = (B256::random(), B256::random());
+
+        let expected = vec![
+            solution(pool_a, Some(winner)),
+            solution(pool_b, Some(winner)),
+            solution(pool_c, None),
+        ];
+        assert!(tob_auction_mismatches(&expected, &expected).is_empty());
+
+        let mut proposed = vec![
+            solution(pool_a, Some(winner)),
+            solution(pool_b, Some(loser)),
+            solution(pool_c, Some(loser)),
+        ];
+        let mut mismatches = tob_auction_mismatches(&proposed, &expected);
+        mismatches.sort();
+        let mut wrong_pools = vec![pool_b, pool_c];
+        wrong_pools.sort();
+        assert_eq!(mismatches, wrong_pools);
+
+        // leaving out a winner is a mismatch too
+        proposed.truncate(1);
+        assert_eq!(tob_auction_mismatches(&proposed, &expected), vec![pool_b]);
+    }
 }
@@ -10,9 +10,12 @@ use angstrom_types::consensus::{PreProposal, PreProposalAggregation, Proposal};
 use matching_engine::MatchingEngineHandle;
 
 use super::{ConsensusState, SharedRoundState};
-use crate::rounds::{
-    finalization::FinalizationState, pre_proposal_aggregation::PreProposalAggregationState,
-    ConsensusMessage
+use crate::{
+    rounds::{
+        finalization::FinalizationState, pre_proposal_aggregation::PreProposalAggregationState,
+        ConsensusMessage
+    },
+    ConsensusRoundName
 };
 
 /// PreProposalState
@@ -44,8 +47,14 @@ impl PreProposalState {
         Matching: MatchingEngineHandle
     {
         // generate my pre_proposal
-        let my_preproposal =
-            PreProposal::new(block_height, &handles.signer, handles.order_storage.get_all_orders());
+        let (orders, private) = handles.order_storage.get_pre_proposal_orders();
+        handles
+            .order_storage
+            .record_pre_proposal(block_height + 1, &orders);
+        handles
+            .order_storage
+            .record_pre_proposal(block_height + 1, &private);
+        let my_preproposal = PreProposal::new(block_height, &handles.signer, orders, private);
 
         // propagate my pre_proposal
         handles.propagate_message(ConsensusMessage::PropagatePreProposal(my_preproposal.clone()));
@@ -75,7 +84,7 @@ where
             StromConsensusEvent::PreProposal(peer_id, pre_proposal) => {
                 handles.handle_pre_proposal(peer_id, pre_proposal, &mut self.pre_proposals);
 
-                if self.pre_proposals.len() >= handles.two_thirds_of_validation_set() {
+                if handles.has_quorum(self.pre_proposals.iter().map(|pre| pre.source)) {
                     self.waker.wake_by_ref();
                 }
             }
@@ -92,9 +101,15 @@ where
                     self.waker.wake_by_ref();
                 }
             }
+            // handled by the round state machine
+            StromConsensusEvent::ProposalTimeout(..) => {}
         }
     }
 
+    fn name(&self) -> ConsensusRoundName {
+        ConsensusRoundName::PreProposal
+    }
+
     fn poll_transition(
         &mut self,
         handles: &mut SharedRoundState<P, Matching>,
@@ -109,9 +124,7 @@ where
             ))))
         }
 
-        let cur_preproposals = self.pre_proposals.len();
-        let twthr = handles.two_thirds_of_validation_set();
-        if cur_preproposals >= twthr {
+        if handles.has_quorum(self.pre_proposals.iter().map(|pre| pre.source)) {
             tracing::info!("got two thrids, moving to pre proposal aggregation");
 
             return Poll::Ready(Some(Box::new(PreProposalAggregationState::new(
@@ -1,16 +1,25 @@
 use std::{
     collections::HashSet,
+    pin::Pin,
     task::{Context, Poll, Waker},
     time::Instant
 };
 
-use alloy::providers::Provider;
+use alloy::{primitives::Address, providers::Provider};
 use angstrom_network::manager::StromConsensusEvent;
-use angstrom_types::consensus::{PreProposal, PreProposalAggregation, Proposal};
+use angstrom_types::{
+    consensus::{PreProposal, PreProposalAggregation, Proposal},
+    primitive::AngstromSigner
+};
+use futures::FutureExt;
 use matching_engine::MatchingEngineHandle;
+use tokio::time::{sleep, Sleep};
 
 use super::{ConsensusState, SharedRoundState};
-use crate::rounds::{finalization::FinalizationState, proposal::ProposalState};
+use crate::{
+    rounds::{finalization::FinalizationState, proposal::ProposalState},
+    ConsensusRoundName
+};
 
 /// PreProposalAggregationState
 ///
@@ -21,10 +30,20 @@ use crate::rounds::{finalization::FinalizationState, proposal::ProposalState};
 /// this node is the leader and receives 2/3 pre_proposals_aggregation ->
 /// proposal state
 /// 2) this node isn't leader and receives the proposal -> finalization
+///
+/// If we haven't heard from the leader by the proposal deadline, we vote to
+/// time it out. Once the votes reach quorum, the round is handed to the next
+/// backup leader, which could be us.
 #[derive(Debug)]
 pub struct PreProposalAggregationState {
     pre_proposals_aggregation: HashSet<PreProposalAggregation>,
     proposal:                  Option<Proposal>,
+    /// unset once it fired for the leader we are waiting on
+    proposal_deadline:         Option<Pin<Box<Sleep>>>,
+    /// the leader the proposal deadline is running for
+    deadline_leader:           Address,
+    /// if we already checked what the leader has to put into its bundle
+    checked_for_orders:        bool,
     trigger_time:              Instant,
     waker:                     Waker
 }
@@ -58,7 +77,54 @@ impl PreProposalAggregationState {
         waker.wake_by_ref();
         tracing::info!("starting pre proposal aggregation");
 
-        Self { pre_proposals_aggregation, proposal: None, waker, trigger_time }
+        Self {
+            pre_proposals_aggregation,
+            proposal: None,
+            proposal_deadline: Some(Box::pin(sleep(handles.timing.proposal_deadline))),
+            deadline_leader: handles.round_leader,
+            checked_for_orders: false,
+            waker,
+            trigger_time
+        }
+    }
+
+    /// only a verified proposal from the current leader counts. Its aggregation
+    /// alone doesn't mean it will ever build the proposal
+    fn heard_from_leader<P, Matching>(&self, handles: &SharedRoundState<P, Matching>) -> bool
+    where
+        P: Provider + 'static,
+        Matching: MatchingEngineHandle
+    {
+        self.proposal.as_ref().is_some_and(|proposal| {
+            AngstromSigner::peer_id_to_address(proposal.source) == handles.round_leader
+        })
+    }
+
+    fn poll_proposal_deadline<P, Matching>(
+        &mut self,
+        handles: &mut SharedRoundState<P, Matching>,
+        cx: &mut Context<'_>
+    ) where
+        P: Provider + 'static,
+        Matching: MatchingEngineHandle
+    {
+        // a quorum moved the round on, the backup gets the same amount of time
+        if self.deadline_leader != handles.round_leader {
+            self.deadline_leader = handles.round_leader;
+            self.proposal_deadline = Some(Box::pin(sleep(handles.timing.proposal_deadline)));
+        }
+
+        let Some(deadline) = self.proposal_deadline.as_mut() else { return };
+        if deadline.poll_unpin(cx).is_pending() {
+            return
+        }
+        self.proposal_deadline = None;
+
+        if !handles.i_am_leader() && !self.heard_from_leader(handles) {
+            handles.vote_proposal_timeout(handles.block_height, handles.round_leader);
+            // our vote can complete the quorum
+            cx.waker().wake_by_ref();
+        }
     }
 }
 
@@ -88,9 +154,15 @@ where
                     self.waker.wake_by_ref();
                 }
             }
+            // handled by the round state machine
+            StromConsensusEvent::ProposalTimeout(..) => {}
         }
     }
 
+    fn name(&self) -> ConsensusRoundName {
+        ConsensusRoundName::PreProposalAggregation
+    }
+
     fn poll_transition(
         &mut self,
         handles: &mut SharedRoundState<P, Matching>,
@@ -105,14 +177,21 @@ where
                 cx.waker().clone()
             ))))
         }
+        self.poll_proposal_deadline(handles, cx);
+
         let cur_preproposals_aggs = self.pre_proposals_aggregation.len();
-        let twthr = handles.two_thirds_of_validation_set();
+        let has_quorum =
+            handles.has_quorum(self.pre_proposals_aggregation.iter().map(|agg| agg.source));
+
+        if has_quorum && !self.checked_for_orders {
+            self.checked_for_orders = true;
+            handles.expects_bundle = handles.has_orders_to_propose(&self.pre_proposals_aggregation);
+        }
 
         // if  we are the leader, then we will transition
-        if cur_preproposals_aggs >= twthr && handles.i_am_leader() {
+        if has_quorum && handles.i_am_leader() {
             tracing::info!(
                 ?cur_preproposals_aggs,
-                ?twthr,
                 is_leader = handles.i_am_leader(),
                 "aggregation transition to proposal"
             );
@@ -7,19 +7,13 @@ use std::{
 
 use tokio::time::{interval, Interval};
 
-use crate::rounds::OrderStorage;
+use crate::{rounds::OrderStorage, ConsensusTimingConfig};
 
-/// How soon we send our pre-proposal
-const DEFAULT_DURATION: Duration = Duration::from_secs(9);
 /// The frequency we adjust our duration estimate. we have it super frequent
 /// because its very low overhead to check
 const CHECK_INTERVAL: Duration = Duration::from_millis(1);
 /// How much to scale per order in the order pool
 const ORDER_SCALING: Duration = Duration::from_millis(10);
-/// How close we want to be to the creation of the ethereum block
-const TARGET_SUBMISSION_TIME_REM: Duration = Duration::from_millis(800);
-/// Eth block time
-const ETH_BLOCK_TIME: Duration = Duration::from_secs(12);
 /// The amount of the difference we scale by to reach
 const SCALING_REM_ADJUSTMENT: u32 = 3;
 
@@ -31,30 +25,38 @@ const SCALING_REM_ADJUSTMENT: u32 = 3;
 #[derive(Debug)]
 pub struct PreProposalWaitTrigger {
     /// the base wait duration that we scale down based on orders.
-    wait_duration:  Duration,
+    wait_duration:              Duration,
+    /// how close to the next block we want our submission to land
+    target_submission_time_rem: Duration,
+    /// block time of the chain we are on
+    block_time:                 Duration,
     /// the start instant
-    start_instant:  Instant,
+    start_instant:              Instant,
     /// to track our scaling
-    order_storage:  Arc<OrderStorage>,
+    order_storage:              Arc<OrderStorage>,
     /// Waker
-    check_interval: Interval
+    check_interval:             Interval
 }
 
 impl Clone for PreProposalWaitTrigger {
     fn clone(&self) -> Self {
         Self {
-            wait_duration:  self.wait_duration,
-            start_instant:  Instant::now(),
-            order_storage:  self.order_storage.clone(),
-            check_interval: interval(CHECK_INTERVAL)
+            wait_duration:              self.wait_duration,
+            target_submission_time_rem: self.target_submission_time_rem,
+            block_time:                 self.block_time,
+            start_instant:              Instant::now(),
+            order_storage:              self.order_storage.clone(),
+            check_interval:             interval(CHECK_INTERVAL)
         }
     }
 }
 
 impl PreProposalWaitTrigger {
-    pub fn new(order_storage: Arc<OrderStorage>) -> Self {
+    pub fn new(order_storage: Arc<OrderStorage>, timing: ConsensusTimingConfig) -> Self {
         Self {
-            wait_duration: DEFAULT_DURATION,
+            wait_duration: timing.pre_proposal_wait,
+            target_submission_time_rem: timing.target_submission_time_rem,
+            block_time: timing.block_time,
             order_storage,
             start_instant: Instant::now(),
             check_interval: interval(CHECK_INTERVAL)
@@ -72,11 +74,11 @@ impl PreProposalWaitTrigger {
     pub fn reset_before_submission(&mut self) {
         self.wait_duration = self
             .wait_duration
-            .saturating_sub(TARGET_SUBMISSION_TIME_REM);
+            .saturating_sub(self.target_submission_time_rem);
     }
 
     fn update_wait_duration_base(&mut self, info: LastRoundInfo) {
-        let base = ETH_BLOCK_TIME - TARGET_SUBMISSION_TIME_REM;
+        let base = self.block_time - self.target_submission_time_rem;
 
         if info.time_to_complete < base && self.wait_duration < base {
             // if we overestimated the time, we will push our trigger back
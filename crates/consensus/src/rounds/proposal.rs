@@ -20,7 +20,10 @@ use matching_engine::MatchingEngineHandle;
 use pade::PadeEncode;
 
 use super::{ConsensusState, SharedRoundState};
-use crate::rounds::{preproposal_wait_trigger::LastRoundInfo, ConsensusMessage};
+use crate::{
+    rounds::{preproposal_wait_trigger::LastRoundInfo, ConsensusMessage},
+    ConsensusRoundName
+};
 
 type MatchingEngineFuture = BoxFuture<'static, eyre::Result<(Vec<PoolSolution>, BundleGasDetails)>>;
 
@@ -100,6 +103,7 @@ impl ProposalState {
 
         self.proposal = Some(proposal.clone());
         let snapshot = handles.fetch_pool_snapshot();
+        let gas_details = gas_info.clone();
 
         let Ok(bundle) =
             AngstromBundle::from_proposal(&proposal, gas_info, &snapshot).inspect_err(|e| {
@@ -111,6 +115,10 @@ impl ProposalState {
             return false
         };
 
+        handles
+            .order_storage
+            .record_bundle(handles.block_height + 1, &proposal.solutions);
+
         let encoded = Angstrom::executeCall::new((bundle.pade_encode().into(),)).abi_encode();
 
         let mut tx = TransactionRequest::default()
@@ -120,18 +128,39 @@ impl ProposalState {
 
         let provider = handles.provider.clone();
         let signer = handles.signer.clone();
+        let target_block = handles.block_height + 1;
 
         let submission_future = async move {
             tracing::info!("building bundle");
             provider
-                .populate_gas_nonce_chain_id(signer.address(), &mut tx)
+                .populate_gas_nonce_chain_id(signer.address(), &mut tx, &gas_details)
                 .await;
 
-            let (hash, success) = provider.sign_and_send(signer, tx).await;
-            tracing::info!("submitted bundle");
-            if !success {
+            let submission = match provider.sign_and_send(signer, tx, target_block).await {
+                Ok(submission) => submission,
+                Err(e) => {
+                    tracing::error!(err=%e, "failed to sign bundle transaction");
+                    return false
+                }
+            };
+            for rejected in submission.rejected() {
+                tracing::warn!(
+                    endpoint=%rejected.endpoint,
+                    err=?rejected.result,
+                    target_block,
+                    "builder did not accept bundle"
+                );
+            }
+            tracing::info!(
+                tx_hash=?submission.tx_hash,
+                target_block,
+                builders=submission.builders.len(),
+                "submitted bundle"
+            );
+            if !submission.any_accepted() {
                 return false
             }
+            let hash = submission.tx_hash;
 
             // wait for next block. then see if transaction landed
             provider
@@ -172,6 +201,10 @@ where
         // ignored.
     }
 
+    fn name(&self) -> ConsensusRoundName {
+        ConsensusRoundName::Proposal
+    }
+
     fn poll_transition(
         &mut self,
         handles: &mut SharedRoundState<P, Matching>,
@@ -0,0 +1,742 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    ops::{Range, RangeInclusive},
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock}
+};
+
+use alloy::{
+    consensus::Transaction,
+    primitives::{
+        aliases::{I24, U24},
+        Address, BlockNumber, B256, U256
+    },
+    sol_types::SolCall
+};
+use angstrom_types::{
+    contract_bindings::angstrom::Angstrom::{executeCall, PoolKey},
+    contract_payloads::{
+        angstrom::{AngstromBundle, AngstromPoolConfigStore},
+        rewards::RewardsUpdate,
+        Asset, Pair
+    },
+    primitive::PoolId
+};
+use pade::PadeDecode;
+use reth_primitives::{Block, Receipt, TransactionSigned};
+use reth_provider::{BlockReader, CanonStateNotification};
+use reth_tasks::TaskSpawner;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+
+/// How far behind the first block we missed a re-sync re-indexes from, as a
+/// reorg we didn't hear about can reach back past it
+const RESYNC_DEPTH: u64 = 150;
+
+/// The price a pool cleared at in a landed bundle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClearingPrice {
+    pub block_number:   BlockNumber,
+    pub pool_id:        PoolId,
+    pub token0:         Address,
+    pub token1:         Address,
+    pub price_1_over_0: U256
+}
+
+/// An order that was filled by a landed bundle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedFill {
+    pub block_number: BlockNumber,
+    pub order_hash:   B256,
+    pub from:         Address,
+    pub pool_id:      PoolId,
+    /// true if this was the winning top of block order for the pool
+    pub top_of_block: bool
+}
+
+/// The rewards a landed bundle paid out to a pool's liquidity providers,
+/// denominated in token0
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolReward {
+    pub block_number: BlockNumber,
+    pub pool_id:      PoolId,
+    pub amount:       u128
+}
+
+/// Everything we keep about the bundle that landed in a block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedBundle {
+    pub block_number:    BlockNumber,
+    pub clearing_prices: Vec<ClearingPrice>,
+    pub fills:           Vec<IndexedFill>,
+    pub pool_rewards:    Vec<PoolReward>
+}
+
+impl IndexedBundle {
+    /// Resolves the pairs of the bundle to their pool ids. The order hashes
+    /// are the same ones that are reported as filled in
+    /// [`crate::manager::EthEvent::NewBlockTransitions`]
+    pub fn from_bundle(
+        block_number: BlockNumber,
+        bundle: &AngstromBundle,
+        angstrom_address: Address,
+        pool_store: &AngstromPoolConfigStore
+    ) -> Self {
+        let pool_ids = bundle
+            .pairs
+            .iter()
+            .map(|pair| pool_id(pair, &bundle.assets, angstrom_address, pool_store))
+            .collect::<Vec<_>>();
+        let pool_of = |pair_index: u16| pool_ids.get(pair_index as usize).copied().flatten();
+
+        let clearing_prices = bundle
+            .pairs
+            .iter()
+            .zip(&pool_ids)
+            .filter_map(|(pair, pool_id)| {
+                Some(ClearingPrice {
+                    block_number,
+                    pool_id: (*pool_id)?,
+                    token0: bundle.assets.get(pair.index0 as usize)?.addr,
+                    token1: bundle.assets.get(pair.index1 as usize)?.addr,
+                    price_1_over_0: pair.price_1over0
+                })
+            })
+            .collect();
+
+        // a resolved pool means the order's pair and assets are in the bundle, so
+        // hashing it can't index out of bounds
+        let tob_fills = bundle.top_of_block_orders.iter().filter_map(|order| {
+            let pool_id = pool_of(order.pairs_index)?;
+            let order_hash = order.order_hash(&bundle.pairs, &bundle.assets, block_number);
+            Some(IndexedFill {
+                block_number,
+                order_hash,
+                from: order.signature.recover_signer(order_hash),
+                pool_id,
+                top_of_block: true
+            })
+        });
+        let user_fills = bundle.user_orders.iter().filter_map(|order| {
+            let pool_id = pool_of(order.pair_index)?;
+            let order_hash = order.order_hash(&bundle.pairs, &bundle.assets, block_number);
+            Some(IndexedFill {
+                block_number,
+                order_hash,
+                from: order.signature.recover_signer(order_hash),
+                pool_id,
+                top_of_block: false
+            })
+        });
+
+        let pool_rewards = bundle
+            .pool_updates
+            .iter()
+            .filter_map(|update| {
+                let amount = match &update.rewards_update {
+                    RewardsUpdate::MultiTick { quantities, .. } => quantities.iter().sum(),
+                    RewardsUpdate::CurrentOnly { amount } => *amount
+                };
+                Some(PoolReward { block_number, pool_id: pool_of(update.pair_index)?, amount })
+            })
+            .collect();
+
+        Self {
+            block_number,
+            clearing_prices,
+            fills: tob_fills.chain(user_fills).collect(),
+            pool_rewards
+        }
+    }
+
+    /// decodes the bundle sent to the angstrom contract in the given block, if
+    /// one landed. Transactions that reverted didn't settle anything and are
+    /// skipped, as are ones that aren't a well formed `execute` call
+    pub fn from_block<'a>(
+        block_number: BlockNumber,
+        transactions: impl IntoIterator<Item = (&'a TransactionSigned, &'a Receipt)>,
+        angstrom_address: Address,
+        pool_store: &AngstromPoolConfigStore
+    ) -> Option<Self> {
+        transactions
+            .into_iter()
+            .filter(|(tx, receipt)| {
+                receipt.success && tx.transaction.to() == Some(angstrom_address)
+            })
+            .find_map(|(tx, _)| {
+                let call = executeCall::abi_decode(tx.input(), true).ok()?;
+                AngstromBundle::pade_decode(&mut call.encoded.as_ref(), None).ok()
+            })
+            .map(|bundle| Self::from_bundle(block_number, &bundle, angstrom_address, pool_store))
+    }
+}
+
+fn pool_id(
+    pair: &Pair,
+    assets: &[Asset],
+    angstrom_address: Address,
+    pool_store: &AngstromPoolConfigStore
+) -> Option<PoolId> {
+    let token0 = assets.get(pair.index0 as usize)?.addr;
+    let token1 = assets.get(pair.index1 as usize)?.addr;
+    let Some(entry) = pool_store.get_entry(token0, token1) else {
+        tracing::warn!(?token0, ?token1, "bundle settled a pair that has no pool config entry");
+        return None
+    };
+
+    Some(PoolId::from(PoolKey {
+        currency0:   token0,
+        currency1:   token1,
+        tickSpacing: I24::from_limbs([entry.tick_spacing as u64]),
+        hooks:       angstrom_address,
+        fee:         U24::from_limbs([entry.fee_in_e6 as u64])
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum IndexEntry {
+    Block(IndexedBundle),
+    /// every block from this height on is no longer canonical
+    Reverted(BlockNumber),
+    /// every block up to and including this height was indexed
+    Synced(BlockNumber)
+}
+
+#[derive(Debug, Default)]
+struct IndexInner {
+    blocks:     BTreeMap<BlockNumber, IndexedBundle>,
+    by_address: HashMap<Address, BTreeSet<BlockNumber>>,
+    synced_to:  Option<BlockNumber>,
+    file:       Option<(PathBuf, File)>
+}
+
+impl IndexInner {
+    fn apply(&mut self, entry: IndexEntry) {
+        match entry {
+            IndexEntry::Block(bundle) => {
+                if let Some(replaced) = self.blocks.remove(&bundle.block_number) {
+                    self.forget_fills(bundle.block_number, replaced);
+                }
+                for fill in &bundle.fills {
+                    self.by_address
+                        .entry(fill.from)
+                        .or_default()
+                        .insert(bundle.block_number);
+                }
+                self.blocks.insert(bundle.block_number, bundle);
+            }
+            IndexEntry::Reverted(from) => {
+                self.revert(from);
+                if self.synced_to.is_some_and(|synced| synced >= from) {
+                    self.synced_to = from.checked_sub(1);
+                }
+            }
+            IndexEntry::Synced(block) => self.synced_to = Some(block)
+        }
+    }
+
+    fn revert(&mut self, from: BlockNumber) {
+        for (block, bundle) in self.blocks.split_off(&from) {
+            self.forget_fills(block, bundle);
+        }
+    }
+
+    fn forget_fills(&mut self, block: BlockNumber, bundle: IndexedBundle) {
+        for fill in bundle.fills {
+            if let Some(blocks) = self.by_address.get_mut(&fill.from) {
+                blocks.remove(&block);
+                if blocks.is_empty() {
+                    self.by_address.remove(&fill.from);
+                }
+            }
+        }
+    }
+
+    fn write(&mut self, entry: &IndexEntry) {
+        let Some((path, file)) = self.file.as_mut() else { return };
+        if let Err(e) = write_entry(file, entry) {
+            tracing::error!(%e, ?path, "failed to write to bundle index");
+        }
+    }
+}
+
+fn write_entry(file: &mut File, entry: &IndexEntry) -> anyhow::Result<()> {
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    file.write_all(line.as_bytes())?;
+
+    Ok(())
+}
+
+/// Clearing prices, fills and rewards of every bundle that landed on chain,
+/// keyed by block. Cheap to clone, all clones share the same index.
+#[derive(Debug, Clone, Default)]
+pub struct BundleIndex {
+    inner: Arc<RwLock<IndexInner>>
+}
+
+impl BundleIndex {
+    /// Loads the index persisted at the given path and appends all further
+    /// updates to it. The file is compacted down to the canonical blocks
+    /// every time it is attached.
+    pub fn attach_file(&self, path: PathBuf) -> anyhow::Result<()> {
+        let mut inner = self.inner.write().unwrap();
+        if path.exists() {
+            for entry in Self::read_entries(&path)? {
+                inner.apply(entry);
+            }
+        }
+
+        let tmp = path.with_extension("tmp");
+        let mut file = File::create(&tmp)?;
+        for bundle in inner.blocks.values() {
+            write_entry(&mut file, &IndexEntry::Block(bundle.clone()))?;
+        }
+        if let Some(synced) = inner.synced_to {
+            write_entry(&mut file, &IndexEntry::Synced(synced))?;
+        }
+        file.sync_all()?;
+        std::fs::rename(&tmp, &path)?;
+
+        let file = OpenOptions::new().append(true).open(&path)?;
+        tracing::info!(?path, blocks = inner.blocks.len(), "opened bundle index");
+        inner.file = Some((path, file));
+
+        Ok(())
+    }
+
+    fn read_entries(path: &Path) -> anyhow::Result<Vec<IndexEntry>> {
+        let mut entries = Vec::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            // a crash can leave a partially written last line
+            let Ok(entry) = serde_json::from_str::<IndexEntry>(&line) else {
+                tracing::warn!(?path, "skipping corrupt bundle index entry");
+                continue
+            };
+            entries.push(entry);
+        }
+
+        Ok(entries)
+    }
+
+    pub fn insert(&self, bundle: IndexedBundle) {
+        self.update(IndexEntry::Block(bundle));
+    }
+
+    /// drops every block from the given height on, used when they get
+    /// reorged out
+    pub fn revert(&self, from: BlockNumber) {
+        self.update(IndexEntry::Reverted(from));
+    }
+
+    /// marks every block up to and including the given one as indexed, so a
+    /// restart picks up after it
+    pub fn set_synced(&self, block: BlockNumber) {
+        self.update(IndexEntry::Synced(block));
+    }
+
+    /// the height up to which every block was indexed, if any were
+    pub fn synced_to(&self) -> Option<BlockNumber> {
+        self.inner.read().unwrap().synced_to
+    }
+
+    fn update(&self, entry: IndexEntry) {
+        let mut inner = self.inner.write().unwrap();
+        inner.write(&entry);
+        inner.apply(entry);
+    }
+
+    pub fn bundle(&self, block_number: BlockNumber) -> Option<IndexedBundle> {
+        self.inner
+            .read()
+            .unwrap()
+            .blocks
+            .get(&block_number)
+            .cloned()
+    }
+
+    /// the prices the pool cleared at in the given (inclusive) block range
+    pub fn clearing_prices(
+        &self,
+        pool_id: PoolId,
+        blocks: RangeInclusive<BlockNumber>
+    ) -> Vec<ClearingPrice> {
+        self.inner
+            .read()
+            .unwrap()
+            .blocks
+            .range(blocks)
+            .flat_map(|(_, bundle)| &bundle.clearing_prices)
+            .filter(|price| price.pool_id == pool_id)
+            .cloned()
+            .collect()
+    }
+
+    /// the indexed fills of orders signed by the given address from
+    /// `from_block` on, oldest first. Whole blocks are returned until at least
+    /// `limit` fills were collected, so that the next page starts at the block
+    /// after the last returned fill
+    pub fn fills_by_address(
+        &self,
+        address: Address,
+        from_block: BlockNumber,
+        limit: usize
+    ) -> Vec<IndexedFill> {
+        let inner = self.inner.read().unwrap();
+        let Some(blocks) = inner.by_address.get(&address) else { return vec![] };
+
+        let mut fills = Vec::new();
+        for bundle in blocks
+            .range(from_block..)
+            .filter_map(|block| inner.blocks.get(block))
+        {
+            if fills.len() >= limit {
+                break
+            }
+            fills.extend(
+                bundle
+                    .fills
+                    .iter()
+                    .filter(|fill| fill.from == address)
+                    .cloned()
+            );
+        }
+
+        fills
+    }
+
+    /// Spawns the task that indexes every bundle that lands on chain. Blocks
+    /// the task missed because it fell behind or the node was down are loaded
+    /// from the provider
+    pub fn spawn_indexer<TP, P>(
+        &self,
+        tp: TP,
+        provider: P,
+        angstrom_address: Address,
+        pool_store: Arc<AngstromPoolConfigStore>,
+        canonical_updates: Receiver<CanonStateNotification>
+    ) where
+        TP: TaskSpawner,
+        P: BlockReader<Block = Block, Receipt = Receipt> + 'static
+    {
+        let index = self.clone();
+        tp.spawn(Box::pin(async move {
+            index
+                .run(provider, angstrom_address, pool_store, canonical_updates)
+                .await
+        }));
+    }
+
+    async fn run<P>(
+        self,
+        provider: P,
+        angstrom_address: Address,
+        pool_store: Arc<AngstromPoolConfigStore>,
+        mut canonical_updates: Receiver<CanonStateNotification>
+    ) where
+        P: BlockReader<Block = Block, Receipt = Receipt>
+    {
+        // catch up on what landed while we were down. The blocks we indexed last
+        // could have been reorged out in the meantime
+        let best = provider.best_block_number().ok();
+        if let (Some(synced), Some(best)) = (self.synced_to(), best) {
+            let from = (synced + 1).saturating_sub(RESYNC_DEPTH);
+            self.revert(from);
+            self.resync(&provider, from..best + 1, angstrom_address, &pool_store);
+            self.set_synced(best);
+        }
+
+        // the first block we haven't indexed yet
+        let mut next_block = best.map(|block| block + 1);
+        let mut lagged = false;
+
+        loop {
+            let notification = match canonical_updates.recv().await {
+                Ok(notification) => notification,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(
+                        skipped,
+                        "bundle indexer fell behind the canonical chain, re-syncing"
+                    );
+                    lagged = true;
+                    continue
+                }
+                Err(RecvError::Closed) => break
+            };
+
+            let new = match notification {
+                CanonStateNotification::Reorg { new, .. } => {
+                    // everything the new chain covers gets re-indexed
+                    self.revert(new.first().number);
+                    new
+                }
+                CanonStateNotification::Commit { new } => new
+            };
+
+            let first = new.first().number;
+            if let Some(next) = next_block.filter(|_| lagged) {
+                let from = next.saturating_sub(RESYNC_DEPTH).min(first);
+                self.revert(from);
+                self.resync(&provider, from..first, angstrom_address, &pool_store);
+            }
+            lagged = false;
+
+            for block in new.blocks_iter() {
+                let receipts = new.receipts_by_block_hash(block.hash()).unwrap_or_default();
+                let Some(bundle) = IndexedBundle::from_block(
+                    block.number,
+                    block.transactions().iter().zip(receipts),
+                    angstrom_address,
+                    &pool_store
+                ) else {
+                    continue
+                };
+                self.insert(bundle);
+            }
+            self.set_synced(new.tip().number);
+            next_block = Some(new.tip().number + 1);
+        }
+    }
+
+    /// re-indexes the given blocks from the provider
+    fn resync<P>(
+        &self,
+        provider: &P,
+        blocks: Range<BlockNumber>,
+        angstrom_address: Address,
+        pool_store: &AngstromPoolConfigStore
+    ) where
+        P: BlockReader<Block = Block, Receipt = Receipt>
+    {
+        tracing::info!(?blocks, "re-syncing bundle index");
+        for block_number in blocks {
+            let (Ok(Some(block)), Ok(Some(receipts))) = (
+                provider.block_by_number(block_number),
+                provider.receipts_by_block(block_number.into())
+            ) else {
+                tracing::error!(block_number, "failed to load block to re-sync bundle index");
+                continue
+            };
+
+            if let Some(bundle) = IndexedBundle::from_block(
+                block_number,
+                block.body.transactions.iter().zip(&receipts),
+                angstrom_address,
+                pool_store
+            ) {
+                self.insert(bundle);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{primitives::TxKind, sol_types::SolCall};
+    use angstrom_types::{
+        contract_bindings::angstrom::Angstrom::executeCall,
+        contract_payloads::{
+            angstrom::{AngPoolConfigEntry, TopOfBlockOrder},
+            rewards::PoolUpdate
+        },
+        primitive::AngstromSigner,
+        sol_bindings::grouped_orders::OrderWithStorageData
+    };
+    use pade::PadeEncode;
+    use reth_primitives::Transaction;
+    use testing_tools::type_generator::orders::ToBOrderBuilder;
+
+    use super::*;
+
+    fn bundle_with_tob(
+        pool_store: &AngstromPoolConfigStore
+    ) -> (AngstromBundle, Address, Address, Address) {
+        let signer = AngstromSigner::random();
+        let tob = ToBOrderBuilder::new()
+            .signing_key(Some(signer.clone()))
+            .build();
+        let tob = OrderWithStorageData { order: tob, ..Default::default() };
+        let (token0, token1) = if tob.asset_in < tob.asset_out {
+            (tob.asset_in, tob.asset_out)
+        } else {
+            (tob.asset_out, tob.asset_in)
+        };
+
+        pool_store.new_pool(
+            token0,
+            token1,
+            AngPoolConfigEntry {
+                pool_partial_key: AngstromPoolConfigStore::derive_store_key(token0, token1),
+                tick_spacing:     60,
+                fee_in_e6:        0,
+                store_index:      0
+            }
+        );
+
+        let assets = vec![
+            Asset { addr: token0, ..Default::default() },
+            Asset { addr: token1, ..Default::default() },
+        ];
+        let pairs = vec![Pair {
+            index0:       0,
+            index1:       1,
+            store_index:  0,
+            price_1over0: U256::from(42)
+        }];
+        let pool_updates = vec![PoolUpdate {
+            zero_for_one:     false,
+            pair_index:       0,
+            swap_in_quantity: 0,
+            rewards_update:   RewardsUpdate::MultiTick {
+                start_tick:      I24::ZERO,
+                start_liquidity: 0,
+                quantities:      vec![10, 20, 30]
+            }
+        }];
+
+        let bundle = AngstromBundle::new(
+            assets,
+            pairs,
+            pool_updates,
+            vec![TopOfBlockOrder::of_max_gas(&tob, 0)],
+            vec![]
+        );
+
+        (bundle, signer.address(), token0, token1)
+    }
+
+    #[test]
+    fn test_index_bundle() {
+        let angstrom_address = Address::random();
+        let pool_store = AngstromPoolConfigStore::default();
+        let (bundle, from, token0, token1) = bundle_with_tob(&pool_store);
+
+        let indexed = IndexedBundle::from_bundle(10, &bundle, angstrom_address, &pool_store);
+        let pool_id = indexed.clearing_prices[0].pool_id;
+
+        assert_eq!(indexed.clearing_prices.len(), 1);
+        assert_eq!(indexed.clearing_prices[0].token0, token0);
+        assert_eq!(indexed.clearing_prices[0].token1, token1);
+        assert_eq!(indexed.clearing_prices[0].price_1_over_0, U256::from(42));
+
+        assert_eq!(indexed.fills.len(), 1);
+        assert!(indexed.fills[0].top_of_block);
+        assert_eq!(indexed.fills[0].from, from);
+        assert_eq!(indexed.fills[0].pool_id, pool_id);
+        assert_eq!(indexed.fills[0].order_hash, bundle.get_order_hashes(10).next().unwrap());
+
+        assert_eq!(
+            indexed.pool_rewards,
+            vec![PoolReward { block_number: 10, pool_id, amount: 60 }]
+        );
+    }
+
+    #[test]
+    fn test_index_landed_execute_call() {
+        let angstrom_address = Address::random();
+        let pool_store = AngstromPoolConfigStore::default();
+        let (bundle, from, ..) = bundle_with_tob(&pool_store);
+
+        let mut tx = TransactionSigned::default();
+        if let Transaction::Legacy(leg) = &mut tx.transaction {
+            leg.to = TxKind::Call(angstrom_address);
+            leg.input = executeCall::new((bundle.pade_encode().into(),))
+                .abi_encode()
+                .into();
+        }
+        let other_tx = TransactionSigned::default();
+        let landed = Receipt { success: true, ..Default::default() };
+        let reverted = Receipt { success: false, ..Default::default() };
+
+        let indexed = IndexedBundle::from_block(
+            10,
+            [(&other_tx, &landed), (&tx, &landed)],
+            angstrom_address,
+            &pool_store
+        )
+        .unwrap();
+        assert_eq!(indexed, IndexedBundle::from_bundle(10, &bundle, angstrom_address, &pool_store));
+        assert_eq!(indexed.fills[0].from, from);
+
+        assert!(IndexedBundle::from_block(10, [(&tx, &reverted)], angstrom_address, &pool_store)
+            .is_none());
+    }
+
+    #[test]
+    fn test_malformed_bundle_is_skipped() {
+        let angstrom_address = Address::random();
+        let pool_store = AngstromPoolConfigStore::default();
+        let (mut bundle, ..) = bundle_with_tob(&pool_store);
+        bundle.pairs[0].index1 = 5;
+
+        let indexed = IndexedBundle::from_bundle(10, &bundle, angstrom_address, &pool_store);
+        assert!(indexed.clearing_prices.is_empty());
+        assert!(indexed.fills.is_empty());
+        assert!(indexed.pool_rewards.is_empty());
+
+        // a bare pade payload isn't an execute call
+        let mut tx = TransactionSigned::default();
+        if let Transaction::Legacy(leg) = &mut tx.transaction {
+            leg.to = TxKind::Call(angstrom_address);
+            leg.input = bundle.pade_encode().into();
+        }
+        let landed = Receipt { success: true, ..Default::default() };
+        assert!(IndexedBundle::from_block(10, [(&tx, &landed)], angstrom_address, &pool_store)
+            .is_none());
+    }
+
+    #[test]
+    fn test_index_survives_restart_and_reorgs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bundles.jsonl");
+        let angstrom_address = Address::random();
+        let pool_store = AngstromPoolConfigStore::default();
+        let (bundle, from, ..) = bundle_with_tob(&pool_store);
+
+        let index = BundleIndex::default();
+        index.attach_file(path.clone()).unwrap();
+        for block in 10..13 {
+            index.insert(IndexedBundle::from_bundle(block, &bundle, angstrom_address, &pool_store));
+        }
+        index.set_synced(12);
+        let pool_id = index.bundle(10).unwrap().clearing_prices[0].pool_id;
+        // block 12 gets reorged out
+        index.revert(12);
+
+        assert_eq!(index.clearing_prices(pool_id, 0..=100).len(), 2);
+        assert_eq!(index.clearing_prices(pool_id, 11..=11).len(), 1);
+        assert!(index.clearing_prices(PoolId::random(), 0..=100).is_empty());
+
+        let reopened = BundleIndex::default();
+        reopened.attach_file(path).unwrap();
+
+        assert!(reopened.bundle(12).is_none());
+        assert_eq!(reopened.synced_to(), Some(11));
+        let fills = reopened.fills_by_address(from, 0, usize::MAX);
+        assert_eq!(fills.iter().map(|f| f.block_number).collect::<Vec<_>>(), vec![10, 11]);
+        assert!(reopened
+            .fills_by_address(Address::random(), 0, usize::MAX)
+            .is_empty());
+
+        // pages end on a block boundary
+        let first_page = reopened.fills_by_address(from, 0, 1);
+        assert_eq!(
+            first_page
+                .iter()
+                .map(|f| f.block_number)
+                .collect::<Vec<_>>(),
+            vec![10]
+        );
+        let second_page = reopened.fills_by_address(from, 11, 1);
+        assert_eq!(
+            second_page
+                .iter()
+                .map(|f| f.block_number)
+                .collect::<Vec<_>>(),
+            vec![11]
+        );
+    }
+}
@@ -1,2 +1,3 @@
 pub mod handle;
+pub mod indexer;
 pub mod manager;
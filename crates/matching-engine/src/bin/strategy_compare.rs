@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use alloy::primitives::{FixedBytes, U256};
+use angstrom_types::matching::{Ray, SqrtPriceX96};
+use clap::Parser;
+use matching_engine::{
+    book::{sort::SortStrategy, OrderBook},
+    simulation::{amm::single_position_amm, orders::order_distribution_with_rng},
+    strategy::{surplus, StrategyKind}
+};
+use rand::{rngs::StdRng, SeedableRng};
+use uniswap_v3_math::tick_math::get_tick_at_sqrt_ratio;
+
+/// Runs every registered matching strategy over the same set of generated
+/// books and reports how they differ.  Books are generated from a seeded RNG so
+/// two runs with the same arguments always compare the same books
+#[derive(Parser, Debug)]
+struct Args {
+    /// Number of books to generate and solve
+    #[arg(short, long, default_value_t = 20)]
+    books:           usize,
+    /// Seed for the first book, every following book increments it by one
+    #[arg(short, long, default_value_t = 0)]
+    seed:            u64,
+    /// Number of orders to generate on each side of a book
+    #[arg(short, long, default_value_t = 100)]
+    orders:          usize,
+    /// Price used as the center of our price distributions
+    #[arg(short, long, default_value_t = 100_000_000.0)]
+    price:           f64,
+    /// Scale of both price distributions
+    #[arg(long, default_value_t = 1000000.0)]
+    price_scale:     f64,
+    /// Shape of the bid price distribution, the ask side uses the negation
+    #[arg(long, default_value_t = -2.0)]
+    bid_price_shape: f64,
+    /// Average order volume to use
+    #[arg(long, default_value_t = 99.0)]
+    volume_mean:     f64,
+    /// Standard deviation of order volume
+    #[arg(long, default_value_t = 1.0)]
+    volume_sd:       f64
+}
+
+/// What a strategy came up with for a single book
+#[derive(Debug, Default, Clone, Copy)]
+struct Outcome {
+    volume:     u128,
+    amm_volume: u128,
+    surplus:    U256,
+    ucp:        Option<Ray>
+}
+
+#[derive(Debug, Default)]
+struct Totals {
+    solved:           usize,
+    volume:           u128,
+    amm_volume:       u128,
+    surplus:          U256,
+    /// sum of the absolute UCP difference from the baseline, in basis points
+    ucp_diff_bps:     f64,
+    /// how many books this strategy picked a different UCP than the baseline
+    ucp_diff_count:   usize,
+    ucp_diff_samples: usize
+}
+
+fn build_book(args: &Args, seed: u64) -> OrderBook {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let bids = order_distribution_with_rng(
+        &mut rng,
+        true,
+        args.orders,
+        args.price,
+        args.price_scale,
+        args.bid_price_shape,
+        args.volume_mean,
+        args.volume_sd,
+        0.0
+    )
+    .unwrap();
+    let asks = order_distribution_with_rng(
+        &mut rng,
+        false,
+        args.orders,
+        args.price,
+        args.price_scale,
+        -args.bid_price_shape,
+        args.volume_mean,
+        args.volume_sd,
+        0.0
+    )
+    .unwrap();
+
+    let middle_tick =
+        get_tick_at_sqrt_ratio(SqrtPriceX96::from_float_price(args.price).into()).unwrap();
+    let amm = single_position_amm(middle_tick, 10000, 2e36 as u128).unwrap();
+
+    OrderBook::new(
+        FixedBytes::default(),
+        Some(amm),
+        bids,
+        asks,
+        Some(SortStrategy::ByPriceByVolume)
+    )
+}
+
+fn ucp_diff_bps(ucp: Ray, baseline: Ray) -> f64 {
+    let baseline = baseline.as_f64();
+    if baseline == 0.0 {
+        return 0.0
+    }
+    ((ucp.as_f64() - baseline) / baseline).abs() * 10_000.0
+}
+
+fn main() {
+    let args = Args::parse();
+    let baseline = StrategyKind::default();
+    let mut totals: HashMap<StrategyKind, Totals> = HashMap::new();
+
+    println!(
+        "{:>6} {:>16} {:>12} {:>12} {:>40} {:>28}",
+        "book", "strategy", "volume", "amm volume", "surplus (T1)", "ucp"
+    );
+    for book_idx in 0..args.books {
+        let book = build_book(&args, args.seed + book_idx as u64);
+
+        let outcomes = StrategyKind::ALL
+            .into_iter()
+            .map(|kind| {
+                let outcome = kind.run(&book).map(|solved| Outcome {
+                    volume:     solved.results().total_volume,
+                    amm_volume: solved.results().amm_volume,
+                    surplus:    surplus(&solved),
+                    ucp:        solved.results().price.map(Ray::from)
+                });
+                (kind, outcome)
+            })
+            .collect::<Vec<_>>();
+        let baseline_ucp = outcomes
+            .iter()
+            .find(|(kind, _)| *kind == baseline)
+            .and_then(|(_, outcome)| outcome.and_then(|o| o.ucp));
+
+        for (kind, outcome) in outcomes {
+            let Some(outcome) = outcome else {
+                println!("{:>6} {:>16} unsolvable", book_idx, kind.name());
+                continue
+            };
+            println!(
+                "{:>6} {:>16} {:>12} {:>12} {:>40} {:>28}",
+                book_idx,
+                kind.name(),
+                outcome.volume,
+                outcome.amm_volume,
+                outcome.surplus,
+                outcome
+                    .ucp
+                    .map(|ucp| format!("{:.6}", ucp.as_f64()))
+                    .unwrap_or_else(|| "-".to_string())
+            );
+
+            let total = totals.entry(kind).or_default();
+            total.solved += 1;
+            total.volume += outcome.volume;
+            total.amm_volume += outcome.amm_volume;
+            total.surplus = total.surplus.saturating_add(outcome.surplus);
+            if let (Some(ucp), Some(baseline_ucp)) = (outcome.ucp, baseline_ucp) {
+                total.ucp_diff_samples += 1;
+                if ucp != baseline_ucp {
+                    total.ucp_diff_count += 1;
+                    total.ucp_diff_bps += ucp_diff_bps(ucp, baseline_ucp);
+                }
+            }
+        }
+    }
+
+    println!();
+    println!("Totals over {} books, UCP differences are against {}", args.books, baseline.name());
+    println!(
+        "{:>16} {:>8} {:>14} {:>14} {:>40} {:>14} {:>18}",
+        "strategy",
+        "solved",
+        "volume",
+        "amm volume",
+        "surplus (T1)",
+        "ucp differs",
+        "avg ucp diff bps"
+    );
+    for kind in StrategyKind::ALL {
+        let total = totals.remove(&kind).unwrap_or_default();
+        let avg_diff = if total.ucp_diff_samples == 0 {
+            0.0
+        } else {
+            total.ucp_diff_bps / total.ucp_diff_samples as f64
+        };
+        println!(
+            "{:>16} {:>8} {:>14} {:>14} {:>40} {:>14} {:>18.4}",
+            kind.name(),
+            total.solved,
+            total.volume,
+            total.amm_volume,
+            total.surplus,
+            total.ucp_diff_count,
+            avg_diff
+        );
+    }
+}
@@ -5,10 +5,11 @@ use std::{
 
 use alloy::providers::Provider;
 use alloy_primitives::{Address, BlockNumber};
+use angstrom_eth::manager::EthEvent;
 use angstrom_types::{
     block_sync::BlockSyncConsumer,
     contract_payloads::angstrom::BundleGasDetails,
-    matching::uniswap::PoolSnapshot,
+    matching::{match_estimate_response::BundleEstimate, uniswap::PoolSnapshot},
     orders::PoolSolution,
     primitive::{PoolId, UniswapPoolRegistry},
     sol_bindings::{
@@ -16,10 +17,12 @@ use angstrom_types::{
     }
 };
 use book::{BookOrder, OrderBook};
-use futures_util::future::BoxFuture;
+use futures_util::{future::BoxFuture, Stream, StreamExt};
 use reth_provider::CanonStateNotifications;
 use uniswap_v4::uniswap::{
-    pool::EnhancedUniswapPool, pool_data_loader::DataLoader, pool_manager::UniswapPoolManager,
+    pool::EnhancedUniswapPool,
+    pool_data_loader::DataLoader,
+    pool_manager::{PoolUpdate, UniswapPoolManager},
     pool_providers::canonical_state_adapter::CanonicalStateAdapter
 };
 
@@ -38,6 +41,15 @@ pub trait MatchingEngineHandle: Send + Sync + Clone + Unpin + 'static {
         searcher: Vec<OrderWithStorageData<TopOfBlockOrder>>,
         pools: HashMap<PoolId, (Address, Address, PoolSnapshot, u16)>
     ) -> BoxFuture<eyre::Result<(Vec<PoolSolution>, BundleGasDetails)>>;
+
+    /// estimates the gas each order would be charged if a bundle was built
+    /// from the given orders.
+    fn estimate_gas_per_pool(
+        &self,
+        limit: Vec<BookOrder>,
+        searcher: Vec<OrderWithStorageData<TopOfBlockOrder>>,
+        pools: HashMap<PoolId, (Address, Address, PoolSnapshot, u16)>
+    ) -> BoxFuture<eyre::Result<BundleEstimate>>;
 }
 
 pub fn build_book(id: PoolId, amm: Option<PoolSnapshot>, orders: HashSet<BookOrder>) -> OrderBook {
@@ -51,13 +63,22 @@ pub fn build_book(id: PoolId, amm: Option<PoolSnapshot>, orders: HashSet<BookOrd
     OrderBook::new(id, amm, bids, asks, Some(book::sort::SortStrategy::ByPriceByVolume))
 }
 
+/// How many initialized ticks we load on each side of the current tick when a
+/// pool is first synced
+pub const DEFAULT_INITIAL_TICKS_PER_SIDE: u16 = 200;
+
+/// Builds the uniswap pool manager for the pools in the registry. Pools that
+/// are configured or removed on the angstrom contract afterwards are picked up
+/// from `pool_events`.
 pub async fn configure_uniswap_manager<BlockSync: BlockSyncConsumer>(
     provider: Arc<impl Provider + 'static>,
     state_notification: CanonStateNotifications,
     uniswap_pool_registry: UniswapPoolRegistry,
     current_block: BlockNumber,
     block_sync: BlockSync,
-    pool_manager_address: Address
+    pool_manager_address: Address,
+    initial_ticks_per_side: u16,
+    pool_events: impl Stream<Item = EthEvent> + Send + 'static
 ) -> UniswapPoolManager<
     CanonicalStateAdapter<impl Provider + 'static>,
     BlockSync,
@@ -69,8 +90,6 @@ pub async fn configure_uniswap_manager<BlockSync: BlockSyncConsumer>(
         .keys()
         .map(|pool_id| {
             let internal = uniswap_pool_registry.conversion_map.get(pool_id).unwrap();
-
-            let initial_ticks_per_side = 200;
             EnhancedUniswapPool::new(
                 DataLoader::new_with_registry(
                     *internal,
@@ -91,6 +110,26 @@ pub async fn configure_uniswap_manager<BlockSync: BlockSyncConsumer>(
     let notifier =
         Arc::new(CanonicalStateAdapter::new(state_notification, provider.clone(), current_block));
 
+    let pool_updates = pool_events.filter_map(move |event| async move {
+        match event {
+            EthEvent::NewPool { pool } => {
+                let mut registry = UniswapPoolRegistry::default();
+                let pool_id = registry.add_pool(pool);
+                let internal = *registry.conversion_map.get(&pool_id).unwrap();
+                let pool = EnhancedUniswapPool::new(
+                    DataLoader::new_with_registry(internal, registry, pool_manager_address),
+                    initial_ticks_per_side
+                );
+
+                Some(PoolUpdate::NewPool { pool_id, pool })
+            }
+            EthEvent::RemovedPool { pool } => {
+                Some(PoolUpdate::RemovedPool { pool_id: PoolId::from(pool) })
+            }
+            _ => None
+        }
+    });
+
     UniswapPoolManager::new(
         uniswap_pools,
         uniswap_pool_registry.conversion_map,
@@ -98,4 +137,5 @@ pub async fn configure_uniswap_manager<BlockSync: BlockSyncConsumer>(
         notifier,
         block_sync
     )
+    .with_pool_updates(pool_updates.boxed())
 }
@@ -4,16 +4,21 @@ use std::{
     sync::Arc
 };
 
-use alloy_primitives::Address;
+use alloy_primitives::{Address, B256, U256};
 use angstrom_types::{
     consensus::PreProposal,
     contract_payloads::angstrom::{AngstromBundle, BundleGasDetails},
-    matching::{match_estimate_response::BundleEstimate, uniswap::PoolSnapshot},
+    matching::{
+        match_estimate_response::{BundleEstimate, PoolEstimate},
+        uniswap::PoolSnapshot
+    },
     orders::PoolSolution,
     primitive::PoolId,
-    sol_bindings::{grouped_orders::OrderWithStorageData, rpc_orders::TopOfBlockOrder}
+    sol_bindings::{
+        grouped_orders::OrderWithStorageData, rpc_orders::TopOfBlockOrder, RawPoolOrder
+    }
 };
-use futures::{stream::FuturesUnordered, Future};
+use futures::{stream::FuturesUnordered, Future, StreamExt};
 use futures_util::FutureExt;
 use reth_tasks::TaskSpawner;
 use tokio::{
@@ -29,7 +34,7 @@ use validation::bundle::BundleValidatorHandle;
 use crate::{
     book::{BookOrder, OrderBook},
     build_book,
-    strategy::{MatchingStrategy, SimpleCheckpointStrategy},
+    strategy::StrategyKind,
     MatchingEngineHandle
 };
 
@@ -77,28 +82,43 @@ impl MatchingEngineHandle for MatcherHandle {
                 .await
         })
     }
+
+    fn estimate_gas_per_pool(
+        &self,
+        limit: Vec<BookOrder>,
+        searcher: Vec<OrderWithStorageData<TopOfBlockOrder>>,
+        pools: HashMap<PoolId, (Address, Address, PoolSnapshot, u16)>
+    ) -> futures_util::future::BoxFuture<eyre::Result<BundleEstimate>> {
+        Box::pin(async move {
+            let (tx, rx) = oneshot::channel();
+            self.send_request(rx, MatcherCommand::EstimateGasPerPool { limit, searcher, pools, tx })
+                .await
+        })
+    }
 }
 
 pub struct MatchingManager<TP: TaskSpawner, V> {
     _futures:          FuturesUnordered<Pin<Box<dyn Future<Output = ()> + Sync + Send + 'static>>>,
     validation_handle: V,
+    strategy:          StrategyKind,
     _tp:               Arc<TP>
 }
 
 impl<TP: TaskSpawner + 'static, V: BundleValidatorHandle> MatchingManager<TP, V> {
-    pub fn new(tp: TP, validation: V) -> Self {
+    pub fn new(tp: TP, validation: V, strategy: StrategyKind) -> Self {
         Self {
-            _futures:          FuturesUnordered::default(),
+            _futures: FuturesUnordered::default(),
             validation_handle: validation,
-            _tp:               tp.into()
+            strategy,
+            _tp: tp.into()
         }
     }
 
-    pub fn spawn(tp: TP, validation: V) -> MatcherHandle {
+    pub fn spawn(tp: TP, validation: V, strategy: StrategyKind) -> MatcherHandle {
         let (tx, rx) = tokio::sync::mpsc::channel(100);
         let tp = Arc::new(tp);
 
-        let fut = manager_thread(rx, tp.clone(), validation).boxed();
+        let fut = manager_thread(rx, tp.clone(), validation, strategy).boxed();
         tp.spawn_critical("matching_engine", fut);
 
         MatcherHandle { sender: tx }
@@ -107,7 +127,7 @@ impl<TP: TaskSpawner + 'static, V: BundleValidatorHandle> MatchingManager<TP, V>
     pub fn orders_by_pool_id(preproposals: &[PreProposal]) -> HashMap<PoolId, HashSet<BookOrder>> {
         preproposals
             .iter()
-            .flat_map(|p| p.limit.iter())
+            .flat_map(|p| p.all_limit())
             .cloned()
             .fold(HashMap::new(), |mut acc, order| {
                 acc.entry(order.pool_id).or_default().insert(order);
@@ -147,23 +167,26 @@ impl<TP: TaskSpawner + 'static, V: BundleValidatorHandle> MatchingManager<TP, V>
             .collect()
     }
 
-    pub async fn build_proposal(
+    /// runs the matching strategy over the book of every pool that has limit
+    /// orders, each one on its own blocking task
+    async fn solve_books(
         &self,
         limit: Vec<BookOrder>,
         searcher: Vec<OrderWithStorageData<TopOfBlockOrder>>,
-        pool_snapshots: HashMap<PoolId, (Address, Address, PoolSnapshot, u16)>
-    ) -> eyre::Result<(Vec<PoolSolution>, BundleGasDetails)> {
-        tracing::info!("starting to build proposal");
+        pool_snapshots: &HashMap<PoolId, (Address, Address, PoolSnapshot, u16)>
+    ) -> Vec<PoolSolution> {
         // Pull all the orders out of all the preproposals and build OrderPools out of
         // them.  This is ugly and inefficient right now
-        let books = Self::build_non_proposal_books(limit.clone(), &pool_snapshots);
+        let books = Self::build_non_proposal_books(limit, pool_snapshots);
 
+        // consensus only hands us the winner of each pool's top of block auction
         let searcher_orders: HashMap<PoolId, OrderWithStorageData<TopOfBlockOrder>> =
             searcher.into_iter().fold(HashMap::new(), |mut acc, order| {
                 acc.entry(order.pool_id).or_insert(order);
                 acc
             });
 
+        let strategy = self.strategy;
         let mut solution_set = JoinSet::new();
         books.into_iter().for_each(|b| {
             let searcher = searcher_orders.get(&b.id()).cloned();
@@ -172,10 +195,9 @@ impl<TP: TaskSpawner + 'static, V: BundleValidatorHandle> MatchingManager<TP, V>
             // dedicated threadpool and some suggest the `rayon` crate.  This is probably
             // not a problem while I'm testing, but leaving this note here as it may be
             // important for future efficiency gains
-            solution_set.spawn_blocking(move || {
-                SimpleCheckpointStrategy::run(&b).map(|s| s.solution(searcher))
-            });
+            solution_set.spawn_blocking(move || strategy.run(&b).map(|s| s.solution(searcher)));
         });
+
         let mut solutions = Vec::new();
         while let Some(res) = solution_set.join_next().await {
             if let Ok(Some(r)) = res {
@@ -183,6 +205,20 @@ impl<TP: TaskSpawner + 'static, V: BundleValidatorHandle> MatchingManager<TP, V>
             }
         }
 
+        solutions
+    }
+
+    pub async fn build_proposal(
+        &self,
+        limit: Vec<BookOrder>,
+        searcher: Vec<OrderWithStorageData<TopOfBlockOrder>>,
+        pool_snapshots: HashMap<PoolId, (Address, Address, PoolSnapshot, u16)>
+    ) -> eyre::Result<(Vec<PoolSolution>, BundleGasDetails)> {
+        tracing::info!("starting to build proposal");
+        let solutions = self
+            .solve_books(limit.clone(), searcher, &pool_snapshots)
+            .await;
+
         // generate bundle without final gas known.
         trace!("Building bundle for gas finalization");
         let bundle =
@@ -201,65 +237,148 @@ impl<TP: TaskSpawner + 'static, V: BundleValidatorHandle> MatchingManager<TP, V>
         })
     }
 
-    async fn _estimate_current_fills(
+    pub async fn estimate_current_fills(
         &self,
         limit: Vec<BookOrder>,
         searcher: Vec<OrderWithStorageData<TopOfBlockOrder>>,
         pool_snapshots: HashMap<PoolId, (Address, Address, PoolSnapshot, u16)>
     ) -> eyre::Result<BundleEstimate> {
-        let books = Self::build_non_proposal_books(limit.clone(), &pool_snapshots);
+        let solutions = self
+            .solve_books(limit.clone(), searcher, &pool_snapshots)
+            .await;
 
-        let searcher_orders: HashMap<PoolId, OrderWithStorageData<TopOfBlockOrder>> =
-            searcher.into_iter().fold(HashMap::new(), |mut acc, order| {
-                acc.entry(order.pool_id).or_insert(order);
-                acc
-            });
+        // the contract splits the shared gas of the bundle evenly across all orders
+        // that get executed, so we need the amount of orders included per pool.
+        let orders_per_pool = solutions
+            .iter()
+            .map(|s| {
+                let limit = s.limit.iter().filter(|o| o.is_filled()).count() as u64;
+                (s.id, limit + s.searcher.is_some() as u64)
+            })
+            .collect::<HashMap<_, _>>();
+        let total_orders: u64 = orders_per_pool.values().sum();
+        if total_orders == 0 {
+            return Ok(Self::amm_only_estimate(&pool_snapshots))
+        }
 
-        let mut solution_set = JoinSet::new();
-        books.into_iter().for_each(|b| {
-            let searcher = searcher_orders.get(&b.id()).cloned();
-            // Using spawn-blocking here is not BAD but it might be suboptimal as it allows
-            // us to spawn many more tasks that the CPu has threads.  Better solution is a
-            // dedicated threadpool and some suggest the `rayon` crate.  This is probably
-            // not a problem while I'm testing, but leaving this note here as it may be
-            // important for future efficiency gains
-            solution_set.spawn_blocking(move || {
-                SimpleCheckpointStrategy::run(&b).map(|s| s.solution(searcher))
-            });
-        });
+        let orders_by_hash = limit
+            .iter()
+            .map(|order| (order.order_id.hash, order))
+            .collect::<HashMap<_, _>>();
+        let fills_per_pool = solutions
+            .iter()
+            .map(|s| (s.id, (s.ucp, Self::filled_volume(s, &orders_by_hash))))
+            .collect::<HashMap<_, _>>();
 
-        let mut solutions = Vec::new();
-        while let Some(res) = solution_set.join_next().await {
-            if let Ok(Some(r)) = res {
-                solutions.push(r);
-            }
-        }
+        let bundle = AngstromBundle::for_gas_finalization(limit, solutions, &pool_snapshots)?;
+        let gas_response = self.validation_handle.fetch_gas_for_bundle(bundle).await?;
+        let gas_per_order = gas_response.total_gas_cost_wei() / total_orders;
 
-        let bundle =
-            AngstromBundle::for_gas_finalization(limit, solutions.clone(), &pool_snapshots)?;
-        let _gas_response = self.validation_handle.fetch_gas_for_bundle(bundle).await?;
+        let pool_estimate = pool_snapshots
+            .iter()
+            .map(|(pool_id, (token_a, token_b, ..))| {
+                let (token0, token1) =
+                    if token_a < token_b { (*token_a, *token_b) } else { (*token_b, *token_a) };
+                let (clearing_price, filled_volume) = fills_per_pool
+                    .get(pool_id)
+                    .map(|(ucp, volume)| (Some(*ucp), *volume))
+                    .unwrap_or_default();
+                let estimate = PoolEstimate {
+                    orders: orders_per_pool.get(pool_id).copied().unwrap_or_default(),
+                    gas_in_wei: gas_per_order,
+                    gas_in_token0: gas_response.gas_in_token0(token0, token1, gas_per_order),
+                    clearing_price,
+                    filled_volume
+                };
+
+                (*pool_id, estimate)
+            })
+            .collect();
+
+        Ok(BundleEstimate { total_orders, pool_estimate })
+    }
+
+    /// With nothing to match, every pool stays at its AMM price and no order
+    /// pays any gas
+    fn amm_only_estimate(
+        pool_snapshots: &HashMap<PoolId, (Address, Address, PoolSnapshot, u16)>
+    ) -> BundleEstimate {
+        let pool_estimate = pool_snapshots
+            .iter()
+            .map(|(pool_id, (_, _, snapshot, _))| {
+                let estimate = PoolEstimate {
+                    orders:         0,
+                    gas_in_wei:     0,
+                    gas_in_token0:  None,
+                    clearing_price: Some(snapshot.current_price().as_ray()),
+                    filled_volume:  U256::ZERO
+                };
+
+                (*pool_id, estimate)
+            })
+            .collect();
 
-        todo!()
+        BundleEstimate { total_orders: 0, pool_estimate }
+    }
+
+    /// the amount of token0 that the filled limit orders of the solution trade
+    fn filled_volume(solution: &PoolSolution, orders: &HashMap<B256, &BookOrder>) -> U256 {
+        solution
+            .limit
+            .iter()
+            .filter(|outcome| outcome.is_filled())
+            .filter_map(|outcome| {
+                let order = orders.get(&outcome.id.hash)?;
+                let filled = outcome.fill_amount(order.max_q());
+                // orders that are specified in token1 need to be converted at the ucp
+                if order.is_bid() == order.exact_in() {
+                    Some(U256::from(solution.ucp.inverse_quantity(filled, !order.is_bid())))
+                } else {
+                    Some(U256::from(filled))
+                }
+            })
+            .fold(U256::ZERO, |acc, volume| acc + volume)
     }
 }
 
 pub async fn manager_thread<TP: TaskSpawner + 'static, V: BundleValidatorHandle>(
     mut input: Receiver<MatcherCommand>,
     tp: Arc<TP>,
-    validation_handle: V
+    validation_handle: V,
+    strategy: StrategyKind
 ) {
-    let manager =
-        MatchingManager { _futures: FuturesUnordered::default(), _tp: tp, validation_handle };
-
-    while let Some(c) = input.recv().await {
-        match c {
-            MatcherCommand::BuildProposal(limit, searcher, snapshot, r) => {
-                r.send(manager.build_proposal(limit, searcher, snapshot).await)
-                    .unwrap();
-            }
-            MatcherCommand::EstimateGasPerPool { .. } => {
-                todo!()
+    let manager = MatchingManager {
+        _futures: FuturesUnordered::default(),
+        _tp: tp,
+        validation_handle,
+        strategy
+    };
+
+    // estimates run alongside the command loop so that simulating one never holds
+    // up building a proposal, commands always get handled first
+    let mut estimates = FuturesUnordered::new();
+    loop {
+        tokio::select! {
+            biased;
+            command = input.recv() => {
+                let Some(command) = command else { break };
+                match command {
+                    MatcherCommand::BuildProposal(limit, searcher, snapshot, r) => {
+                        r.send(manager.build_proposal(limit, searcher, snapshot).await)
+                            .unwrap();
+                    }
+                    MatcherCommand::EstimateGasPerPool { limit, searcher, pools, tx } => {
+                        estimates.push(
+                            manager
+                                .estimate_current_fills(limit, searcher, pools)
+                                .map(move |estimate| {
+                                    let _ = tx.send(estimate);
+                                })
+                        );
+                    }
+                }
             }
+            Some(()) = estimates.next(), if !estimates.is_empty() => {}
         }
     }
 }
@@ -71,6 +71,10 @@ impl<'a> VolumeFillMatcher<'a> {
         new_element
     }
 
+    pub fn book(&self) -> &'a OrderBook {
+        self.book
+    }
+
     pub fn results(&self) -> &Solution {
         &self.results
     }
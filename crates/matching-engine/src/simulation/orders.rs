@@ -7,6 +7,7 @@ use angstrom_types::{
         rpc_orders::ExactFlashOrder
     }
 };
+use rand::Rng;
 use rand_distr::{Distribution, SkewNormal};
 
 use crate::book::BookOrder;
@@ -22,15 +23,39 @@ pub fn order_distribution(
     quantity_scale: f64,
     quantity_shape: f64
 ) -> Result<Vec<BookOrder>, String> {
-    let mut rng = rand::thread_rng();
-    let mut rng2 = rand::thread_rng();
+    order_distribution_with_rng(
+        &mut rand::thread_rng(),
+        is_bid,
+        number,
+        price_location,
+        price_scale,
+        price_shape,
+        quantity_location,
+        quantity_scale,
+        quantity_shape
+    )
+}
+
+/// Same as [`order_distribution`] but draws from the provided RNG so a seeded
+/// RNG will always generate the same book
+#[allow(clippy::too_many_arguments)]
+pub fn order_distribution_with_rng<R: Rng + ?Sized>(
+    rng: &mut R,
+    is_bid: bool,
+    number: usize,
+    price_location: f64,
+    price_scale: f64,
+    price_shape: f64,
+    quantity_location: f64,
+    quantity_scale: f64,
+    quantity_shape: f64
+) -> Result<Vec<BookOrder>, String> {
     let price_gen = SkewNormal::new(price_location, price_scale, price_shape)
         .map_err(|e| format!("Error creating price distribution: {}", e))?;
     let quantity_gen = SkewNormal::new(quantity_location, quantity_scale, quantity_shape)
         .map_err(|e| format!("Error creating price distribution: {}", e))?;
-    Ok(price_gen
-        .sample_iter(&mut rng)
-        .zip(quantity_gen.sample_iter(&mut rng2))
+    Ok((0..number)
+        .map(|_| (price_gen.sample(rng), quantity_gen.sample(rng)))
         .map(|(p, q)| {
             let order = GroupedVanillaOrder::KillOrFill(
                 angstrom_types::sol_bindings::grouped_orders::FlashVariants::Exact(
@@ -67,6 +92,5 @@ pub fn order_distribution(
                 tob_reward: U256::ZERO
             }
         })
-        .take(number)
         .collect())
 }
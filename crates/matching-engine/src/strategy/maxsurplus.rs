@@ -0,0 +1,17 @@
+use super::{checkpoints, surplus, MatchingStrategy};
+use crate::{book::OrderBook, matcher::VolumeFillMatcher};
+
+/// Steps through every checkpoint the volume matcher reaches and keeps the one
+/// that gives the filled orders the most price improvement at its clearing
+/// price.  Ties go to the later checkpoint as it clears more volume
+pub struct MaxSurplusStrategy {}
+
+impl<'a> MatchingStrategy<'a> for MaxSurplusStrategy {
+    fn run(book: &'a OrderBook) -> Option<VolumeFillMatcher<'a>> {
+        checkpoints(book).into_iter().max_by_key(surplus)
+    }
+
+    fn finalize(solver: VolumeFillMatcher) -> Option<VolumeFillMatcher> {
+        solver.from_checkpoint()
+    }
+}
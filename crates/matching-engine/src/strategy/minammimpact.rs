@@ -0,0 +1,23 @@
+use std::cmp::Reverse;
+
+use super::{checkpoints, MatchingStrategy};
+use crate::{book::OrderBook, matcher::VolumeFillMatcher};
+
+/// Steps through every checkpoint the volume matcher reaches and keeps the one
+/// that matches the most volume between book orders, preferring the least
+/// volume routed through the AMM when that's tied.  This keeps us from moving
+/// the pool price any further than the book needs us to
+pub struct MinAmmImpactStrategy {}
+
+impl<'a> MatchingStrategy<'a> for MinAmmImpactStrategy {
+    fn run(book: &'a OrderBook) -> Option<VolumeFillMatcher<'a>> {
+        checkpoints(book).into_iter().max_by_key(|solver| {
+            let results = solver.results();
+            (results.total_volume.saturating_sub(results.amm_volume), Reverse(results.amm_volume))
+        })
+    }
+
+    fn finalize(solver: VolumeFillMatcher) -> Option<VolumeFillMatcher> {
+        solver.from_checkpoint()
+    }
+}
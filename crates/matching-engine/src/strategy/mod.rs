@@ -6,12 +6,27 @@
 ///
 /// The intent is to implement several different strategies here and compare
 /// them via a suite of tests that will help us determine what the optimal
-/// matching strategy could be.
+/// matching strategy could be.  [`StrategyKind`] is the registry of the
+/// strategies we have so far, it's what the node config selects from and what
+/// the `strategy_compare` binary iterates over.
+use alloy::primitives::U256;
+use angstrom_types::{matching::Ray, orders::OrderFillState};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
 use crate::{book::OrderBook, matcher::VolumeFillMatcher};
 
+mod maxsurplus;
+mod minammimpact;
 mod simplecheckpoint;
+pub use maxsurplus::MaxSurplusStrategy;
+pub use minammimpact::MinAmmImpactStrategy;
 pub use simplecheckpoint::SimpleCheckpointStrategy;
 
+/// Upper bound on the amount of steps we'll take through a book, matches the
+/// bound that `VolumeFillMatcher::run_match()` enforces
+const MAX_MATCH_STEPS: usize = 1000;
+
 /// Basic trait to describe a matching strategy
 pub trait MatchingStrategy<'a> {
     /// Utility function to run this strategy against an order book.  Does the
@@ -28,3 +43,208 @@ pub trait MatchingStrategy<'a> {
     /// `None` if the book is considered unsolveable.
     fn finalize(solver: VolumeFillMatcher) -> Option<VolumeFillMatcher>;
 }
+
+/// All of the matching strategies that a node can be configured to use.  Every
+/// node in the validator set has to run the same strategy, otherwise the
+/// leader's proposal won't line up with the solutions the other nodes compute
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StrategyKind {
+    /// Runs the volume matcher to completion and takes the last good
+    /// checkpoint, see [`SimpleCheckpointStrategy`]
+    #[default]
+    #[serde(alias = "simple-checkpoint")]
+    MaxVolume,
+    /// See [`MaxSurplusStrategy`]
+    MaxSurplus,
+    /// See [`MinAmmImpactStrategy`]
+    MinAmmImpact
+}
+
+impl StrategyKind {
+    pub const ALL: [StrategyKind; 3] =
+        [StrategyKind::MaxVolume, StrategyKind::MaxSurplus, StrategyKind::MinAmmImpact];
+
+    /// The name this strategy is selected by in the node config
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::MaxVolume => "max-volume",
+            Self::MaxSurplus => "max-surplus",
+            Self::MinAmmImpact => "min-amm-impact"
+        }
+    }
+
+    /// Run the selected strategy against an order book
+    pub fn run<'a>(&self, book: &'a OrderBook) -> Option<VolumeFillMatcher<'a>> {
+        match self {
+            Self::MaxVolume => SimpleCheckpointStrategy::run(book),
+            Self::MaxSurplus => MaxSurplusStrategy::run(book),
+            Self::MinAmmImpact => MinAmmImpactStrategy::run(book)
+        }
+    }
+}
+
+/// Steps the volume matcher through the book and collects every valid
+/// checkpoint it passes through along the way, starting with the empty solve.
+/// Strategies that want to weigh up more than the final checkpoint can pick
+/// from these
+pub fn checkpoints(book: &OrderBook) -> Vec<VolumeFillMatcher<'_>> {
+    let mut solver = VolumeFillMatcher::new(book);
+    let mut checkpoints: Vec<VolumeFillMatcher<'_>> =
+        solver.from_checkpoint().into_iter().collect();
+
+    for _ in 0..MAX_MATCH_STEPS {
+        let end = solver.single_match();
+        if let Some(checkpoint) = solver.from_checkpoint() {
+            // only keep a checkpoint if the solver has moved past the last one
+            if checkpoints
+                .last()
+                .map(|last| last.results().total_volume != checkpoint.results().total_volume)
+                .unwrap_or(true)
+            {
+                checkpoints.push(checkpoint);
+            }
+        }
+        if end.is_some() {
+            return checkpoints
+        }
+    }
+    warn!(steps = MAX_MATCH_STEPS, "matcher never finished stepping through the book");
+
+    checkpoints
+}
+
+/// The total price improvement that the filled orders of a solve get at its
+/// uniform clearing price, denominated in T1.  Each filled order contributes
+/// the distance between its limit price and the UCP multiplied by its filled
+/// T0 quantity
+pub fn surplus(solver: &VolumeFillMatcher) -> U256 {
+    let Some(ucp) = solver.results().price.map(Ray::from) else { return U256::ZERO };
+    let book = solver.book();
+
+    book.bids()
+        .iter()
+        .zip(solver.bid_outcomes.iter())
+        .chain(book.asks().iter().zip(solver.ask_outcomes.iter()))
+        .filter_map(|(order, state)| {
+            let filled = match state {
+                OrderFillState::CompleteFill => order.max_q(),
+                OrderFillState::PartialFill(q) => *q,
+                _ => return None
+            };
+            // orders that are specified in token1 need to be converted at the ucp
+            let filled_t0 = if order.is_bid == order.exact_in() {
+                ucp.inverse_quantity(filled, !order.is_bid)
+            } else {
+                filled
+            };
+            let limit = order.price_for_book_side(order.is_bid);
+            let improvement =
+                if order.is_bid { limit.saturating_sub(*ucp) } else { ucp.saturating_sub(*limit) };
+
+            Some(Ray::from(improvement).mul_quantity(U256::from(filled_t0)))
+        })
+        .fold(U256::ZERO, |acc, surplus| acc.saturating_add(surplus))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::FixedBytes;
+    use angstrom_types::matching::SqrtPriceX96;
+    use rand::{rngs::StdRng, SeedableRng};
+    use uniswap_v3_math::tick_math::get_tick_at_sqrt_ratio;
+
+    use super::*;
+    use crate::{
+        book::sort::SortStrategy,
+        simulation::{amm::single_position_amm, orders::order_distribution_with_rng}
+    };
+
+    fn crossed_book(seed: u64) -> OrderBook {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let bids = order_distribution_with_rng(
+            &mut rng,
+            true,
+            50,
+            100_000_000.0,
+            1_000_000.0,
+            -2.0,
+            99.0,
+            1.0,
+            0.0
+        )
+        .unwrap();
+        let asks = order_distribution_with_rng(
+            &mut rng,
+            false,
+            50,
+            100_000_000.0,
+            1_000_000.0,
+            2.0,
+            99.0,
+            1.0,
+            0.0
+        )
+        .unwrap();
+        let middle_tick =
+            get_tick_at_sqrt_ratio(SqrtPriceX96::from_float_price(100_000_000.0).into()).unwrap();
+        let amm = single_position_amm(middle_tick, 10000, 2e36 as u128).unwrap();
+        OrderBook::new(
+            FixedBytes::default(),
+            Some(amm),
+            bids,
+            asks,
+            Some(SortStrategy::ByPriceByVolume)
+        )
+    }
+
+    #[test]
+    fn strategies_pick_from_the_checkpoints() {
+        let book = crossed_book(7);
+        let checkpoints = checkpoints(&book);
+        assert!(!checkpoints.is_empty());
+
+        let max_volume = StrategyKind::MaxVolume.run(&book).unwrap();
+        let max_surplus = StrategyKind::MaxSurplus.run(&book).unwrap();
+        let min_amm = StrategyKind::MinAmmImpact.run(&book).unwrap();
+
+        // nothing that we pick can clear more volume than running to completion
+        assert!(max_surplus.results().total_volume <= max_volume.results().total_volume);
+        assert!(min_amm.results().total_volume <= max_volume.results().total_volume);
+        assert!(checkpoints
+            .iter()
+            .all(|c| surplus(c) <= surplus(&max_surplus)));
+    }
+
+    #[test]
+    fn strategies_are_deterministic() {
+        let first = crossed_book(42);
+        let second = crossed_book(42);
+        for kind in StrategyKind::ALL {
+            let a = kind.run(&first).map(|s| s.results().clone());
+            let b = kind.run(&second).map(|s| s.results().clone());
+            assert_eq!(
+                a.map(|r| (r.total_volume, r.amm_volume, r.price)),
+                b.map(|r| (r.total_volume, r.amm_volume, r.price)),
+                "{} is not deterministic",
+                kind.name()
+            );
+        }
+    }
+
+    #[test]
+    fn strategy_kind_from_config() {
+        #[derive(Deserialize)]
+        struct Config {
+            strategy: StrategyKind
+        }
+        let parse = |s: &str| serde_json::from_str::<Config>(s).map(|c| c.strategy);
+
+        assert_eq!(parse(r#"{"strategy":"max-surplus"}"#).unwrap(), StrategyKind::MaxSurplus);
+        assert_eq!(parse(r#"{"strategy":"simple-checkpoint"}"#).unwrap(), StrategyKind::MaxVolume);
+        assert!(parse(r#"{"strategy":"max-profit"}"#).is_err());
+        for kind in StrategyKind::ALL {
+            assert_eq!(parse(&format!(r#"{{"strategy":"{}"}}"#, kind.name())).unwrap(), kind);
+        }
+    }
+}
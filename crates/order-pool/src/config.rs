@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use angstrom_types::primitive::PoolId;
 
 /// Guarantees max orders per sender
@@ -15,6 +17,9 @@ pub const SEARCHER_SUBPOOL_MAX_ORDERS_DEFAULT: usize = 100;
 /// The default maximum allowed size of the searcher subpool.
 pub const SEARCHER_SUBPOOL_MAX_SIZE_MB_DEFAULT: usize = 5;
 
+/// The block time of ethereum mainnet
+pub const DEFAULT_BLOCK_TIME: Duration = Duration::from_secs(12);
+
 /// Configuration options for the Transaction pool.
 #[derive(Debug, Clone)]
 pub struct PoolConfig {
@@ -31,7 +36,9 @@ pub struct PoolConfig {
     /// Max number of transaction in the searcher & composable searcher sub-pool
     pub s_pending_limit:   SearcherSubPoolLimit,
     /// Max number of executable transaction slots guaranteed per account
-    pub max_account_slots: usize
+    pub max_account_slots: usize,
+    /// Block time of the chain, used for order expiry and propagation deadlines
+    pub block_time:        Duration
 }
 
 impl Default for PoolConfig {
@@ -43,7 +50,8 @@ impl Default for PoolConfig {
             lo_parked_limit:   Default::default(),
             cl_pending_limit:  Default::default(),
             s_pending_limit:   Default::default(),
-            max_account_slots: ORDER_POOL_MAX_ACCOUNT_SLOTS_PER_SENDER
+            max_account_slots: ORDER_POOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+            block_time:        DEFAULT_BLOCK_TIME
         }
     }
 }
@@ -0,0 +1,285 @@
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH}
+};
+
+use alloy::primitives::{Address, B256};
+use angstrom_types::{orders::OrderOrigin, sol_bindings::grouped_orders::AllOrders};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+enum JournalEntry {
+    Order(AllOrders),
+    /// an order that came in through the private rpc and must stay off the
+    /// network after a restart
+    PrivateOrder(AllOrders),
+    Removed(B256),
+    Cancelled(JournaledCancel)
+}
+
+/// A cancellation that is kept until `valid_until`, so that an order that
+/// propagates late can't sneak back in after a restart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournaledCancel {
+    pub order_hash:  B256,
+    pub from:        Address,
+    pub valid_until: u64
+}
+
+#[derive(Debug, Default)]
+struct LiveEntries {
+    orders:    Vec<(OrderOrigin, AllOrders)>,
+    cancelled: Vec<JournaledCancel>
+}
+
+/// Append only log of the orders that made it into the pool, when they left
+/// it and the cancellations that are still in effect, so that standing orders
+/// survive a restart. The journal is compacted down to the live entries every
+/// time it is opened and whenever [`OrderJournal::compact`] is called.
+#[derive(Debug)]
+pub struct OrderJournal {
+    path:      PathBuf,
+    file:      File,
+    /// the orders that were still in the pool when the node stopped
+    replay:    Vec<(OrderOrigin, AllOrders)>,
+    /// the cancellations that were still in effect when the node stopped
+    cancelled: Vec<JournaledCancel>
+}
+
+impl OrderJournal {
+    /// opens the journal at the given path, creating it if it doesn't exist
+    pub fn open(path: PathBuf) -> eyre::Result<Self> {
+        let live = if path.exists() { Self::read_live_entries(&path)? } else { Default::default() };
+        let file = Self::rewrite(&path, &live)?;
+        tracing::info!(
+            ?path,
+            orders = live.orders.len(),
+            cancelled = live.cancelled.len(),
+            "opened order journal"
+        );
+
+        Ok(Self { path, file, replay: live.orders, cancelled: live.cancelled })
+    }
+
+    /// drops everything that is no longer live from the journal, so it doesn't
+    /// grow with every order the pool ever saw
+    pub fn compact(&mut self) -> eyre::Result<()> {
+        let live = Self::read_live_entries(&self.path)?;
+        self.file = Self::rewrite(&self.path, &live)?;
+        tracing::debug!(
+            path=?self.path,
+            orders = live.orders.len(),
+            cancelled = live.cancelled.len(),
+            "compacted order journal"
+        );
+
+        Ok(())
+    }
+
+    /// replaces the journal with only the given entries, returning it opened
+    /// for appending
+    fn rewrite(path: &Path, live: &LiveEntries) -> eyre::Result<File> {
+        let tmp = path.with_extension("tmp");
+        let mut file = File::create(&tmp)?;
+        for (origin, order) in &live.orders {
+            Self::write_entry(&mut file, &Self::order_entry(*origin, order))?;
+        }
+        for cancel in &live.cancelled {
+            Self::write_entry(&mut file, &JournalEntry::Cancelled(*cancel))?;
+        }
+        file.sync_all()?;
+        std::fs::rename(&tmp, path)?;
+
+        Ok(OpenOptions::new().append(true).open(path)?)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// the orders that were still in the pool when the node stopped. These
+    /// need to go through validation again before re-entering the pool
+    pub fn take_replay(&mut self) -> Vec<(OrderOrigin, AllOrders)> {
+        std::mem::take(&mut self.replay)
+    }
+
+    /// the cancellations that were still in effect when the node stopped
+    pub fn take_cancelled(&mut self) -> Vec<JournaledCancel> {
+        std::mem::take(&mut self.cancelled)
+    }
+
+    pub fn record_order(&mut self, origin: OrderOrigin, order: &AllOrders) {
+        self.append(Self::order_entry(origin, order));
+    }
+
+    fn order_entry(origin: OrderOrigin, order: &AllOrders) -> JournalEntry {
+        if origin == OrderOrigin::Private {
+            JournalEntry::PrivateOrder(order.clone())
+        } else {
+            JournalEntry::Order(order.clone())
+        }
+    }
+
+    /// the order was filled, cancelled, expired or became invalid
+    pub fn record_removal(&mut self, order_hash: B256) {
+        self.append(JournalEntry::Removed(order_hash));
+    }
+
+    pub fn record_cancel(&mut self, cancel: JournaledCancel) {
+        self.append(JournalEntry::Cancelled(cancel));
+    }
+
+    fn append(&mut self, entry: JournalEntry) {
+        if let Err(e) = Self::write_entry(&mut self.file, &entry) {
+            tracing::error!(%e, path=?self.path, "failed to write to order journal");
+        }
+    }
+
+    fn write_entry(file: &mut File, entry: &JournalEntry) -> eyre::Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        file.write_all(line.as_bytes())?;
+
+        Ok(())
+    }
+
+    fn read_live_entries(path: &Path) -> eyre::Result<LiveEntries> {
+        let mut live = HashMap::new();
+        let mut cancelled = HashMap::new();
+        // keep the order the orders came in, so that nonce ordering is respected on
+        // replay
+        let mut arrival = Vec::new();
+
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            // a crash can leave a partially written last line
+            let Ok(entry) = serde_json::from_str::<JournalEntry>(&line) else {
+                tracing::warn!(?path, "skipping corrupt order journal entry");
+                continue
+            };
+
+            match entry {
+                JournalEntry::Order(order) => {
+                    let hash = order.order_hash();
+                    if live.insert(hash, (OrderOrigin::Local, order)).is_none() {
+                        arrival.push(hash);
+                    }
+                }
+                JournalEntry::PrivateOrder(order) => {
+                    let hash = order.order_hash();
+                    if live.insert(hash, (OrderOrigin::Private, order)).is_none() {
+                        arrival.push(hash);
+                    }
+                }
+                JournalEntry::Removed(hash) => {
+                    live.remove(&hash);
+                }
+                JournalEntry::Cancelled(cancel) => {
+                    cancelled.insert(cancel.order_hash, cancel);
+                }
+            }
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        Ok(LiveEntries {
+            orders:    arrival
+                .into_iter()
+                .filter_map(|hash| live.remove(&hash))
+                .collect(),
+            cancelled: cancelled
+                .into_values()
+                .filter(|cancel| cancel.valid_until >= now)
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use testing_tools::type_generator::orders::UserOrderBuilder;
+
+    use super::*;
+
+    fn order(amount: u128) -> AllOrders {
+        UserOrderBuilder::new()
+            .standing()
+            .amount(amount)
+            .build()
+            .into()
+    }
+
+    #[test]
+    fn test_journal_replays_live_orders() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders.jsonl");
+
+        let mut journal = OrderJournal::open(path.clone()).unwrap();
+        assert!(journal.take_replay().is_empty());
+
+        let (filled, standing, private) = (order(100), order(200), order(300));
+        journal.record_order(OrderOrigin::External, &filled);
+        journal.record_order(OrderOrigin::External, &standing);
+        // revalidated orders get recorded again
+        journal.record_order(OrderOrigin::Local, &standing);
+        journal.record_order(OrderOrigin::Private, &private);
+        journal.record_removal(filled.order_hash());
+        drop(journal);
+
+        let expected =
+            vec![(OrderOrigin::Local, standing.clone()), (OrderOrigin::Private, private.clone())];
+        let mut journal = OrderJournal::open(path.clone()).unwrap();
+        assert_eq!(journal.take_replay(), expected);
+        drop(journal);
+
+        // the journal got compacted, so opening it again gives the same result
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+        let mut journal = OrderJournal::open(path).unwrap();
+        assert_eq!(journal.take_replay(), expected);
+    }
+
+    #[test]
+    fn test_journal_compacts_and_keeps_live_cancels() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders.jsonl");
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let mut journal = OrderJournal::open(path.clone()).unwrap();
+        let (filled, standing) = (order(100), order(200));
+        journal.record_order(OrderOrigin::External, &filled);
+        journal.record_order(OrderOrigin::External, &standing);
+        journal.record_removal(filled.order_hash());
+        let live_cancel = JournaledCancel {
+            order_hash:  B256::random(),
+            from:        Address::random(),
+            valid_until: now + 60
+        };
+        journal.record_cancel(live_cancel);
+        journal.record_cancel(JournaledCancel {
+            order_hash:  B256::random(),
+            from:        Address::random(),
+            valid_until: now - 1
+        });
+
+        journal.compact().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+
+        // appending keeps working after the compaction
+        journal.record_removal(standing.order_hash());
+        drop(journal);
+
+        let mut journal = OrderJournal::open(path).unwrap();
+        assert!(journal.take_replay().is_empty());
+        assert_eq!(journal.take_cancelled(), vec![live_cancel]);
+    }
+}
@@ -1,7 +1,9 @@
 mod common;
 mod config;
 mod finalization_pool;
+mod journal;
 mod limit;
+mod order_history;
 mod order_indexer;
 pub mod order_storage;
 
@@ -12,12 +14,13 @@ use std::future::Future;
 
 use alloy::primitives::{Address, FixedBytes, B256};
 use angstrom_types::{
-    orders::{CancelOrderRequest, OrderLocation, OrderOrigin, OrderStatus},
-    primitive::OrderPoolNewOrderResult,
+    orders::{CancelOrderRequest, OrderHistoryEntry, OrderLocation, OrderOrigin, OrderStatus},
+    primitive::{OrderPoolNewOrderResult, OrderRejectionReason},
     sol_bindings::grouped_orders::{AllOrders, OrderWithStorageData}
 };
 pub use angstrom_utils::*;
-pub use config::PoolConfig;
+pub use config::*;
+pub use journal::OrderJournal;
 pub use order_indexer::*;
 use tokio_stream::wrappers::BroadcastStream;
 
@@ -26,7 +29,16 @@ pub enum PoolManagerUpdate {
     NewOrder(OrderWithStorageData<AllOrders>),
     FilledOrder(u64, OrderWithStorageData<AllOrders>),
     UnfilledOrders(OrderWithStorageData<AllOrders>),
-    CancelledOrder { user: Address, pool_id: FixedBytes<32>, order_hash: B256 }
+    CancelledOrder {
+        user:       Address,
+        pool_id:    FixedBytes<32>,
+        order_hash: B256
+    },
+    /// an order failed validation or was dropped on revalidation
+    RejectedOrder {
+        order_hash: B256,
+        reason:     OrderRejectionReason
+    }
 }
 
 /// The OrderPool Trait is how other processes can interact with the orderpool
@@ -55,4 +67,9 @@ pub trait OrderPoolHandle: Send + Sync + Clone + Unpin + 'static {
         &self,
         order_hash: B256
     ) -> impl Future<Output = Option<OrderStatus>> + Send;
+
+    fn fetch_order_history(
+        &self,
+        order_hash: B256
+    ) -> impl Future<Output = Vec<OrderHistoryEntry>> + Send;
 }
@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 
+use alloy::primitives::B256;
 use angstrom_metrics::ComposableLimitOrderPoolMetricsWrapper;
 use angstrom_types::{
+    orders::OrderStatus,
     primitive::{NewInitializedPool, PoolId},
     sol_bindings::grouped_orders::{GroupedComposableOrder, OrderWithStorageData}
 };
@@ -31,6 +33,16 @@ impl ComposableLimitPool {
             .and_then(|pool| pool.get_order(order_id))
     }
 
+    pub fn get_order_status(&self, order_hash: B256) -> Option<OrderStatus> {
+        self.map
+            .values()
+            .find_map(|pool| pool.get_order(order_hash).map(|_| OrderStatus::Pending))
+    }
+
+    pub fn get_all_orders(&self) -> Vec<OrderWithStorageData<GroupedComposableOrder>> {
+        self.map.values().flat_map(|p| p.get_all_orders()).collect()
+    }
+
     pub fn add_order(
         &mut self,
         order: OrderWithStorageData<GroupedComposableOrder>
@@ -62,7 +62,9 @@ impl LimitOrderPool {
     }
 
     pub fn get_order_status(&self, order_hash: B256) -> Option<OrderStatus> {
-        self.limit_orders.get_order_status(order_hash)
+        self.limit_orders
+            .get_order_status(order_hash)
+            .or_else(|| self.composable_orders.get_order_status(order_hash))
     }
 
     pub fn add_composable_order(
@@ -108,21 +110,39 @@ impl LimitOrderPool {
             })
     }
 
+    /// All orders that can be matched, composable orders are matched the same
+    /// way vanilla orders are.
     pub fn get_all_orders(&self) -> Vec<OrderWithStorageData<GroupedVanillaOrder>> {
-        self.limit_orders.get_all_orders()
+        let mut orders = self.limit_orders.get_all_orders();
+        orders.extend(
+            self.composable_orders
+                .get_all_orders()
+                .into_iter()
+                .map(|order| order.try_map_inner(|this| Ok(this.into())).unwrap())
+        );
+
+        orders
     }
 
     pub fn get_all_orders_from_pool(&self, pool: FixedBytes<32>) -> Vec<AllOrders> {
-        self.limit_orders
+        let vanilla = self
+            .limit_orders
             .pending_orders
             .get(&pool)
-            .map(|pool| {
-                pool.get_all_orders()
-                    .into_iter()
-                    .map(|p| p.order.into())
-                    .collect::<Vec<_>>()
-            })
+            .map(|pool| pool.get_all_orders())
             .unwrap_or_default()
+            .into_iter()
+            .map(|p| p.order.into());
+        let composable = self
+            .composable_orders
+            .map
+            .get(&pool)
+            .map(|pool| pool.get_all_orders())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|p| p.order.into());
+
+        vanilla.chain(composable).collect()
     }
 
     pub fn park_order(&mut self, id: &OrderId) {
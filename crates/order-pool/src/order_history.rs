@@ -0,0 +1,130 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{SystemTime, UNIX_EPOCH}
+};
+
+use alloy::primitives::{BlockNumber, B256};
+use angstrom_types::orders::{OrderFillState, OrderHistoryEntry, OrderLifecycleEvent, OrderStatus};
+
+/// the most events we keep for a single order, older ones are dropped first
+const MAX_EVENTS_PER_ORDER: usize = 32;
+/// the most orders we keep a history for, the ones we saw first are dropped
+/// first
+const MAX_TRACKED_ORDERS: usize = 100_000;
+
+/// Bounded log of what happened to every order this node has seen, so that
+/// orders that already left the pool can still be looked up
+#[derive(Debug, Default)]
+pub struct OrderHistory {
+    orders:  HashMap<B256, VecDeque<OrderHistoryEntry>>,
+    /// order hashes by the time they were first seen, used for eviction
+    arrival: VecDeque<B256>
+}
+
+impl OrderHistory {
+    pub fn record(
+        &mut self,
+        order_hash: B256,
+        block_number: BlockNumber,
+        event: OrderLifecycleEvent
+    ) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let entry = OrderHistoryEntry { block_number, timestamp, event };
+
+        let events = self.orders.entry(order_hash).or_insert_with(|| {
+            self.arrival.push_back(order_hash);
+            VecDeque::new()
+        });
+        if events.len() == MAX_EVENTS_PER_ORDER {
+            events.pop_front();
+        }
+        events.push_back(entry);
+
+        while self.arrival.len() > MAX_TRACKED_ORDERS {
+            if let Some(evicted) = self.arrival.pop_front() {
+                self.orders.remove(&evicted);
+            }
+        }
+    }
+
+    pub fn history(&self, order_hash: &B256) -> Vec<OrderHistoryEntry> {
+        self.orders
+            .get(order_hash)
+            .map(|events| events.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// how the order left the pool, if it did
+    pub fn final_status(&self, order_hash: &B256) -> Option<OrderStatus> {
+        self.orders
+            .get(order_hash)?
+            .back()
+            .and_then(|entry| entry.event.final_status())
+    }
+
+    /// the fill of the last bundle we built that included the order
+    pub fn bundle_fill(&self, order_hash: &B256) -> Option<OrderFillState> {
+        self.orders
+            .get(order_hash)?
+            .iter()
+            .rev()
+            .find_map(|entry| match entry.event {
+                OrderLifecycleEvent::InBundle { fill } => Some(fill),
+                _ => None
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use angstrom_types::primitive::OrderRejectionReason;
+
+    use super::*;
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = OrderHistory::default();
+        let hash = B256::random();
+
+        for block in 0..(MAX_EVENTS_PER_ORDER as u64 + 5) {
+            history.record(hash, block, OrderLifecycleEvent::Pending);
+        }
+        let events = history.history(&hash);
+        assert_eq!(events.len(), MAX_EVENTS_PER_ORDER);
+        assert_eq!(events[0].block_number, 5);
+
+        for _ in 0..MAX_TRACKED_ORDERS {
+            history.record(B256::random(), 0, OrderLifecycleEvent::Validated);
+        }
+        assert!(history.history(&hash).is_empty());
+    }
+
+    #[test]
+    fn test_final_status() {
+        let mut history = OrderHistory::default();
+        let hash = B256::random();
+
+        history.record(hash, 1, OrderLifecycleEvent::Pending);
+        assert_eq!(history.final_status(&hash), None);
+
+        history.record(
+            hash,
+            2,
+            OrderLifecycleEvent::InBundle { fill: OrderFillState::PartialFill(7) }
+        );
+        assert_eq!(history.bundle_fill(&hash), Some(OrderFillState::PartialFill(7)));
+
+        history.record(
+            hash,
+            3,
+            OrderLifecycleEvent::Invalidated { reason: OrderRejectionReason::DuplicateNonce }
+        );
+        assert_eq!(
+            history.final_status(&hash),
+            Some(OrderStatus::Invalid(OrderRejectionReason::DuplicateNonce))
+        );
+    }
+}
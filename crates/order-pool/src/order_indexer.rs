@@ -8,8 +8,11 @@ use std::{
 
 use alloy::primitives::{Address, BlockNumber, FixedBytes, B256, U256};
 use angstrom_types::{
-    orders::{OrderId, OrderLocation, OrderOrigin, OrderSet, OrderStatus},
-    primitive::{NewInitializedPool, PeerId, PoolId},
+    orders::{
+        OrderHistoryEntry, OrderId, OrderLifecycleEvent, OrderLocation, OrderOrigin, OrderSet,
+        OrderStatus
+    },
+    primitive::{NewInitializedPool, OrderRejectionReason, PeerId, PoolId},
     sol_bindings::{
         grouped_orders::{AllOrders, OrderWithStorageData, *},
         rpc_orders::TopOfBlockOrder,
@@ -25,19 +28,20 @@ use validation::order::{
 };
 
 use crate::{
+    config::{DEFAULT_BLOCK_TIME, ORDER_POOL_MAX_ACCOUNT_SLOTS_PER_SENDER},
+    journal::{JournaledCancel, OrderJournal},
     order_storage::OrderStorage,
     validator::{OrderValidator, OrderValidatorRes},
     PoolManagerUpdate
 };
 
-/// This is used to remove validated orders. During validation
-/// the same check wil be ran but with more accuracy
-const ETH_BLOCK_TIME: Duration = Duration::from_secs(12);
 /// mostly arbitrary
 const SEEN_INVALID_ORDERS_CAPACITY: usize = 10000;
 /// represents the maximum number of blocks that we allow for new orders to not
 /// propagate (again mostly arbitrary)
 const MAX_NEW_ORDER_DELAY_PROPAGATION: u64 = 7000;
+/// how many blocks go by between dropping dead entries from the order journal
+const JOURNAL_COMPACTION_INTERVAL: u64 = 256;
 
 struct CancelOrderRequest {
     /// The address of the entity requesting the cancellation.
@@ -68,7 +72,19 @@ pub struct OrderIndexer<V: OrderValidatorHandle> {
     /// List of subscribers for order validation result
     order_validation_subs:  HashMap<B256, Vec<Sender<OrderValidationResults>>>,
     /// List of subscribers for order state change notifications
-    orders_subscriber_tx:   tokio::sync::broadcast::Sender<PoolManagerUpdate>
+    orders_subscriber_tx:   tokio::sync::broadcast::Sender<PoolManagerUpdate>,
+    /// on disk record of the pool so that orders survive restarts
+    journal:                Option<OrderJournal>,
+    /// orders from the journal that get re-validated once we are on a new block
+    pending_replay:         Vec<(OrderOrigin, AllOrders)>,
+    /// hashes of the journaled orders that haven't made it back into the pool
+    /// yet. If they don't, their journal entry has to be removed
+    replayed:               HashSet<B256>,
+    /// the most orders a single account can have in the pool
+    max_account_slots:      usize,
+    /// This is used to remove validated orders. During validation
+    /// the same check wil be ran but with more accuracy
+    block_time:             Duration
 }
 
 impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
@@ -90,10 +106,52 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
             cancelled_orders: HashMap::new(),
             order_validation_subs: HashMap::new(),
             validator: OrderValidator::new(validator),
-            orders_subscriber_tx
+            orders_subscriber_tx,
+            journal: None,
+            pending_replay: Vec::new(),
+            replayed: HashSet::new(),
+            max_account_slots: ORDER_POOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+            block_time: DEFAULT_BLOCK_TIME
         }
     }
 
+    pub fn with_max_account_slots(mut self, max_account_slots: usize) -> Self {
+        self.max_account_slots = max_account_slots;
+        self
+    }
+
+    pub fn with_block_time(mut self, block_time: Duration) -> Self {
+        self.block_time = block_time;
+        self
+    }
+
+    /// journals all pool changes. The orders from the previous run are
+    /// validated against the state of the first block we process
+    pub fn with_journal(mut self, mut journal: OrderJournal) -> Self {
+        self.pending_replay = journal.take_replay();
+        self.replayed = self
+            .pending_replay
+            .iter()
+            .map(|(_, order)| order.order_hash())
+            .collect();
+        self.pending_replay
+            .iter()
+            .filter(|(origin, _)| *origin == OrderOrigin::Private)
+            .for_each(|(_, order)| self.order_storage.mark_private(order.order_hash()));
+        self.cancelled_orders
+            .extend(journal.take_cancelled().into_iter().map(|cancel| {
+                (
+                    cancel.order_hash,
+                    CancelOrderRequest {
+                        from:        cancel.from,
+                        valid_until: cancel.valid_until
+                    }
+                )
+            }));
+        self.journal = Some(journal);
+        self
+    }
+
     pub fn pending_orders_for_address(
         &self,
         address: Address
@@ -101,26 +159,7 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         let mut orders = Vec::new();
         if let Some(order_ids) = self.address_to_orders.get(&address) {
             for order_id in order_ids {
-                let order = match order_id.location {
-                    angstrom_types::orders::OrderLocation::Limit => self
-                        .order_storage
-                        .limit_orders
-                        .lock()
-                        .expect("lock poisoned")
-                        .get_order(order_id)
-                        .and_then(|order| order.try_map_inner(|inner| Ok(inner.into())).ok()),
-                    angstrom_types::orders::OrderLocation::Searcher => self
-                        .order_storage
-                        .searcher_orders
-                        .lock()
-                        .expect("lock poisoned")
-                        .get_order(order_id.pool_id, order_id.hash)
-                        .and_then(|order| {
-                            order.try_map_inner(|inner| Ok(AllOrders::TOB(inner))).ok()
-                        })
-                };
-
-                if let Some(order) = order {
+                if let Some(order) = self.get_order(order_id) {
                     orders.push(order);
                 }
             }
@@ -128,12 +167,69 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         orders
     }
 
+    fn get_order(&self, order_id: &OrderId) -> Option<OrderWithStorageData<AllOrders>> {
+        match order_id.location {
+            angstrom_types::orders::OrderLocation::Limit => self
+                .order_storage
+                .limit_orders
+                .lock()
+                .expect("lock poisoned")
+                .get_order(order_id)
+                .and_then(|order| order.try_map_inner(|inner| Ok(inner.into())).ok()),
+            angstrom_types::orders::OrderLocation::Searcher => self
+                .order_storage
+                .searcher_orders
+                .lock()
+                .expect("lock poisoned")
+                .get_order(order_id.pool_id, order_id.hash)
+                .and_then(|order| order.try_map_inner(|inner| Ok(AllOrders::TOB(inner))).ok())
+        }
+    }
+
+    pub fn pool_ids(&self) -> Vec<PoolId> {
+        self.order_storage
+            .searcher_orders
+            .lock()
+            .expect("poisoned")
+            .get_all_pool_ids()
+    }
+
+    /// the hashes of all public orders we are tracking in the given pools
+    pub fn order_hashes_by_pool(&self, pools: &[PoolId]) -> Vec<B256> {
+        self.order_hash_to_order_id
+            .iter()
+            .filter(|(hash, id)| pools.contains(&id.pool_id) && !self.is_private(hash))
+            .map(|(hash, _)| *hash)
+            .collect()
+    }
+
+    pub fn orders_by_hash(&self, hashes: &[B256]) -> Vec<AllOrders> {
+        hashes
+            .iter()
+            .filter(|hash| !self.is_private(hash))
+            .filter_map(|hash| self.order_hash_to_order_id.get(hash))
+            .filter_map(|id| self.get_order(id))
+            .map(|order| order.order)
+            .collect()
+    }
+
+    /// filters out all of the hashes we have already seen, either as a valid or
+    /// invalid order
+    pub fn missing_orders(&self, hashes: Vec<B256>) -> Vec<B256> {
+        hashes
+            .into_iter()
+            .filter(|hash| {
+                self.is_missing(hash) && !self.is_seen_invalid(hash) && !self.is_cancelled(hash)
+            })
+            .collect()
+    }
+
     pub fn orders_by_pool(
         &self,
         pool_id: FixedBytes<32>,
         order_location: OrderLocation
     ) -> Vec<AllOrders> {
-        match order_location {
+        let mut orders = match order_location {
             OrderLocation::Limit => self
                 .order_storage
                 .limit_orders
@@ -146,11 +242,43 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
                 .lock()
                 .expect("poisoned")
                 .get_all_orders_from_pool(pool_id)
-        }
+        };
+        orders.retain(|order| !self.is_private(&order.order_hash()));
+
+        orders
     }
 
     pub fn order_status(&self, order_hash: B256) -> Option<OrderStatus> {
-        self.order_storage.fetch_status_of_order(order_hash)
+        self.order_storage
+            .fetch_status_of_order(order_hash)
+            .map(|status| match status {
+                OrderStatus::Pending | OrderStatus::Blocked if self.is_private(&order_hash) => {
+                    OrderStatus::Private
+                }
+                status => status
+            })
+    }
+
+    /// everything that happened to the order while this node was tracking it
+    pub fn order_history(&self, order_hash: B256) -> Vec<OrderHistoryEntry> {
+        self.order_storage.order_history(order_hash)
+    }
+
+    fn record_event(&self, order_hash: B256, event: OrderLifecycleEvent) {
+        self.order_storage
+            .record_order_event(order_hash, self.block_number, event);
+    }
+
+    fn is_private(&self, order_hash: &B256) -> bool {
+        self.order_storage.is_private(order_hash)
+    }
+
+    fn revalidation_origin(&self, order_hash: &B256) -> OrderOrigin {
+        if self.is_private(order_hash) {
+            OrderOrigin::Private
+        } else {
+            OrderOrigin::Local
+        }
     }
 
     fn is_missing(&self, order_hash: &B256) -> bool {
@@ -165,8 +293,25 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         self.cancelled_orders.contains_key(order_hash)
     }
 
-    pub fn remove_pool(&self, key: PoolId) {
+    pub fn remove_pool(&mut self, key: PoolId) {
         self.order_storage.remove_pool(key);
+
+        let removed = self
+            .order_hash_to_order_id
+            .iter()
+            .filter(|(_, id)| id.pool_id == key)
+            .map(|(hash, _)| *hash)
+            .collect::<Vec<_>>();
+        for hash in removed {
+            self.order_hash_to_order_id.remove(&hash);
+            self.order_hash_to_peer_id.remove(&hash);
+            self.order_storage.unmark_private(&hash);
+            self.journal_removal(hash);
+        }
+        self.address_to_orders.retain(|_, ids| {
+            ids.retain(|id| id.pool_id != key);
+            !ids.is_empty()
+        });
     }
 
     fn is_duplicate(&self, order_hash: &B256) -> bool {
@@ -210,7 +355,7 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
                 .duration_since(UNIX_EPOCH)
                 .unwrap()
                 .as_secs()
-                + MAX_NEW_ORDER_DELAY_PROPAGATION * ETH_BLOCK_TIME.as_secs();
+                + MAX_NEW_ORDER_DELAY_PROPAGATION * self.block_time.as_secs();
             self.insert_cancel_request_with_deadline(
                 request.user_address,
                 &request.order_id,
@@ -221,6 +366,9 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         }
         let id = self.order_hash_to_order_id.remove(&request.order_id);
         if let Some(order) = id.and_then(|v| self.order_storage.cancel_order(&v)) {
+            self.record_event(order.order_hash(), OrderLifecycleEvent::Cancelled);
+            self.journal_removal(order.order_hash());
+            self.order_storage.unmark_private(&order.order_hash());
             self.order_hash_to_order_id.remove(&order.order_hash());
             self.order_hash_to_peer_id.remove(&order.order_hash());
             self.insert_cancel_request_with_deadline(
@@ -263,6 +411,9 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         );
         self.cancelled_orders
             .insert(*order_hash, CancelOrderRequest { from, valid_until });
+        if let Some(journal) = self.journal.as_mut() {
+            journal.record_cancel(JournaledCancel { order_hash: *order_hash, from, valid_until });
+        }
     }
 
     fn new_order(
@@ -299,12 +450,27 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
                     });
                 }
                 self.order_storage.log_cancel_order(&order);
+                self.record_event(hash, OrderLifecycleEvent::Cancelled);
             }
-            self.notify_validation_subscribers(&hash, OrderValidationResults::Invalid(hash));
+            let reason = if is_valid_cancel_request {
+                OrderRejectionReason::Cancelled
+            } else if self.is_seen_invalid(&hash) {
+                OrderRejectionReason::PreviouslyRejected
+            } else {
+                OrderRejectionReason::DuplicateOrder
+            };
+            self.notify_validation_subscribers(
+                &hash,
+                OrderValidationResults::Invalid(hash, reason)
+            );
             return
         }
 
         let hash = order.order_hash();
+        self.record_event(hash, OrderLifecycleEvent::Received { origin });
+        if origin == OrderOrigin::Private {
+            self.order_storage.mark_private(hash);
+        }
         if let Some(peer) = peer_id {
             self.order_hash_to_peer_id
                 .entry(hash)
@@ -319,7 +485,7 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
     fn remove_expired_orders(&mut self, block_number: BlockNumber) -> Vec<B256> {
         self.block_number = block_number;
         let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
-        let expiry_deadline = U256::from((time + ETH_BLOCK_TIME).as_secs()); // grab all expired hashes
+        let expiry_deadline = U256::from((time + self.block_time).as_secs()); // grab all expired hashes
         let hashes = self
             .order_hash_to_order_id
             .iter()
@@ -331,6 +497,11 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
             })
             .map(|(k, _)| *k)
             .collect::<Vec<_>>();
+        hashes.iter().for_each(|hash| {
+            self.record_event(*hash, OrderLifecycleEvent::Expired);
+            self.journal_removal(*hash);
+            self.order_storage.unmark_private(hash);
+        });
 
         // TODO: notify rpc of dead orders
         let _expired_orders = hashes
@@ -365,14 +536,22 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
                         return
                     };
 
-                    self.validator
-                        .validate_order(OrderOrigin::Local, order.order);
+                    let origin = self.revalidation_origin(&order.order_hash());
+                    self.validator.validate_order(origin, order.order);
                 })
             });
     }
 
     pub fn finalized_block(&mut self, block_number: BlockNumber) {
         self.order_storage.finalized_block(block_number);
+
+        // filled private orders are kept around until they can no longer be
+        // reorged back into the pool
+        self.order_storage.retain_private(|hash| {
+            self.order_hash_to_order_id.contains_key(hash)
+                || self.order_validation_subs.contains_key(hash)
+                || self.order_storage.fetch_status_of_order(*hash).is_some()
+        });
     }
 
     pub fn reorg(&mut self, orders: Vec<B256>) {
@@ -380,9 +559,10 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
             .reorg(orders)
             .into_iter()
             .for_each(|order| {
+                self.record_event(order.order_hash(), OrderLifecycleEvent::Reorged);
                 self.notify_order_subscribers(PoolManagerUpdate::UnfilledOrders(order.clone()));
-                self.validator
-                    .validate_order(OrderOrigin::Local, order.order)
+                let origin = self.revalidation_origin(&order.order_hash());
+                self.validator.validate_order(origin, order.order)
             });
     }
 
@@ -402,6 +582,14 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
             .collect::<Vec<OrderWithStorageData<AllOrders>>>();
 
         filled_orders.iter().for_each(|order| {
+            let hash = order.order_hash();
+            let fill = self.order_storage.bundle_fill(hash);
+            self.order_storage.record_order_event(
+                hash,
+                block_number,
+                OrderLifecycleEvent::Filled { fill }
+            );
+            self.journal_removal(hash);
             self.notify_order_subscribers(PoolManagerUpdate::FilledOrder(
                 block_number,
                 order.clone()
@@ -418,6 +606,13 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
             .iter()
             .filter_map(|tx_hash| self.order_hash_to_order_id.get(tx_hash))
             .collect::<Vec<_>>();
+        for order_id in &order_info {
+            self.order_storage.record_order_event(
+                order_id.hash,
+                self.block_number,
+                OrderLifecycleEvent::Parked
+            );
+        }
         self.order_storage.park_orders(order_info);
     }
 
@@ -431,45 +626,136 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
 
                 // what about the deadline?
                 if valid.valid_block != self.block_number {
+                    let reason = OrderRejectionReason::StaleValidation {
+                        validated: valid.valid_block,
+                        current:   self.block_number
+                    };
+                    self.record_event(
+                        hash,
+                        OrderLifecycleEvent::Invalidated { reason: reason.clone() }
+                    );
+                    self.notify_order_subscribers(PoolManagerUpdate::RejectedOrder {
+                        order_hash: hash,
+                        reason:     reason.clone()
+                    });
                     self.notify_validation_subscribers(
                         &hash,
-                        OrderValidationResults::Invalid(hash)
+                        OrderValidationResults::Invalid(hash, reason)
                     );
 
+                    if self.replayed.remove(&hash)
+                        || self.order_hash_to_order_id.contains_key(&hash)
+                    {
+                        self.journal_removal(hash);
+                    }
+                    self.order_storage.unmark_private(&hash);
                     self.seen_invalid_orders.insert(hash);
                     let peers = self.order_hash_to_peer_id.remove(&hash).unwrap_or_default();
                     return Ok(PoolInnerEvent::BadOrderMessages(peers))
                 }
 
-                self.notify_order_subscribers(PoolManagerUpdate::NewOrder(valid.clone()));
+                // orders that are being re-validated already hold their slot
+                if !self.order_hash_to_order_id.contains_key(&hash)
+                    && self.account_slots_used(valid.from()) >= self.max_account_slots
+                {
+                    let reason =
+                        OrderRejectionReason::AccountSlotsExceeded { max: self.max_account_slots };
+                    self.record_event(
+                        hash,
+                        OrderLifecycleEvent::Invalidated { reason: reason.clone() }
+                    );
+                    self.notify_order_subscribers(PoolManagerUpdate::RejectedOrder {
+                        order_hash: hash,
+                        reason:     reason.clone()
+                    });
+                    self.notify_validation_subscribers(
+                        &hash,
+                        OrderValidationResults::Invalid(hash, reason)
+                    );
+                    if self.replayed.remove(&hash) {
+                        self.journal_removal(hash);
+                    }
+                    // the order is valid, so the peers that sent it did nothing wrong
+                    self.order_hash_to_peer_id.remove(&hash);
+                    return Ok(PoolInnerEvent::None)
+                }
+
+                // private orders are only surfaced to the rpc that submitted them
+                let private = self.is_private(&hash);
+                if !private {
+                    self.notify_order_subscribers(PoolManagerUpdate::NewOrder(valid.clone()));
+                }
                 self.notify_validation_subscribers(
                     &hash,
                     OrderValidationResults::Valid(valid.clone())
                 );
 
                 let to_propagate = valid.order.clone();
+                self.replayed.remove(&hash);
+                // re-validations of orders already in the pool are in the journal
+                let is_new = !self.order_hash_to_order_id.contains_key(&hash);
+                if is_new {
+                    self.record_event(hash, OrderLifecycleEvent::Validated);
+                }
                 self.update_order_tracking(&hash, valid.from(), valid.order_id);
                 self.park_transactions(&valid.invalidates);
                 self.insert_order(valid)?;
+                let state = match self.order_storage.fetch_status_of_order(hash) {
+                    Some(OrderStatus::Blocked) => OrderLifecycleEvent::Parked,
+                    _ => OrderLifecycleEvent::Pending
+                };
+                self.record_event(hash, state);
+                if let Some(journal) = self.journal.as_mut().filter(|_| is_new) {
+                    let origin = if private { OrderOrigin::Private } else { OrderOrigin::External };
+                    journal.record_order(origin, &to_propagate);
+                }
 
+                if private {
+                    return Ok(PoolInnerEvent::None)
+                }
                 Ok(PoolInnerEvent::Propagation(to_propagate))
             }
-            OrderValidationResults::Invalid(bad_hash) => {
+            OrderValidationResults::Invalid(bad_hash, reason) => {
+                let bad_hook = matches!(reason, OrderRejectionReason::InvalidHook(_));
+                self.record_event(
+                    bad_hash,
+                    OrderLifecycleEvent::Invalidated { reason: reason.clone() }
+                );
+                self.notify_order_subscribers(PoolManagerUpdate::RejectedOrder {
+                    order_hash: bad_hash,
+                    reason:     reason.clone()
+                });
                 self.notify_validation_subscribers(
                     &bad_hash,
-                    OrderValidationResults::Invalid(bad_hash)
+                    OrderValidationResults::Invalid(bad_hash, reason)
                 );
+                // an order that was in the pool, or in the journal, failed re-validation
+                if self.replayed.remove(&bad_hash)
+                    || self.order_hash_to_order_id.contains_key(&bad_hash)
+                {
+                    self.journal_removal(bad_hash);
+                }
+                self.order_storage.unmark_private(&bad_hash);
                 self.seen_invalid_orders.insert(bad_hash);
                 let peers = self
                     .order_hash_to_peer_id
                     .remove(&bad_hash)
                     .unwrap_or_default();
+                if bad_hook {
+                    return Ok(PoolInnerEvent::BadComposableOrderMessages(peers))
+                }
                 Ok(PoolInnerEvent::BadOrderMessages(peers))
             }
             OrderValidationResults::TransitionedToBlock => Ok(PoolInnerEvent::None)
         }
     }
 
+    fn journal_removal(&mut self, order_hash: B256) {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.record_removal(order_hash);
+        }
+    }
+
     fn notify_order_subscribers(&mut self, update: PoolManagerUpdate) {
         let _ = self.orders_subscriber_tx.send(update);
     }
@@ -500,7 +786,14 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
                 .order_storage
                 .add_new_limit_order(
                     res.try_map_inner(|inner| {
+                        let composable = inner.is_composable();
                         Ok(match inner {
+                            AllOrders::Standing(p) if composable => {
+                                GroupedUserOrder::Composable(GroupedComposableOrder::Partial(p))
+                            }
+                            AllOrders::Flash(kof) if composable => GroupedUserOrder::Composable(
+                                GroupedComposableOrder::KillOrFill(kof)
+                            ),
                             AllOrders::Standing(p) => {
                                 GroupedUserOrder::Vanilla(GroupedVanillaOrder::Standing(p))
                             }
@@ -516,6 +809,18 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         }
     }
 
+    /// the amount of orders the account currently has in the pool
+    fn account_slots_used(&self, user: Address) -> usize {
+        // re-validated orders get tracked again, so only count unique live orders
+        self.address_to_orders.get(&user).map_or(0, |ids| {
+            ids.iter()
+                .filter(|id| self.order_hash_to_order_id.contains_key(&id.hash))
+                .map(|id| id.hash)
+                .collect::<HashSet<_>>()
+                .len()
+        })
+    }
+
     fn update_order_tracking(&mut self, hash: &B256, user: UserAddress, id: OrderId) {
         self.order_hash_to_peer_id.remove(hash);
         self.order_hash_to_order_id.insert(*hash, id);
@@ -563,11 +868,28 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         self.cancelled_orders
             .retain(|_, request| request.valid_until >= time_now);
 
+        if block_number % JOURNAL_COMPACTION_INTERVAL == 0 {
+            if let Some(journal) = self.journal.as_mut() {
+                if let Err(e) = journal.compact() {
+                    tracing::error!(%e, path=?journal.path(), "failed to compact order journal");
+                }
+            }
+        }
+
         self.validator.notify_validation_on_changes(
             block_number,
             completed_orders,
             address_changes
         );
+
+        // these get queued until the validator is on the new block, so that their
+        // nonces and deadlines are checked against current state
+        if !self.pending_replay.is_empty() {
+            tracing::info!(orders = self.pending_replay.len(), "replaying journaled orders");
+            std::mem::take(&mut self.pending_replay)
+                .into_iter()
+                .for_each(|(origin, order)| self.validator.validate_order(origin, order));
+        }
     }
 }
 
@@ -610,6 +932,8 @@ where
 pub enum PoolInnerEvent {
     Propagation(AllOrders),
     BadOrderMessages(Vec<PeerId>),
+    /// peers that propagated a composable order whose hook fails
+    BadComposableOrderMessages(Vec<PeerId>),
     HasTransitionedToNewBlock(u64),
     None
 }
@@ -945,7 +1269,10 @@ mod tests {
         indexer.new_rpc_order(OrderOrigin::Local, order.clone(), tx);
 
         indexer
-            .handle_validated_order(OrderValidationResults::Invalid(order_hash))
+            .handle_validated_order(OrderValidationResults::Invalid(
+                order_hash,
+                OrderRejectionReason::UnknownPool
+            ))
             .unwrap();
 
         // Verify order was marked as invalid
@@ -953,11 +1280,125 @@ mod tests {
 
         // Verify validation result
         match rx.await {
-            Ok(OrderValidationResults::Invalid(hash)) => assert_eq!(hash, order_hash),
+            Ok(OrderValidationResults::Invalid(hash, reason)) => {
+                assert_eq!(hash, order_hash);
+                assert_eq!(reason, OrderRejectionReason::UnknownPool);
+            }
             _ => panic!("Expected invalid order result")
         }
     }
 
+    #[tokio::test]
+    async fn test_replayed_orders_that_are_now_invalid_leave_the_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders.jsonl");
+        let pool_key = PoolKey {
+            currency0: Address::random(),
+            currency1: Address::random(),
+            ..Default::default()
+        };
+        let (invalid, stale) = (
+            create_test_order(Address::random(), pool_key.clone(), None, None),
+            create_test_order(Address::random(), pool_key.clone(), None, None)
+        );
+
+        let mut journal = OrderJournal::open(path.clone()).unwrap();
+        journal.record_order(OrderOrigin::External, &invalid);
+        journal.record_order(OrderOrigin::External, &stale);
+        drop(journal);
+
+        let mut indexer =
+            setup_test_indexer().with_journal(OrderJournal::open(path.clone()).unwrap());
+        indexer
+            .handle_validated_order(OrderValidationResults::Invalid(
+                invalid.order_hash(),
+                OrderRejectionReason::UnknownPool
+            ))
+            .unwrap();
+        indexer
+            .handle_validated_order(OrderValidationResults::Valid(OrderWithStorageData {
+                order:              stale.clone(),
+                order_id:           OrderId { hash: stale.order_hash(), ..Default::default() },
+                // validated against the block before the restart
+                valid_block:        0,
+                pool_id:            PoolId::from(pool_key),
+                is_bid:             true,
+                is_currently_valid: true,
+                is_valid:           true,
+                priority_data:      Default::default(),
+                invalidates:        vec![],
+                tob_reward:         U256::ZERO
+            }))
+            .unwrap();
+        drop(indexer);
+
+        // neither order made it back into the pool, so neither gets replayed again
+        let mut journal = OrderJournal::open(path).unwrap();
+        assert!(journal.take_replay().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_revalidated_orders_are_journaled_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders.jsonl");
+        let mut indexer =
+            setup_test_indexer().with_journal(OrderJournal::open(path.clone()).unwrap());
+
+        let pool_key = PoolKey {
+            currency0: Address::random(),
+            currency1: Address::random(),
+            ..Default::default()
+        };
+        let pool_id = PoolId::from(pool_key.clone());
+        indexer.new_pool(NewInitializedPool {
+            id:           pool_id,
+            currency_in:  pool_key.currency0,
+            currency_out: pool_key.currency1
+        });
+
+        let from = Address::random();
+        let order = create_test_order(from, pool_key, None, None);
+        let (tx, _) = tokio::sync::oneshot::channel();
+        indexer.new_rpc_order(OrderOrigin::Local, order.clone(), tx);
+        let valid = OrderWithStorageData {
+            order: order.clone(),
+            order_id: OrderId {
+                address: from,
+                reuse_avoidance: RespendAvoidanceMethod::Nonce(1),
+                hash: order.order_hash(),
+                pool_id,
+                location: OrderLocation::Limit,
+                deadline: None,
+                flash_block: None
+            },
+            valid_block: 1,
+            pool_id,
+            is_bid: true,
+            is_currently_valid: true,
+            is_valid: true,
+            priority_data: Default::default(),
+            invalidates: vec![],
+            tob_reward: U256::ZERO
+        };
+        indexer
+            .handle_validated_order(OrderValidationResults::Valid(valid.clone()))
+            .unwrap();
+
+        // the account changed, so the order goes through validation again
+        indexer.eoa_state_change(&[from]);
+        indexer
+            .handle_validated_order(OrderValidationResults::Valid(valid))
+            .unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+
+        // orders of a removed pool leave the journal with it
+        indexer.remove_pool(pool_id);
+        drop(indexer);
+        let mut journal = OrderJournal::open(path).unwrap();
+        assert!(journal.take_replay().is_empty());
+    }
+
     #[tokio::test]
     async fn test_pool_management() {
         let mut indexer = setup_test_indexer();
@@ -1087,6 +1528,282 @@ mod tests {
         assert!(indexer.address_to_orders.contains_key(&from));
     }
 
+    #[tokio::test]
+    async fn test_order_sync_lookups() {
+        let mut indexer = setup_test_indexer();
+        let s = AngstromSigner::random();
+        let from = s.address();
+
+        let pool_key = PoolKey {
+            currency0: Address::random(),
+            currency1: Address::random(),
+            ..Default::default()
+        };
+        let pool_id = PoolId::from(pool_key.clone());
+        indexer.new_pool(NewInitializedPool {
+            currency_out: pool_key.currency0,
+            currency_in:  pool_key.currency1,
+            id:           pool_id
+        });
+        let order = create_test_order(from, pool_key, None, Some(s));
+        let order_hash = order.order_hash();
+
+        indexer
+            .handle_validated_order(OrderValidationResults::Valid(OrderWithStorageData {
+                order: order.clone(),
+                order_id: OrderId {
+                    address: from,
+                    reuse_avoidance: RespendAvoidanceMethod::Nonce(1),
+                    hash: order_hash,
+                    pool_id,
+                    location: OrderLocation::Limit,
+                    deadline: None,
+                    flash_block: None
+                },
+                valid_block: 1,
+                pool_id,
+                is_bid: true,
+                is_currently_valid: true,
+                is_valid: true,
+                priority_data: Default::default(),
+                invalidates: vec![],
+                tob_reward: U256::ZERO
+            }))
+            .unwrap();
+
+        assert_eq!(indexer.pool_ids(), vec![pool_id]);
+        assert_eq!(indexer.order_hashes_by_pool(&[pool_id]), vec![order_hash]);
+        assert!(indexer.order_hashes_by_pool(&[PoolId::random()]).is_empty());
+        assert_eq!(indexer.orders_by_hash(&[order_hash, B256::random()]), vec![order]);
+
+        // only unknown orders need to be fetched from the peer
+        let unknown = B256::random();
+        assert_eq!(indexer.missing_orders(vec![order_hash, unknown]), vec![unknown]);
+    }
+
+    #[tokio::test]
+    async fn test_account_slot_limit() {
+        let mut indexer = setup_test_indexer().with_max_account_slots(1);
+        let s = AngstromSigner::random();
+        let from = s.address();
+
+        let pool_key = PoolKey {
+            currency0: Address::random(),
+            currency1: Address::random(),
+            ..Default::default()
+        };
+        let pool_id = PoolId::from(pool_key.clone());
+        indexer.new_pool(NewInitializedPool {
+            currency_out: pool_key.currency0,
+            currency_in:  pool_key.currency1,
+            id:           pool_id
+        });
+
+        let validated = |order: AllOrders, nonce: u64| {
+            OrderValidationResults::Valid(OrderWithStorageData {
+                order_id: OrderId {
+                    address: from,
+                    reuse_avoidance: RespendAvoidanceMethod::Nonce(nonce),
+                    hash: order.order_hash(),
+                    pool_id,
+                    location: OrderLocation::Limit,
+                    deadline: None,
+                    flash_block: None
+                },
+                order,
+                valid_block: 1,
+                pool_id,
+                is_bid: true,
+                is_currently_valid: true,
+                is_valid: true,
+                priority_data: Default::default(),
+                invalidates: vec![],
+                tob_reward: U256::ZERO
+            })
+        };
+
+        let first = create_test_order(from, pool_key.clone(), None, Some(s.clone()));
+        let second = create_test_order(
+            from,
+            pool_key,
+            Some(OrderValidity { flash_block: Some(2), ..Default::default() }),
+            Some(s)
+        );
+        let (first_hash, second_hash) = (first.order_hash(), second.order_hash());
+
+        indexer.handle_validated_order(validated(first, 1)).unwrap();
+        indexer
+            .handle_validated_order(validated(second, 2))
+            .unwrap();
+
+        assert!(indexer.order_hash_to_order_id.contains_key(&first_hash));
+        assert!(!indexer.order_hash_to_order_id.contains_key(&second_hash));
+        // the slot limit isn't a validation failure, so the order can come back later
+        assert!(!indexer.is_seen_invalid(&second_hash));
+        assert_eq!(indexer.account_slots_used(from), 1);
+    }
+
+    #[tokio::test]
+    async fn test_composable_orders() {
+        let mut indexer = setup_test_indexer();
+        let s = AngstromSigner::random();
+        let from = s.address();
+
+        let pool_key = PoolKey {
+            currency0: Address::random(),
+            currency1: Address::random(),
+            ..Default::default()
+        };
+        let pool_id = PoolId::from(pool_key.clone());
+        indexer.new_pool(NewInitializedPool {
+            currency_out: pool_key.currency0,
+            currency_in:  pool_key.currency1,
+            id:           pool_id
+        });
+
+        let with_hook = |order: AllOrders| match order {
+            AllOrders::Flash(FlashVariants::Exact(mut o)) => {
+                o.hook_data = [Address::random().as_slice(), &[1, 2, 3]].concat().into();
+                AllOrders::Flash(FlashVariants::Exact(o))
+            }
+            AllOrders::Flash(FlashVariants::Partial(mut o)) => {
+                o.hook_data = [Address::random().as_slice(), &[1, 2, 3]].concat().into();
+                AllOrders::Flash(FlashVariants::Partial(o))
+            }
+            _ => unreachable!()
+        };
+
+        let order = with_hook(create_test_order(from, pool_key.clone(), None, Some(s.clone())));
+        assert!(order.is_composable());
+        let order_hash = order.order_hash();
+        let order_id = OrderId {
+            address: from,
+            reuse_avoidance: RespendAvoidanceMethod::Nonce(1),
+            hash: order_hash,
+            pool_id,
+            location: OrderLocation::Limit,
+            deadline: None,
+            flash_block: None
+        };
+        indexer
+            .handle_validated_order(OrderValidationResults::Valid(OrderWithStorageData {
+                order: order.clone(),
+                order_id,
+                valid_block: 1,
+                pool_id,
+                is_bid: true,
+                is_currently_valid: true,
+                is_valid: true,
+                priority_data: Default::default(),
+                invalidates: vec![],
+                tob_reward: U256::ZERO
+            }))
+            .unwrap();
+
+        // lands in the composable sub-pool but is still handed to matching
+        let stored = indexer
+            .order_storage
+            .limit_orders
+            .lock()
+            .unwrap()
+            .get_order(&order_id)
+            .unwrap();
+        assert!(stored.is_composable());
+        assert!(indexer
+            .get_all_orders()
+            .limit
+            .iter()
+            .any(|o| o.order_id.hash == order_hash));
+        assert_eq!(indexer.order_status(order_hash), Some(OrderStatus::Pending));
+
+        // a propagated order with a failing hook costs the peer composable reputation
+        let bad_order = with_hook(create_test_order(
+            from,
+            pool_key,
+            Some(OrderValidity { flash_block: Some(2), ..Default::default() }),
+            Some(s)
+        ));
+        let bad_hash = bad_order.order_hash();
+        let peer_id = PeerId::random();
+        indexer.new_network_order(peer_id, OrderOrigin::External, bad_order);
+
+        let event = indexer
+            .handle_validated_order(OrderValidationResults::Invalid(
+                bad_hash,
+                OrderRejectionReason::InvalidHook("reverted".to_string())
+            ))
+            .unwrap();
+        assert!(matches!(
+            event,
+            PoolInnerEvent::BadComposableOrderMessages(peers) if peers == vec![peer_id]
+        ));
+        assert!(indexer.is_seen_invalid(&bad_hash));
+    }
+
+    #[tokio::test]
+    async fn test_private_orders() {
+        let mut indexer = setup_test_indexer();
+        let s = AngstromSigner::random();
+        let from = s.address();
+
+        let pool_key = PoolKey {
+            currency0: Address::random(),
+            currency1: Address::random(),
+            ..Default::default()
+        };
+        let pool_id = PoolId::from(pool_key.clone());
+        indexer.new_pool(NewInitializedPool {
+            currency_out: pool_key.currency0,
+            currency_in:  pool_key.currency1,
+            id:           pool_id
+        });
+        let order = create_test_order(from, pool_key, None, Some(s));
+        let order_hash = order.order_hash();
+
+        let (tx, mut rx) = tokio::sync::oneshot::channel();
+        indexer.new_rpc_order(OrderOrigin::Private, order.clone(), tx);
+
+        let event = indexer
+            .handle_validated_order(OrderValidationResults::Valid(OrderWithStorageData {
+                order: order.clone(),
+                order_id: OrderId {
+                    address: from,
+                    reuse_avoidance: RespendAvoidanceMethod::Nonce(1),
+                    hash: order_hash,
+                    pool_id,
+                    location: OrderLocation::Limit,
+                    deadline: None,
+                    flash_block: None
+                },
+                valid_block: 1,
+                pool_id,
+                is_bid: true,
+                is_currently_valid: true,
+                is_valid: true,
+                priority_data: Default::default(),
+                invalidates: vec![],
+                tob_reward: U256::ZERO
+            }))
+            .unwrap();
+
+        // accepted, but never handed to the network
+        assert!(matches!(event, PoolInnerEvent::None));
+        assert!(matches!(rx.try_recv(), Ok(OrderValidationResults::Valid(_))));
+        assert!(indexer.order_hashes_by_pool(&[pool_id]).is_empty());
+        assert!(indexer.orders_by_hash(&[order_hash]).is_empty());
+        assert!(indexer
+            .orders_by_pool(pool_id, OrderLocation::Limit)
+            .is_empty());
+        assert_eq!(indexer.order_status(order_hash), Some(OrderStatus::Private));
+
+        // still part of our own pre-proposal
+        assert!(indexer
+            .get_all_orders()
+            .limit
+            .iter()
+            .any(|o| o.order_id.hash == order_hash));
+    }
+
     #[tokio::test]
     async fn test_cancel_order() {
         let mut indexer = setup_test_indexer();
@@ -1149,6 +1866,23 @@ mod tests {
         assert!(result);
         assert!(indexer.cancelled_orders.contains_key(&order_hash));
         assert!(!indexer.order_hash_to_order_id.contains_key(&order_hash));
+
+        // the order left the pool, but we still know what happened to it
+        assert_eq!(indexer.order_status(order_hash), Some(OrderStatus::Cancelled));
+        let events = indexer
+            .order_history(order_hash)
+            .into_iter()
+            .map(|entry| entry.event)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            vec![
+                OrderLifecycleEvent::Received { origin: OrderOrigin::Local },
+                OrderLifecycleEvent::Validated,
+                OrderLifecycleEvent::Pending,
+                OrderLifecycleEvent::Cancelled,
+            ]
+        );
     }
 
     #[tokio::test]
@@ -1204,7 +1938,10 @@ mod tests {
 
         // The duplicate order should be rejected
         match rx2.await {
-            Ok(OrderValidationResults::Invalid(hash)) => assert_eq!(hash, order_hash),
+            Ok(OrderValidationResults::Invalid(hash, reason)) => {
+                assert_eq!(hash, order_hash);
+                assert_eq!(reason, OrderRejectionReason::DuplicateOrder);
+            }
             _ => panic!("Expected invalid order result")
         }
     }
@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     default::Default,
     fmt::Debug,
     sync::{Arc, Mutex},
@@ -9,7 +9,10 @@ use std::{
 use alloy::primitives::{BlockNumber, FixedBytes, B256};
 use angstrom_metrics::OrderStorageMetricsWrapper;
 use angstrom_types::{
-    orders::{OrderId, OrderLocation, OrderSet, OrderStatus},
+    orders::{
+        OrderFillState, OrderHistoryEntry, OrderId, OrderLifecycleEvent, OrderLocation, OrderSet,
+        OrderStatus, PoolSolution
+    },
     primitive::{NewInitializedPool, PoolId},
     sol_bindings::{
         grouped_orders::{AllOrders, GroupedUserOrder, GroupedVanillaOrder, OrderWithStorageData},
@@ -20,6 +23,7 @@ use angstrom_types::{
 use crate::{
     finalization_pool::FinalizationPool,
     limit::{LimitOrderPool, LimitPoolError},
+    order_history::OrderHistory,
     searcher::{SearcherPool, SearcherPoolError},
     PoolConfig
 };
@@ -33,6 +37,12 @@ pub struct OrderStorage {
     /// we store filled order hashes until they are expired time wise to ensure
     /// we don't waste processing power in the validator.
     pub filled_orders:               Arc<Mutex<HashMap<B256, Instant>>>,
+    /// what happened to every order we have seen, kept after they leave the
+    /// pool
+    pub order_history:               Arc<Mutex<OrderHistory>>,
+    /// orders submitted through the private rpc. They are never gossiped and
+    /// only go into the private section of our own pre-proposal
+    pub private_orders:              Arc<Mutex<HashSet<B256>>>,
     pub metrics:                     OrderStorageMetricsWrapper
 }
 
@@ -59,6 +69,8 @@ impl OrderStorage {
             limit_orders,
             searcher_orders,
             pending_finalization_orders,
+            order_history: Arc::new(Mutex::new(OrderHistory::default())),
+            private_orders: Arc::new(Mutex::new(HashSet::default())),
             metrics: OrderStorageMetricsWrapper::default()
         }
     }
@@ -96,6 +108,75 @@ impl OrderStorage {
             .lock()
             .expect("poisoned")
             .get_order_status(order)
+            .or_else(|| {
+                self.order_history
+                    .lock()
+                    .expect("poisoned")
+                    .final_status(&order)
+            })
+    }
+
+    pub fn record_order_event(
+        &self,
+        order_hash: B256,
+        block_number: BlockNumber,
+        event: OrderLifecycleEvent
+    ) {
+        self.order_history
+            .lock()
+            .expect("poisoned")
+            .record(order_hash, block_number, event);
+    }
+
+    pub fn order_history(&self, order_hash: B256) -> Vec<OrderHistoryEntry> {
+        self.order_history
+            .lock()
+            .expect("poisoned")
+            .history(&order_hash)
+    }
+
+    /// the fill of the last bundle we built that included the order
+    pub fn bundle_fill(&self, order_hash: B256) -> Option<OrderFillState> {
+        self.order_history
+            .lock()
+            .expect("poisoned")
+            .bundle_fill(&order_hash)
+    }
+
+    /// marks the orders of our pre-proposal for the given block
+    pub fn record_pre_proposal(
+        &self,
+        block_number: BlockNumber,
+        orders: &OrderSet<GroupedVanillaOrder, TopOfBlockOrder>
+    ) {
+        let mut history = self.order_history.lock().expect("poisoned");
+        let limit = orders.limit.iter().map(|order| order.order_id.hash);
+        let searcher = orders.searcher.iter().map(|order| order.order_id.hash);
+        for order_hash in limit.chain(searcher) {
+            history.record(order_hash, block_number, OrderLifecycleEvent::InPreProposal);
+        }
+    }
+
+    /// marks the orders that made it into the bundle we built for the given
+    /// block
+    pub fn record_bundle(&self, block_number: BlockNumber, solutions: &[PoolSolution]) {
+        let mut history = self.order_history.lock().expect("poisoned");
+        for solution in solutions {
+            if let Some(searcher) = &solution.searcher {
+                history.record(
+                    searcher.order_id.hash,
+                    block_number,
+                    OrderLifecycleEvent::InBundle { fill: OrderFillState::CompleteFill }
+                );
+            }
+            for outcome in solution.limit.iter().filter(|outcome| outcome.is_filled()) {
+                history.record(
+                    outcome.id.hash,
+                    block_number,
+                    OrderLifecycleEvent::InBundle { fill: outcome.outcome }
+                );
+            }
+        }
     }
 
     // unfortunately, any other solution is just as ugly
@@ -164,6 +245,15 @@ impl OrderStorage {
     }
 
     pub fn top_tob_orders(&self) -> Vec<OrderWithStorageData<TopOfBlockOrder>> {
+        self.top_tob_orders_where(|_| true)
+    }
+
+    /// the best top of block order of every pool out of the orders that pass
+    /// the filter
+    fn top_tob_orders_where(
+        &self,
+        filter: impl Fn(&OrderWithStorageData<TopOfBlockOrder>) -> bool
+    ) -> Vec<OrderWithStorageData<TopOfBlockOrder>> {
         let mut top_orders = Vec::new();
         let searcher_orders = self.searcher_orders.lock().expect("lock poisoned");
 
@@ -172,6 +262,7 @@ impl OrderStorage {
                 .get_orders_for_pool(&pool_id)
                 .unwrap_or_else(|| panic!("pool {} does not exist", pool_id))
                 .iter()
+                .filter(|order| filter(order))
                 .max_by_key(|order| order.tob_reward)
                 .cloned()
             {
@@ -308,6 +399,62 @@ impl OrderStorage {
         OrderSet { limit, searcher }
     }
 
+    /// the orders for our pre-proposal, split into the public ones and the
+    /// ones that were submitted privately to us
+    pub fn get_pre_proposal_orders(
+        &self
+    ) -> (
+        OrderSet<GroupedVanillaOrder, TopOfBlockOrder>,
+        OrderSet<GroupedVanillaOrder, TopOfBlockOrder>
+    ) {
+        let private_orders = self.private_orders.lock().expect("poisoned").clone();
+        let is_private = |hash: B256| private_orders.contains(&hash);
+
+        let (private_limit, limit) = self
+            .limit_orders
+            .lock()
+            .expect("poisoned")
+            .get_all_orders()
+            .into_iter()
+            .partition(|order| is_private(order.order_id.hash));
+        let searcher = self.top_tob_orders_where(|order| !is_private(order.order_id.hash));
+        let private_searcher = self.top_tob_orders_where(|order| is_private(order.order_id.hash));
+
+        (
+            OrderSet { limit, searcher },
+            OrderSet { limit: private_limit, searcher: private_searcher }
+        )
+    }
+
+    pub fn mark_private(&self, order_hash: B256) {
+        self.private_orders
+            .lock()
+            .expect("poisoned")
+            .insert(order_hash);
+    }
+
+    pub fn unmark_private(&self, order_hash: &B256) {
+        self.private_orders
+            .lock()
+            .expect("poisoned")
+            .remove(order_hash);
+    }
+
+    pub fn is_private(&self, order_hash: &B256) -> bool {
+        self.private_orders
+            .lock()
+            .expect("poisoned")
+            .contains(order_hash)
+    }
+
+    /// drops the private markers of the orders that fail the filter
+    pub fn retain_private(&self, keep: impl Fn(&B256) -> bool) {
+        self.private_orders
+            .lock()
+            .expect("poisoned")
+            .retain(|hash| keep(hash));
+    }
+
     pub fn new_pool(&self, pool: NewInitializedPool) {
         self.limit_orders.lock().expect("poisoned").new_pool(pool);
         self.searcher_orders
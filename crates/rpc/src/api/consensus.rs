@@ -0,0 +1,30 @@
+use std::collections::HashSet;
+
+use angstrom_types::consensus::Evidence;
+use consensus::ConsensusRoundInfo;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+use crate::types::ConsensusSubscriptionKind;
+
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "angstrom_consensus"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "angstrom_consensus"))]
+#[async_trait::async_trait]
+pub trait ConsensusApi {
+    /// The state of the consensus round we are currently in
+    #[method(name = "roundState")]
+    async fn round_state(&self) -> RpcResult<ConsensusRoundInfo>;
+
+    /// All evidence of validator misbehaviour this node has collected
+    #[method(name = "evidence")]
+    async fn evidence(&self) -> RpcResult<Vec<Evidence>>;
+
+    #[subscription(
+        name = "subscribe",
+        unsubscribe = "unsubscribe",
+        item = crate::types::subscriptions::ConsensusSubscriptionResult
+    )]
+    async fn subscribe_consensus(
+        &self,
+        kind: HashSet<ConsensusSubscriptionKind>
+    ) -> jsonrpsee::core::SubscriptionResult;
+}
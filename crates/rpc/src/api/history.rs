@@ -0,0 +1,31 @@
+use alloy_primitives::{Address, BlockNumber};
+use angstrom_eth::indexer::{ClearingPrice, IndexedFill};
+use angstrom_types::primitive::PoolId;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "angstrom"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "angstrom"))]
+#[async_trait::async_trait]
+pub trait HistoryApi {
+    /// The prices the pool cleared at in the landed bundles of the given
+    /// (inclusive) block range
+    #[method(name = "clearingPrices")]
+    async fn clearing_prices(
+        &self,
+        pool_id: PoolId,
+        from_block: BlockNumber,
+        to_block: BlockNumber
+    ) -> RpcResult<Vec<ClearingPrice>>;
+
+    /// The fills of orders signed by the given address that landed on chain
+    /// from `from_block` on, oldest first. Pages hold whole blocks and at most
+    /// about `limit` fills, the next page starts at the block after the last
+    /// returned fill
+    #[method(name = "fillsByAddress")]
+    async fn fills_by_address(
+        &self,
+        address: Address,
+        from_block: Option<BlockNumber>,
+        limit: Option<usize>
+    ) -> RpcResult<Vec<IndexedFill>>;
+}
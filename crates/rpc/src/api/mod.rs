@@ -1,5 +1,10 @@
+mod consensus;
+mod history;
 mod orders;
 mod quoting;
 
+pub use history::*;
 pub use orders::*;
 pub use quoting::*;
+
+pub use self::consensus::*;
@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use alloy_primitives::{Address, B256, U256};
 use angstrom_types::{
-    orders::{CancelOrderRequest, OrderLocation, OrderStatus},
+    orders::{CancelOrderRequest, OrderHistoryEntry, OrderLocation, OrderStatus},
     primitive::{OrderPoolNewOrderResult, PoolId},
     sol_bindings::grouped_orders::AllOrders
 };
@@ -41,6 +41,11 @@ pub trait OrderApi {
     #[method(name = "orderStatus")]
     async fn order_status(&self, order_hash: B256) -> RpcResult<Option<OrderStatus>>;
 
+    /// Everything that happened to the order while this node was tracking
+    /// it, oldest first
+    #[method(name = "orderHistory")]
+    async fn order_history(&self, order_hash: B256) -> RpcResult<Vec<OrderHistoryEntry>>;
+
     #[method(name = "ordersByPair")]
     async fn orders_by_pool_id(
         &self,
@@ -141,3 +146,14 @@ pub trait OrderApi {
             .collect())
     }
 }
+
+/// Served on the authenticated rpc only
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "angstrom"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "angstrom"))]
+#[async_trait::async_trait]
+pub trait PrivateOrderApi {
+    /// Submit an order that is never gossiped to peers. It is only included in
+    /// this node's own pre-proposal
+    #[method(name = "sendPrivateOrder")]
+    async fn send_private_order(&self, order: AllOrders) -> RpcResult<OrderPoolNewOrderResult>;
+}
@@ -1,8 +1,8 @@
 use std::collections::HashSet;
 
-use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
-use crate::types::GasEstimateFilter;
+use crate::types::{FillEstimate, GasEstimateFilter};
 
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "quoting"))]
 #[cfg_attr(feature = "client", rpc(server, client, namespace = "quoting"))]
@@ -18,3 +18,14 @@ pub trait QuotingApi {
         filters: HashSet<GasEstimateFilter>
     ) -> jsonrpsee::core::SubscriptionResult;
 }
+
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "angstrom"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "angstrom"))]
+#[async_trait::async_trait]
+pub trait FillsApi {
+    /// Simulates the bundle that would be built from the orders that are
+    /// pending right now, returning the expected clearing price, filled volume
+    /// and gas per order of the pools that match the filters
+    #[method(name = "estimateFills")]
+    async fn estimate_fills(&self, filters: HashSet<GasEstimateFilter>) -> RpcResult<FillEstimate>;
+}
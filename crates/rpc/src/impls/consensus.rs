@@ -0,0 +1,217 @@
+use std::{collections::HashSet, sync::Arc};
+
+use alloy_primitives::BlockNumber;
+use angstrom_types::consensus::Evidence;
+use consensus::{rounds::ConsensusMessage, ConsensusHandle, ConsensusRoundInfo};
+use futures::StreamExt;
+use jsonrpsee::{core::RpcResult, PendingSubscriptionSink, SubscriptionMessage};
+use reth_tasks::TaskSpawner;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+
+use crate::{
+    api::ConsensusApiServer,
+    types::{ConsensusSubscriptionKind, ConsensusSubscriptionResult}
+};
+
+pub struct ConsensusApi<Consensus, Spawner> {
+    consensus:    Consensus,
+    task_spawner: Spawner
+}
+
+impl<Consensus, Spawner> ConsensusApi<Consensus, Spawner> {
+    pub fn new(consensus: Consensus, task_spawner: Spawner) -> Self {
+        Self { consensus, task_spawner }
+    }
+}
+
+#[async_trait::async_trait]
+impl<Consensus, Spawner> ConsensusApiServer for ConsensusApi<Consensus, Spawner>
+where
+    Consensus: ConsensusHandle,
+    Spawner: TaskSpawner + 'static
+{
+    async fn round_state(&self) -> RpcResult<ConsensusRoundInfo> {
+        self.consensus
+            .fetch_round_state()
+            .await
+            .ok_or_else(|| ConsensusApiError::ConsensusUnavailable.into())
+    }
+
+    async fn evidence(&self) -> RpcResult<Vec<Evidence>> {
+        self.consensus
+            .fetch_evidence()
+            .await
+            .ok_or_else(|| ConsensusApiError::ConsensusUnavailable.into())
+    }
+
+    async fn subscribe_consensus(
+        &self,
+        pending: PendingSubscriptionSink,
+        kind: HashSet<ConsensusSubscriptionKind>
+    ) -> jsonrpsee::core::SubscriptionResult {
+        let sink = pending.accept().await?;
+        let mut subscription = self.consensus.subscribe_consensus_events();
+
+        self.task_spawner.spawn(Box::pin(async move {
+            let mut best_pre_proposal = BestPreProposal::default();
+
+            while let Some(event) = subscription.next().await {
+                if sink.is_closed() {
+                    break
+                }
+                let event = match event {
+                    Ok(event) => event,
+                    Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                        tracing::debug!(skipped, "consensus subscriber fell behind");
+                        continue
+                    }
+                };
+
+                let Some(result) = event.filter_out_event(&kind, &mut best_pre_proposal) else {
+                    continue
+                };
+
+                match SubscriptionMessage::from_json(&result) {
+                    Ok(message) => {
+                        if sink.send(message).await.is_err() {
+                            break
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to serialize subscription message: {:?}", e);
+                    }
+                }
+            }
+        }));
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConsensusApiError {
+    #[error("consensus manager is not running")]
+    ConsensusUnavailable
+}
+
+impl From<ConsensusApiError> for jsonrpsee::types::ErrorObjectOwned {
+    fn from(error: ConsensusApiError) -> Self {
+        match error {
+            ConsensusApiError::ConsensusUnavailable => jsonrpsee::types::ErrorObject::owned(
+                jsonrpsee::types::error::INTERNAL_ERROR_CODE,
+                error.to_string(),
+                None::<()>
+            )
+        }
+    }
+}
+
+/// The largest pre-proposal (by order count) seen for the current block
+#[derive(Debug, Default)]
+struct BestPreProposal {
+    block_height: BlockNumber,
+    order_count:  usize
+}
+
+impl BestPreProposal {
+    /// returns true if the pre-proposal has more orders than any other we have
+    /// seen for its block
+    fn update(&mut self, block_height: BlockNumber, order_count: usize) -> bool {
+        if block_height > self.block_height {
+            *self = Self { block_height, order_count };
+            return true
+        }
+
+        if block_height == self.block_height && order_count > self.order_count {
+            self.order_count = order_count;
+            return true
+        }
+
+        false
+    }
+}
+
+trait ConsensusFilterMatching {
+    fn filter_out_event(
+        self,
+        kind: &HashSet<ConsensusSubscriptionKind>,
+        best_pre_proposal: &mut BestPreProposal
+    ) -> Option<ConsensusSubscriptionResult>;
+}
+
+impl ConsensusFilterMatching for ConsensusMessage {
+    fn filter_out_event(
+        self,
+        kind: &HashSet<ConsensusSubscriptionKind>,
+        best_pre_proposal: &mut BestPreProposal
+    ) -> Option<ConsensusSubscriptionResult> {
+        match self {
+            ConsensusMessage::PropagatePreProposal(pre_proposal) => {
+                // always update so that the best stays accurate no matter the kinds
+                let is_best = best_pre_proposal.update(
+                    pre_proposal.block_height,
+                    pre_proposal.limit.len() + pre_proposal.searcher.len()
+                );
+
+                (kind.contains(&ConsensusSubscriptionKind::PreProposal)
+                    || (is_best && kind.contains(&ConsensusSubscriptionKind::NewBestPreProposal)))
+                .then(|| ConsensusSubscriptionResult::PreProposal(Arc::new(pre_proposal)))
+            }
+            ConsensusMessage::PropagateProposal(proposal)
+                if kind.contains(&ConsensusSubscriptionKind::Proposal) =>
+            {
+                Some(ConsensusSubscriptionResult::Proposal(Arc::new(proposal)))
+            }
+            _ => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use angstrom_types::consensus::PreProposal;
+
+    use super::*;
+
+    fn pre_proposal(block_height: BlockNumber, orders: usize) -> ConsensusMessage {
+        ConsensusMessage::PropagatePreProposal(PreProposal {
+            block_height,
+            limit: vec![Default::default(); orders],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_new_best_pre_proposal_filter() {
+        let kind = HashSet::from([ConsensusSubscriptionKind::NewBestPreProposal]);
+        let mut best = BestPreProposal::default();
+
+        assert!(pre_proposal(1, 2)
+            .filter_out_event(&kind, &mut best)
+            .is_some());
+        // fewer or equal orders for the same block isn't a new best
+        assert!(pre_proposal(1, 1)
+            .filter_out_event(&kind, &mut best)
+            .is_none());
+        assert!(pre_proposal(1, 2)
+            .filter_out_event(&kind, &mut best)
+            .is_none());
+        assert!(pre_proposal(1, 3)
+            .filter_out_event(&kind, &mut best)
+            .is_some());
+        // a new block resets the best
+        assert!(pre_proposal(2, 0)
+            .filter_out_event(&kind, &mut best)
+            .is_some());
+
+        let kind = HashSet::from([ConsensusSubscriptionKind::PreProposal]);
+        assert!(pre_proposal(2, 0)
+            .filter_out_event(&kind, &mut best)
+            .is_some());
+
+        let kind = HashSet::from([ConsensusSubscriptionKind::Proposal]);
+        assert!(pre_proposal(2, 5)
+            .filter_out_event(&kind, &mut best)
+            .is_none());
+    }
+}
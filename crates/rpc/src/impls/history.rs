@@ -0,0 +1,122 @@
+use alloy_primitives::{Address, BlockNumber};
+use angstrom_eth::indexer::{BundleIndex, ClearingPrice, IndexedFill};
+use angstrom_types::primitive::PoolId;
+use jsonrpsee::core::RpcResult;
+
+use crate::api::HistoryApiServer;
+
+/// the largest block range a single clearing price query can cover
+const MAX_CLEARING_PRICE_RANGE: u64 = 10_000;
+/// the most fills a single fills by address query collects
+const MAX_FILLS_PER_PAGE: usize = 1_000;
+
+pub struct HistoryApi {
+    index: BundleIndex
+}
+
+impl HistoryApi {
+    pub fn new(index: BundleIndex) -> Self {
+        Self { index }
+    }
+}
+
+#[async_trait::async_trait]
+impl HistoryApiServer for HistoryApi {
+    async fn clearing_prices(
+        &self,
+        pool_id: PoolId,
+        from_block: BlockNumber,
+        to_block: BlockNumber
+    ) -> RpcResult<Vec<ClearingPrice>> {
+        if from_block > to_block {
+            return Err(HistoryApiError::InvalidRange { from_block, to_block }.into())
+        }
+        if to_block - from_block >= MAX_CLEARING_PRICE_RANGE {
+            return Err(HistoryApiError::RangeTooLarge(MAX_CLEARING_PRICE_RANGE).into())
+        }
+
+        Ok(self.index.clearing_prices(pool_id, from_block..=to_block))
+    }
+
+    async fn fills_by_address(
+        &self,
+        address: Address,
+        from_block: Option<BlockNumber>,
+        limit: Option<usize>
+    ) -> RpcResult<Vec<IndexedFill>> {
+        let limit = limit.unwrap_or(MAX_FILLS_PER_PAGE).min(MAX_FILLS_PER_PAGE);
+        Ok(self
+            .index
+            .fills_by_address(address, from_block.unwrap_or_default(), limit))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum HistoryApiError {
+    #[error("from block {from_block} is after to block {to_block}")]
+    InvalidRange { from_block: BlockNumber, to_block: BlockNumber },
+    #[error("block range can cover at most {0} blocks")]
+    RangeTooLarge(u64)
+}
+
+impl From<HistoryApiError> for jsonrpsee::types::ErrorObjectOwned {
+    fn from(error: HistoryApiError) -> Self {
+        jsonrpsee::types::ErrorObject::owned(
+            jsonrpsee::types::error::INVALID_PARAMS_CODE,
+            error.to_string(),
+            None::<()>
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{B256, U256};
+    use angstrom_eth::indexer::IndexedBundle;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_history_queries() {
+        let (pool_id, from) = (PoolId::random(), Address::random());
+        let index = BundleIndex::default();
+        for block_number in 1..=3 {
+            index.insert(IndexedBundle {
+                block_number,
+                clearing_prices: vec![ClearingPrice {
+                    block_number,
+                    pool_id,
+                    token0: Address::ZERO,
+                    token1: Address::ZERO,
+                    price_1_over_0: U256::from(block_number)
+                }],
+                fills: vec![IndexedFill {
+                    block_number,
+                    order_hash: B256::random(),
+                    from,
+                    pool_id,
+                    top_of_block: false
+                }],
+                pool_rewards: vec![]
+            });
+        }
+        let api = HistoryApi::new(index);
+
+        let prices = api.clearing_prices(pool_id, 2, 10).await.unwrap();
+        assert_eq!(prices.iter().map(|p| p.block_number).collect::<Vec<_>>(), vec![2, 3]);
+        assert!(api.clearing_prices(pool_id, 3, 2).await.is_err());
+        assert!(api
+            .clearing_prices(pool_id, 0, MAX_CLEARING_PRICE_RANGE)
+            .await
+            .is_err());
+
+        assert_eq!(api.fills_by_address(from, None, None).await.unwrap().len(), 3);
+        let page = api.fills_by_address(from, Some(2), Some(1)).await.unwrap();
+        assert_eq!(page.iter().map(|f| f.block_number).collect::<Vec<_>>(), vec![2]);
+        assert!(api
+            .fills_by_address(Address::random(), None, None)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
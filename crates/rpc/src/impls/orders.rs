@@ -2,20 +2,21 @@ use std::collections::HashSet;
 
 use alloy_primitives::{Address, B256};
 use angstrom_types::{
-    orders::{CancelOrderRequest, OrderLocation, OrderOrigin, OrderStatus},
-    primitive::{OrderPoolNewOrderResult, PoolId},
+    orders::{CancelOrderRequest, OrderHistoryEntry, OrderLocation, OrderOrigin, OrderStatus},
+    primitive::{OrderPoolNewOrderResult, OrderRejectionReason, PoolId},
     sol_bindings::grouped_orders::AllOrders
 };
 use futures::StreamExt;
 use jsonrpsee::{core::RpcResult, PendingSubscriptionSink, SubscriptionMessage};
 use order_pool::{OrderPoolHandle, PoolManagerUpdate};
 use reth_tasks::TaskSpawner;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
 use validation::order::OrderValidatorHandle;
 
 use crate::{
-    api::{GasEstimateResponse, OrderApiServer},
+    api::{GasEstimateResponse, OrderApiServer, PrivateOrderApiServer},
     types::{OrderSubscriptionFilter, OrderSubscriptionKind, OrderSubscriptionResult},
-    OrderApiError::GasEstimationError
+    OrderApiError::{GasEstimationError, OrderRejected}
 };
 
 pub struct OrderApi<OrderPool, Spawner, Validator> {
@@ -38,7 +39,20 @@ where
     Validator: OrderValidatorHandle
 {
     async fn send_order(&self, order: AllOrders) -> RpcResult<OrderPoolNewOrderResult> {
-        Ok(self.pool.new_order(OrderOrigin::External, order).await)
+        match self.pool.new_order(OrderOrigin::External, order).await {
+            OrderPoolNewOrderResult::Invalid(reason) => Err(OrderRejected(reason).into()),
+            res => Ok(res)
+        }
+    }
+
+    async fn send_orders(&self, orders: Vec<AllOrders>) -> RpcResult<Vec<OrderPoolNewOrderResult>> {
+        // a single rejected order shouldn't fail the whole batch, so the reasons are
+        // returned inline here instead of as an error.
+        Ok(futures::stream::iter(orders.into_iter())
+            .map(|order| self.pool.new_order(OrderOrigin::External, order))
+            .buffered(3)
+            .collect::<Vec<_>>()
+            .await)
     }
 
     async fn pending_order(&self, from: Address) -> RpcResult<Vec<AllOrders>> {
@@ -62,6 +76,10 @@ where
         Ok(self.pool.fetch_order_status(order_hash).await)
     }
 
+    async fn order_history(&self, order_hash: B256) -> RpcResult<Vec<OrderHistoryEntry>> {
+        Ok(self.pool.fetch_order_history(order_hash).await)
+    }
+
     async fn orders_by_pool_id(
         &self,
         pool_id: PoolId,
@@ -83,10 +101,17 @@ where
             .map(move |update| update.map(|value| value.filter_out_order(&kind, &filter)));
 
         self.task_spawner.spawn(Box::pin(async move {
-            while let Some(Ok(order)) = subscription.next().await {
+            while let Some(order) = subscription.next().await {
                 if sink.is_closed() {
                     break
                 }
+                let order = match order {
+                    Ok(order) => order,
+                    Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                        tracing::debug!(skipped, "order subscriber fell behind");
+                        continue
+                    }
+                };
 
                 if let Some(result) = order {
                     match SubscriptionMessage::from_json(&result) {
@@ -107,6 +132,29 @@ where
     }
 }
 
+pub struct PrivateOrderApi<OrderPool> {
+    pool: OrderPool
+}
+
+impl<OrderPool> PrivateOrderApi<OrderPool> {
+    pub fn new(pool: OrderPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl<OrderPool> PrivateOrderApiServer for PrivateOrderApi<OrderPool>
+where
+    OrderPool: OrderPoolHandle
+{
+    async fn send_private_order(&self, order: AllOrders) -> RpcResult<OrderPoolNewOrderResult> {
+        match self.pool.new_order(OrderOrigin::Private, order).await {
+            OrderPoolNewOrderResult::Invalid(reason) => Err(OrderRejected(reason).into()),
+            res => Ok(res)
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum OrderApiError {
     #[error("invalid transaction signature")]
@@ -114,7 +162,9 @@ pub enum OrderApiError {
     #[error("failed to recover signer from signature")]
     SignatureRecoveryError,
     #[error("failed to estimate gas: {0}")]
-    GasEstimationError(String)
+    GasEstimationError(String),
+    #[error("order rejected: {0}")]
+    OrderRejected(OrderRejectionReason)
 }
 
 impl From<OrderApiError> for jsonrpsee::types::ErrorObjectOwned {
@@ -122,11 +172,37 @@ impl From<OrderApiError> for jsonrpsee::types::ErrorObjectOwned {
         match error {
             OrderApiError::InvalidSignature => invalid_params_rpc_err(error.to_string()),
             OrderApiError::SignatureRecoveryError => invalid_params_rpc_err(error.to_string()),
-            OrderApiError::GasEstimationError(e) => invalid_params_rpc_err(e)
+            OrderApiError::GasEstimationError(e) => invalid_params_rpc_err(e),
+            OrderApiError::OrderRejected(ref reason) => jsonrpsee::types::ErrorObject::owned(
+                rejection_error_code(reason),
+                error.to_string(),
+                Some(reason)
+            )
         }
     }
 }
 
+/// Stable error codes for rejected orders. These are part of the public api, so
+/// existing codes must never be changed or reused.
+pub fn rejection_error_code(reason: &OrderRejectionReason) -> i32 {
+    match reason {
+        OrderRejectionReason::InvalidSignature => -32001,
+        OrderRejectionReason::UnknownPool => -32002,
+        OrderRejectionReason::DuplicateNonce => -32003,
+        OrderRejectionReason::BadBlock { .. } => -32004,
+        OrderRejectionReason::StaleValidation { .. } => -32005,
+        OrderRejectionReason::Cancelled => -32006,
+        OrderRejectionReason::DuplicateOrder => -32007,
+        OrderRejectionReason::PreviouslyRejected => -32008,
+        OrderRejectionReason::GasExceedsMaxFee { .. } => -32009,
+        OrderRejectionReason::GasSimulationFailed(_) => -32010,
+        OrderRejectionReason::AccountSlotsExceeded { .. } => -32011,
+        OrderRejectionReason::InvalidHook(_) => -32012,
+        OrderRejectionReason::InsufficientBalance { .. } => -32013,
+        OrderRejectionReason::InsufficientApproval { .. } => -32014
+    }
+}
+
 pub fn invalid_params_rpc_err(msg: impl Into<String>) -> jsonrpsee::types::ErrorObjectOwned {
     rpc_err(jsonrpsee::types::error::INVALID_PARAMS_CODE, msg, None)
 }
@@ -193,6 +269,12 @@ impl OrderFilterMatching for PoolManagerUpdate {
             {
                 Some(OrderSubscriptionResult::CancelledOrder(order_hash))
             }
+            PoolManagerUpdate::RejectedOrder { order_hash, reason }
+                if kind.contains(&OrderSubscriptionKind::RejectedOrders)
+                    && filter.contains(&OrderSubscriptionFilter::None) =>
+            {
+                Some(OrderSubscriptionResult::RejectedOrder { order_hash, reason })
+            }
             _ => None
         }
     }
@@ -205,7 +287,7 @@ mod tests {
     use alloy_primitives::{Address, B256, U256};
     use angstrom_network::pool_manager::OrderCommand;
     use angstrom_types::{
-        orders::{OrderOrigin, OrderStatus},
+        orders::{OrderHistoryEntry, OrderOrigin, OrderStatus},
         sol_bindings::grouped_orders::{AllOrders, FlashVariants, StandingVariants}
     };
     use futures::FutureExt;
@@ -259,6 +341,53 @@ mod tests {
             .is_valid());
     }
 
+    #[tokio::test]
+    async fn test_send_private_order() {
+        let (to_pool, mut pool_rx) = unbounded_channel();
+        let api = PrivateOrderApi::new(MockOrderPoolHandle::new(to_pool));
+
+        assert!(api
+            .send_private_order(create_standing_order())
+            .await
+            .expect("to not throw error")
+            .is_valid());
+        assert!(matches!(
+            pool_rx.recv().await,
+            Some(OrderCommand::NewOrder(OrderOrigin::Private, ..))
+        ));
+    }
+
+    #[test]
+    fn test_rejected_order_has_stable_code() {
+        let err: jsonrpsee::types::ErrorObjectOwned =
+            OrderRejected(OrderRejectionReason::DuplicateNonce).into();
+        assert_eq!(err.code(), -32003);
+
+        let err: jsonrpsee::types::ErrorObjectOwned =
+            OrderRejected(OrderRejectionReason::GasExceedsMaxFee { gas_cost: 2, max_fee: 1 })
+                .into();
+        assert_eq!(err.code(), -32009);
+        assert!(err.data().is_some());
+
+        let err: jsonrpsee::types::ErrorObjectOwned =
+            OrderRejected(OrderRejectionReason::InsufficientBalance {
+                token:     Address::ZERO,
+                required:  U256::from(2),
+                available: U256::from(1)
+            })
+            .into();
+        assert_eq!(err.code(), -32013);
+
+        let err: jsonrpsee::types::ErrorObjectOwned =
+            OrderRejected(OrderRejectionReason::InsufficientApproval {
+                token:     Address::ZERO,
+                required:  U256::from(2),
+                available: U256::from(1)
+            })
+            .into();
+        assert_eq!(err.code(), -32014);
+    }
+
     fn setup_order_api(
     ) -> (OrderApiTestHandle, OrderApi<MockOrderPoolHandle, TokioTaskExecutor, MockValidator>) {
         let (to_pool, pool_rx) = unbounded_channel();
@@ -328,6 +457,13 @@ mod tests {
         fn fetch_order_status(&self, _: B256) -> impl Future<Output = Option<OrderStatus>> + Send {
             future::ready(None)
         }
+
+        fn fetch_order_history(
+            &self,
+            _: B256
+        ) -> impl Future<Output = Vec<OrderHistoryEntry>> + Send {
+            future::ready(vec![])
+        }
     }
 
     #[derive(Debug, Clone)]
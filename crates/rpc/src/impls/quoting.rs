@@ -1,26 +1,191 @@
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet},
+    time::{SystemTime, UNIX_EPOCH}
+};
 
-use jsonrpsee::PendingSubscriptionSink;
+use alloy_primitives::BlockNumber;
+use angstrom_types::matching::match_estimate_response::BundleEstimate;
+use consensus::ConsensusHandle;
+use futures::StreamExt;
+use jsonrpsee::{core::RpcResult, PendingSubscriptionSink, SubscriptionMessage};
 use reth_tasks::TaskSpawner;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
 
-use crate::{api::QuotingApiServer, types::GasEstimateFilter};
+use crate::{
+    api::{FillsApiServer, QuotingApiServer},
+    types::{FillEstimate, GasEstimateFilter, GasEstimateUpdate}
+};
 
-pub struct QuotesApi<OrderPool, Spawner> {
-    _pool:         OrderPool,
-    _task_spawner: Spawner
+pub struct QuotesApi<Spawner> {
+    gas_estimates: broadcast::Sender<(BlockNumber, BundleEstimate)>,
+    task_spawner:  Spawner
+}
+
+impl<Spawner> QuotesApi<Spawner> {
+    pub fn new(
+        gas_estimates: broadcast::Sender<(BlockNumber, BundleEstimate)>,
+        task_spawner: Spawner
+    ) -> Self {
+        Self { gas_estimates, task_spawner }
+    }
 }
 
 #[async_trait::async_trait]
-impl<OrderPool, Spawner> QuotingApiServer for QuotesApi<OrderPool, Spawner>
+impl<Spawner> QuotingApiServer for QuotesApi<Spawner>
 where
-    OrderPool: Send + Sync + 'static,
     Spawner: TaskSpawner + 'static
 {
     async fn subscribe_gas_estimates(
         &self,
-        _pending: PendingSubscriptionSink,
-        _filters: HashSet<GasEstimateFilter>
+        pending: PendingSubscriptionSink,
+        filters: HashSet<GasEstimateFilter>
     ) -> jsonrpsee::core::SubscriptionResult {
+        let sink = pending.accept().await?;
+        let mut subscription = BroadcastStream::new(self.gas_estimates.subscribe());
+
+        self.task_spawner.spawn(Box::pin(async move {
+            // last estimate sent per pool so that clients can see the delta
+            let mut last_estimates = HashMap::new();
+
+            while let Some(update) = subscription.next().await {
+                if sink.is_closed() {
+                    break
+                }
+                let (block_number, estimate) = match update {
+                    Ok(update) => update,
+                    Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                        tracing::debug!(skipped, "gas estimate subscriber fell behind");
+                        continue
+                    }
+                };
+
+                let timestamp = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis();
+
+                for (pair, pool_estimate) in estimate.pool_estimate {
+                    if !GasEstimateFilter::matches(&filters, pair) {
+                        continue
+                    }
+
+                    let update = GasEstimateUpdate {
+                        timestamp,
+                        block_number,
+                        pair,
+                        estimate_wei: pool_estimate.gas_in_wei,
+                        old_estimate_erc: last_estimates
+                            .insert(pair, pool_estimate.gas_in_token0)
+                            .flatten(),
+                        new_estimate_erc: pool_estimate.gas_in_token0
+                    };
+
+                    match SubscriptionMessage::from_json(&update) {
+                        Ok(message) => {
+                            if sink.send(message).await.is_err() {
+                                return
+                            }
+                        }
+                        Err(e) => {
+                            tracing::error!("Failed to serialize subscription message: {:?}", e);
+                        }
+                    }
+                }
+            }
+        }));
+
         Ok(())
     }
 }
+
+pub struct FillsApi<Consensus> {
+    consensus: Consensus
+}
+
+impl<Consensus> FillsApi<Consensus> {
+    pub fn new(consensus: Consensus) -> Self {
+        Self { consensus }
+    }
+}
+
+#[async_trait::async_trait]
+impl<Consensus> FillsApiServer for FillsApi<Consensus>
+where
+    Consensus: ConsensusHandle
+{
+    async fn estimate_fills(&self, filters: HashSet<GasEstimateFilter>) -> RpcResult<FillEstimate> {
+        let (block_number, estimate) =
+            self.consensus.fetch_fill_estimate().await.ok_or_else(|| {
+                jsonrpsee::types::ErrorObjectOwned::from(FillsApiError::NoEstimate)
+            })?;
+
+        Ok(FillEstimate::new(block_number, estimate, &filters))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FillsApiError {
+    #[error("no bundle could be simulated from the pending orders")]
+    NoEstimate
+}
+
+impl From<FillsApiError> for jsonrpsee::types::ErrorObjectOwned {
+    fn from(error: FillsApiError) -> Self {
+        match error {
+            FillsApiError::NoEstimate => jsonrpsee::types::ErrorObject::owned(
+                jsonrpsee::types::error::INTERNAL_ERROR_CODE,
+                error.to_string(),
+                None::<()>
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::FixedBytes;
+
+    use super::*;
+
+    #[test]
+    fn test_gas_estimate_filter() {
+        let pair = FixedBytes::with_last_byte(1);
+        let other = FixedBytes::with_last_byte(2);
+
+        assert!(GasEstimateFilter::matches(&HashSet::new(), pair));
+        assert!(GasEstimateFilter::matches(&HashSet::from([GasEstimateFilter::None]), pair));
+        assert!(GasEstimateFilter::matches(&HashSet::from([GasEstimateFilter::Pair(pair)]), pair));
+        assert!(!GasEstimateFilter::matches(
+            &HashSet::from([GasEstimateFilter::Pair(pair)]),
+            other
+        ));
+    }
+
+    #[test]
+    fn test_fill_estimate_filter() {
+        use angstrom_types::{matching::match_estimate_response::PoolEstimate, sol_bindings::Ray};
+
+        let pool = |orders| PoolEstimate {
+            orders,
+            gas_in_wei: 100,
+            gas_in_token0: None,
+            clearing_price: Some(Ray::default()),
+            filled_volume: Default::default()
+        };
+        let (pair, other) = (FixedBytes::with_last_byte(1), FixedBytes::with_last_byte(2));
+        let estimate = BundleEstimate {
+            total_orders:  3,
+            pool_estimate: HashMap::from([(pair, pool(1)), (other, pool(2))])
+        };
+
+        let all = FillEstimate::new(7, estimate.clone(), &HashSet::new());
+        assert_eq!(all.block_number, 7);
+        assert_eq!(all.pools.iter().map(|p| p.pair).collect::<Vec<_>>(), vec![pair, other]);
+
+        let filtered =
+            FillEstimate::new(7, estimate, &HashSet::from([GasEstimateFilter::Pair(other)]));
+        assert_eq!(filtered.pools.len(), 1);
+        assert_eq!(filtered.pools[0].orders, 2);
+    }
+}
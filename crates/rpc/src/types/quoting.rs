@@ -1,13 +1,23 @@
-use alloy_primitives::{FixedBytes, U256};
+use std::collections::HashSet;
+
+use alloy_primitives::{BlockNumber, FixedBytes, U256};
+use angstrom_types::{matching::match_estimate_response::BundleEstimate, sol_bindings::Ray};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GasEstimateUpdate {
-    timestamp:        u128,
-    pair:             FixedBytes<32>,
-    estimate_wei:     u64,
-    old_estimate_erc: U256,
-    new_estimate_erc: U256
+    /// unix timestamp in millis of when the estimate was sent
+    pub timestamp:        u128,
+    /// block the estimate was made for
+    pub block_number:     u64,
+    pub pair:             FixedBytes<32>,
+    /// gas charged to a single order
+    pub estimate_wei:     u64,
+    /// the previous estimate in token0 that was sent for this pair
+    pub old_estimate_erc: Option<U256>,
+    /// gas charged to a single order converted to token0
+    pub new_estimate_erc: Option<U256>
 }
 
 #[derive(
@@ -21,3 +31,61 @@ pub enum GasEstimateFilter {
     None,
     Pair(FixedBytes<32>)
 }
+
+impl GasEstimateFilter {
+    /// no filters is the same as [`GasEstimateFilter::None`]
+    pub fn matches(filters: &HashSet<Self>, pair: FixedBytes<32>) -> bool {
+        filters.is_empty() || filters.contains(&Self::None) || filters.contains(&Self::Pair(pair))
+    }
+}
+
+/// What a pool would look like if the bundle was built from the orders that
+/// are pending right now
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolFillEstimate {
+    pub pair:              FixedBytes<32>,
+    /// uniform clearing price in ray. None if the pool wouldn't clear
+    pub clearing_price:    Option<Ray>,
+    /// amount of token0 traded by the limit orders that would be filled
+    pub filled_volume:     U256,
+    /// amount of orders from the pool that would be included
+    pub orders:            u64,
+    /// gas charged to a single order
+    pub gas_per_order_wei: u64,
+    /// gas charged to a single order converted to token0
+    pub gas_per_order_erc: Option<U256>
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FillEstimate {
+    /// block the estimate was made for
+    pub block_number: BlockNumber,
+    pub pools:        Vec<PoolFillEstimate>
+}
+
+impl FillEstimate {
+    pub fn new(
+        block_number: BlockNumber,
+        estimate: BundleEstimate,
+        filters: &HashSet<GasEstimateFilter>
+    ) -> Self {
+        let mut pools = estimate
+            .pool_estimate
+            .into_iter()
+            .filter(|(pair, _)| GasEstimateFilter::matches(filters, *pair))
+            .map(|(pair, pool)| PoolFillEstimate {
+                pair,
+                clearing_price: pool.clearing_price,
+                filled_volume: pool.filled_volume,
+                orders: pool.orders,
+                gas_per_order_wei: pool.gas_in_wei,
+                gas_per_order_erc: pool.gas_in_token0
+            })
+            .collect::<Vec<_>>();
+        pools.sort_by_key(|pool| pool.pair);
+
+        Self { block_number, pools }
+    }
+}
@@ -1,7 +1,9 @@
 use std::sync::Arc;
 
 use alloy_primitives::{Address, FixedBytes, B256};
-use angstrom_types::{consensus::*, sol_bindings::grouped_orders::AllOrders};
+use angstrom_types::{
+    consensus::*, primitive::OrderRejectionReason, sol_bindings::grouped_orders::AllOrders
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Deserialize, Serialize)]
@@ -37,7 +39,9 @@ pub enum OrderSubscriptionKind {
     /// Any new reorged orders
     UnfilleOrders,
     /// Any new cancelled orders
-    CancelledOrders
+    CancelledOrders,
+    /// Any orders that were rejected or dropped by validation
+    RejectedOrders
 }
 
 #[derive(
@@ -62,5 +66,6 @@ pub enum OrderSubscriptionResult {
     NewOrder(AllOrders),
     FilledOrder(u64, AllOrders),
     UnfilledOrder(AllOrders),
-    CancelledOrder(B256)
+    CancelledOrder(B256),
+    RejectedOrder { order_hash: B256, reason: OrderRejectionReason }
 }
@@ -1,28 +1,220 @@
+use alloy::primitives::BlockNumber;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-#[derive(Debug, Error)]
+use super::{PreProposal, PreProposalAggregation, Proposal};
+use crate::{orders::PoolSolution, primitive::PeerId};
+
+#[derive(Debug, Error, PartialEq, Eq)]
 pub enum EvidenceError {
     #[error("invalid evidence")]
-    InvalidEvidence
+    InvalidEvidence,
+    #[error("vote has an invalid signature")]
+    InvalidSignature,
+    #[error("votes are from different sources or heights")]
+    MismatchedVotes,
+    #[error("votes don't conflict")]
+    NotConflicting
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// Signed proof of a validator misbehaving, that can be used for slashing
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Evidence {
-    DuplicateVoteEvidence(DuplicateVoteEvidence)
+    DuplicatePreProposal(DuplicateVoteEvidence<PreProposal>),
+    DuplicatePreProposalAggregation(DuplicateVoteEvidence<PreProposalAggregation>),
+    InvalidProposal(InvalidProposalEvidence)
+}
+
+impl Evidence {
+    /// the validator that misbehaved
+    pub fn offender(&self) -> PeerId {
+        match self {
+            Self::DuplicatePreProposal(e) => e.vote_a.source(),
+            Self::DuplicatePreProposalAggregation(e) => e.vote_a.source(),
+            Self::InvalidProposal(e) => e.proposal.source
+        }
+    }
+
+    pub fn block_height(&self) -> BlockNumber {
+        match self {
+            Self::DuplicatePreProposal(e) => e.vote_a.block_height(),
+            Self::DuplicatePreProposalAggregation(e) => e.vote_a.block_height(),
+            Self::InvalidProposal(e) => e.proposal.block_height
+        }
+    }
+}
+
+impl From<DuplicateVoteEvidence<PreProposal>> for Evidence {
+    fn from(value: DuplicateVoteEvidence<PreProposal>) -> Self {
+        Self::DuplicatePreProposal(value)
+    }
+}
+
+impl From<DuplicateVoteEvidence<PreProposalAggregation>> for Evidence {
+    fn from(value: DuplicateVoteEvidence<PreProposalAggregation>) -> Self {
+        Self::DuplicatePreProposalAggregation(value)
+    }
+}
+
+impl From<InvalidProposalEvidence> for Evidence {
+    fn from(value: InvalidProposalEvidence) -> Self {
+        Self::InvalidProposal(value)
+    }
+}
+
+/// A signed consensus message that a validator should only produce once per
+/// block
+pub trait ConsensusVote {
+    fn source(&self) -> PeerId;
+
+    fn block_height(&self) -> BlockNumber;
+
+    fn is_valid(&self, block_height: &BlockNumber) -> bool;
+
+    /// true if both votes are for the same slot but sign over different
+    /// content. Signatures are ignored as a re-signed vote isn't a conflict
+    fn conflicts_with(&self, other: &Self) -> bool;
+}
+
+impl ConsensusVote for PreProposal {
+    fn source(&self) -> PeerId {
+        self.source
+    }
+
+    fn block_height(&self) -> BlockNumber {
+        self.block_height
+    }
+
+    fn is_valid(&self, block_height: &BlockNumber) -> bool {
+        self.is_valid(block_height)
+    }
+
+    fn conflicts_with(&self, other: &Self) -> bool {
+        self.source == other.source
+            && self.block_height == other.block_height
+            && self.content() != other.content()
+    }
+}
+
+impl ConsensusVote for PreProposalAggregation {
+    fn source(&self) -> PeerId {
+        self.source
+    }
+
+    fn block_height(&self) -> BlockNumber {
+        self.block_height
+    }
+
+    fn is_valid(&self, block_height: &BlockNumber) -> bool {
+        self.is_valid(block_height)
+    }
+
+    fn conflicts_with(&self, other: &Self) -> bool {
+        self.source == other.source
+            && self.block_height == other.block_height
+            && self.pre_proposals != other.pre_proposals
+    }
 }
 
-/// Duplicate vote evidence
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
-pub struct DuplicateVoteEvidence {
-    // pub vote_a:             Vote,
-    // pub vote_b:             Vote,
+/// Two conflicting votes signed by the same validator for the same block
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DuplicateVoteEvidence<V> {
+    pub vote_a:             V,
+    pub vote_b:             V,
     pub total_voting_power: u64,
     pub validator_power:    u64
 }
 
-impl DuplicateVoteEvidence {
-    /// constructor
-    pub fn new() -> Result<Self, EvidenceError> {
-        Ok(Self { total_voting_power: Default::default(), validator_power: Default::default() })
+impl<V: ConsensusVote> DuplicateVoteEvidence<V> {
+    /// constructor, will error if the votes aren't both signed by the same
+    /// validator or don't actually conflict
+    pub fn new(
+        vote_a: V,
+        vote_b: V,
+        total_voting_power: u64,
+        validator_power: u64
+    ) -> Result<Self, EvidenceError> {
+        if vote_a.source() != vote_b.source() || vote_a.block_height() != vote_b.block_height() {
+            return Err(EvidenceError::MismatchedVotes)
+        }
+
+        let block_height = vote_a.block_height();
+        if !vote_a.is_valid(&block_height) || !vote_b.is_valid(&block_height) {
+            return Err(EvidenceError::InvalidSignature)
+        }
+
+        if !vote_a.conflicts_with(&vote_b) {
+            return Err(EvidenceError::NotConflicting)
+        }
+
+        Ok(Self { vote_a, vote_b, total_voting_power, validator_power })
+    }
+}
+
+/// A signed proposal whose solutions don't match the deterministic result of
+/// running the matching engine over its pre-proposals
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct InvalidProposalEvidence {
+    pub proposal:           Proposal,
+    /// the solutions we computed from the proposal's pre-proposals, sorted by
+    /// pool id
+    pub expected_solutions: Vec<PoolSolution>
+}
+
+impl InvalidProposalEvidence {
+    pub fn new(
+        proposal: Proposal,
+        mut expected_solutions: Vec<PoolSolution>
+    ) -> Result<Self, EvidenceError> {
+        if !proposal.is_valid(&proposal.block_height) {
+            return Err(EvidenceError::InvalidSignature)
+        }
+
+        expected_solutions.sort_by_key(|sol| sol.id);
+        let mut proposed = proposal.solutions.clone();
+        proposed.sort_by_key(|sol| sol.id);
+
+        if proposed == expected_solutions {
+            return Err(EvidenceError::NotConflicting)
+        }
+
+        Ok(Self { proposal, expected_solutions })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{primitive::AngstromSigner, sol_bindings::grouped_orders::OrderWithStorageData};
+
+    #[test]
+    fn duplicate_pre_proposal_evidence() {
+        let signer = AngstromSigner::random();
+        let vote_a = PreProposal::generate_pre_proposal(1, &signer, vec![], vec![]);
+        let vote_b = PreProposal::generate_pre_proposal(
+            1,
+            &signer,
+            vec![OrderWithStorageData::default()],
+            vec![]
+        );
+
+        assert!(DuplicateVoteEvidence::new(vote_a.clone(), vote_b.clone(), 300, 100).is_ok());
+        assert_eq!(
+            DuplicateVoteEvidence::new(vote_a.clone(), vote_a.clone(), 300, 100),
+            Err(EvidenceError::NotConflicting)
+        );
+
+        let other_height = PreProposal::generate_pre_proposal(2, &signer, vec![], vec![]);
+        assert_eq!(
+            DuplicateVoteEvidence::new(vote_a.clone(), other_height, 300, 100),
+            Err(EvidenceError::MismatchedVotes)
+        );
+
+        let other_signer =
+            PreProposal::generate_pre_proposal(1, &AngstromSigner::random(), vec![], vec![]);
+        assert_eq!(
+            DuplicateVoteEvidence::new(vote_a, other_signer, 300, 100),
+            Err(EvidenceError::MismatchedVotes)
+        );
     }
 }
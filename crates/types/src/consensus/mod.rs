@@ -2,8 +2,10 @@ pub mod evidence;
 pub mod pre_prepose;
 pub mod pre_propose_agg;
 pub mod proposal;
+pub mod proposal_timeout;
 
 pub use evidence::*;
 pub use pre_prepose::*;
 pub use pre_propose_agg::*;
 pub use proposal::*;
+pub use proposal_timeout::*;
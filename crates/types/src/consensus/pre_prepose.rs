@@ -29,11 +29,36 @@ pub struct PreProposal {
     pub limit:        Vec<OrderWithStorageData<GroupedVanillaOrder>>,
     // TODO: this really should be another type with HashMap<PoolId, {order, tob_reward}>
     pub searcher:     Vec<OrderWithStorageData<TopOfBlockOrder>>,
-    /// The signature is over the ethereum height as well as the limit and
-    /// searcher sets
+    /// orders that were submitted privately to the source. They are kept out
+    /// of the order gossip and subscriptions, so no other validator has them
+    /// to reach quorum with and they are included in the proposal on the
+    /// source's word alone. Private only means not public before the block:
+    /// the pre-proposal still goes out to every validator, so the whole
+    /// validator set sees them for this round.
+    pub private:      PrivateOrders,
+    /// The signature is over the ethereum height as well as the limit,
+    /// searcher and private sets
     pub signature:    Signature
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct PrivateOrders {
+    pub limit:    Vec<OrderWithStorageData<GroupedVanillaOrder>>,
+    pub searcher: Vec<OrderWithStorageData<TopOfBlockOrder>>
+}
+
+impl PrivateOrders {
+    pub fn is_empty(&self) -> bool {
+        self.limit.is_empty() && self.searcher.is_empty()
+    }
+}
+
+impl From<OrderSet<GroupedVanillaOrder, TopOfBlockOrder>> for PrivateOrders {
+    fn from(value: OrderSet<GroupedVanillaOrder, TopOfBlockOrder>) -> Self {
+        Self { limit: value.limit, searcher: value.searcher }
+    }
+}
+
 impl Default for PreProposal {
     fn default() -> Self {
         Self {
@@ -41,7 +66,8 @@ impl Default for PreProposal {
             block_height: Default::default(),
             source:       Default::default(),
             limit:        Default::default(),
-            searcher:     Default::default()
+            searcher:     Default::default(),
+            private:      Default::default()
         }
     }
 }
@@ -51,7 +77,8 @@ pub struct PreProposalContent {
     pub block_height: BlockNumber,
     pub source:       PeerId,
     pub limit:        Vec<OrderWithStorageData<GroupedVanillaOrder>>,
-    pub searcher:     Vec<OrderWithStorageData<TopOfBlockOrder>>
+    pub searcher:     Vec<OrderWithStorageData<TopOfBlockOrder>>,
+    pub private:      PrivateOrders
 }
 
 // the reason for the manual implementation is because EcDSA signatures are not
@@ -64,6 +91,7 @@ impl std::hash::Hash for PreProposalContent {
         self.source.hash(state);
         self.limit.hash(state);
         self.searcher.hash(state);
+        self.private.hash(state);
     }
 }
 
@@ -73,7 +101,8 @@ impl PreProposal {
             block_height: self.block_height,
             source:       self.source,
             limit:        self.limit.clone(),
-            searcher:     self.searcher.clone()
+            searcher:     self.searcher.clone(),
+            private:      self.private.clone()
         }
     }
 }
@@ -90,22 +119,40 @@ impl PreProposal {
         limit: Vec<OrderWithStorageData<GroupedVanillaOrder>>,
         searcher: Vec<OrderWithStorageData<TopOfBlockOrder>>
     ) -> Self {
-        let payload = Self::serialize_payload(&ethereum_height, &limit, &searcher);
+        Self::generate_with_private(ethereum_height, sk, limit, searcher, PrivateOrders::default())
+    }
+
+    pub fn generate_with_private(
+        ethereum_height: BlockNumber,
+        sk: &AngstromSigner,
+        limit: Vec<OrderWithStorageData<GroupedVanillaOrder>>,
+        searcher: Vec<OrderWithStorageData<TopOfBlockOrder>>,
+        private: PrivateOrders
+    ) -> Self {
+        let payload = Self::serialize_payload(&ethereum_height, &limit, &searcher, &private);
         let signature = Self::sign_payload(sk, payload);
 
-        Self { limit, source: sk.id(), searcher, block_height: ethereum_height, signature }
+        Self { limit, source: sk.id(), searcher, private, block_height: ethereum_height, signature }
     }
 
     pub fn new(
         ethereum_height: u64,
         sk: &AngstromSigner,
-        orders: OrderSet<GroupedVanillaOrder, TopOfBlockOrder>
+        orders: OrderSet<GroupedVanillaOrder, TopOfBlockOrder>,
+        private: OrderSet<GroupedVanillaOrder, TopOfBlockOrder>
     ) -> Self {
         let OrderSet { limit, searcher } = orders;
         let limit_orders = limit.len();
         let searcher_orders = searcher.len();
-        tracing::info!(%limit_orders,%searcher_orders, %ethereum_height,"building my pre_proposal");
-        Self::generate_pre_proposal(ethereum_height, sk, limit, searcher)
+        let private_orders = private.total_orders();
+        tracing::info!(
+            %limit_orders,
+            %searcher_orders,
+            %private_orders,
+            %ethereum_height,
+            "building my pre_proposal"
+        );
+        Self::generate_with_private(ethereum_height, sk, limit, searcher, private.into())
     }
 
     /// ensures block height is correct as-well as validates the signature.
@@ -122,17 +169,29 @@ impl PreProposal {
     fn serialize_payload(
         block_height: &BlockNumber,
         limit: &Vec<OrderWithStorageData<GroupedVanillaOrder>>,
-        searcher: &Vec<OrderWithStorageData<TopOfBlockOrder>>
+        searcher: &Vec<OrderWithStorageData<TopOfBlockOrder>>,
+        private: &PrivateOrders
     ) -> Vec<u8> {
         let mut buf = Vec::new();
         buf.extend(bincode::serialize(block_height).unwrap());
         buf.extend(bincode::serialize(limit).unwrap());
         buf.extend(bincode::serialize(searcher).unwrap());
+        buf.extend(bincode::serialize(private).unwrap());
         buf
     }
 
     fn payload(&self) -> Bytes {
-        Bytes::from(Self::serialize_payload(&self.block_height, &self.limit, &self.searcher))
+        Bytes::from(Self::serialize_payload(
+            &self.block_height,
+            &self.limit,
+            &self.searcher,
+            &self.private
+        ))
+    }
+
+    /// the public limit orders followed by the private ones
+    pub fn all_limit(&self) -> impl Iterator<Item = &OrderWithStorageData<GroupedVanillaOrder>> {
+        self.limit.iter().chain(self.private.limit.iter())
     }
 
     pub fn orders_by_pool_id(
@@ -140,7 +199,7 @@ impl PreProposal {
     ) -> HashMap<PoolId, HashSet<OrderWithStorageData<GroupedVanillaOrder>>> {
         preproposals
             .iter()
-            .flat_map(|p| p.limit.iter())
+            .flat_map(|p| p.all_limit())
             .cloned()
             .fold(HashMap::new(), |mut acc, order| {
                 acc.entry(order.pool_id).or_default().insert(order);
@@ -152,7 +211,7 @@ impl PreProposal {
 #[cfg(test)]
 mod tests {
 
-    use super::PreProposal;
+    use super::{PreProposal, PrivateOrders};
     use crate::primitive::AngstromSigner;
 
     #[test]
@@ -175,4 +234,15 @@ mod tests {
 
         assert!(preproposal.is_valid(&ethereum_height), "Unable to validate self");
     }
+
+    #[test]
+    fn private_orders_are_signed() {
+        let sk = AngstromSigner::random();
+        let private = PrivateOrders { limit: vec![Default::default()], searcher: vec![] };
+        let mut preproposal = PreProposal::generate_with_private(100, &sk, vec![], vec![], private);
+        assert!(preproposal.is_valid(&100));
+
+        preproposal.private = PrivateOrders::default();
+        assert!(!preproposal.is_valid(&100), "private orders aren't covered by the signature");
+    }
 }
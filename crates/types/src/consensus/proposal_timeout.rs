@@ -0,0 +1,97 @@
+use alloy::{
+    primitives::{keccak256, Address, BlockNumber, ChainId},
+    signers::{Signature, SignerSync}
+};
+use serde::{Deserialize, Serialize};
+
+use crate::primitive::{AngstromSigner, PeerId};
+
+/// A validators signed statement that the leader of the round at
+/// `block_height` didn't deliver, either because it never showed up before the
+/// proposal deadline or because its bundle never landed. Once validators
+/// holding 2f+1 of the voting power signed one for the same leader, every node
+/// hands the round to the next backup and records the leader as missed.
+///
+/// The signature also covers the chain id and the Angstrom contract address,
+/// so a vote can't be replayed against another deployment.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct ProposalTimeout {
+    pub block_height: BlockNumber,
+    pub leader:       Address,
+    pub source:       PeerId,
+    pub signature:    Signature
+}
+
+impl ProposalTimeout {
+    pub fn new(
+        block_height: BlockNumber,
+        leader: Address,
+        chain_id: ChainId,
+        angstrom_address: Address,
+        sk: &AngstromSigner
+    ) -> Self {
+        let hash =
+            keccak256(Self::serialize_payload(&block_height, &leader, chain_id, &angstrom_address));
+        let signature = sk.sign_hash_sync(&hash).unwrap();
+
+        Self { block_height, leader, source: sk.id(), signature }
+    }
+
+    fn serialize_payload(
+        block_height: &BlockNumber,
+        leader: &Address,
+        chain_id: ChainId,
+        angstrom_address: &Address
+    ) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend(bincode::serialize(&chain_id).unwrap());
+        buf.extend(angstrom_address.as_slice());
+        buf.extend(bincode::serialize(block_height).unwrap());
+        buf.extend(leader.as_slice());
+        buf
+    }
+
+    pub fn is_valid(&self, chain_id: ChainId, angstrom_address: Address) -> bool {
+        let hash = keccak256(Self::serialize_payload(
+            &self.block_height,
+            &self.leader,
+            chain_id,
+            &angstrom_address
+        ));
+        let Ok(source) = self.signature.recover_from_prehash(&hash) else {
+            return false;
+        };
+
+        AngstromSigner::public_key_to_peer_id(&source) == self.source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeout_is_bound_to_leader_and_height() {
+        let sk = AngstromSigner::random();
+        let leader = Address::random();
+        let angstrom = Address::random();
+        let timeout = ProposalTimeout::new(10, leader, 1, angstrom, &sk);
+        assert!(timeout.is_valid(1, angstrom));
+
+        let other_leader = ProposalTimeout { leader: Address::random(), ..timeout.clone() };
+        assert!(!other_leader.is_valid(1, angstrom));
+
+        let other_height = ProposalTimeout { block_height: 11, ..timeout };
+        assert!(!other_height.is_valid(1, angstrom));
+    }
+
+    #[test]
+    fn timeout_is_bound_to_deployment() {
+        let sk = AngstromSigner::random();
+        let angstrom = Address::random();
+        let timeout = ProposalTimeout::new(10, Address::random(), 1, angstrom, &sk);
+
+        assert!(!timeout.is_valid(11155111, angstrom));
+        assert!(!timeout.is_valid(1, Address::random()));
+    }
+}
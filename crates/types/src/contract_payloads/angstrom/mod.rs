@@ -2,7 +2,7 @@ use std::{
     collections::{HashMap, HashSet},
     hash::Hash,
     ops::Deref,
-    sync::Arc
+    sync::{Arc, RwLock}
 };
 
 use alloy::{
@@ -10,7 +10,7 @@ use alloy::{
     network::Network,
     primitives::{keccak256, Address, FixedBytes, B256, U256},
     providers::Provider,
-    sol_types::SolValue,
+    sol_types::{SolCall, SolValue},
     transports::Transport
 };
 use alloy_primitives::I256;
@@ -27,7 +27,7 @@ use super::{
 };
 use crate::{
     consensus::{PreProposal, Proposal},
-    contract_bindings::angstrom::Angstrom::PoolKey,
+    contract_bindings::angstrom::Angstrom::{executeCall, PoolKey},
     matching::{uniswap::PoolSnapshot, Ray},
     orders::{OrderFillState, OrderOutcome, PoolSolution},
     primitive::{PoolId, UniswapPoolRegistry},
@@ -112,16 +112,56 @@ impl AngstromBundle {
         TestnetStateOverrides { approvals, balances }
     }
 
+    /// Decodes a bundle out of the calldata of a transaction to the angstrom
+    /// contract. Accepts both `execute` calldata and a bare pade payload
+    pub fn from_calldata(calldata: &[u8]) -> eyre::Result<Self> {
+        let payload = executeCall::abi_decode(calldata, true)
+            .map(|call| call.encoded.to_vec())
+            .unwrap_or_else(|_| calldata.to_vec());
+
+        pade::PadeDecode::pade_decode(&mut payload.as_slice(), None)
+            .map_err(|e| eyre::eyre!("failed to decode angstrom bundle: {e:?}"))
+    }
+
     pub fn assert_book_matches(&self) {
-        let map = self
-            .user_orders
+        let deltas = match self.user_order_deltas() {
+            Ok(deltas) => deltas,
+            Err(e) => {
+                tracing::error!(%e, "can't compute user order deltas");
+                return
+            }
+        };
+        for (address, delta) in deltas {
+            if !delta.is_zero() {
+                tracing::error!(?address, ?delta, "user orders don't cancel out");
+            } else {
+                tracing::info!(?address, "solid delta");
+            }
+        }
+    }
+
+    /// the net amount of every asset the user orders put in (positive) or take
+    /// out (negative) of the book, assuming they fill at their limit price. A
+    /// matched book nets out to zero for every asset. Fails if an order points
+    /// at a pair or asset the bundle doesn't hold
+    pub fn user_order_deltas(&self) -> eyre::Result<HashMap<Address, I256>> {
+        self.user_orders
             .iter()
-            .fold(HashMap::<Address, I256>::new(), |mut acc, user| {
-                let pair = &self.pairs[user.pair_index as usize];
-                let asset_in = &self.assets
-                    [if user.zero_for_one { pair.index0 } else { pair.index1 } as usize];
-                let asset_out = &self.assets
-                    [if user.zero_for_one { pair.index1 } else { pair.index0 } as usize];
+            .try_fold(HashMap::<Address, I256>::new(), |mut acc, user| {
+                let pair = self.pairs.get(user.pair_index as usize).ok_or_else(|| {
+                    eyre::eyre!("user order references missing pair {}", user.pair_index)
+                })?;
+                let (index_in, index_out) = if user.zero_for_one {
+                    (pair.index0, pair.index1)
+                } else {
+                    (pair.index1, pair.index0)
+                };
+                let asset = |index: u16| {
+                    self.assets
+                        .get(index as usize)
+                        .ok_or_else(|| eyre::eyre!("pair references missing asset {index}"))
+                };
+                let (asset_in, asset_out) = (asset(index_in)?, asset(index_out)?);
 
                 let price = Ray::from(user.min_price);
                 // if we are exact in, then we can attribute amoutn
@@ -140,16 +180,8 @@ impl AngstromBundle {
                 *acc.entry(asset_in.addr).or_default() += I256::from_raw(amount_in);
                 *acc.entry(asset_out.addr).or_default() -= I256::from_raw(amount_out);
 
-                acc
-            });
-
-        for (address, delta) in map {
-            if !delta.is_zero() {
-                tracing::error!(?address, ?delta, "user orders don't cancel out");
-            } else {
-                tracing::info!(?address, "solid delta");
-            }
-        }
+                Ok(acc)
+            })
     }
 
     /// the block number is the block that this bundle was executed at.
@@ -712,6 +744,18 @@ impl BundleGasDetails {
     ) -> Self {
         Self { token_price_per_wei, total_gas_cost_wei }
     }
+
+    pub fn total_gas_cost_wei(&self) -> u64 {
+        self.total_gas_cost_wei
+    }
+
+    /// converts the given amount of gas into token0 of the (sorted) pair.
+    /// returns None if we don't have a conversion price for the pair
+    pub fn gas_in_token0(&self, token0: Address, token1: Address, gas: u64) -> Option<U256> {
+        self.token_price_per_wei
+            .get(&(token0, token1))
+            .map(|price| (*price * U256::from(gas)).scale_out_of_ray())
+    }
 }
 
 impl AngstromBundle {
@@ -854,9 +898,11 @@ impl TryFrom<&[u8]> for AngstromPoolConfigStore {
     }
 }
 
+/// Clones share the same set of uniswap pools, so pools that are configured or
+/// removed at runtime are seen by every holder of the registry
 #[derive(Default, Clone)]
 pub struct UniswapAngstromRegistry {
-    uniswap_pools:         UniswapPoolRegistry,
+    uniswap_pools:         Arc<RwLock<UniswapPoolRegistry>>,
     angstrom_config_store: Arc<AngstromPoolConfigStore>
 }
 
@@ -865,11 +911,32 @@ impl UniswapAngstromRegistry {
         uniswap_pools: UniswapPoolRegistry,
         angstrom_config_store: Arc<AngstromPoolConfigStore>
     ) -> Self {
-        UniswapAngstromRegistry { uniswap_pools, angstrom_config_store }
+        UniswapAngstromRegistry {
+            uniswap_pools: Arc::new(RwLock::new(uniswap_pools)),
+            angstrom_config_store
+        }
     }
 
     pub fn get_uni_pool(&self, pool_id: &PoolId) -> Option<PoolKey> {
-        self.uniswap_pools.get(pool_id).cloned()
+        self.uniswap_pools
+            .read()
+            .expect("poisoned")
+            .get(pool_id)
+            .cloned()
+    }
+
+    pub fn add_pool(&self, pool_key: PoolKey) -> PoolId {
+        self.uniswap_pools
+            .write()
+            .expect("poisoned")
+            .add_pool(pool_key)
+    }
+
+    pub fn remove_pool(&self, pool_id: &PoolId) -> Option<PoolKey> {
+        self.uniswap_pools
+            .write()
+            .expect("poisoned")
+            .remove_pool(pool_id)
     }
 
     pub fn get_ang_entry(&self, pool_id: &PoolId) -> Option<AngPoolConfigEntry> {
@@ -881,7 +948,9 @@ impl UniswapAngstromRegistry {
 
 #[cfg(test)]
 mod test {
-    use super::AngstromBundle;
+    use alloy::{primitives::Address, sol_types::SolCall};
+
+    use super::{executeCall, AngstromBundle, Asset};
 
     #[test]
     fn can_be_constructed() {
@@ -943,4 +1012,23 @@ mod test {
         let user = bundle.user_orders.remove(0);
         println!("{user:?}");
     }
+
+    #[test]
+    fn decode_bundle_from_calldata() {
+        let bundle = AngstromBundle::new(
+            vec![Asset { addr: Address::random(), ..Default::default() }],
+            vec![],
+            vec![],
+            vec![],
+            vec![]
+        );
+        let payload = pade::PadeEncode::pade_encode(&bundle);
+        let calldata = executeCall::new((payload.clone().into(),)).abi_encode();
+
+        for input in [payload, calldata] {
+            let decoded = AngstromBundle::from_calldata(&input).unwrap();
+            assert_eq!(decoded.assets[0].addr, bundle.assets[0].addr);
+        }
+        assert!(AngstromBundle::from_calldata(&[1, 2, 3]).is_err());
+    }
 }
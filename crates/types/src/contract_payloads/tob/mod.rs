@@ -6,7 +6,9 @@ use eyre::eyre;
 use super::rewards::RewardsUpdate;
 use crate::{
     matching::uniswap::{PoolSnapshot, Quantity, Tick},
-    sol_bindings::{grouped_orders::OrderWithStorageData, rpc_orders::TopOfBlockOrder}
+    sol_bindings::{
+        grouped_orders::OrderWithStorageData, rpc_orders::TopOfBlockOrder, RawPoolOrder
+    }
 };
 
 #[derive(Debug, Default, PartialEq, Eq)]
@@ -86,3 +88,54 @@ impl ToBOutcome {
         }
     }
 }
+
+/// Picks the winner of a pool's sealed-bid top of block auction. The bid that
+/// gives the most value to the pool wins, ties go to the lowest order hash so
+/// that every node picks the same winner.
+pub fn select_tob_winner(
+    bids: impl IntoIterator<Item = (OrderWithStorageData<TopOfBlockOrder>, ToBOutcome)>
+) -> Option<OrderWithStorageData<TopOfBlockOrder>> {
+    bids.into_iter()
+        .map(|(order, outcome)| (outcome.total_value(), order))
+        .max_by(|(value_a, a), (value_b, b)| {
+            value_a
+                .cmp(value_b)
+                .then_with(|| b.order_hash().cmp(&a.order_hash()))
+        })
+        .map(|(_, order)| order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bid(quantity_in: u128, tribute: u64) -> (OrderWithStorageData<TopOfBlockOrder>, ToBOutcome) {
+        let order = OrderWithStorageData {
+            order: TopOfBlockOrder { quantity_in, ..Default::default() },
+            ..Default::default()
+        };
+        let outcome = ToBOutcome { tribute: U256::from(tribute), ..Default::default() };
+
+        (order, outcome)
+    }
+
+    #[test]
+    fn test_highest_value_bid_wins() {
+        let (first, second, third) = (bid(1, 10), bid(2, 30), bid(3, 20));
+        let winner = select_tob_winner([first, second.clone(), third]).unwrap();
+        assert_eq!(winner, second.0);
+
+        assert!(select_tob_winner([]).is_none());
+    }
+
+    #[test]
+    fn test_tob_tie_break_is_deterministic() {
+        let (a, b) = (bid(1, 10), bid(2, 10));
+        let lowest_hash =
+            if a.0.order_hash() < b.0.order_hash() { a.0.clone() } else { b.0.clone() };
+
+        // the arrival order of the bids can't change the winner
+        assert_eq!(select_tob_winner([a.clone(), b.clone()]).unwrap(), lowest_hash);
+        assert_eq!(select_tob_winner([b, a]).unwrap(), lowest_hash);
+    }
+}
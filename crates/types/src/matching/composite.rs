@@ -278,6 +278,28 @@ mod tests {
         assert!(co.quantity(target_price) == partial_sweep, "CompositeOrder did not respect bound")
     }
 
+    #[test]
+    fn quantities_include_lp_fee() {
+        let market = simple_amm_at_tick(100000, 100, 1_000_000_000_000_000_u128);
+        let fee_market = market.clone().with_fee(3000).unwrap();
+        let co = CompositeOrder::new(None, Some(market.current_price()), None);
+        let fee_co = CompositeOrder::new(None, Some(fee_market.current_price()), None);
+
+        // Selling T0 into the AMM, the fee is charged on top of the T0 we put in
+        let target_price = Ray::from(SqrtPriceX96::at_tick(99990).unwrap());
+        let fee_free = co.quantity(target_price);
+        let expected = fee_free + (fee_free * 3000).div_ceil(1_000_000 - 3000);
+        assert_eq!(fee_co.quantity(target_price), expected, "Fee not charged on T0 input");
+
+        // Buying T0 from the AMM, the T0 we get out is unaffected by the fee
+        let target_price = Ray::from(SqrtPriceX96::at_tick(100010).unwrap());
+        assert_eq!(
+            fee_co.quantity(target_price),
+            co.quantity(target_price),
+            "Fee charged on T0 output"
+        );
+    }
+
     #[test]
     fn negative_quantities_are_zero() {
         let cur_price = Ray::from(SqrtPriceX96::at_tick(100000).unwrap());
@@ -3,7 +3,11 @@ use std::collections::HashMap;
 use alloy_primitives::{FixedBytes, U256};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::sol_bindings::Ray;
+
+/// The expected gas usage of the bundle that would be built from the current
+/// set of pending orders.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct BundleEstimate {
     pub total_orders:  u64,
     pub pool_estimate: HashMap<FixedBytes<32>, PoolEstimate>
@@ -11,7 +15,15 @@ pub struct BundleEstimate {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PoolEstimate {
-    pub orders:        u64,
-    pub gas_in_wei:    u64,
-    pub gas_in_token0: Option<U256>
+    /// amount of orders from this pool that are included in the bundle
+    pub orders:         u64,
+    /// gas charged to a single order in this pool
+    pub gas_in_wei:     u64,
+    /// gas charged to a single order in this pool, converted to token0. None if
+    /// there is no conversion price for the pool
+    pub gas_in_token0:  Option<U256>,
+    /// the uniform clearing price of the pool. None if the pool doesn't clear
+    pub clearing_price: Option<Ray>,
+    /// amount of token0 traded by the limit orders that get filled
+    pub filled_volume:  U256
 }
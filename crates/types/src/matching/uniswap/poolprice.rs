@@ -126,13 +126,16 @@ impl<'a> PoolPrice<'a> {
             );
             let sqrt_ratio_target_x_96 = cur_liq_range.end_price(direction).price.into();
             debug!(cur_quantity = ?cur_quantity, amount_remaining = ?amount_remaining, target_price = ?sqrt_ratio_target_x_96, "Settings before compute_swap_step");
-            let (new_price, amount_in, amount_out, _) = compute_swap_step(
+            let (new_price, amount_in, amount_out, amount_fee) = compute_swap_step(
                 sqrt_ratio_current_x_96,
                 sqrt_ratio_target_x_96,
                 cur_liq_range.liquidity(),
                 amount_remaining,
-                0
+                cur_liq_range.pool_snap.fee
             )?;
+            // The LP fee is charged on top of the input, so it counts against the
+            // quantity we're spending when T0 is our input
+            let amount_in = amount_in + amount_fee;
 
             // If we didn't hit our target and we didn't use all of our quantity then we've
             // hit a weird error
@@ -3,6 +3,7 @@ use std::{cmp::Ordering, collections::HashMap};
 use alloy::primitives::{Uint, I256, U256};
 use eyre::{eyre, Context, OptionExt};
 use uniswap_v3_math::{
+    full_math::mul_div_rounding_up,
     sqrt_price_math::{
         _get_amount_0_delta, _get_amount_1_delta, get_next_sqrt_price_from_input,
         get_next_sqrt_price_from_output
@@ -171,6 +172,41 @@ impl<'a> SwapStep<'a> {
     }
 }
 
+/// The LP fee owed on top of `amount_in` for a swap step that moves the price
+/// all the way to its target, matching `compute_swap_step`
+fn fee_on_net_input(amount_in: u128, fee_pips: u32) -> u128 {
+    if fee_pips == 0 {
+        return 0
+    }
+    mul_div_rounding_up(
+        U256::from(amount_in),
+        U256::from(fee_pips),
+        U256::from(1_000_000 - fee_pips)
+    )
+    .map(|fee| fee.saturating_to())
+    .unwrap_or_default()
+}
+
+/// The portion of a gross input that is taken as the LP fee, matching how
+/// `compute_swap_step` charges a swap that doesn't reach its target price
+fn fee_on_gross_input(amount: u128, fee_pips: u32) -> u128 {
+    if fee_pips == 0 {
+        return 0
+    }
+    mul_div_rounding_up(U256::from(amount), U256::from(fee_pips), U256::from(1_000_000))
+        .map(|fee| fee.saturating_to())
+        .unwrap_or_default()
+}
+
+/// Adds the LP fee to whichever of `(d_t0, d_t1)` is the input for the given
+/// direction
+fn with_input_fee(d_t0: u128, d_t1: u128, direction: Direction, fee_pips: u32) -> (u128, u128) {
+    match direction {
+        Direction::BuyingT0 => (d_t0, d_t1.saturating_add(fee_on_net_input(d_t1, fee_pips))),
+        Direction::SellingT0 => (d_t0.saturating_add(fee_on_net_input(d_t0, fee_pips)), d_t1)
+    }
+}
+
 #[derive(Debug)]
 pub struct DonationResult {
     pub tick_donations: HashMap<Tick, U256>,
@@ -195,8 +231,12 @@ impl<'a> PoolPriceVec<'a> {
     /// when we know that we're building a short-range PoolPriceVec that exists
     /// within a single liquidity position
     pub fn new(start_bound: PoolPrice<'a>, end_bound: PoolPrice<'a>) -> Self {
-        let (d_t0, d_t1) =
-            Self::delta_to_price(start_bound.price, end_bound.price, start_bound.liquidity());
+        let (d_t0, d_t1) = Self::delta_to_price(
+            start_bound.price,
+            end_bound.price,
+            start_bound.liquidity(),
+            start_bound.liq_range.pool_snap.fee
+        );
         Self { start_bound, end_bound, d_t0, d_t1, steps: None }
     }
 
@@ -279,8 +319,13 @@ impl<'a> PoolPriceVec<'a> {
         end: PoolPrice<'a>,
         steps: Vec<SwapStep<'a>>
     ) -> eyre::Result<Self> {
+        // Each step only records what moved through the liquidity range, the LP fee is
+        // charged on top of the input side of every step that gets crossed
+        let direction = Direction::from_prices(start.price, end.price);
+        let fee_pips = start.liq_range.pool_snap.fee;
         let (d_t0, d_t1) = steps.iter().fold((0_u128, 0_u128), |(t0, t1), step| {
-            (t0.saturating_add(step.d_t0), t1.saturating_add(step.d_t1))
+            let (step_t0, step_t1) = with_input_fee(step.d_t0, step.d_t1, direction, fee_pips);
+            (t0.saturating_add(step_t0), t1.saturating_add(step_t1))
         });
         Ok(Self { start_bound: start, end_bound: end, d_t0, d_t1, steps: Some(steps) })
     }
@@ -290,14 +335,14 @@ impl<'a> PoolPriceVec<'a> {
         direction: Direction,
         quantity: Quantity
     ) -> eyre::Result<Self> {
-        let fee_pips = 0;
+        let fee_pips = start.liq_range.pool_snap.fee;
         let mut total_in = U256::ZERO;
+        let mut total_out = U256::ZERO;
         let mut current_price = start.price;
         let mut current_liq_range: Option<_> = Some(start.liquidity_range());
         let q = quantity.magnitude();
 
         let mut steps: Vec<SwapStep> = Vec::new();
-        let total_out = U256::from(q);
 
         let mut remaining = I256::try_from(q).wrap_err_with(|| {
             // Should be impossible
@@ -309,7 +354,7 @@ impl<'a> PoolPriceVec<'a> {
             remaining *= I256::MINUS_ONE;
         }
 
-        while remaining < I256::ZERO {
+        while remaining != I256::ZERO {
             // Update our current liquidiy range
             let liq_range =
                 current_liq_range.ok_or_else(|| eyre!("Unable to find next liquidity range"))?;
@@ -339,16 +384,26 @@ impl<'a> PoolPriceVec<'a> {
                 )
             })?;
 
-            // See how much output we have yet to go
-            let signed_out = I256::try_from(amount_out)
-                .wrap_err("Output of step too large to convert U256 -> I256")?;
-            remaining = remaining
-                .checked_add(signed_out)
-                .ok_or_eyre("Unable to add signed_out to expected_out")?;
+            // See how much input or output we have yet to go, mirroring how the pool
+            // itself tracks an exact in or exact out swap
+            if remaining > I256::ZERO {
+                let signed_in = I256::try_from(amount_in + amount_fee)
+                    .wrap_err("Input of step too large to convert U256 -> I256")?;
+                remaining = remaining
+                    .checked_sub(signed_in)
+                    .ok_or_eyre("Unable to subtract signed_in from expected_in")?;
+            } else {
+                let signed_out = I256::try_from(amount_out)
+                    .wrap_err("Output of step too large to convert U256 -> I256")?;
+                remaining = remaining
+                    .checked_add(signed_out)
+                    .ok_or_eyre("Unable to add signed_out to expected_out")?;
+            }
 
             // Add the amount in and our total fee to our cost
             total_in += amount_in;
             total_in += amount_fee;
+            total_out += amount_out;
 
             // Based on our direction, sort out what our token0 and token1 are
             let (d_t0, d_t1) = direction.sort_tokens(amount_in.to(), amount_out.to());
@@ -474,11 +529,12 @@ impl<'a> PoolPriceVec<'a> {
     }
 
     /// A very raw delta to a specific price presuming the liquidity is constant
-    /// for the duration of the swap
+    /// for the duration of the swap.  The LP fee is included on the input side
     fn delta_to_price(
         start_price: SqrtPriceX96,
         end_price: SqrtPriceX96,
-        liquidity: u128
+        liquidity: u128,
+        fee_pips: u32
     ) -> (u128, u128) {
         let sqrt_ratio_a_x_96 = start_price.into();
         let sqrt_ratio_b_x_96 = end_price.into();
@@ -486,7 +542,8 @@ impl<'a> PoolPriceVec<'a> {
             .unwrap_or(Uint::from(0));
         let d_t1 = _get_amount_1_delta(sqrt_ratio_a_x_96, sqrt_ratio_b_x_96, liquidity, false)
             .unwrap_or(Uint::from(0));
-        (d_t0.to(), d_t1.to())
+        let direction = Direction::from_prices(start_price, end_price);
+        with_input_fee(d_t0.to(), d_t1.to(), direction, fee_pips)
     }
 
     pub fn is_buy(&self) -> bool {
@@ -502,8 +559,12 @@ impl<'a> PoolPriceVec<'a> {
             return (self.d_t0, self.d_t1, OrderPrice::from(self.end_bound.price));
         }
 
-        let (d_t0, d_t1) =
-            Self::delta_to_price(self.start_bound.price, t, self.start_bound.liquidity());
+        let (d_t0, d_t1) = Self::delta_to_price(
+            self.start_bound.price,
+            t,
+            self.start_bound.liquidity(),
+            self.start_bound.liq_range.pool_snap.fee
+        );
         (d_t0, d_t1, target_price)
     }
 
@@ -512,6 +573,7 @@ impl<'a> PoolPriceVec<'a> {
     // always be OK?
     pub fn fill(&self, quantity: u128) -> Self {
         let liquidity = self.start_bound.liquidity();
+        let fee_pips = self.start_bound.liq_range.pool_snap.fee;
         let end_sqrt_price = if self.is_buy() {
             get_next_sqrt_price_from_output(
                 self.start_bound.price.into(),
@@ -522,16 +584,19 @@ impl<'a> PoolPriceVec<'a> {
             .map(SqrtPriceX96::from)
             .unwrap()
         } else {
+            // Only the portion of our input left after the LP fee moves the price
+            let quantity_less_fee = quantity - fee_on_gross_input(quantity, fee_pips);
             get_next_sqrt_price_from_input(
                 self.start_bound.price.into(),
                 liquidity,
-                U256::from(quantity),
+                U256::from(quantity_less_fee),
                 true
             )
             .map(SqrtPriceX96::from)
             .unwrap()
         };
-        let (d_t0, d_t1) = Self::delta_to_price(self.start_bound.price, end_sqrt_price, liquidity);
+        let (d_t0, d_t1) =
+            Self::delta_to_price(self.start_bound.price, end_sqrt_price, liquidity, fee_pips);
         let mut end_bound = self.start_bound.clone();
         end_bound.price = end_sqrt_price;
         Self { end_bound, start_bound: self.start_bound.clone(), d_t0, d_t1, steps: None }
@@ -11,6 +11,12 @@ use super::{
 };
 use crate::matching::{math::low_to_high, SqrtPriceX96};
 
+/// The LP fee of a pool has to stay below 100%, in pips
+pub const MAX_LP_FEE: u32 = 1_000_000;
+/// Set in a pool key's fee when the pool's hook sets its LP fee, it is never
+/// the fee a swap is charged
+pub const DYNAMIC_FEE_FLAG: u32 = 0x800000;
+
 /// Snapshot of a particular Uniswap pool and a map of its liquidity.
 #[derive(Default, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PoolSnapshot {
@@ -25,7 +31,11 @@ pub struct PoolSnapshot {
     pub(crate) current_tick:   Tick,
     /// Index into the 'ranges' vector for the PoolRange that includes the tick
     /// our current price lives at/in
-    pub(crate) cur_tick_idx:   usize
+    pub(crate) cur_tick_idx:   usize,
+    /// The LP fee charged by this pool on the input side of a swap, in pips
+    /// (hundredths of a basis point)
+    #[serde(default)]
+    pub(crate) fee:            u32
 }
 
 impl PoolSnapshot {
@@ -58,7 +68,26 @@ impl PoolSnapshot {
             ));
         };
 
-        Ok(Self { ranges, sqrt_price_x96, current_tick, cur_tick_idx })
+        Ok(Self { ranges, sqrt_price_x96, current_tick, cur_tick_idx, fee: 0 })
+    }
+
+    /// Set the LP fee, in pips, that swaps against this snapshot are charged.
+    /// Fails on the dynamic fee flag and on fees that aren't below 100%
+    pub fn with_fee(mut self, fee: u32) -> eyre::Result<Self> {
+        if fee & DYNAMIC_FEE_FLAG != 0 {
+            return Err(eyre!("LP fee '{fee:#x}' is the dynamic fee flag, not a fee"));
+        }
+        if fee >= MAX_LP_FEE {
+            return Err(eyre!("LP fee '{fee}' is not below {MAX_LP_FEE} pips"));
+        }
+        self.fee = fee;
+
+        Ok(self)
+    }
+
+    /// The LP fee for this pool in pips
+    pub fn fee(&self) -> u32 {
+        self.fee
     }
 
     /// Find the PoolRange in this market snapshot that the provided tick lies
@@ -1,52 +1,248 @@
-use std::{ops::Deref, pin::Pin, sync::Arc};
+use std::{ops::Deref, pin::Pin, sync::Arc, time::Duration};
 
 use alloy::{
+    consensus::TxEnvelope,
     eips::eip2718::Encodable2718,
+    hex,
     network::TransactionBuilder,
-    primitives::{Address, TxHash},
+    primitives::{keccak256, Address, BlockNumber, Bytes, ChainId, TxHash, B256, U64},
     providers::{Provider, ProviderBuilder, RootProvider},
     rpc::types::TransactionRequest,
-    transports::{http::reqwest::Url, Transport}
+    signers::SignerSync,
+    transports::http::{
+        reqwest::{Client, Url},
+        Http
+    }
 };
-use futures::{Future, FutureExt};
+use futures::{future::join_all, Future, FutureExt};
+use serde::{Deserialize, Serialize};
+
+use crate::{contract_payloads::angstrom::BundleGasDetails, primitive::AngstromSigner};
 
-use crate::primitive::AngstromSigner;
+/// Gas limit used for the bundle transaction if we don't have a simulated gas
+/// usage to go off of
+pub const MAX_BUNDLE_GAS_LIMIT: u64 = 30_000_000;
+/// Headroom, in percent, that is put on top of the simulated gas usage so that
+/// small state changes between simulation and inclusion don't make us run out
+/// of gas
+pub const BUNDLE_GAS_HEADROOM_PCT: u64 = 20;
+/// Header flashbots style builders use to attribute a bundle to a searcher
+pub const FLASHBOTS_SIGNATURE_HEADER: &str = "X-Flashbots-Signature";
+/// How long a single endpoint gets to take a submission if we don't know the
+/// chain's block time. A bundle for the target block is worthless after that
+pub const DEFAULT_SUBMISSION_TIMEOUT: Duration = Duration::from_secs(12);
 
 /// Allows for us to have a look at the angstrom payload to ensure that we can
 /// set balances properly for when the transaction is submitted
 pub trait SubmitTx: Send + Sync {
+    /// Where this submits to, used to attribute per builder results
+    fn endpoint(&self) -> String;
+
     fn submit_transaction<'a>(
         &'a self,
         signer: &'a AngstromSigner,
-        tx: TransactionRequest
-    ) -> Pin<Box<dyn Future<Output = (TxHash, bool)> + Send + 'a>>;
+        tx: &'a TxEnvelope,
+        target_block: BlockNumber
+    ) -> Pin<Box<dyn Future<Output = eyre::Result<()>> + Send + 'a>>;
 }
 
-// Default impl
-impl<T> SubmitTx for RootProvider<T>
-where
-    T: Transport + Clone
-{
+/// Broadcasts the signed transaction as is through `eth_sendRawTransaction`.
+/// There is no notion of a target block here, the transaction is valid until
+/// it lands
+pub struct RawTxSubmitter {
+    url:      Url,
+    provider: RootProvider<Http<Client>>
+}
+
+impl RawTxSubmitter {
+    pub fn new(url: Url) -> Self {
+        let provider = ProviderBuilder::<_, _, _>::default().on_http(url.clone());
+        Self { url, provider }
+    }
+}
+
+impl SubmitTx for RawTxSubmitter {
+    fn endpoint(&self) -> String {
+        self.url.to_string()
+    }
+
+    fn submit_transaction<'a>(
+        &'a self,
+        _: &'a AngstromSigner,
+        tx: &'a TxEnvelope,
+        _: BlockNumber
+    ) -> Pin<Box<dyn Future<Output = eyre::Result<()>> + Send + 'a>> {
+        async move {
+            self.provider
+                .send_raw_transaction(&tx.encoded_2718())
+                .await?;
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+/// `eth_sendBundle` params
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendBundleRequest {
+    /// signed, 2718 encoded transactions in the order they execute
+    pub txs:                 Vec<Bytes>,
+    /// the only block this bundle is valid for
+    pub block_number:        U64,
+    /// transactions that are allowed to revert without the bundle being
+    /// dropped. We never set any, a reverting angstrom bundle should never
+    /// land
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reverting_tx_hashes: Vec<TxHash>
+}
+
+impl SendBundleRequest {
+    pub fn new(tx: &TxEnvelope, target_block: BlockNumber) -> Self {
+        Self {
+            txs:                 vec![tx.encoded_2718().into()],
+            block_number:        U64::from(target_block),
+            reverting_tx_hashes: vec![]
+        }
+    }
+}
+
+/// `eth_sendBundle` result
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendBundleResponse {
+    pub bundle_hash: B256
+}
+
+/// Submits the signed transaction to a block builder as a single transaction
+/// bundle through `eth_sendBundle`, only valid for the target block
+pub struct BundleSubmitter {
+    url:    Url,
+    client: Client
+}
+
+impl BundleSubmitter {
+    pub fn new(url: Url) -> Self {
+        Self { url, client: Client::new() }
+    }
+
+    /// `address:signature` where the signature is over the hex encoded hash of
+    /// the request body
+    fn flashbots_signature(signer: &AngstromSigner, body: &str) -> eyre::Result<String> {
+        let body_hash = hex::encode_prefixed(keccak256(body));
+        let signature = signer.sign_message_sync(body_hash.as_bytes())?;
+        Ok(format!("{}:{}", signer.address(), hex::encode_prefixed(signature.as_bytes())))
+    }
+}
+
+impl SubmitTx for BundleSubmitter {
+    fn endpoint(&self) -> String {
+        self.url.to_string()
+    }
+
     fn submit_transaction<'a>(
         &'a self,
         signer: &'a AngstromSigner,
-        tx: TransactionRequest
-    ) -> Pin<Box<dyn Future<Output = (TxHash, bool)> + Send + 'a>> {
+        tx: &'a TxEnvelope,
+        target_block: BlockNumber
+    ) -> Pin<Box<dyn Future<Output = eyre::Result<()>> + Send + 'a>> {
         async move {
-            let tx = tx.build(&signer).await.unwrap();
-            let hash = *tx.tx_hash();
-            let encoded = tx.encoded_2718();
+            let body = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "eth_sendBundle",
+                "params": [SendBundleRequest::new(tx, target_block)]
+            })
+            .to_string();
+            let signature = Self::flashbots_signature(signer, &body)?;
+
+            let response = self
+                .client
+                .post(self.url.clone())
+                .header("Content-Type", "application/json")
+                .header(FLASHBOTS_SIGNATURE_HEADER, signature)
+                .body(body)
+                .send()
+                .await?
+                .text()
+                .await?;
 
-            let submitted = self.send_raw_transaction(&encoded).await.is_ok();
-            (hash, submitted)
+            let response: serde_json::Value = serde_json::from_str(&response)?;
+            if let Some(error) = response.get("error") {
+                eyre::bail!("builder rejected bundle: {error}");
+            }
+            let result = response
+                .get("result")
+                .ok_or_else(|| eyre::eyre!("builder response had no result: {response}"))?;
+            let _: SendBundleResponse = serde_json::from_value(result.clone())?;
+
+            Ok(())
         }
         .boxed()
     }
 }
 
+/// How a single builder handled our submission
+#[derive(Debug)]
+pub struct BuilderSubmission {
+    pub endpoint: String,
+    pub result:   eyre::Result<()>
+}
+
+impl BuilderSubmission {
+    /// Submits to a single endpoint, which has until `timeout` to take it
+    async fn submit(
+        provider: &dyn SubmitTx,
+        signer: &AngstromSigner,
+        tx: &TxEnvelope,
+        target_block: BlockNumber,
+        timeout: Duration
+    ) -> Self {
+        let result =
+            tokio::time::timeout(timeout, provider.submit_transaction(signer, tx, target_block))
+                .await
+                .unwrap_or_else(|_| Err(eyre::eyre!("timed out after {timeout:?}")));
+
+        Self { endpoint: provider.endpoint(), result }
+    }
+}
+
+/// Outcome of fanning a transaction out to all configured builders
+#[derive(Debug)]
+pub struct SubmissionResult {
+    pub tx_hash:      TxHash,
+    pub target_block: BlockNumber,
+    pub builders:     Vec<BuilderSubmission>
+}
+
+impl SubmissionResult {
+    /// true if at least one builder took the transaction, which is all we
+    /// need for it to have a chance at landing
+    pub fn any_accepted(&self) -> bool {
+        self.builders.iter().any(|b| b.result.is_ok())
+    }
+
+    pub fn rejected(&self) -> impl Iterator<Item = &BuilderSubmission> + '_ {
+        self.builders.iter().filter(|b| b.result.is_err())
+    }
+}
+
+/// The gas limit to set on the bundle transaction given its simulated gas
+/// usage
+pub fn bundle_gas_limit(gas_details: &BundleGasDetails) -> u64 {
+    let simulated = gas_details.total_gas_cost_wei();
+    if simulated == 0 {
+        return MAX_BUNDLE_GAS_LIMIT
+    }
+
+    (simulated + simulated * BUNDLE_GAS_HEADROOM_PCT / 100).min(MAX_BUNDLE_GAS_LIMIT)
+}
+
 pub struct MevBoostProvider<P> {
     mev_boost_providers: Vec<Arc<Box<dyn SubmitTx>>>,
-    node_provider:       Arc<P>
+    node_provider:       Arc<P>,
+    chain_id:            ChainId,
+    submission_timeout:  Duration
 }
 
 impl<P> MevBoostProvider<P>
@@ -55,24 +251,52 @@ where
 {
     pub fn new_from_raw(
         node_provider: Arc<P>,
-        mev_boost_providers: Vec<Arc<Box<dyn SubmitTx>>>
+        mev_boost_providers: Vec<Arc<Box<dyn SubmitTx>>>,
+        chain_id: ChainId
     ) -> Self {
-        Self { node_provider, mev_boost_providers }
+        Self {
+            node_provider,
+            mev_boost_providers,
+            chain_id,
+            submission_timeout: DEFAULT_SUBMISSION_TIMEOUT
+        }
     }
 
-    pub fn new_from_urls(node_provider: Arc<P>, urls: &[Url]) -> Self {
-        let mev_boost_providers = urls
+    /// `urls` get the raw signed transaction, `builder_urls` get it wrapped in
+    /// a bundle for the target block
+    pub fn new_from_urls(
+        node_provider: Arc<P>,
+        urls: &[Url],
+        builder_urls: &[Url],
+        chain_id: ChainId
+    ) -> Self {
+        let raw = urls
             .iter()
-            .map(|url| {
-                Arc::new(Box::new(ProviderBuilder::<_, _, _>::default().on_http(url.clone()))
-                    as Box<dyn SubmitTx>)
-            })
-            .collect::<Vec<_>>();
+            .map(|url| Arc::new(Box::new(RawTxSubmitter::new(url.clone())) as Box<dyn SubmitTx>));
+        let builders = builder_urls
+            .iter()
+            .map(|url| Arc::new(Box::new(BundleSubmitter::new(url.clone())) as Box<dyn SubmitTx>));
+
+        Self::new_from_raw(node_provider, raw.chain(builders).collect(), chain_id)
+    }
+
+    /// Endpoints that didn't take the submission within the timeout are
+    /// reported as rejected. Should be bound by the block time
+    pub fn with_submission_timeout(mut self, submission_timeout: Duration) -> Self {
+        self.submission_timeout = submission_timeout;
+        self
+    }
 
-        Self { mev_boost_providers, node_provider }
+    pub fn chain_id(&self) -> ChainId {
+        self.chain_id
     }
 
-    pub async fn populate_gas_nonce_chain_id(&self, tx_from: Address, tx: &mut TransactionRequest) {
+    pub async fn populate_gas_nonce_chain_id(
+        &self,
+        tx_from: Address,
+        tx: &mut TransactionRequest,
+        gas_details: &BundleGasDetails
+    ) {
         let next_nonce = self
             .node_provider
             .get_transaction_count(tx_from)
@@ -80,7 +304,7 @@ where
             .unwrap();
 
         tx.set_nonce(next_nonce);
-        tx.set_gas_limit(30_000_000);
+        tx.set_gas_limit(bundle_gas_limit(gas_details));
         let fees = self
             .node_provider
             .estimate_eip1559_fees(None)
@@ -88,24 +312,33 @@ where
             .unwrap();
         tx.set_max_fee_per_gas(fees.max_fee_per_gas);
         tx.set_max_priority_fee_per_gas(fees.max_priority_fee_per_gas);
-        tx.set_chain_id(1);
+        tx.set_chain_id(self.chain_id);
     }
 
-    // has as consumption here due to weird to general error
+    /// Signs the transaction once and submits it to every configured endpoint
+    /// at the same time, targeting `target_block`. Endpoints that don't answer
+    /// within the submission timeout count as rejected
     pub async fn sign_and_send(
         &self,
         signer: AngstromSigner,
-        tx: TransactionRequest
-    ) -> (TxHash, bool) {
-        let mut submitted = true;
-        let mut phash = None;
-        for provider in self.mev_boost_providers.clone() {
-            let (hash, sent) = provider.submit_transaction(&signer, tx.clone()).await;
-            phash = Some(hash);
-            submitted &= sent;
-        }
+        tx: TransactionRequest,
+        target_block: BlockNumber
+    ) -> eyre::Result<SubmissionResult> {
+        let tx = tx.build(&signer).await?;
+        let tx_hash = *tx.tx_hash();
 
-        (phash.expect("no mev boost endpoint was set"), submitted)
+        let builders = join_all(self.mev_boost_providers.iter().map(|provider| {
+            BuilderSubmission::submit(
+                provider.as_ref().as_ref(),
+                &signer,
+                &tx,
+                target_block,
+                self.submission_timeout
+            )
+        }))
+        .await;
+
+        Ok(SubmissionResult { tx_hash, target_block, builders })
     }
 }
 
@@ -116,3 +349,67 @@ impl<P> Deref for MevBoostProvider<P> {
         &self.node_provider
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    /// an endpoint that never answers
+    struct StalledSubmitter;
+
+    impl SubmitTx for StalledSubmitter {
+        fn endpoint(&self) -> String {
+            "stalled".to_string()
+        }
+
+        fn submit_transaction<'a>(
+            &'a self,
+            _: &'a AngstromSigner,
+            _: &'a TxEnvelope,
+            _: BlockNumber
+        ) -> Pin<Box<dyn Future<Output = eyre::Result<()>> + Send + 'a>> {
+            futures::future::pending().boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn stalled_builder_is_rejected_after_timeout() {
+        let signer = AngstromSigner::random();
+        let tx = TransactionRequest::default()
+            .with_to(Address::random())
+            .with_nonce(0)
+            .with_chain_id(1)
+            .with_gas_limit(21_000)
+            .with_max_fee_per_gas(1)
+            .with_max_priority_fee_per_gas(1)
+            .build(&signer)
+            .await
+            .unwrap();
+
+        let submission = BuilderSubmission::submit(
+            &StalledSubmitter,
+            &signer,
+            &tx,
+            1,
+            Duration::from_millis(10)
+        )
+        .await;
+        assert_eq!(submission.endpoint, "stalled");
+        assert!(submission.result.is_err());
+    }
+
+    #[test]
+    fn gas_limit_follows_simulation() {
+        let details = BundleGasDetails::new(HashMap::new(), 500_000);
+        assert_eq!(bundle_gas_limit(&details), 600_000);
+
+        // nothing simulated, fall back to the block sized limit
+        assert_eq!(bundle_gas_limit(&BundleGasDetails::default()), MAX_BUNDLE_GAS_LIMIT);
+
+        // never go over what a block can hold
+        let details = BundleGasDetails::new(HashMap::new(), MAX_BUNDLE_GAS_LIMIT);
+        assert_eq!(bundle_gas_limit(&details), MAX_BUNDLE_GAS_LIMIT);
+    }
+}
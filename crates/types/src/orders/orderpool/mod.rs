@@ -4,8 +4,9 @@ use alloy::primitives::{Address, B256, U256};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use super::{OrderFillState, OrderOrigin};
 use crate::{
-    primitive::PoolId,
+    primitive::{OrderRejectionReason, PoolId},
     sol_bindings::{ext::RespendAvoidanceMethod, RawPoolOrder}
 };
 
@@ -13,7 +14,68 @@ use crate::{
 pub enum OrderStatus {
     Filled,
     Pending,
-    Blocked
+    Blocked,
+    /// pending in this node's pool, but never shared with the network
+    Private,
+    Cancelled,
+    Expired,
+    /// dropped from the pool, either on entry or when it was re-validated
+    Invalid(OrderRejectionReason)
+}
+
+/// A single step in the life of an order, as seen by this node
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum OrderLifecycleEvent {
+    Received {
+        origin: OrderOrigin
+    },
+    Validated,
+    Pending,
+    /// a higher priority order of the same account holds the nonce
+    Parked,
+    /// included in our pre-proposal for the block
+    InPreProposal,
+    /// matched into the bundle we built for the block
+    InBundle {
+        fill: OrderFillState
+    },
+    /// landed on chain. The fill is known if this node built the bundle
+    Filled {
+        fill: Option<OrderFillState>
+    },
+    Cancelled,
+    Expired,
+    /// the block that filled the order got reorged out
+    Reorged,
+    Invalidated {
+        reason: OrderRejectionReason
+    }
+}
+
+impl OrderLifecycleEvent {
+    /// the status of an order that is no longer in the pool, if this event
+    /// ended its life
+    pub fn final_status(&self) -> Option<OrderStatus> {
+        match self {
+            Self::Filled { .. } => Some(OrderStatus::Filled),
+            Self::Cancelled => Some(OrderStatus::Cancelled),
+            Self::Expired => Some(OrderStatus::Expired),
+            Self::Invalidated { reason } => Some(OrderStatus::Invalid(reason.clone())),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderHistoryEntry {
+    /// the block the event happened at, or for pre-proposals and bundles the
+    /// block they target
+    pub block_number: u64,
+    /// unix timestamp in seconds
+    pub timestamp:    u64,
+    pub event:        OrderLifecycleEvent
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::OnceLock};
 
 use alloy::{
     dyn_abi::Eip712Domain,
@@ -21,14 +21,54 @@ use crate::primitive::PoolId;
 pub const TESTNET_ANGSTROM_ADDRESS: Address =
     alloy::primitives::address!("293954613283cC7B82BfE9676D3cc0fb0A58fAa0");
 
-// The `eip712_domain` macro lets you easily define an EIP-712 domain
-// object :)
-pub const ANGSTROM_DOMAIN: Eip712Domain = eip712_domain!(
-    name: "Angstrom",
-    version: "v1",
-    chain_id: 1,
-    verifying_contract: TESTNET_ANGSTROM_ADDRESS,
-);
+/// the chain and angstrom contract orders are signed for, set once at startup
+static ANGSTROM_DOMAIN: OnceLock<(u64, Address)> = OnceLock::new();
+
+/// sets the chain id and angstrom contract used for order signatures and
+/// simulation. Can only be set once, setting it again to a different domain is
+/// an error
+pub fn init_angstrom_domain(chain_id: u64, angstrom_address: Address) -> eyre::Result<()> {
+    let set = *ANGSTROM_DOMAIN.get_or_init(|| (chain_id, angstrom_address));
+    if set != (chain_id, angstrom_address) {
+        eyre::bail!(
+            "angstrom domain is already set to chain {} and contract {}, can't change it to chain \
+             {chain_id} and contract {angstrom_address}",
+            set.0,
+            set.1
+        )
+    }
+
+    Ok(())
+}
+
+fn domain_parts() -> (u64, Address) {
+    // tests sign orders without going through startup
+    #[cfg(any(test, feature = "test-utils"))]
+    let domain = ANGSTROM_DOMAIN.get_or_init(|| (1, TESTNET_ANGSTROM_ADDRESS));
+    #[cfg(not(any(test, feature = "test-utils")))]
+    let domain = ANGSTROM_DOMAIN
+        .get()
+        .expect("angstrom domain used before init_angstrom_domain was called");
+
+    *domain
+}
+
+/// the chain id set at startup. Panics if it was never set
+pub fn angstrom_chain_id() -> u64 {
+    domain_parts().0
+}
+
+/// the EIP-712 domain orders are signed over, bound to the chain we run on and
+/// the angstrom contract. Panics if it was never set
+pub fn angstrom_domain() -> Eip712Domain {
+    let (chain_id, angstrom_address) = domain_parts();
+    eip712_domain!(
+        name: "Angstrom",
+        version: "v1",
+        chain_id: chain_id,
+        verifying_contract: angstrom_address,
+    )
+}
 
 #[derive(Default, Clone)]
 pub struct UniswapPoolRegistry {
@@ -43,26 +83,32 @@ impl UniswapPoolRegistry {
     pub fn pools(&self) -> HashMap<PoolId, PoolKey> {
         self.pools.clone()
     }
+
+    /// registers a pool, returning its public id
+    pub fn add_pool(&mut self, pool_key: PoolKey) -> PoolId {
+        let pool_id_pub = PoolId::from(pool_key.clone());
+
+        let mut private_key = pool_key.clone();
+        private_key.fee = U24::from(0x800000);
+        let pool_id_priv = PoolId::from(private_key);
+
+        self.pools.insert(pool_id_pub, pool_key);
+        self.conversion_map.insert(pool_id_pub, pool_id_priv);
+
+        pool_id_pub
+    }
+
+    pub fn remove_pool(&mut self, pool_id: &PoolId) -> Option<PoolKey> {
+        self.conversion_map.remove(pool_id);
+        self.pools.remove(pool_id)
+    }
 }
 impl From<Vec<PoolKey>> for UniswapPoolRegistry {
     fn from(pools: Vec<PoolKey>) -> Self {
-        let pubmap = pools
-            .iter()
-            .map(|pool_key| {
-                let pool_id = PoolId::from(pool_key.clone());
-                (pool_id, pool_key.clone())
-            })
-            .collect();
-
-        let priv_map = pools
-            .into_iter()
-            .map(|mut pool_key| {
-                let pool_id_pub = PoolId::from(pool_key.clone());
-                pool_key.fee = U24::from(0x800000);
-                let pool_id_priv = PoolId::from(pool_key.clone());
-                (pool_id_pub, pool_id_priv)
-            })
-            .collect();
-        Self { pools: pubmap, conversion_map: priv_map }
+        let mut this = Self::default();
+        for pool_key in pools {
+            this.add_pool(pool_key);
+        }
+        this
     }
 }
@@ -6,7 +6,7 @@ use alloy::{
     primitives::PrimitiveSignature,
     signers::{local::PrivateKeySigner, SignerSync}
 };
-use alloy_primitives::Address;
+use alloy_primitives::{keccak256, Address};
 use k256::{ecdsa::VerifyingKey, elliptic_curve::sec1::ToEncodedPoint};
 use reth_network_peers::PeerId;
 
@@ -45,6 +45,12 @@ impl AngstromSigner {
         PeerId::from_slice(&encoded.as_bytes()[1..])
     }
 
+    /// The ethereum address of the node that owns the given peer id
+    pub fn peer_id_to_address(peer_id: PeerId) -> Address {
+        let hash = keccak256(peer_id);
+        Address::from_slice(&hash[12..])
+    }
+
     fn sign_transaction_inner(
         &self,
         tx: &mut dyn SignableTransaction<PrimitiveSignature>
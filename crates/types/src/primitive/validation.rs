@@ -1,9 +1,11 @@
+use alloy::primitives::{Address, U256};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum OrderPoolNewOrderResult {
     Valid,
-    Invalid,
+    Invalid(OrderRejectionReason),
     TransitionedToBlock,
     Error(String)
 }
@@ -12,6 +14,14 @@ impl OrderPoolNewOrderResult {
     pub fn is_valid(&self) -> bool {
         matches!(self, OrderPoolNewOrderResult::Valid)
     }
+
+    /// the reason the order was rejected, if it was
+    pub fn rejection_reason(&self) -> Option<&OrderRejectionReason> {
+        match self {
+            OrderPoolNewOrderResult::Invalid(reason) => Some(reason),
+            _ => None
+        }
+    }
 }
 
 impl<T: Into<Self>, E: std::error::Error> From<Result<T, E>> for OrderPoolNewOrderResult {
@@ -22,3 +32,37 @@ impl<T: Into<Self>, E: std::error::Error> From<Result<T, E>> for OrderPoolNewOrd
         }
     }
 }
+
+/// Why an order was refused entry into, or dropped from, the order pool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Error)]
+#[serde(rename_all = "camelCase")]
+pub enum OrderRejectionReason {
+    #[error("order signature is invalid")]
+    InvalidSignature,
+    #[error("no angstrom pool exists for the order's token pair")]
+    UnknownPool,
+    #[error("nonce has already been used or is held by a higher priority order")]
+    DuplicateNonce,
+    #[error("flash order is for block {requested} but the next block is {expected}")]
+    BadBlock { expected: u64, requested: u64 },
+    #[error("order was validated against block {validated} but the pool is at {current}")]
+    StaleValidation { validated: u64, current: u64 },
+    #[error("order has been cancelled")]
+    Cancelled,
+    #[error("order is already in the pool")]
+    DuplicateOrder,
+    #[error("order was previously rejected")]
+    PreviouslyRejected,
+    #[error("gas cost of {gas_cost} in asset0 exceeds the order max of {max_fee}")]
+    GasExceedsMaxFee { gas_cost: u128, max_fee: u128 },
+    #[error("failed to simulate order gas: {0}")]
+    GasSimulationFailed(String),
+    #[error("account already has the maximum of {max} orders in the pool")]
+    AccountSlotsExceeded { max: usize },
+    #[error("composable order hook failed: {0}")]
+    InvalidHook(String),
+    #[error("order needs {required} of {token} but the account only holds {available}")]
+    InsufficientBalance { token: Address, required: U256, available: U256 },
+    #[error("order needs {required} of {token} but angstrom is only approved for {available}")]
+    InsufficientApproval { token: Address, required: U256, available: U256 }
+}
@@ -12,7 +12,7 @@ use super::{GenerateFlippedOrder, RawPoolOrder, RespendAvoidanceMethod};
 use crate::{
     matching::{Debt, Ray},
     orders::{OrderId, OrderLocation, OrderPriorityData},
-    primitive::{PoolId, ANGSTROM_DOMAIN},
+    primitive::{angstrom_domain, PoolId},
     sol_bindings::rpc_orders::{
         ExactFlashOrder, ExactStandingOrder, OmitOrderMeta, PartialFlashOrder,
         PartialStandingOrder, TopOfBlockOrder
@@ -180,6 +180,17 @@ impl From<GroupedComposableOrder> for AllOrders {
     }
 }
 
+/// Composable orders are matched and encoded into the bundle exactly like
+/// vanilla orders, the hook only changes what the contract does after the fill
+impl From<GroupedComposableOrder> for GroupedVanillaOrder {
+    fn from(value: GroupedComposableOrder) -> Self {
+        match value {
+            GroupedComposableOrder::Partial(p) => GroupedVanillaOrder::Standing(p),
+            GroupedComposableOrder::KillOrFill(kof) => GroupedVanillaOrder::KillOrFill(kof)
+        }
+    }
+}
+
 impl From<GroupedVanillaOrder> for AllOrders {
     fn from(value: GroupedVanillaOrder) -> Self {
         match value {
@@ -218,6 +229,16 @@ impl AllOrders {
             Self::TOB(t) => t.eip712_hash_struct()
         }
     }
+
+    /// A user order is composable if it carries hook data for the contract to
+    /// call once the order has been executed
+    pub fn is_composable(&self) -> bool {
+        match self {
+            Self::Standing(p) => !p.hook_data().is_empty(),
+            Self::Flash(f) => !f.hook_data().is_empty(),
+            Self::TOB(_) => false
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -632,6 +653,14 @@ pub enum GroupedComposableOrder {
 }
 
 impl GroupedComposableOrder {
+    /// the hook address followed by the payload it gets called with
+    pub fn hook_data(&self) -> &Bytes {
+        match self {
+            Self::Partial(p) => p.hook_data(),
+            Self::KillOrFill(k) => k.hook_data()
+        }
+    }
+
     pub fn hash(&self) -> B256 {
         match self {
             Self::Partial(p) => match p {
@@ -693,7 +722,7 @@ impl RawPoolOrder for TopOfBlockOrder {
 
     fn is_valid_signature(&self) -> bool {
         let Ok(sig) = self.order_signature() else { return false };
-        let hash = self.no_meta_eip712_signing_hash(&ANGSTROM_DOMAIN);
+        let hash = self.no_meta_eip712_signing_hash(&angstrom_domain());
 
         sig.recover_address_from_prehash(&hash)
             .map(|addr| addr == self.meta.from)
@@ -730,7 +759,7 @@ impl RawPoolOrder for PartialStandingOrder {
         let mut slice = s.as_slice();
 
         let Ok(sig) = Signature::pade_decode(&mut slice, None) else { return false };
-        let hash = self.no_meta_eip712_signing_hash(&ANGSTROM_DOMAIN);
+        let hash = self.no_meta_eip712_signing_hash(&angstrom_domain());
 
         sig.recover_address_from_prehash(&hash)
             .map(|addr| addr == self.meta.from)
@@ -803,7 +832,7 @@ impl RawPoolOrder for ExactStandingOrder {
         let mut slice = s.as_slice();
 
         let Ok(sig) = Signature::pade_decode(&mut slice, None) else { return false };
-        let hash = self.no_meta_eip712_signing_hash(&ANGSTROM_DOMAIN);
+        let hash = self.no_meta_eip712_signing_hash(&angstrom_domain());
 
         sig.recover_address_from_prehash(&hash)
             .map(|addr| addr == self.meta.from)
@@ -876,7 +905,7 @@ impl RawPoolOrder for PartialFlashOrder {
         let mut slice = s.as_slice();
 
         let Ok(sig) = Signature::pade_decode(&mut slice, None) else { return false };
-        let hash = self.no_meta_eip712_signing_hash(&ANGSTROM_DOMAIN);
+        let hash = self.no_meta_eip712_signing_hash(&angstrom_domain());
 
         sig.recover_address_from_prehash(&hash)
             .map(|addr| addr == self.meta.from)
@@ -949,7 +978,7 @@ impl RawPoolOrder for ExactFlashOrder {
         let mut slice = s.as_slice();
 
         let Ok(sig) = Signature::pade_decode(&mut slice, None) else { return false };
-        let hash = self.no_meta_eip712_signing_hash(&ANGSTROM_DOMAIN);
+        let hash = self.no_meta_eip712_signing_hash(&angstrom_domain());
 
         sig.recover_address_from_prehash(&hash)
             .map(|addr| addr == self.meta.from)
@@ -9,6 +9,8 @@ use crate::contract_bindings::angstrom::Angstrom::PoolKey;
 pub struct InitialTestnetState {
     pub angstrom_addr:     Address,
     pub pool_manager_addr: Address,
+    pub controller_addr:   Address,
+    pub pool_gate_addr:    Address,
     pub state:             Option<Bytes>,
     pub pool_keys:         Vec<PoolKey>
 }
@@ -17,10 +19,12 @@ impl InitialTestnetState {
     pub fn new(
         angstrom_addr: Address,
         pool_manager_addr: Address,
+        controller_addr: Address,
+        pool_gate_addr: Address,
         state: Option<Bytes>,
         pool_keys: Vec<PoolKey>
     ) -> Self {
-        Self { angstrom_addr, state, pool_manager_addr, pool_keys }
+        Self { angstrom_addr, state, pool_manager_addr, controller_addr, pool_gate_addr, pool_keys }
     }
 }
 
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use alloy::{
+    network::TransactionBuilder,
+    providers::{ProviderBuilder, RootProvider},
+    rpc::types::TransactionRequest,
+    transports::http::{reqwest::Client, Http}
+};
+use alloy_primitives::{Address, Bytes};
+use angstrom_types::{mev_boost::MevBoostProvider, primitive::AngstromSigner};
+use testing_tools::mocks::builder::MockBuilder;
+
+fn signed_tx_request(signer: &AngstromSigner) -> TransactionRequest {
+    TransactionRequest::default()
+        .with_to(Address::random())
+        .with_from(signer.address())
+        .with_input(Bytes::from_static(&[1, 2, 3]))
+        .with_nonce(0)
+        .with_gas_limit(100_000)
+        .with_max_fee_per_gas(1_000_000_000)
+        .with_max_priority_fee_per_gas(1_000_000)
+        .with_chain_id(1)
+}
+
+#[tokio::test]
+async fn fans_bundle_out_to_all_builders() {
+    let accepting = MockBuilder::accepting();
+    let rejecting = MockBuilder::rejecting();
+    let (accepting_url, _accepting_handle) = accepting.spawn().await.unwrap();
+    let (rejecting_url, _rejecting_handle) = rejecting.spawn().await.unwrap();
+
+    // the node provider is never hit when sending, any url will do
+    let node_provider: Arc<RootProvider<Http<Client>>> =
+        Arc::new(ProviderBuilder::<_, _, _>::default().on_http(accepting_url.clone()));
+    let provider = MevBoostProvider::new_from_urls(
+        node_provider,
+        &[],
+        &[accepting_url.clone(), rejecting_url.clone()],
+        1
+    );
+
+    let signer = AngstromSigner::random();
+    let submission = provider
+        .sign_and_send(signer.clone(), signed_tx_request(&signer), 101)
+        .await
+        .unwrap();
+
+    assert_eq!(submission.target_block, 101);
+    assert_eq!(submission.builders.len(), 2, "not every builder got the bundle");
+    assert!(submission.any_accepted());
+
+    let rejected = submission.rejected().collect::<Vec<_>>();
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0].endpoint, rejecting_url.to_string());
+
+    let received = accepting.received();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].target_block, 101);
+    assert_eq!(received[0].tx_hashes, vec![submission.tx_hash]);
+    assert!(received[0].reverting_tx_hashes.is_empty(), "bundle allowed to revert");
+    assert!(rejecting.received().is_empty());
+}
+
+#[tokio::test]
+async fn not_accepted_when_every_builder_rejects() {
+    let rejecting = MockBuilder::rejecting();
+    let (rejecting_url, _handle) = rejecting.spawn().await.unwrap();
+
+    let node_provider: Arc<RootProvider<Http<Client>>> =
+        Arc::new(ProviderBuilder::<_, _, _>::default().on_http(rejecting_url.clone()));
+    let provider = MevBoostProvider::new_from_urls(node_provider, &[], &[rejecting_url], 1);
+
+    let signer = AngstromSigner::random();
+    let submission = provider
+        .sign_and_send(signer.clone(), signed_tx_request(&signer), 7)
+        .await
+        .unwrap();
+
+    assert!(!submission.any_accepted());
+    assert_eq!(submission.rejected().count(), 1);
+}
@@ -112,18 +112,41 @@ where
             return Err(PoolError::PoolNotInitialized)
         }
 
-        let liq_ranges = self
+        // walk the initialized ticks upwards, summing their net liquidity to get the
+        // liquidity active in each range relative to the lowest tick we have loaded
+        let mut running_net = 0i128;
+        let net_ranges = self
             .ticks
             .iter()
             .sorted_unstable_by(|a, b| a.0.cmp(b.0))
-            .map_windows(|[(tick_lower, _), (tick_upper, tick_inner_upper)]| {
+            .map_windows(|[(tick_lower, lower_info), (tick_upper, _)]| {
                 // ensure everything is spaced properly
                 assert_eq!((**tick_upper - **tick_lower).abs(), self.tick_spacing);
-                LiqRange::new(**tick_lower, **tick_upper, tick_inner_upper.liquidity_gross).unwrap()
+                running_net += lower_info.liquidity_net;
+                (**tick_lower, **tick_upper, running_net)
             })
             .collect::<Vec<_>>();
 
-        Ok((self.token0, self.token1, PoolSnapshot::new(liq_ranges, self.sqrt_price.into())?))
+        // anchor the relative liquidity on the range our current tick is in, where we
+        // know the active liquidity exactly
+        let offset = net_ranges
+            .iter()
+            .find(|(tick_lower, tick_upper, _)| *tick_lower <= self.tick && self.tick < *tick_upper)
+            .map(|(_, _, net)| self.liquidity as i128 - net)
+            .ok_or(PoolError::CurrentTickNotLoaded(self.tick))?;
+
+        let liq_ranges = net_ranges
+            .into_iter()
+            .map(|(tick_lower, tick_upper, net)| {
+                let liquidity = u128::try_from(net + offset)
+                    .map_err(|_| PoolError::NegativeLiquidity(tick_lower, tick_upper))?;
+                Ok(LiqRange::new(tick_lower, tick_upper, liquidity).unwrap())
+            })
+            .collect::<Result<Vec<_>, PoolError>>()?;
+
+        let snapshot = PoolSnapshot::new(liq_ranges, self.sqrt_price.into())?.with_fee(self.fee)?;
+
+        Ok((self.token0, self.token1, snapshot))
     }
 
     pub async fn initialize<T: Transport + Clone>(
@@ -736,6 +759,10 @@ pub enum PoolError {
     PoolAlreadyInitialized,
     #[error("Pool is not initialized")]
     PoolNotInitialized,
+    #[error("Current tick {0} is outside of the loaded tick ranges")]
+    CurrentTickNotLoaded(i32),
+    #[error("Loaded ticks give the range [{0}, {1}) negative liquidity")]
+    NegativeLiquidity(i32, i32),
     #[error(transparent)]
     SwapSimulationError(#[from] SwapSimulationError),
     #[error(transparent)]
@@ -752,6 +779,7 @@ pub enum PoolError {
 mod tests {
     use std::sync::Once;
 
+    use angstrom_types::matching::uniswap::{Direction, PoolPriceVec, Quantity};
     use tracing_subscriber::{fmt, EnvFilter};
     use uniswap_v3_math::tick_math::get_sqrt_ratio_at_tick;
 
@@ -1019,4 +1047,128 @@ mod tests {
         assert_eq!(token_b, pool.token1);
         assert!(!snapshot.ranges.is_empty());
     }
+
+    /// A pool with overlapping positions across three contiguous ranges and our
+    /// price sitting in the middle one
+    fn setup_liquid_pool(fee: u32) -> EnhancedUniswapPool<MockLoader> {
+        let mut pool = setup_basic_pool();
+        pool.fee = fee;
+        pool.update_position(-120, -60, 1_000_000_000_000_000_000);
+        pool.update_position(-60, 0, 2_000_000_000_000_000_000);
+        pool.update_position(0, 60, 3_000_000_000_000_000_000);
+        pool.update_position(-120, 60, 500_000_000_000_000_000);
+        pool.sqrt_price = get_sqrt_ratio_at_tick(-30).unwrap();
+        pool.tick = -30;
+        pool.liquidity = 2_500_000_000_000_000_000;
+        pool
+    }
+
+    /// Runs the same swap through the pool and through the matching math on the
+    /// pool's snapshot and checks that both agree on the amounts and the end
+    /// price
+    fn assert_snapshot_swap_matches_sim(
+        pool: &EnhancedUniswapPool<MockLoader>,
+        token_in: Address,
+        amount_specified: I256
+    ) {
+        let sim = pool
+            ._simulate_swap(token_in, amount_specified, None)
+            .unwrap();
+
+        let zero_for_one = token_in == pool.token0;
+        let q = amount_specified.unsigned_abs().to::<u128>();
+        let (direction, quantity) = match (zero_for_one, amount_specified.is_positive()) {
+            (true, true) => (Direction::SellingT0, Quantity::Token0(q)),
+            (true, false) => (Direction::SellingT0, Quantity::Token1(q)),
+            (false, true) => (Direction::BuyingT0, Quantity::Token1(q)),
+            (false, false) => (Direction::BuyingT0, Quantity::Token0(q))
+        };
+
+        let (_, _, snapshot) = pool.fetch_pool_snapshot().unwrap();
+        let vec = PoolPriceVec::from_swap(snapshot.current_price(), direction, quantity).unwrap();
+
+        assert_eq!(U256::from(vec.d_t0), sim.amount0.unsigned_abs(), "token0 amounts differ");
+        assert_eq!(U256::from(vec.d_t1), sim.amount1.unsigned_abs(), "token1 amounts differ");
+        assert_eq!(
+            U256::from(vec.end_bound.as_sqrtpricex96()),
+            sim.sqrt_price_x_96,
+            "end prices differ"
+        );
+    }
+
+    #[test]
+    fn test_snapshot_swap_matches_simulate_swap() {
+        setup_tracing();
+        for fee in [0, 500, 3000] {
+            let pool = setup_liquid_pool(fee);
+            let token0 = pool.token0;
+            let token1 = pool.token1;
+
+            // exact in and exact out each way, all crossing an initialized tick
+            assert_snapshot_swap_matches_sim(
+                &pool,
+                token0,
+                I256::try_from(5_000_000_000_000_000i64).unwrap()
+            );
+            assert_snapshot_swap_matches_sim(
+                &pool,
+                token0,
+                I256::try_from(-5_000_000_000_000_000i64).unwrap()
+            );
+            assert_snapshot_swap_matches_sim(
+                &pool,
+                token1,
+                I256::try_from(10_000_000_000_000_000i64).unwrap()
+            );
+            assert_snapshot_swap_matches_sim(
+                &pool,
+                token1,
+                I256::try_from(-5_000_000_000_000_000i64).unwrap()
+            );
+            // and a small swap that stays inside our current range
+            assert_snapshot_swap_matches_sim(
+                &pool,
+                token0,
+                I256::try_from(1_000_000_000_000i64).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_fetch_pool_snapshot_carries_fee() {
+        let pool = setup_liquid_pool(3000);
+        let (_, _, snapshot) = pool.fetch_pool_snapshot().unwrap();
+        assert_eq!(snapshot.fee(), 3000);
+
+        // every range holds the liquidity that is active while the price is in it
+        let liquidity = snapshot
+            .ranges()
+            .map(|r| (r.lower_tick(), r.liquidity()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            liquidity,
+            vec![
+                (-120, 1_500_000_000_000_000_000),
+                (-60, 2_500_000_000_000_000_000),
+                (0, 3_500_000_000_000_000_000)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fetch_pool_snapshot_rejects_invalid_state() {
+        // the dynamic fee flag of a hooked pool's key is not a fee
+        let pool = setup_liquid_pool(0x800000);
+        assert!(pool.fetch_pool_snapshot().is_err());
+        let pool = setup_liquid_pool(1_000_000);
+        assert!(pool.fetch_pool_snapshot().is_err());
+
+        let mut pool = setup_liquid_pool(3000);
+        pool.tick = 90;
+        assert!(matches!(pool.fetch_pool_snapshot(), Err(PoolError::CurrentTickNotLoaded(90))));
+
+        let mut pool = setup_liquid_pool(3000);
+        pool.liquidity = 0;
+        assert!(matches!(pool.fetch_pool_snapshot(), Err(PoolError::NegativeLiquidity(..))));
+    }
 }
@@ -1,10 +1,13 @@
 use std::{collections::HashMap, future::Future, sync::Arc};
 
 use alloy::{
-    primitives::{aliases::I24, Address, BlockNumber, U256},
+    primitives::{
+        aliases::{I24, U24},
+        keccak256, Address, BlockNumber, U256
+    },
     providers::Provider,
     sol,
-    sol_types::{SolEvent, SolType},
+    sol_types::{SolEvent, SolType, SolValue},
     transports::Transport
 };
 use alloy_primitives::{Log, B256, I256};
@@ -22,6 +25,11 @@ use super::loaders::{
 };
 use crate::uniswap::{i128_to_i256, i256_to_i128, pool::PoolError};
 
+/// storage slot of the pool manager's mapping from pool id to pool state
+const POOLS_SLOT: u64 = 6;
+/// bit offset of the LP fee in a pool's packed slot0
+const LP_FEE_OFFSET: usize = 208;
+
 sol! {
     #[derive(Debug)]
     struct PoolData {
@@ -283,6 +291,25 @@ impl DataLoader<AngstromPoolId> {
     ) -> Self {
         Self { address, pool_registry: Some(registry), pool_manager: Some(pool_manager) }
     }
+
+    /// The LP fee the pool manager charges in this pool, read out of the
+    /// pool's slot0. The pool key of a hooked pool only holds the dynamic fee
+    /// flag
+    async fn load_lp_fee<P: Provider<T>, T: Transport + Clone>(
+        &self,
+        block_number: Option<BlockNumber>,
+        provider: Arc<P>
+    ) -> Result<U24, PoolError> {
+        let slot0_slot = keccak256((self.address(), U256::from(POOLS_SLOT)).abi_encode());
+        let request = provider.get_storage_at(self.pool_manager(), slot0_slot.into());
+        let slot0 = match block_number {
+            Some(number) => request.block_id(number.into()).await,
+            None => request.await
+        }
+        .map_err(alloy::contract::Error::from)?;
+
+        Ok(U24::from_limbs([(slot0 >> LP_FEE_OFFSET).to::<u64>() & 0xffffff]))
+    }
 }
 
 impl PoolDataLoader<AngstromPoolId> for DataLoader<AngstromPoolId> {
@@ -316,7 +343,7 @@ impl PoolDataLoader<AngstromPoolId> for DataLoader<AngstromPoolId> {
         tracing::trace!(?block_number, ?pool_key, "loading pool data");
 
         let deployer = GetUniswapV4PoolData::deploy_builder(
-            provider,
+            provider.clone(),
             self.address(),
             self.pool_manager(),
             pool_key.currency0,
@@ -329,18 +356,19 @@ impl PoolDataLoader<AngstromPoolId> for DataLoader<AngstromPoolId> {
         };
 
         let pool_data_v4 = PoolDataV4::abi_decode(&data, true)?;
+        let fee = self.load_lp_fee(block_number, provider).await?;
 
         Ok(PoolData {
-            tokenA:         pool_key.currency0,
+            tokenA: pool_key.currency0,
             tokenADecimals: pool_data_v4.token0Decimals,
-            tokenB:         pool_key.currency1,
+            tokenB: pool_key.currency1,
             tokenBDecimals: pool_data_v4.token1Decimals,
-            liquidity:      pool_data_v4.liquidity,
-            sqrtPrice:      pool_data_v4.sqrtPrice,
-            tick:           pool_data_v4.tick,
-            tickSpacing:    pool_key.tickSpacing,
-            fee:            pool_key.fee,
-            liquidityNet:   pool_data_v4.liquidityNet
+            liquidity: pool_data_v4.liquidity,
+            sqrtPrice: pool_data_v4.sqrtPrice,
+            tick: pool_data_v4.tick,
+            tickSpacing: pool_key.tickSpacing,
+            fee,
+            liquidityNet: pool_data_v4.liquidityNet
         })
     }
 
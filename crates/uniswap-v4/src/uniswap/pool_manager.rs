@@ -3,8 +3,7 @@ use std::{
     fmt::Debug,
     future::Future,
     hash::Hash,
-    ops::Deref,
-    sync::{Arc, RwLock, RwLockReadGuard},
+    sync::{Arc, RwLock},
     task::Poll
 };
 
@@ -16,13 +15,15 @@ use alloy::{
 use alloy_primitives::Log;
 use angstrom_types::{
     block_sync::BlockSyncConsumer,
-    contract_payloads::tob::ToBOutcome,
+    contract_payloads::tob::{select_tob_winner, ToBOutcome},
     matching::uniswap::PoolSnapshot,
     primitive::PoolId,
-    sol_bindings::{grouped_orders::OrderWithStorageData, rpc_orders::TopOfBlockOrder}
+    sol_bindings::{
+        grouped_orders::OrderWithStorageData, rpc_orders::TopOfBlockOrder, RawPoolOrder
+    }
 };
 use arraydeque::ArrayDeque;
-use futures::FutureExt;
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt};
 use futures_util::{stream::BoxStream, StreamExt};
 use thiserror::Error;
 use tokio::sync::Notify;
@@ -49,8 +50,20 @@ pub struct TickRangeToLoad<A = PoolId> {
     pub tick_count: u16
 }
 
-type PoolMap<Loader, A> = Arc<HashMap<A, Arc<RwLock<EnhancedUniswapPool<Loader, A>>>>>;
+type PoolMap<Loader, A> = Arc<RwLock<HashMap<A, SyncedUniswapPool<A, Loader>>>>;
+
+/// How many times a new pool is loaded before giving up on it, when the chain
+/// keeps moving on while it loads
+const MAX_POOL_LOAD_ATTEMPTS: u8 = 5;
+
+/// a pool that was configured after startup along with the block it was loaded
+/// at and how many times it has been loaded
+type LoadedPool<Loader, A> =
+    (A, BlockNumber, u8, Result<EnhancedUniswapPool<Loader, A>, PoolError>);
 
+/// The pools kept in sync by the [`UniswapPoolManager`], keyed by their public
+/// pool id. All clones share the same map so pools that are added or removed
+/// at runtime are seen everywhere.
 #[derive(Clone)]
 pub struct SyncedUniswapPools<A = PoolId, Loader = DataLoader<A>>
 where
@@ -60,14 +73,40 @@ where
     tx:    tokio::sync::mpsc::Sender<(TickRangeToLoad<A>, Arc<Notify>)>
 }
 
-impl<A, Loader> Deref for SyncedUniswapPools<A, Loader>
+impl<A, Loader> SyncedUniswapPools<A, Loader>
 where
-    Loader: PoolDataLoader<A>
+    Loader: PoolDataLoader<A>,
+    A: Hash + Eq + Copy
 {
-    type Target = PoolMap<Loader, A>;
+    pub fn get(&self, pool_id: &A) -> Option<SyncedUniswapPool<A, Loader>> {
+        self.pools.read().unwrap().get(pool_id).cloned()
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.pools
+    pub fn contains_key(&self, pool_id: &A) -> bool {
+        self.pools.read().unwrap().contains_key(pool_id)
+    }
+
+    pub fn pool_ids(&self) -> Vec<A> {
+        self.pools.read().unwrap().keys().copied().collect()
+    }
+
+    /// iterates over the pools that are synced at the time of the call
+    pub fn iter(&self) -> impl Iterator<Item = (A, SyncedUniswapPool<A, Loader>)> {
+        self.pools
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(pool_id, pool)| (*pool_id, pool.clone()))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn insert_pool(&self, pool_id: A, pool: SyncedUniswapPool<A, Loader>) {
+        self.pools.write().unwrap().insert(pool_id, pool);
+    }
+
+    fn remove_pool(&self, pool_id: &A) -> Option<SyncedUniswapPool<A, Loader>> {
+        self.pools.write().unwrap().remove(pool_id)
     }
 }
 
@@ -82,10 +121,10 @@ where
     A: Debug + Hash + PartialEq + Eq + Copy + Default
 {
     pub fn new(
-        pools: PoolMap<Loader, A>,
+        pools: HashMap<A, SyncedUniswapPool<A, Loader>>,
         tx: tokio::sync::mpsc::Sender<(TickRangeToLoad<A>, Arc<Notify>)>
     ) -> Self {
-        Self { pools, tx }
+        Self { pools: Arc::new(RwLock::new(pools)), tx }
     }
 
     /// Will calculate the tob rewards that this order specifies. More Notably,
@@ -99,10 +138,12 @@ where
     ) -> eyre::Result<ToBOutcome> {
         tracing::info!("calculate_rewards function");
 
+        let Some(pool) = self.get(&pool_id) else { eyre::bail!("pool {pool_id:?} is not synced") };
+
         let mut cnt = ATTEMPTS;
         loop {
             let market_snapshot = {
-                let pool = self.pools.get(&pool_id).unwrap().read().unwrap();
+                let pool = pool.read().unwrap();
                 pool.fetch_pool_snapshot().map(|v| v.2).unwrap()
             };
 
@@ -113,7 +154,7 @@ where
                 let not = Arc::new(Notify::new());
                 // scope for awaits
                 let start_tick = {
-                    let pool = self.pools.get(&pool_id).unwrap().read().unwrap();
+                    let pool = pool.read().unwrap();
                     if zfo {
                         pool.fetch_lowest_tick()
                     } else {
@@ -150,6 +191,53 @@ where
     }
 }
 
+impl<Loader> SyncedUniswapPools<PoolId, Loader>
+where
+    Loader: PoolDataLoader<PoolId> + Default
+{
+    /// Runs the sealed-bid top of block auction of every pool, returning the
+    /// winning searcher order of each one. Bids that can't be executed against
+    /// the current state of their pool are dropped.
+    pub async fn tob_auction(
+        &self,
+        searcher: Vec<OrderWithStorageData<TopOfBlockOrder>>
+    ) -> Vec<OrderWithStorageData<TopOfBlockOrder>> {
+        let mut bids: HashMap<PoolId, Vec<_>> = HashMap::new();
+        for order in searcher {
+            if !self.contains_key(&order.pool_id) {
+                tracing::debug!(pool_id=?order.pool_id, "top of block bid for an unknown pool");
+                continue
+            }
+
+            match self.calculate_rewards(order.pool_id, &order).await {
+                Ok(outcome) => bids
+                    .entry(order.pool_id)
+                    .or_default()
+                    .push((order, outcome)),
+                Err(e) => {
+                    tracing::debug!(%e, order_hash=?order.order_hash(), "dropping top of block bid")
+                }
+            }
+        }
+
+        bids.into_values().filter_map(select_tob_winner).collect()
+    }
+}
+
+/// A change to the set of pools kept in sync, for pools that are configured or
+/// removed on the angstrom contract while the node is running
+pub enum PoolUpdate<Loader: PoolDataLoader<A>, A> {
+    /// `pool_id` is the public id of the pool, `pool` still has to be
+    /// initialized
+    NewPool {
+        pool_id: A,
+        pool:    EnhancedUniswapPool<Loader, A>
+    },
+    RemovedPool {
+        pool_id: A
+    }
+}
+
 pub struct UniswapPoolManager<P, BlockSync, Loader: PoolDataLoader<A>, A = Address>
 where
     A: Debug + Copy
@@ -162,7 +250,10 @@ where
     provider:            Arc<P>,
     block_sync:          BlockSync,
     block_stream:        BoxStream<'static, Option<PoolMangerBlocks>>,
-    rx:                  tokio::sync::mpsc::Receiver<(TickRangeToLoad<A>, Arc<Notify>)>
+    rx:                  tokio::sync::mpsc::Receiver<(TickRangeToLoad<A>, Arc<Notify>)>,
+    pool_updates:        Option<BoxStream<'static, PoolUpdate<Loader, A>>>,
+    /// newly configured pools that are still loading their ticks
+    pending_pools:       FuturesUnordered<BoxFuture<'static, LoadedPool<Loader, A>>>
 }
 
 impl<P, BlockSync, Loader, A> UniswapPoolManager<P, BlockSync, Loader, A>
@@ -183,7 +274,11 @@ where
 
         let rwlock_pools = pools
             .into_iter()
-            .map(|pool| (pool.address(), Arc::new(RwLock::new(pool))))
+            .map(|pool| {
+                let pool_id = Self::public_id(&conversion_map, &pool.address())
+                    .expect("pool is missing from the conversion map");
+                (pool_id, Arc::new(RwLock::new(pool)))
+            })
             .collect();
 
         let block_stream = <P as Clone>::clone(&provider);
@@ -192,69 +287,58 @@ where
 
         Self {
             conversion_map,
-            pools: SyncedUniswapPools::new(Arc::new(rwlock_pools), tx),
+            pools: SyncedUniswapPools::new(rwlock_pools, tx),
             latest_synced_block,
             state_change_cache: Arc::new(RwLock::new(HashMap::new())),
             block_stream,
             provider,
             block_sync,
-            rx
+            rx,
+            pool_updates: None,
+            pending_pools: FuturesUnordered::new()
         }
     }
 
+    /// Lets pools be added and removed while the manager is running
+    pub fn with_pool_updates(
+        mut self,
+        pool_updates: BoxStream<'static, PoolUpdate<Loader, A>>
+    ) -> Self {
+        self.pool_updates = Some(pool_updates);
+        self
+    }
+
     pub fn fetch_pool_snapshots(&self) -> HashMap<A, PoolSnapshot> {
         self.pools
             .iter()
             .filter_map(|(key, pool)| {
-                // gotta
-                Some((
-                    self.convert_to_pub_id(key),
-                    pool.read().unwrap().fetch_pool_snapshot().ok()?.2
-                ))
+                Some((key, pool.read().unwrap().fetch_pool_snapshot().ok()?.2))
             })
             .collect()
     }
 
     pub fn pool_addresses(&self) -> impl Iterator<Item = A> + '_ {
-        self.pools.keys().map(|k| self.convert_to_pub_id(k))
+        self.pools.pool_ids().into_iter()
     }
 
     pub fn pools(&self) -> SyncedUniswapPools<A, Loader> {
-        let mut c = self.pools.clone();
-        c.pools = Arc::new(
-            c.pools
-                .iter()
-                .map(|(k, v)| (self.convert_to_pub_id(k), v.clone()))
-                .collect()
-        );
-
-        c
+        self.pools.clone()
     }
 
-    fn convert_to_pub_id(&self, key: &A) -> A {
-        self.conversion_map
+    /// maps the dynamic fee pool id that uniswap logs are emitted with back to
+    /// the public pool id
+    fn public_id(conversion_map: &HashMap<A, A>, key: &A) -> Option<A> {
+        conversion_map
             .iter()
-            .find_map(|(r, m)| {
-                if m == key {
-                    return Some(r)
-                }
-                None
-            })
-            .copied()
-            .unwrap()
+            .find_map(|(r, m)| (m == key).then_some(*r))
     }
 
-    pub fn pool(&self, address: &A) -> Option<RwLockReadGuard<'_, EnhancedUniswapPool<Loader, A>>> {
-        let addr = self.conversion_map.get(address)?;
-        let pool = self.pools.get(addr)?;
-        Some(pool.read().unwrap())
+    pub fn pool(&self, address: &A) -> Option<SyncedUniswapPool<A, Loader>> {
+        self.pools.get(address)
     }
 
     pub fn filter(&self) -> Filter {
-        // it should crash given that no pools makes no sense
-        let pool = self.pools.values().next().unwrap();
-        let pool = pool.read().unwrap();
-        Filter::new().event_signature(pool.event_signatures())
+        Filter::new().event_signature(Loader::event_signatures())
     }
 
     /// Unwinds the state changes cache for every block from the most recent
@@ -361,7 +445,7 @@ where
         if is_reorg {
             // scope for locks
             let mut state_change_cache = self.state_change_cache.write().unwrap();
-            for pool in self.pools.values() {
+            for (_, pool) in self.pools.iter() {
                 let mut pool_guard = pool.write().unwrap();
                 Self::unwind_state_changes(
                     &mut pool_guard,
@@ -379,7 +463,9 @@ where
                 continue
             }
 
-            let Some(pool) = self.pools.get(&addr) else {
+            let Some(pool) = Self::public_id(&self.conversion_map, &addr)
+                .and_then(|pool_id| self.pools.get(&pool_id))
+            else {
                 continue;
             };
 
@@ -405,6 +491,99 @@ where
         }
     }
 
+    fn on_pool_update(&mut self, update: PoolUpdate<Loader, A>) {
+        match update {
+            PoolUpdate::NewPool { pool_id, pool } => {
+                tracing::info!(?pool_id, block = self.latest_synced_block, "loading new pool");
+                self.conversion_map.insert(pool_id, pool.address());
+                self.load_pool(pool_id, pool, 1);
+            }
+            PoolUpdate::RemovedPool { pool_id } => {
+                tracing::info!(?pool_id, "removing pool");
+                if let Some(private_id) = self.conversion_map.remove(&pool_id) {
+                    self.state_change_cache.write().unwrap().remove(&private_id);
+                }
+                self.pools.remove_pool(&pool_id);
+            }
+        }
+    }
+
+    /// loads the pool's state and ticks at the block we are synced to
+    fn load_pool(&mut self, pool_id: A, mut pool: EnhancedUniswapPool<Loader, A>, attempt: u8) {
+        let provider = self.provider.clone();
+        let block_number = self.latest_synced_block;
+        self.pending_pools.push(Box::pin(async move {
+            let res = pool
+                .initialize(Some(block_number), provider.provider())
+                .await;
+            (pool_id, block_number, attempt, res.map(|_| pool))
+        }));
+    }
+
+    /// starts tracking a newly loaded pool if it was loaded at the block we are
+    /// synced to, otherwise loads it again, up to [`MAX_POOL_LOAD_ATTEMPTS`]
+    /// times.
+    fn on_pool_loaded(
+        &mut self,
+        pool_id: A,
+        loaded_at: BlockNumber,
+        attempt: u8,
+        pool: Result<EnhancedUniswapPool<Loader, A>, PoolError>
+    ) {
+        let mut pool = match pool {
+            Ok(pool) => pool,
+            Err(e) => {
+                tracing::error!(?pool_id, %e, "failed to load new pool");
+                self.conversion_map.remove(&pool_id);
+                return
+            }
+        };
+        // the pool was removed again while it was loading
+        if self.conversion_map.get(&pool_id) != Some(&pool.address()) {
+            return
+        }
+
+        // blocks went by (or got reorged out) while the pool was loading. The
+        // provider only serves the logs of the block we are synced to, so the
+        // missed ones can't be replayed and the pool is loaded again instead
+        if loaded_at != self.latest_synced_block {
+            if attempt >= MAX_POOL_LOAD_ATTEMPTS {
+                tracing::error!(
+                    ?pool_id,
+                    attempt,
+                    "new pool kept falling behind the chain while loading, giving up"
+                );
+                self.conversion_map.remove(&pool_id);
+                return
+            }
+            tracing::info!(
+                ?pool_id,
+                loaded_at,
+                block = self.latest_synced_block,
+                attempt,
+                "new pool loaded at a stale block, reloading"
+            );
+            self.load_pool(pool_id, pool, attempt + 1);
+            return
+        }
+
+        let mut state_change_cache = self.state_change_cache.write().unwrap();
+        if let Err(e) = Self::handle_state_changes_from_logs(
+            &mut pool,
+            &mut state_change_cache,
+            vec![],
+            self.latest_synced_block
+        ) {
+            tracing::error!(?pool_id, %e, "failed to sync new pool");
+            self.conversion_map.remove(&pool_id);
+            return
+        }
+        drop(state_change_cache);
+
+        tracing::info!(?pool_id, "new pool is synced");
+        self.pools.insert_pool(pool_id, Arc::new(RwLock::new(pool)));
+    }
+
     #[allow(clippy::await_holding_lock)]
     async fn load_more_ticks(
         notifier: Arc<Notify>,
@@ -413,7 +592,12 @@ where
         tick_req: TickRangeToLoad<A>
     ) {
         let node_provider = provider.provider();
-        let mut pool = pools.get(&tick_req.pool_id).unwrap().write().unwrap();
+        let Some(pool) = pools.get(&tick_req.pool_id) else {
+            // the pool was removed while the request was in flight
+            notifier.notify_one();
+            return
+        };
+        let mut pool = pool.write().unwrap();
 
         // given we force this to resolve, should'nt be problematic
         let ticks = pool
@@ -444,6 +628,18 @@ where
         while let Poll::Ready(Some(Some(block_info))) = self.block_stream.poll_next_unpin(cx) {
             self.handle_new_block_info(block_info);
         }
+        while let Some(Poll::Ready(Some(update))) = self
+            .pool_updates
+            .as_mut()
+            .map(|updates| updates.poll_next_unpin(cx))
+        {
+            self.on_pool_update(update);
+        }
+        while let Poll::Ready(Some((pool_id, loaded_at, attempt, pool))) =
+            self.pending_pools.poll_next_unpin(cx)
+        {
+            self.on_pool_loaded(pool_id, loaded_at, attempt, pool);
+        }
         while let Poll::Ready(Some((ticks, not))) = self.rx.poll_recv(cx) {
             // hacky for now but only way to avoid lock problems
             let pools = self.pools.clone();
@@ -638,6 +834,40 @@ mod annoying_tests {
         assert!(cache.contains_key(&pool_id));
     }
 
+    #[tokio::test]
+    async fn test_pool_loaded_behind_tip_is_reloaded() {
+        let provider = Arc::new(MockProvider::new().await);
+        let mut manager = UniswapPoolManager::<_, _, DataLoader<PoolId>, PoolId>::new(
+            vec![],
+            HashMap::new(),
+            100,
+            provider.clone(),
+            MockBlockSync
+        );
+
+        let pool = EnhancedUniswapPool::<DataLoader<PoolId>, PoolId>::default();
+        let pool_id = PoolId::random();
+        manager.conversion_map.insert(pool_id, pool.address());
+
+        // loading took two blocks, the logs of 99 and 100 are gone by now
+        manager.on_pool_loaded(pool_id, 98, 1, Ok(pool.clone()));
+        assert!(!manager.pools.contains_key(&pool_id), "pool tracked from a stale block");
+        assert_eq!(manager.pending_pools.len(), 1, "pool wasn't loaded again");
+        assert_eq!(manager.conversion_map.get(&pool_id), Some(&pool.address()));
+
+        // loaded at the block we are synced to
+        manager.on_pool_loaded(pool_id, 100, 2, Ok(pool.clone()));
+        assert!(manager.pools.contains_key(&pool_id));
+
+        // a pool that never catches up is dropped instead of reloading forever
+        let other_id = PoolId::random();
+        manager.conversion_map.insert(other_id, pool.address());
+        manager.on_pool_loaded(other_id, 99, MAX_POOL_LOAD_ATTEMPTS, Ok(pool));
+        assert!(!manager.pools.contains_key(&other_id));
+        assert_eq!(manager.pending_pools.len(), 1, "pool was loaded again");
+        assert!(!manager.conversion_map.contains_key(&other_id));
+    }
+
     /// NOTE: when reorgs occur, lets say we reorg back 2 blocks from 100 to 98,
     /// the system will roll back to block 97.
     #[tokio::test]
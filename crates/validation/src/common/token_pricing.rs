@@ -8,13 +8,36 @@ use alloy::{
     providers::Provider,
     transports::Transport
 };
-use angstrom_types::{pair_with_price::PairsWithPrice, primitive::PoolId, sol_bindings::Ray};
-use futures::StreamExt;
+use angstrom_eth::manager::EthEvent;
+use angstrom_types::{
+    pair_with_price::PairsWithPrice,
+    primitive::{PoolId, UniswapPoolRegistry},
+    sol_bindings::Ray
+};
+use futures::{Stream, StreamExt};
 use tracing::warn;
-use uniswap_v4::uniswap::{pool_data_loader::PoolDataLoader, pool_manager::SyncedUniswapPools};
+use uniswap_v4::uniswap::{
+    pool_data_loader::{DataLoader, PoolDataLoader},
+    pool_manager::SyncedUniswapPools
+};
+
+use crate::validator::{ValidationClient, ValidationRequest};
 
 const BLOCKS_TO_AVG_PRICE: u64 = 5;
 pub const WETH_ADDRESS: Address = address!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
+pub const SEPOLIA_WETH_ADDRESS: Address = address!("fFf9976782d46CC05630D1f6eBAb18b2324d6B14");
+pub const HOLESKY_WETH_ADDRESS: Address = address!("94373a4919B3240D86eA41593D5eBa789FEF3848");
+
+/// The canonical wrapped native token of the chains we know about. Anything
+/// else has to be configured explicitly
+pub fn wrapped_native_for_chain(chain_id: u64) -> Option<Address> {
+    match chain_id {
+        1 => Some(WETH_ADDRESS),
+        11155111 => Some(SEPOLIA_WETH_ADDRESS),
+        17000 => Some(HOLESKY_WETH_ADDRESS),
+        _ => None
+    }
+}
 
 // crazy that this is a thing
 #[allow(clippy::too_long_first_doc_paragraph)]
@@ -29,7 +52,9 @@ pub struct TokenPriceGenerator {
     prev_prices:         HashMap<PoolId, VecDeque<PairsWithPrice>>,
     pair_to_pool:        HashMap<(Address, Address), PoolId>,
     cur_block:           u64,
-    blocks_to_avg_price: u64
+    blocks_to_avg_price: u64,
+    /// the token gas is priced in, wrapped so that it can sit in a pool
+    wrapped_native:      Address
 }
 
 impl TokenPriceGenerator {
@@ -39,7 +64,8 @@ impl TokenPriceGenerator {
         provider: Arc<P>,
         current_block: u64,
         uni: SyncedUniswapPools<PoolId, Loader>,
-        blocks_to_avg_price_override: Option<u64>
+        blocks_to_avg_price_override: Option<u64>,
+        wrapped_native: Address
     ) -> eyre::Result<Self>
     where
         Loader: PoolDataLoader<PoolId> + Default + Clone + Send + Sync + 'static
@@ -47,7 +73,7 @@ impl TokenPriceGenerator {
         let mut pair_to_pool = HashMap::default();
         for (key, pool) in uni.iter() {
             let pool = pool.read().unwrap();
-            pair_to_pool.insert((pool.token0, pool.token1), *key);
+            pair_to_pool.insert((pool.token0, pool.token1), key);
         }
 
         let blocks_to_avg_price = blocks_to_avg_price_override.unwrap_or(BLOCKS_TO_AVG_PRICE);
@@ -58,7 +84,6 @@ impl TokenPriceGenerator {
                 let provider = provider.clone();
 
                 async move {
-                    let mut queue = VecDeque::new();
                     // scoping
                     let data_loader = {
                         let pool_read = pool.read().unwrap();
@@ -67,27 +92,17 @@ impl TokenPriceGenerator {
                         data_loader
                     };
 
-                    for block_number in
-                        current_block.saturating_sub(blocks_to_avg_price)..current_block
-                    {
-                        tracing::debug!(block_number, current_block, ?pool_key, "loading pool");
-                        let pool_data = data_loader
-                            .load_pool_data(Some(block_number), provider.clone())
-                            .await
-                            .expect("failed to load historical price for token price conversion");
-
-                        // price as ray
-                        let price = pool_data.get_raw_price();
-
-                        queue.push_back(PairsWithPrice {
-                            token0:         pool_data.tokenA,
-                            token1:         pool_data.tokenB,
-                            block_num:      block_number,
-                            price_1_over_0: price
-                        });
-                    }
-
-                    (*pool_key, queue)
+                    tracing::debug!(current_block, ?pool_key, "loading pool");
+                    let queue = Self::load_price_history(
+                        provider,
+                        data_loader,
+                        current_block,
+                        blocks_to_avg_price
+                    )
+                    .await
+                    .expect("failed to load historical price for token price conversion");
+
+                    (pool_key, queue)
                 }
             })
             .fold(HashMap::default(), |mut acc, x| async {
@@ -97,7 +112,61 @@ impl TokenPriceGenerator {
             })
             .await;
 
-        Ok(Self { prev_prices: pools, cur_block: current_block, pair_to_pool, blocks_to_avg_price })
+        Ok(Self {
+            prev_prices: pools,
+            cur_block: current_block,
+            pair_to_pool,
+            blocks_to_avg_price,
+            wrapped_native
+        })
+    }
+
+    /// loads the price of the pool for each of the blocks we average over
+    /// before `current_block`
+    pub async fn load_price_history<P: Provider<T>, T: Transport + Clone, Loader>(
+        provider: Arc<P>,
+        data_loader: Loader,
+        current_block: u64,
+        blocks_to_avg_price: u64
+    ) -> eyre::Result<VecDeque<PairsWithPrice>>
+    where
+        Loader: PoolDataLoader<PoolId>
+    {
+        let mut queue = VecDeque::new();
+        for block_number in current_block.saturating_sub(blocks_to_avg_price)..current_block {
+            let pool_data = data_loader
+                .load_pool_data(Some(block_number), provider.clone())
+                .await?;
+
+            // price as ray
+            let price = pool_data.get_raw_price();
+
+            queue.push_back(PairsWithPrice {
+                token0:         pool_data.tokenA,
+                token1:         pool_data.tokenB,
+                block_num:      block_number,
+                price_1_over_0: price
+            });
+        }
+
+        Ok(queue)
+    }
+
+    /// starts converting gas for a pool that was configured after startup
+    pub fn add_pool(
+        &mut self,
+        pool_id: PoolId,
+        token0: Address,
+        token1: Address,
+        prices: VecDeque<PairsWithPrice>
+    ) {
+        self.pair_to_pool.insert((token0, token1), pool_id);
+        self.prev_prices.insert(pool_id, prices);
+    }
+
+    pub fn remove_pool(&mut self, pool_id: PoolId) {
+        self.pair_to_pool.retain(|_, id| *id != pool_id);
+        self.prev_prices.remove(&pool_id);
     }
 
     pub fn generate_lookup_map(&self) -> HashMap<(Address, Address), Ray> {
@@ -120,10 +189,15 @@ impl TokenPriceGenerator {
             // make sure we aren't replaying
             assert!(pool_update.block_num == self.cur_block + 1);
 
-            let pool_key = self
+            // pools configured after startup only get updates once their history
+            // has been seeded
+            let Some(pool_key) = self
                 .pair_to_pool
                 .get(&(pool_update.token0, pool_update.token1))
-                .expect("got pool update that we don't have stored");
+            else {
+                tracing::debug!(?pool_update, "got price update for a pool that isn't tracked yet");
+                continue
+            };
             let prev_prices = self
                 .prev_prices
                 .get_mut(pool_key)
@@ -138,12 +212,12 @@ impl TokenPriceGenerator {
     /// the previous prices are stored in RAY (1e27).
     /// we take this price. then
     pub fn get_eth_conversion_price(&self, token_0: Address, token_1: Address) -> Option<Ray> {
-        if token_0 == WETH_ADDRESS {
+        if token_0 == self.wrapped_native {
             return Some(Ray::scale_to_ray(U256::from(1)))
         }
         // should only be called if token_1 is weth or needs multi-hop as otherwise
         // conversion factor will be 1-1
-        if token_1 == WETH_ADDRESS {
+        if token_1 == self.wrapped_native {
             // if so, just pull the price
             let pool_key = self
                 .pair_to_pool
@@ -170,16 +244,16 @@ impl TokenPriceGenerator {
         }
 
         // need to pass through a pair.
-        let (first_flip, token_0_hop1, token_1_hop1) = if token_0 < WETH_ADDRESS {
-            (false, token_0, WETH_ADDRESS)
+        let (first_flip, token_0_hop1, token_1_hop1) = if token_0 < self.wrapped_native {
+            (false, token_0, self.wrapped_native)
         } else {
-            (true, WETH_ADDRESS, token_0)
+            (true, self.wrapped_native, token_0)
         };
 
-        let (second_flip, token_0_hop2, token_1_hop2) = if token_1 < WETH_ADDRESS {
-            (false, token_1, WETH_ADDRESS)
+        let (second_flip, token_0_hop2, token_1_hop2) = if token_1 < self.wrapped_native {
+            (false, token_1, self.wrapped_native)
         } else {
-            (true, WETH_ADDRESS, token_1)
+            (true, self.wrapped_native, token_1)
         };
 
         // check token_0 first for a weth pair. otherwise, check token_1.
@@ -261,6 +335,65 @@ impl TokenPriceGenerator {
     }
 }
 
+/// Seeds the gas conversion prices of pools that are configured on the angstrom
+/// contract while the node is running and hands them to the validator, which
+/// also stops pricing the pools that get removed.
+pub async fn sync_configured_pool_prices<P: Provider<T>, T: Transport + Clone>(
+    provider: Arc<P>,
+    pool_manager_address: Address,
+    blocks_to_avg_price_override: Option<u64>,
+    mut pool_events: impl Stream<Item = EthEvent> + Unpin,
+    validator: ValidationClient
+) {
+    let blocks_to_avg_price = blocks_to_avg_price_override.unwrap_or(BLOCKS_TO_AVG_PRICE);
+
+    while let Some(event) = pool_events.next().await {
+        match event {
+            EthEvent::NewPool { pool } => {
+                let (token0, token1) = (pool.currency0, pool.currency1);
+                let mut registry = UniswapPoolRegistry::default();
+                let pool_id = registry.add_pool(pool);
+                let internal = *registry.conversion_map.get(&pool_id).unwrap();
+                let data_loader =
+                    DataLoader::new_with_registry(internal, registry, pool_manager_address);
+
+                let prices = match provider.get_block_number().await {
+                    Ok(current_block) => {
+                        TokenPriceGenerator::load_price_history(
+                            provider.clone(),
+                            data_loader,
+                            current_block,
+                            blocks_to_avg_price
+                        )
+                        .await
+                    }
+                    Err(e) => Err(e.into())
+                };
+                let prices = match prices {
+                    Ok(prices) => prices,
+                    Err(e) => {
+                        tracing::error!(?pool_id, %e, "failed to seed prices of new pool");
+                        continue
+                    }
+                };
+
+                let _ = validator.0.send(ValidationRequest::NewPool {
+                    pool_id,
+                    token0,
+                    token1,
+                    prices
+                });
+            }
+            EthEvent::RemovedPool { pool } => {
+                let _ = validator
+                    .0
+                    .send(ValidationRequest::RemovedPool { pool_id: PoolId::from(pool) });
+            }
+            _ => {}
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod test {
     use std::collections::{HashMap, VecDeque};
@@ -354,7 +487,8 @@ pub mod test {
             cur_block:           0,
             prev_prices:         prices,
             pair_to_pool:        pairs_to_key,
-            blocks_to_avg_price: BLOCKS_TO_AVG_PRICE
+            blocks_to_avg_price: BLOCKS_TO_AVG_PRICE,
+            wrapped_native:      WETH_ADDRESS
         }
     }
 
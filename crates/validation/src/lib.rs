@@ -24,7 +24,10 @@ use crate::{
     order::{
         order_validator::OrderValidator,
         sim::SimValidation,
-        state::{db_state_utils::FetchUtils, pools::AngstromPoolsTracker}
+        state::{
+            db_state_utils::{layouts::TokenLayoutStore, FetchUtils},
+            pools::AngstromPoolsTracker
+        }
     },
     validator::{ValidationClient, ValidationRequest}
 };
@@ -43,13 +46,14 @@ pub fn init_validation<
     uniswap_pools: SyncedUniswapPools,
     price_generator: TokenPriceGenerator,
     pool_store: Arc<AngstromPoolConfigStore>,
+    token_layouts: TokenLayoutStore,
     validator_rx: UnboundedReceiver<ValidationRequest>
 ) where
     <DB as revm::DatabaseRef>::Error: Send + Sync + Debug
 {
     let current_block = Arc::new(AtomicU64::new(current_block));
     let revm_lru = Arc::new(db);
-    let fetch = FetchUtils::new(Address::default(), revm_lru.clone());
+    let fetch = FetchUtils::new(Address::default(), revm_lru.clone(), token_layouts.clone());
 
     std::thread::spawn(move || {
         let rt = tokio::runtime::Builder::new_multi_thread()
@@ -62,7 +66,8 @@ pub fn init_validation<
         let pools = AngstromPoolsTracker::new(angstrom_address, pool_store);
         // load storage slot state + pools
         let thread_pool = KeySplitThreadpool::new(handle, MAX_VALIDATION_PER_ADDR);
-        let sim = SimValidation::new(revm_lru.clone(), angstrom_address, node_address);
+        let sim =
+            SimValidation::new(revm_lru.clone(), angstrom_address, node_address, token_layouts);
 
         // load price update stream;
         let update_stream =
@@ -3,7 +3,7 @@ use std::{fmt::Debug, future::Future, pin::Pin};
 use alloy::primitives::{Address, B256, U256};
 use angstrom_types::{
     orders::OrderOrigin,
-    primitive::OrderPoolNewOrderResult,
+    primitive::{OrderPoolNewOrderResult, OrderRejectionReason},
     sol_bindings::{
         ext::RawPoolOrder,
         grouped_orders::{
@@ -12,7 +12,7 @@ use angstrom_types::{
         rpc_orders::TopOfBlockOrder
     }
 };
-use sim::SimValidation;
+use sim::{GasInToken0, SimValidation};
 use tokio::sync::oneshot::{channel, Sender};
 
 use crate::{common::TokenPriceGenerator, validator::ValidationRequest};
@@ -42,29 +42,26 @@ impl From<OrderValidationRequest> for OrderValidation {
         match value {
             OrderValidationRequest::ValidateOrder(tx, order, orign) => match order {
                 AllOrders::Standing(p) => {
-                    // TODO: check hook data and deal with composable
-                    // if p.hook_data.is_empty() {
-                    OrderValidation::Limit(tx, GroupedVanillaOrder::Standing(p), orign)
-                    // } else {
-                    //
-                    //     OrderValidation::LimitComposable(
-                    //         tx,
-                    //         GroupedComposableOrder::Partial(p),
-                    //         orign
-                    //     )
-                    // }
+                    if p.hook_data().is_empty() {
+                        OrderValidation::Limit(tx, GroupedVanillaOrder::Standing(p), orign)
+                    } else {
+                        OrderValidation::LimitComposable(
+                            tx,
+                            GroupedComposableOrder::Partial(p),
+                            orign
+                        )
+                    }
                 }
                 AllOrders::Flash(kof) => {
-                    // TODO: check hook data and deal with composable
-                    // if kof.hook_data.is_empty() {
-                    OrderValidation::Limit(tx, GroupedVanillaOrder::KillOrFill(kof), orign)
-                    // } else {
-                    //     OrderValidation::LimitComposable(
-                    //         tx,
-                    //         GroupedComposableOrder::KillOrFill(kof),
-                    //         orign
-                    //     )
-                    // }
+                    if kof.hook_data().is_empty() {
+                        OrderValidation::Limit(tx, GroupedVanillaOrder::KillOrFill(kof), orign)
+                    } else {
+                        OrderValidation::LimitComposable(
+                            tx,
+                            GroupedComposableOrder::KillOrFill(kof),
+                            orign
+                        )
+                    }
                 }
                 AllOrders::TOB(tob) => OrderValidation::Searcher(tx, tob, orign)
             }
@@ -79,8 +76,8 @@ pub enum ValidationMessage {
 #[derive(Debug, Clone)]
 pub enum OrderValidationResults {
     Valid(OrderWithStorageData<AllOrders>),
-    // the raw hash to be removed
-    Invalid(B256),
+    // the raw hash to be removed along with why it was rejected
+    Invalid(B256, OrderRejectionReason),
     TransitionedToBlock
 }
 
@@ -122,14 +119,27 @@ impl OrderValidationResults {
                     SimValidation::calculate_user_gas
                 );
 
-                if let Err(e) = res {
-                    tracing::info!(%e, "failed to add gas to order");
-                    *self = OrderValidationResults::Invalid(order_hash);
+                let (order, gas_in_token0) = match res {
+                    Ok(res) => res,
+                    Err(e) => {
+                        tracing::info!(%e, "failed to add gas to order");
+                        *self = OrderValidationResults::Invalid(
+                            order_hash,
+                            OrderRejectionReason::GasSimulationFailed(e.to_string())
+                        );
+
+                        return
+                    }
+                };
+
+                if let Err(reason) = check_max_gas_fee(gas_in_token0, order.max_gas_token_0()) {
+                    tracing::debug!(%reason, "order gas exceeds max extra fee");
+                    *self = OrderValidationResults::Invalid(order_hash, reason);
 
                     return
                 }
 
-                res
+                Ok(order)
             } else {
                 let res = Self::map_and_process(
                     order,
@@ -145,18 +155,51 @@ impl OrderValidationResults {
                 );
                 if let Err(e) = res {
                     tracing::info!(%e, "failed to add gas to order");
-                    *self = OrderValidationResults::Invalid(order_hash);
+                    *self = OrderValidationResults::Invalid(
+                        order_hash,
+                        OrderRejectionReason::GasSimulationFailed(e.to_string())
+                    );
 
                     return
                 }
 
-                res
+                res.map(|(order, _)| order)
             };
 
             *self = OrderValidationResults::Valid(finalized_order.unwrap())
         }
     }
 
+    /// Runs the hook of a valid composable order against the current state,
+    /// invalidating the order if the hook wouldn't return successfully
+    pub fn simulate_hook_or_invalidate<DB>(&mut self, sim: &SimValidation<DB>, block: u64)
+    where
+        DB: Unpin
+            + Clone
+            + 'static
+            + revm::DatabaseRef
+            + reth_provider::BlockNumReader
+            + Send
+            + Sync,
+        <DB as revm::DatabaseRef>::Error: Send + Sync + std::fmt::Debug
+    {
+        let Self::Valid(order) = self else { return };
+        let order_hash = order.order_hash();
+        let hook_data = match &order.order {
+            AllOrders::Standing(p) => p.hook_data().clone(),
+            AllOrders::Flash(f) => f.hook_data().clone(),
+            AllOrders::TOB(_) => return
+        };
+
+        if let Err(e) = sim.simulate_hook(order.from(), &hook_data, block) {
+            tracing::debug!(%e, "composable order hook failed");
+            *self = OrderValidationResults::Invalid(
+                order_hash,
+                OrderRejectionReason::InvalidHook(e.to_string())
+            );
+        }
+    }
+
     // hmm the structure here is probably overkill to avoid 8 extra lines of code
     fn map_and_process<Old, New, DB>(
         order: OrderWithStorageData<Old>,
@@ -171,7 +214,7 @@ impl OrderValidationResults {
             &TokenPriceGenerator,
             u64
         ) -> eyre::Result<(u64, U256)>
-    ) -> eyre::Result<OrderWithStorageData<Old>>
+    ) -> eyre::Result<(OrderWithStorageData<Old>, GasInToken0)>
     where
         DB: Unpin + Clone + 'static + revm::DatabaseRef + Send + Sync,
         <DB as revm::DatabaseRef>::Error: Sync + Send + 'static
@@ -180,19 +223,36 @@ impl OrderValidationResults {
             .try_map_inner(move |order| Ok(map_new(order)))
             .unwrap();
 
-        let (gas_units, gas_used) = (calculate_function)(sim, &order, token_price, block)?;
-        order.priority_data.gas += gas_used;
+        let (gas_units, gas_in_token0) = (calculate_function)(sim, &order, token_price, block)?;
+        order.priority_data.gas += gas_in_token0;
         order.priority_data.gas_units = gas_units;
 
-        order.try_map_inner(move |new_order| Ok(map_old(new_order)))
+        Ok((order.try_map_inner(move |new_order| Ok(map_old(new_order)))?, gas_in_token0))
     }
 }
 
+/// The gas cost is paid out of the order in the pair's token0, so it can't
+/// exceed the max extra fee, also denominated in token0, that the user signed
+/// off on
+fn check_max_gas_fee(
+    gas_in_token0: GasInToken0,
+    max_fee_token0: u128
+) -> Result<(), OrderRejectionReason> {
+    if gas_in_token0 > U256::from(max_fee_token0) {
+        return Err(OrderRejectionReason::GasExceedsMaxFee {
+            gas_cost: gas_in_token0.saturating_to(),
+            max_fee:  max_fee_token0
+        })
+    }
+
+    Ok(())
+}
+
 impl From<OrderValidationResults> for OrderPoolNewOrderResult {
     fn from(val: OrderValidationResults) -> Self {
         match val {
             OrderValidationResults::Valid(_) => OrderPoolNewOrderResult::Valid,
-            OrderValidationResults::Invalid(_) => OrderPoolNewOrderResult::Invalid,
+            OrderValidationResults::Invalid(_, reason) => OrderPoolNewOrderResult::Invalid(reason),
             OrderValidationResults::TransitionedToBlock => {
                 OrderPoolNewOrderResult::TransitionedToBlock
             }
@@ -288,7 +348,9 @@ impl OrderValidatorHandle for ValidationClient {
                 OrderValidationResults::Valid(o) => {
                     Ok((o.priority_data.gas_units, o.priority_data.gas))
                 }
-                OrderValidationResults::Invalid(e) => Err(format!("Invalid order: {}", e)),
+                OrderValidationResults::Invalid(_, reason) => {
+                    Err(format!("Invalid order: {reason}"))
+                }
                 OrderValidationResults::TransitionedToBlock => {
                     Err("Order transitioned to block".to_string())
                 }
@@ -296,3 +358,26 @@ impl OrderValidatorHandle for ValidationClient {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gas_is_checked_against_max_fee_in_token0() {
+        assert_eq!(check_max_gas_fee(U256::from(100), 100), Ok(()));
+        assert_eq!(
+            check_max_gas_fee(U256::from(101), 100),
+            Err(OrderRejectionReason::GasExceedsMaxFee { gas_cost: 101, max_fee: 100 })
+        );
+
+        // a cost that doesn't fit in a u128 can't slip past a max fee of u128::MAX
+        assert_eq!(
+            check_max_gas_fee(U256::from(u128::MAX) + U256::from(1), u128::MAX),
+            Err(OrderRejectionReason::GasExceedsMaxFee {
+                gas_cost: u128::MAX,
+                max_fee:  u128::MAX
+            })
+        );
+    }
+}
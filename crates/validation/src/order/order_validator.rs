@@ -100,6 +100,26 @@ where
                             })
                             .await;
                     }
+                    OrderValidation::LimitComposable(tx, order, _) => {
+                        metrics
+                            .new_order(false, || async {
+                                let mut results = cloned_state.handle_regular_order(
+                                    order,
+                                    block_number,
+                                    metrics.clone()
+                                );
+                                results.simulate_hook_or_invalidate(&cloned_sim, block_number);
+                                results.add_gas_cost_or_invalidate(
+                                    &cloned_sim,
+                                    &token_conversion,
+                                    true,
+                                    block_number
+                                );
+
+                                let _ = tx.send(results);
+                            })
+                            .await;
+                    }
                     OrderValidation::Searcher(tx, order, _) => {
                         metrics
                             .new_order(true, || async {
@@ -118,7 +138,6 @@ where
                             })
                             .await;
                     }
-                    _ => unreachable!()
                 }
             })
         );
@@ -5,9 +5,9 @@ use alloy::{
     sol_types::{SolCall, SolValue}
 };
 use angstrom_types::{
-    contract_bindings::mintable_mock_erc_20::MintableMockERC20::{allowanceCall, balanceOfCall},
     contract_payloads::angstrom::AngstromBundle,
     matching::{uniswap::UniswapFlags, Ray},
+    primitive::angstrom_chain_id,
     sol_bindings::{
         grouped_orders::{GroupedVanillaOrder, OrderWithStorageData},
         rpc_orders::TopOfBlockOrder,
@@ -25,17 +25,32 @@ use revm::{
 };
 
 use super::gas_inspector::{GasSimulationInspector, GasUsed};
-use crate::order::state::db_state_utils::finders::{
-    find_slot_offset_for_approval, find_slot_offset_for_balance
+use crate::order::state::{
+    config::SlotLayout,
+    db_state_utils::{
+        finders::{
+            call_allowance, call_balance_of, find_approval_slot, find_balance_slot,
+            verify_approval_slot, verify_balance_slot
+        },
+        layouts::TokenLayoutStore
+    }
 };
 
 /// A address we can use to deploy contracts
 const DEFAULT_FROM: Address = address!("aa250d5630b4cf539739df2c5dacb4c659f2488d");
 const DEFAULT_CREATE2_FACTORY: Address = address!("4e59b44847b379578588920cA78FbF26c0B4956C");
+/// What a hook has to return for the contract to accept it,
+/// `keccak256("Angstrom.hook.return-magic")[-4:]`
+const EXPECTED_HOOK_RETURN_MAGIC: u32 = 0x24a2e44b;
 // const SETUP_BYTECODE: FixedBytes<32> =
 //     fixed_bytes!("
 // 907ea7ad6d1fbded0236f040aea693e2c9711b62b065fc95c4262972aca03996");
 
+alloy::sol!(
+    /// `IAngstromComposable`, called on the hook of a composable order
+    function compose(address from, bytes calldata payload) external returns (uint32);
+);
+
 /// deals with the calculation of gas for a given type of order.
 /// user orders and tob orders take different paths and are different size and
 /// as such, pay different amount of gas in order to execute.
@@ -50,7 +65,9 @@ pub struct OrderGasCalculations<DB> {
     // the deployed addresses in cache_db
     angstrom_address: Address,
     /// the address(pubkey) of this node.
-    node_address:     Option<Address>
+    node_address:     Option<Address>,
+    /// where tokens keep the balances and approvals we override
+    token_layouts:    TokenLayoutStore
 }
 
 impl<DB> OrderGasCalculations<DB>
@@ -61,7 +78,8 @@ where
     pub fn new(
         db: Arc<DB>,
         angstrom_address: Option<Address>,
-        node_address: Address
+        node_address: Address,
+        token_layouts: TokenLayoutStore
     ) -> eyre::Result<Self> {
         // let bytecode = keccak256(&Angstrom::BYTECODE);
         // assert!(
@@ -71,12 +89,17 @@ where
         // );
 
         if let Some(angstrom_address) = angstrom_address {
-            Ok(Self { db: CacheDB::new(db), angstrom_address, node_address: Some(node_address) })
+            Ok(Self {
+                db: CacheDB::new(db),
+                angstrom_address,
+                node_address: Some(node_address),
+                token_layouts
+            })
         } else {
             let ConfiguredRevm { db, angstrom } =
                 Self::setup_revm_cache_database_for_simulation(db)?;
 
-            Ok(Self { db, angstrom_address: angstrom, node_address: None })
+            Ok(Self { db, angstrom_address: angstrom, node_address: None, token_layouts })
         }
     }
 
@@ -166,6 +189,50 @@ where
         .map_err(|e| eyre!("user order err={} {:?}", e, order.from()))
     }
 
+    /// Calls the hook the same way the contract does after filling a
+    /// composable order. The first 20 bytes of the hook data are the hook
+    /// address, the rest is the payload it gets called with.
+    pub fn simulate_hook(&self, from: Address, hook_data: &Bytes, block: u64) -> eyre::Result<()> {
+        if hook_data.len() < 20 {
+            return Err(eyre!("hook data is too short to contain a hook address"))
+        }
+        let hook = Address::from_slice(&hook_data[..20]);
+        let payload = Bytes::copy_from_slice(&hook_data[20..]);
+
+        let mut evm_handler = EnvWithHandlerCfg::default();
+        evm_handler.block.number = U256::from(block + 1);
+        let tx = &mut evm_handler.tx;
+        tx.caller = self.angstrom_address;
+        tx.transact_to = TxKind::Call(hook);
+        tx.data = composeCall::new((from, payload)).abi_encode().into();
+
+        let mut evm = revm::Evm::builder()
+            .with_ref_db(self.db.clone())
+            .with_env_with_handler_cfg(evm_handler)
+            .modify_env(|env| {
+                env.cfg.disable_balance_check = true;
+                env.cfg.chain_id = angstrom_chain_id();
+            })
+            .build();
+
+        let result = evm
+            .transact()
+            .map_err(|e| eyre!("failed to transact with revm: {e:?}"))?;
+        if !result.result.is_success() {
+            return Err(eyre!("hook reverted err={:?}", result.result))
+        }
+
+        let output = result.result.output().cloned().unwrap_or_default();
+        let magic = composeCall::abi_decode_returns(&output, true)
+            .map_err(|e| eyre!("hook returned malformed data: {e}"))?
+            ._0;
+        if magic != EXPECTED_HOOK_RETURN_MAGIC {
+            return Err(eyre!("hook returned {magic:#x} instead of the expected magic"))
+        }
+
+        Ok(())
+    }
+
     fn execute_with_db<D: DatabaseRef, F>(db: D, f: F) -> eyre::Result<(ResultAndState, D)>
     where
         F: FnOnce(&mut TxEnv),
@@ -281,13 +348,14 @@ where
 
         apply_slot_overrides_for_tokens(
             &mut db,
+            &self.token_layouts,
             overrides.token_in,
             overrides.token_out,
             overrides.amount_in,
             overrides.amount_out,
             overrides.user_address,
             self.angstrom_address
-        );
+        )?;
 
         {
             let mut evm = revm::Evm::builder()
@@ -297,7 +365,7 @@ where
                 .append_handler_register(inspector_handle_register)
                 .modify_env(|env| {
                     env.cfg.disable_balance_check = true;
-                    env.cfg.chain_id = 1;
+                    env.cfg.chain_id = angstrom_chain_id();
                 })
                 .build();
 
@@ -323,125 +391,97 @@ where
     }
 }
 
+/// Gives the user the `token_in` balance and approval and angstrom the
+/// `token_out` balance the simulated bundle needs. Tokens whose values can't be
+/// overridden are simulated against their real state instead
+#[allow(clippy::too_many_arguments)]
 fn apply_slot_overrides_for_tokens<DB: revm::DatabaseRef + Clone>(
     db: &mut CacheDB<Arc<DB>>,
+    layouts: &TokenLayoutStore,
     token_in: Address,
     token_out: Address,
     amount_in: U256,
     amount_out: U256,
     user: Address,
     angstrom: Address
-) where
+) -> eyre::Result<()>
+where
     <DB as revm::DatabaseRef>::Error: Debug
 {
-    let balance_slot_in = find_slot_offset_for_balance(&db, token_in);
-    let balance_slot_out = find_slot_offset_for_balance(&db, token_out);
-    let approval_slot_in = find_slot_offset_for_approval(&db, token_in);
-
-    // first thing we will do is setup the users token_in balance.
-    let user_balance_slot = keccak256((user, balance_slot_in).abi_encode());
-    let user_approval_slot =
-        keccak256((angstrom, keccak256((user, approval_slot_in).abi_encode())).abi_encode());
-
-    let user_approval_slot2 =
-        keccak256((user, keccak256((angstrom, approval_slot_in).abi_encode())).abi_encode());
-    // now that we have the above slots, we want to set slots for angstrom to have
-    // the funds to transfer out
-    let angstrom_balance_out = keccak256((angstrom, balance_slot_out).abi_encode());
-
+    let balance_in = layouts.balance_layout(
+        token_in,
+        |slot| verify_balance_slot(&*db, token_in, slot),
+        || find_balance_slot(&*db, token_in)
+    )?;
+    let balance_out = layouts.balance_layout(
+        token_out,
+        |slot| verify_balance_slot(&*db, token_out, slot),
+        || find_balance_slot(&*db, token_out)
+    )?;
+    let approval_in = layouts.approval_layout(
+        token_in,
+        |slot| verify_approval_slot(&*db, token_in, slot),
+        || find_approval_slot(&*db, token_in)
+    )?;
+
+    let (override_in, override_out) = (U256::from(2) * amount_in, U256::from(2) * amount_out);
+    let mut set = |token: Address, slot: U256, value: U256| {
+        db.insert_account_storage(token, slot, value)
+            .map_err(|e| eyre!("failed to override storage of {token}: {e:?}"))
+    };
     // set the users balance on the token_in
-    db.insert_account_storage(token_in, user_balance_slot.into(), U256::from(2) * amount_in)
-        .unwrap();
+    if let SlotLayout::Mapping(slot) = &balance_in {
+        set(token_in, slot.generate_slot(user), override_in)?;
+    }
     // give angstrom approval
-    db.insert_account_storage(token_in, user_approval_slot.into(), U256::from(2) * amount_in)
-        .unwrap();
-    db.insert_account_storage(token_in, user_approval_slot2.into(), U256::from(2) * amount_in)
-        .unwrap();
+    if let SlotLayout::Mapping(slot) = &approval_in {
+        set(token_in, slot.generate_slot(user, angstrom), override_in)?;
+    }
     // give angstrom funds on token_out
-    db.insert_account_storage(token_out, angstrom_balance_out.into(), U256::from(2) * amount_out)
-        .unwrap();
+    if let SlotLayout::Mapping(slot) = &balance_out {
+        set(token_out, slot.generate_slot(angstrom), override_out)?;
+    }
 
     // verify that everything is setup as we want
-    verify_overrides(db, token_in, token_out, amount_in, amount_out, user, angstrom);
+    let no_overrides = HashMap::new();
+    verify_funds(
+        &balance_in,
+        call_balance_of(&*db, token_in, user, &no_overrides)?,
+        override_in,
+        amount_in,
+        "user balance"
+    )?;
+    verify_funds(
+        &balance_out,
+        call_balance_of(&*db, token_out, angstrom, &no_overrides)?,
+        override_out,
+        amount_out,
+        "angstrom balance"
+    )?;
+    verify_funds(
+        &approval_in,
+        call_allowance(&*db, token_in, user, angstrom, &no_overrides)?,
+        override_in,
+        amount_in,
+        "angstrom allowance"
+    )
 }
 
-fn verify_overrides<DB: revm::DatabaseRef + Clone>(
-    db: &CacheDB<Arc<DB>>,
-    token_in: Address,
-    token_out: Address,
-    amount_in: U256,
-    amount_out: U256,
-    user: Address,
-    angstrom: Address
-) where
-    <DB as revm::DatabaseRef>::Error: Debug
-{
-    let evm_handler = EnvWithHandlerCfg::default();
-
-    // user balance
-    let mut evm = revm::Evm::builder()
-        .with_ref_db(db.clone())
-        .with_env_with_handler_cfg(evm_handler.clone())
-        .modify_env(|env| {
-            env.cfg.disable_balance_check = true;
-        })
-        .modify_tx_env(|tx| {
-            tx.caller = user;
-            tx.transact_to = TxKind::Call(token_in);
-            tx.data = balanceOfCall::new((user,)).abi_encode().into();
-            tx.value = U256::from(0);
-            tx.nonce = None;
-        })
-        .build();
-
-    let output = evm.transact().unwrap().result.output().unwrap().to_vec();
-    let return_data = balanceOfCall::abi_decode_returns(&output, false).unwrap();
-    if return_data._0 != U256::from(2) * amount_in {
-        panic!("failed to set user balance");
-    }
-
-    // angstrom balance
-    let mut evm = revm::Evm::builder()
-        .with_ref_db(db.clone())
-        .with_env_with_handler_cfg(evm_handler.clone())
-        .modify_env(|env| {
-            env.cfg.disable_balance_check = true;
-        })
-        .modify_tx_env(|tx| {
-            tx.caller = user;
-            tx.transact_to = TxKind::Call(token_out);
-            tx.data = balanceOfCall::new((angstrom,)).abi_encode().into();
-            tx.value = U256::from(0);
-            tx.nonce = None;
-        })
-        .build();
-
-    let output = evm.transact().unwrap().result.output().unwrap().to_vec();
-    let return_data = balanceOfCall::abi_decode_returns(&output, false).unwrap();
-    if return_data._0 != (amount_out * U256::from(2)) {
-        panic!("failed to set angstrom balance");
-    }
-
-    // verify approval
-    let mut evm = revm::Evm::builder()
-        .with_ref_db(db.clone())
-        .with_env_with_handler_cfg(evm_handler.clone())
-        .modify_env(|env| {
-            env.cfg.disable_balance_check = true;
-        })
-        .modify_tx_env(|tx| {
-            tx.caller = user;
-            tx.transact_to = TxKind::Call(token_in);
-            tx.data = allowanceCall::new((user, angstrom)).abi_encode().into();
-            tx.value = U256::from(0);
-            tx.nonce = None;
-        })
-        .build();
-
-    let output = evm.transact().unwrap().result.output().unwrap().to_vec();
-    let return_data = allowanceCall::abi_decode_returns(&output, false).unwrap();
-    if return_data._0 != U256::from(2) * amount_in {
-        panic!("angstrom doesn't have proper allowance");
+/// overridden values have to read back exactly, the real values of tokens we
+/// can't override have to cover what the simulation moves
+fn verify_funds<S>(
+    layout: &SlotLayout<S>,
+    actual: U256,
+    overridden: U256,
+    needed: U256,
+    what: &str
+) -> eyre::Result<()> {
+    match layout {
+        SlotLayout::Mapping(_) if actual != overridden => eyre::bail!("failed to set {what}"),
+        SlotLayout::Call if actual < needed => {
+            eyre::bail!("{what} of {actual} can't be overridden and doesn't cover {needed}")
+        }
+        _ => Ok(())
     }
 }
 
@@ -527,7 +567,12 @@ pub mod test {
     fn ensure_creation_of_mock_works() {
         let db_path = Path::new("/home/data/reth/db/");
         let db = load_reth_db(db_path);
-        let res = OrderGasCalculations::new(Arc::new(RethDbWrapper::new(db)), None, Address::ZERO);
+        let res = OrderGasCalculations::new(
+            Arc::new(RethDbWrapper::new(db)),
+            None,
+            Address::ZERO,
+            TokenLayoutStore::default()
+        );
 
         if let Err(e) = res.as_ref() {
             eprintln!("{}", e);
@@ -601,8 +646,12 @@ pub mod test {
         let db_path = Path::new("/home/data/reth/db/");
         let db = Arc::new(RethDbWrapper::new(load_reth_db(db_path)));
 
-        let gas_calculations =
-            OrderGasCalculations::new(Arc::new(RethDbWrapper::new(db)), None, Address::ZERO);
+        let gas_calculations = OrderGasCalculations::new(
+            Arc::new(RethDbWrapper::new(db)),
+            None,
+            Address::ZERO,
+            TokenLayoutStore::default()
+        );
 
         assert!(gas_calculations.is_ok(), "failed to deploy angstrom structure and v4 to chain");
         let mut gas_calculations = gas_calculations.unwrap();
@@ -641,8 +690,12 @@ pub mod test {
         let db_path = Path::new("/home/data/reth/db/");
         let db = Arc::new(RethDbWrapper::new(load_reth_db(db_path)));
 
-        let gas_calculations =
-            OrderGasCalculations::new(Arc::new(RethDbWrapper::new(db)), None, Address::ZERO);
+        let gas_calculations = OrderGasCalculations::new(
+            Arc::new(RethDbWrapper::new(db)),
+            None,
+            Address::ZERO,
+            TokenLayoutStore::default()
+        );
 
         assert!(gas_calculations.is_ok(), "failed to deploy angstrom structure and v4 to chain");
         let mut gas_calculations = gas_calculations.unwrap();
@@ -1,6 +1,6 @@
 use std::{fmt::Debug, sync::Arc};
 
-use alloy::primitives::Address;
+use alloy::primitives::{Address, Bytes};
 use angstrom_metrics::validation::ValidationMetrics;
 use angstrom_types::sol_bindings::{
     grouped_orders::{GroupedVanillaOrder, OrderWithStorageData},
@@ -11,7 +11,10 @@ use gas::OrderGasCalculations;
 use revm::primitives::ruint::aliases::U256;
 use tracing::error_span;
 
-use crate::{common::TokenPriceGenerator, order::sim::gas_inspector::GasUsed};
+use crate::{
+    common::TokenPriceGenerator,
+    order::{sim::gas_inspector::GasUsed, state::db_state_utils::layouts::TokenLayoutStore}
+};
 
 pub mod console_log;
 mod gas;
@@ -30,10 +33,19 @@ where
     DB: Unpin + Clone + 'static + revm::DatabaseRef + reth_provider::BlockNumReader + Send + Sync,
     <DB as revm::DatabaseRef>::Error: Send + Sync + Debug
 {
-    pub fn new(db: Arc<DB>, angstrom_address: Address, node_address: Address) -> Self {
-        let gas_calculator =
-            OrderGasCalculations::new(db.clone(), Some(angstrom_address), node_address)
-                .expect("failed to deploy baseline angstrom for gas calculations");
+    pub fn new(
+        db: Arc<DB>,
+        angstrom_address: Address,
+        node_address: Address,
+        token_layouts: TokenLayoutStore
+    ) -> Self {
+        let gas_calculator = OrderGasCalculations::new(
+            db.clone(),
+            Some(angstrom_address),
+            node_address,
+            token_layouts
+        )
+        .expect("failed to deploy baseline angstrom for gas calculations");
         Self { gas_calculator, metrics: ValidationMetrics::new() }
     }
 
@@ -65,6 +77,13 @@ where
         })
     }
 
+    /// Calls the hook of a composable order the same way the contract will once
+    /// the order is filled, against the latest state
+    pub fn simulate_hook(&self, from: Address, hook_data: &Bytes, block: u64) -> eyre::Result<()> {
+        let span = error_span!("hook", ?from);
+        span.in_scope(|| self.gas_calculator.simulate_hook(from, hook_data, block))
+    }
+
     pub fn calculate_user_gas(
         &self,
         order: &OrderWithStorageData<GroupedVanillaOrder>,
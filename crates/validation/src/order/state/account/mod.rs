@@ -3,6 +3,7 @@
 use alloy::primitives::{Address, B256, U256};
 use angstrom_types::{
     orders::OrderId,
+    primitive::OrderRejectionReason,
     sol_bindings::{ext::RawPoolOrder, grouped_orders::OrderWithStorageData}
 };
 use thiserror::Error;
@@ -68,6 +69,12 @@ impl<S: StateFetchUtils> UserAccountProcessor<S> {
         }
         tracing::trace!(?conflicting_orders);
 
+        // orders the account couldn't fund even without its other pending orders are
+        // rejected, only orders that are waiting on those get parked
+        self.user_accounts
+            .get_baseline_state(user, pool_info.token, &self.fetch_utils)
+            .ensure_funds_for(&order)?;
+
         // if new order has lower hash cancel all orders with the same nonce
         conflicting_orders.iter().for_each(|order| {
             self.user_accounts.cancel_order(&user, &order.order_hash);
@@ -141,7 +148,41 @@ pub enum UserAccountVerificationError<O: RawPoolOrder> {
     #[error("Nonce exists for a current order hash: {0:?}")]
     DuplicateNonce(B256),
     #[error("block for flash order is not for next block. next_block: {0}, requested_block: {1}.")]
-    BadBlock(u64, u64)
+    BadBlock(u64, u64),
+    #[error("order needs {required} of {token} but the account only holds {available}")]
+    InsufficientBalance { token: Address, required: U256, available: U256 },
+    #[error("order needs {required} of {token} but angstrom is only approved for {available}")]
+    InsufficientApproval { token: Address, required: U256, available: U256 }
+}
+
+impl<O: RawPoolOrder> UserAccountVerificationError<O> {
+    /// the reason surfaced to the submitter of the order
+    pub fn rejection_reason(&self) -> OrderRejectionReason {
+        match self {
+            Self::BlockMissMatch { requested, current, .. } => {
+                OrderRejectionReason::StaleValidation { validated: *requested, current: *current }
+            }
+            Self::OrderIsCancelled(_) => OrderRejectionReason::Cancelled,
+            Self::DuplicateNonce(_) => OrderRejectionReason::DuplicateNonce,
+            Self::BadBlock(expected, requested) => {
+                OrderRejectionReason::BadBlock { expected: *expected, requested: *requested }
+            }
+            Self::InsufficientBalance { token, required, available } => {
+                OrderRejectionReason::InsufficientBalance {
+                    token:     *token,
+                    required:  *required,
+                    available: *available
+                }
+            }
+            Self::InsufficientApproval { token, required, available } => {
+                OrderRejectionReason::InsufficientApproval {
+                    token:     *token,
+                    required:  *required,
+                    available: *available
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -150,7 +191,7 @@ pub mod tests {
 
     use alloy::primitives::{Address, U256};
     use angstrom_types::{
-        primitive::{AngstromSigner, PoolId},
+        primitive::{AngstromSigner, OrderRejectionReason, PoolId},
         sol_bindings::{grouped_orders::GroupedVanillaOrder, RawPoolOrder}
     };
     use testing_tools::type_generator::orders::UserOrderBuilder;
@@ -448,14 +489,17 @@ pub mod tests {
             .fetch_utils
             .set_approval_for_user(user, token0, U256::from(1000));
 
-        let result = processor
+        let err = processor
             .verify_order(order, pool_info, 420)
-            .expect("verification should complete");
+            .expect_err("order should be rejected");
 
-        assert!(
-            !result.is_currently_valid,
-            "Order should be marked as invalid due to insufficient balance {:?}",
-            result
+        assert_eq!(
+            err.rejection_reason(),
+            OrderRejectionReason::InsufficientBalance {
+                token:     token0,
+                required:  U256::from(1000),
+                available: U256::from(500)
+            }
         );
     }
 
@@ -492,13 +536,17 @@ pub mod tests {
             .fetch_utils
             .set_approval_for_user(user, token0, U256::from(500));
 
-        let result = processor
+        let err = processor
             .verify_order(order, pool_info, 420)
-            .expect("verification should complete");
+            .expect_err("order should be rejected");
 
-        assert!(
-            !result.is_currently_valid,
-            "Order should be marked as invalid due to insufficient approval"
+        assert_eq!(
+            err.rejection_reason(),
+            OrderRejectionReason::InsufficientApproval {
+                token:     token0,
+                required:  U256::from(1000),
+                available: U256::from(500)
+            }
         );
     }
 
@@ -4,6 +4,7 @@ use alloy::primitives::{Address, B256, U256};
 use angstrom_types::sol_bindings::{ext::RawPoolOrder, RespendAvoidanceMethod};
 use dashmap::DashMap;
 
+use super::UserAccountVerificationError;
 use crate::order::state::{db_state_utils::StateFetchUtils, pools::UserOrderPoolInfo};
 
 pub type UserAddress = Address;
@@ -56,6 +57,43 @@ impl LiveState {
             pool_info: pool_info.clone()
         })
     }
+
+    /// errors with whatever falls short if this state can't fund the order
+    pub fn ensure_funds_for<O: RawPoolOrder>(
+        &self,
+        order: &O
+    ) -> Result<(), UserAccountVerificationError<O>> {
+        let token = self.token;
+        let required = U256::from(order.amount_in());
+
+        if order.use_internal() {
+            if self.angstrom_balance < required {
+                return Err(UserAccountVerificationError::InsufficientBalance {
+                    token,
+                    required,
+                    available: self.angstrom_balance
+                })
+            }
+            return Ok(())
+        }
+
+        if self.balance < required {
+            return Err(UserAccountVerificationError::InsufficientBalance {
+                token,
+                required,
+                available: self.balance
+            })
+        }
+        if self.approval < required {
+            return Err(UserAccountVerificationError::InsufficientApproval {
+                token,
+                required,
+                available: self.approval
+            })
+        }
+
+        Ok(())
+    }
 }
 
 /// deltas to be applied to the base user action
@@ -166,6 +204,37 @@ impl UserAccounts {
             })
     }
 
+    /// the last known on chain state of the user for the token, without any of
+    /// their pending orders applied
+    pub fn get_baseline_state<S: StateFetchUtils>(
+        &self,
+        user: UserAddress,
+        token: TokenAddress,
+        utils: &S
+    ) -> LiveState {
+        self.try_fetch_baseline_state(user, token)
+            .unwrap_or_else(|| {
+                self.load_state_for(user, token, utils);
+                self.try_fetch_baseline_state(user, token)
+                    .expect("after loading state for a address, the state wasn't found")
+            })
+    }
+
+    fn try_fetch_baseline_state(
+        &self,
+        user: UserAddress,
+        token: TokenAddress
+    ) -> Option<LiveState> {
+        let baseline = self.last_known_state.get(&user)?;
+
+        Some(LiveState {
+            token,
+            approval: *baseline.token_approval.get(&token)?,
+            balance: *baseline.token_balance.get(&token)?,
+            angstrom_balance: *baseline.angstrom_balance.get(&token)?
+        })
+    }
+
     fn load_state_for<S: StateFetchUtils>(
         &self,
         user: UserAddress,
@@ -1,81 +1,94 @@
 use std::fmt::Debug;
 
-use alloy::primitives::{keccak256, Address, U256};
+use alloy::primitives::{keccak256, Address, B256, U256};
 use eyre::eyre;
 use reth_revm::DatabaseRef;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Deserialize)]
+/// How a mapping key and the slot of the mapping are combined into the
+/// storage slot of the value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum HashMethod {
+    /// `keccak256(key . slot)`
     #[serde(rename = "sol")]
     Solidity,
+    /// `keccak256(slot . key)`
     #[serde(rename = "vyper")]
     Vyper
 }
+
 impl HashMethod {
-    const fn is_solidity(&self) -> bool {
-        matches!(self, HashMethod::Solidity)
+    /// the storage slot of `key` in the mapping stored at `slot`
+    pub fn mapping_slot(&self, key: B256, slot: U256) -> U256 {
+        let slot = B256::from(slot);
+        let (first, second) = match self {
+            HashMethod::Solidity => (key, slot),
+            HashMethod::Vyper => (slot, key)
+        };
+
+        let mut buf = [0u8; 64];
+        buf[..32].copy_from_slice(first.as_slice());
+        buf[32..].copy_from_slice(second.as_slice());
+
+        U256::from_be_bytes(*keccak256(buf))
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// Where a token keeps a value we need to read or override
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SlotLayout<S> {
+    /// the value sits in a plain storage mapping of the token
+    Mapping(S),
+    /// the value is packed, rebased or otherwise computed, so the only way to
+    /// get it is to call the token
+    Call
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TokenBalanceSlot {
     pub token:       Address,
     pub hash_method: HashMethod,
-    pub slot_index:  u8
+    pub slot_index:  U256
 }
 
 impl TokenBalanceSlot {
-    pub fn new(token: Address, slot_index: u8) -> Self {
-        Self { token, hash_method: HashMethod::Solidity, slot_index }
+    pub fn new(token: Address, hash_method: HashMethod, slot_index: U256) -> Self {
+        Self { token, hash_method, slot_index }
     }
 
-    pub fn generate_slot(&self, of: Address) -> eyre::Result<U256> {
-        if !self.hash_method.is_solidity() {
-            return Err(eyre::eyre!("current type of contract hashing is not supported"))
-        }
-
-        let mut buf = [0u8; 64];
-        buf[12..32].copy_from_slice(&**of);
-        buf[63] = self.slot_index;
-
-        Ok(U256::from_be_bytes(*keccak256(buf)))
+    pub fn generate_slot(&self, of: Address) -> U256 {
+        self.hash_method
+            .mapping_slot(of.into_word(), self.slot_index)
     }
 
     pub fn load_balance<DB: revm::DatabaseRef>(&self, of: Address, db: &DB) -> eyre::Result<U256>
     where
         <DB as DatabaseRef>::Error: Sync + Send + 'static
     {
-        db.storage_ref(self.token, self.generate_slot(of)?)
+        db.storage_ref(self.token, self.generate_slot(of))
             .map_err(|_| eyre!("failed to load balance slot"))
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TokenApprovalSlot {
     pub token:       Address,
     pub hash_method: HashMethod,
-    pub slot_index:  u8
+    pub slot_index:  U256
 }
 
 impl TokenApprovalSlot {
-    pub fn new(token: Address, slot_index: u8) -> Self {
-        Self { token, slot_index, hash_method: HashMethod::Solidity }
+    pub fn new(token: Address, hash_method: HashMethod, slot_index: U256) -> Self {
+        Self { token, hash_method, slot_index }
     }
 
-    pub fn generate_slot(&self, user: Address, contract: Address) -> eyre::Result<U256> {
-        if !self.hash_method.is_solidity() {
-            return Err(eyre::eyre!("current type of contract hashing is not supported"))
-        }
-        let mut inner_buf = [0u8; 64];
-        inner_buf[12..32].copy_from_slice(&**contract);
-        inner_buf[63] = self.slot_index;
-        let inner_hash = keccak256(inner_buf);
-        let mut next = [0u8; 64];
-        next[12..32].copy_from_slice(&**user);
-        next[32..64].copy_from_slice(&*inner_hash);
+    /// the slot of `allowance[user][contract]`
+    pub fn generate_slot(&self, user: Address, contract: Address) -> U256 {
+        let inner = self
+            .hash_method
+            .mapping_slot(user.into_word(), self.slot_index);
 
-        Ok(U256::from_be_bytes(*keccak256(next)))
+        self.hash_method.mapping_slot(contract.into_word(), inner)
     }
 
     pub fn load_approval_amount<DB: revm::DatabaseRef>(
@@ -87,11 +100,7 @@ impl TokenApprovalSlot {
     where
         <DB as DatabaseRef>::Error: Sync + Send + 'static
     {
-        if !self.hash_method.is_solidity() {
-            return Err(eyre::eyre!("current type of contract hashing is not supported"))
-        }
-
-        db.storage_ref(self.token, self.generate_slot(user, contract)?)
+        db.storage_ref(self.token, self.generate_slot(user, contract))
             .map_err(|_| eyre!("failed to load approval slot"))
     }
 }
@@ -1,21 +1,23 @@
 use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
 use alloy::primitives::{Address, U256};
-use dashmap::DashMap;
 use reth_revm::DatabaseRef;
 
-use super::finders::find_slot_offset_for_approval;
-use crate::order::state::config::TokenApprovalSlot;
+use super::{
+    finders::{call_allowance, find_approval_slot, verify_approval_slot},
+    layouts::TokenLayoutStore
+};
+use crate::order::state::config::SlotLayout;
 
 #[derive(Clone)]
 pub struct Approvals {
     angstrom_address: Address,
-    slots:            DashMap<Address, TokenApprovalSlot>
+    layouts:          TokenLayoutStore
 }
 
 impl Approvals {
-    pub fn new(angstrom_address: Address) -> Self {
-        Self { angstrom_address, slots: DashMap::default() }
+    pub fn new(angstrom_address: Address, layouts: TokenLayoutStore) -> Self {
+        Self { angstrom_address, layouts }
     }
 
     pub fn fetch_approval_balance_for_token_overrides<DB: revm::DatabaseRef>(
@@ -28,16 +30,17 @@ impl Approvals {
     where
         <DB as revm::DatabaseRef>::Error: Debug
     {
-        self.slots
-            .get(&token)
-            .or_else(|| {
-                let slot = find_slot_offset_for_approval(&db, token);
-                let slot = TokenApprovalSlot::new(token, slot as u8);
-                self.slots.insert(token, slot);
-                self.slots.get(&token)
-            })
-            .and_then(|slot| {
-                let slot_addr = slot.generate_slot(user, self.angstrom_address).ok()?;
+        match self
+            .layouts
+            .approval_layout(
+                token,
+                |slot| verify_approval_slot(&db, token, slot),
+                || find_approval_slot(&db, token)
+            )
+            .unwrap_or(SlotLayout::Call)
+        {
+            SlotLayout::Mapping(slot) => {
+                let slot_addr = slot.generate_slot(user, self.angstrom_address);
                 if let Some(address_slots) = overrides.get(&token) {
                     if let Some(s_override) = address_slots.get(&slot_addr) {
                         return Some(*s_override)
@@ -45,7 +48,11 @@ impl Approvals {
                 }
 
                 db.storage_ref(token, slot_addr).ok()
-            })
+            }
+            SlotLayout::Call => {
+                call_allowance(&db, token, user, self.angstrom_address, overrides).ok()
+            }
+        }
     }
 
     pub fn fetch_approval_balance_for_token<DB: revm::DatabaseRef>(
@@ -57,17 +64,21 @@ impl Approvals {
     where
         <DB as DatabaseRef>::Error: Debug + Sync + Send + 'static
     {
-        self.slots
-            .get(&token)
-            .or_else(|| {
-                let slot = find_slot_offset_for_approval(db, token);
-                let slot = TokenApprovalSlot::new(token, slot as u8);
-                self.slots.insert(token, slot);
-                self.slots.get(&token)
-            })
-            .and_then(|slot| {
-                slot.load_approval_amount(user, self.angstrom_address, db)
-                    .ok()
-            })
+        let approval = match self
+            .layouts
+            .approval_layout(
+                token,
+                |slot| verify_approval_slot(db, token, slot),
+                || find_approval_slot(db, token)
+            )
+            .unwrap_or(SlotLayout::Call)
+        {
+            SlotLayout::Mapping(slot) => slot.load_approval_amount(user, self.angstrom_address, db),
+            SlotLayout::Call => {
+                call_allowance(db, token, user, self.angstrom_address, &HashMap::new())
+            }
+        };
+
+        approval.ok()
     }
 }
@@ -4,22 +4,24 @@ use alloy::{
     primitives::{keccak256, Address, U256},
     sol_types::SolValue
 };
-use dashmap::DashMap;
 use reth_revm::DatabaseRef;
 
-use super::finders::find_slot_offset_for_balance;
-use crate::order::state::config::TokenBalanceSlot;
+use super::{
+    finders::{call_balance_of, find_balance_slot, verify_balance_slot},
+    layouts::TokenLayoutStore
+};
+use crate::order::state::config::SlotLayout;
 
 #[derive(Clone)]
 pub struct Balances {
-    tokens:           DashMap<Address, TokenBalanceSlot>,
+    layouts:          TokenLayoutStore,
     angstrom_address: Address
 }
 const ANGSTROM_BALANCE_SLOT_OFFSET: u32 = 4;
 
 impl Balances {
-    pub fn new(angstrom_address: Address) -> Self {
-        Self { tokens: DashMap::default(), angstrom_address }
+    pub fn new(angstrom_address: Address, layouts: TokenLayoutStore) -> Self {
+        Self { layouts, angstrom_address }
     }
 
     pub fn fetch_balance_for_token_overrides<DB: revm::DatabaseRef>(
@@ -32,24 +34,27 @@ impl Balances {
     where
         <DB as revm::DatabaseRef>::Error: Debug
     {
-        // Existing code remains unchanged
-        self.tokens
-            .get(&token)
-            .or_else(|| {
-                let slot = find_slot_offset_for_balance(&db, token);
-                let slot = TokenBalanceSlot::new(token, slot as u8);
-                self.tokens.insert(token, slot);
-                self.tokens.get(&token)
-            })
-            .and_then(|slot| {
-                let slot_addr = slot.generate_slot(user).ok()?;
+        match self
+            .layouts
+            .balance_layout(
+                token,
+                |slot| verify_balance_slot(&db, token, slot),
+                || find_balance_slot(&db, token)
+            )
+            // a token we couldn't trace yet can still be called
+            .unwrap_or(SlotLayout::Call)
+        {
+            SlotLayout::Mapping(slot) => {
+                let slot_addr = slot.generate_slot(user);
                 if let Some(address_slots) = overrides.get(&token) {
                     if let Some(s_override) = address_slots.get(&slot_addr) {
                         return Some(*s_override);
                     }
                 }
                 db.storage_ref(token, slot_addr).ok()
-            })
+            }
+            SlotLayout::Call => call_balance_of(&db, token, user, overrides).ok()
+        }
     }
 
     pub fn fetch_balance_for_token<DB: revm::DatabaseRef>(
@@ -61,16 +66,20 @@ impl Balances {
     where
         <DB as DatabaseRef>::Error: Debug + Sync + Send + 'static
     {
-        self.tokens
-            .get(&token)
-            .or_else(|| {
-                let slot = find_slot_offset_for_balance(db, token);
-                let slot = TokenBalanceSlot::new(token, slot as u8);
-                self.tokens.insert(token, slot);
-                self.tokens.get(&token)
-            })
-            .and_then(|slot| slot.load_balance(user, db).ok())
-            .unwrap_or_default()
+        let balance = match self
+            .layouts
+            .balance_layout(
+                token,
+                |slot| verify_balance_slot(db, token, slot),
+                || find_balance_slot(db, token)
+            )
+            .unwrap_or(SlotLayout::Call)
+        {
+            SlotLayout::Mapping(slot) => slot.load_balance(user, db),
+            SlotLayout::Call => call_balance_of(db, token, user, &HashMap::new())
+        };
+
+        balance.unwrap_or_default()
     }
 
     pub fn fetch_balance_in_angstrom<DB: revm::DatabaseRef>(
@@ -1,10 +1,13 @@
-// Given that the gas spec when fuzzing balances runs off the assumption
-// that all tokens are ERC-20 which all are written in solidity and don't
-// pack the balance / approval slots. we align to the same assumptions here.
-use std::fmt::Debug;
+// Tokens behind proxies, written in vyper or keeping their storage anywhere
+// other than the first few slots can't be found by guessing offsets. Instead
+// we trace the `balanceOf` / `allowance` call, pick the storage reads that
+// hash our probe address into a mapping slot and verify that overriding the
+// slot changes what the token reports. Anything we can't verify this way is
+// read by calling the token.
+use std::{collections::HashMap, fmt::Debug};
 
 use alloy::{
-    primitives::{keccak256, Address, U256},
+    primitives::{Address, B256, U256},
     sol_types::*
 };
 use angstrom_types::contract_bindings::mintable_mock_erc_20::MintableMockERC20::{
@@ -12,96 +15,493 @@ use angstrom_types::contract_bindings::mintable_mock_erc_20::MintableMockERC20::
 };
 use revm::{
     db::CacheDB,
-    primitives::{EnvWithHandlerCfg, TxKind}
+    inspector_handle_register,
+    interpreter::{opcode, Interpreter},
+    primitives::{EnvWithHandlerCfg, TxKind},
+    Database, EvmContext, Inspector
 };
 
-/// panics if we cannot find the slot for the given token
-pub fn find_slot_offset_for_balance<DB: revm::DatabaseRef>(db: &DB, token_address: Address) -> u64
+use crate::order::state::config::{HashMethod, SlotLayout, TokenApprovalSlot, TokenBalanceSlot};
+
+/// Written to a candidate slot to check that it is the one the token reads.
+/// Uses the full word so that packed values don't pass
+const SENTINEL: U256 = U256::from_limbs([
+    0x0123_4567_89ab_cdef,
+    0xfedc_ba98_7654_3210,
+    0x0f1e_2d3c_4b5a_6978,
+    0x8796_a5b4_c3d2_e1f0
+]);
+
+/// Why a call to a token didn't give us a value
+#[derive(Debug, thiserror::Error)]
+pub enum TokenCallError {
+    /// the call couldn't be run, e.g. because the database failed. Says
+    /// nothing about the token
+    #[error("failed to call token {0}: {1}")]
+    Execution(Address, String),
+    /// the token reverted or returned something we can't decode
+    #[error("call to token {0} failed: {1}")]
+    Token(Address, String)
+}
+
+/// Records the storage reads of a call and the preimages of the mapping slots
+/// it hashed
+#[derive(Debug, Default)]
+struct StorageReadInspector {
+    /// the account and slot of every `SLOAD`, in execution order
+    reads:     Vec<(Address, U256)>,
+    /// 64 byte `KECCAK256` inputs by their output
+    preimages: HashMap<U256, [u8; 64]>,
+    /// input of the `KECCAK256` that is currently executing
+    pending:   Option<[u8; 64]>
+}
+
+impl StorageReadInspector {
+    /// splits a mapping slot into the key and the slot of the mapping, given
+    /// the key we expect to find in it
+    fn mapping_key(&self, slot: U256, key: B256) -> Option<(HashMethod, U256)> {
+        let preimage = self.preimages.get(&slot)?;
+        let (first, second) = (&preimage[..32], &preimage[32..]);
+
+        if first == key.as_slice() {
+            Some((HashMethod::Solidity, U256::from_be_slice(second)))
+        } else if second == key.as_slice() {
+            Some((HashMethod::Vyper, U256::from_be_slice(first)))
+        } else {
+            None
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for StorageReadInspector {
+    fn step(&mut self, interp: &mut Interpreter, _: &mut EvmContext<DB>) {
+        match interp.current_opcode() {
+            opcode::SLOAD => {
+                if let Ok(slot) = interp.stack().peek(0) {
+                    self.reads.push((interp.contract().target_address, slot));
+                }
+            }
+            opcode::KECCAK256 => {
+                let (Ok(offset), Ok(len)) = (interp.stack().peek(0), interp.stack().peek(1)) else {
+                    return
+                };
+                // mapping slots are always the hash of two words
+                if len != U256::from(64) {
+                    return
+                }
+                let offset = offset.saturating_to::<usize>();
+                if offset.saturating_add(64) > interp.shared_memory.len() {
+                    return
+                }
+
+                let mut preimage = [0u8; 64];
+                preimage.copy_from_slice(interp.shared_memory.slice(offset, 64));
+                self.pending = Some(preimage);
+            }
+            _ => {}
+        }
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, _: &mut EvmContext<DB>) {
+        let Some(preimage) = self.pending.take() else { return };
+        if let Ok(hash) = interp.stack().peek(0) {
+            self.preimages.insert(hash, preimage);
+        }
+    }
+}
+
+/// Calls `token` with `call`, optionally recording what it reads
+fn call_token<DB: revm::DatabaseRef, C: SolCall>(
+    db: DB,
+    token: Address,
+    call: C,
+    inspector: Option<&mut StorageReadInspector>
+) -> Result<C::Return, TokenCallError>
+where
+    <DB as revm::DatabaseRef>::Error: Debug
+{
+    let mut default_inspector = StorageReadInspector::default();
+    let mut evm = revm::Evm::builder()
+        .with_external_context(inspector.unwrap_or(&mut default_inspector))
+        .with_ref_db(db)
+        .with_env_with_handler_cfg(EnvWithHandlerCfg::default())
+        .append_handler_register(inspector_handle_register)
+        .modify_env(|env| {
+            env.cfg.disable_balance_check = true;
+        })
+        .modify_tx_env(|tx| {
+            tx.caller = Address::ZERO;
+            tx.transact_to = TxKind::Call(token);
+            tx.data = call.abi_encode().into();
+            tx.value = U256::ZERO;
+            tx.nonce = None;
+        })
+        .build();
+
+    let result = evm
+        .transact()
+        .map_err(|e| TokenCallError::Execution(token, format!("{e:?}")))?
+        .result;
+    if !result.is_success() {
+        return Err(TokenCallError::Token(token, format!("{result:?}")))
+    }
+
+    let output = result
+        .output()
+        .map(|output| output.as_ref())
+        .unwrap_or_default();
+    C::abi_decode_returns(output, false).map_err(|e| TokenCallError::Token(token, e.to_string()))
+}
+
+/// Tracing a token that isn't deployed yet, or while the database is failing,
+/// tells us nothing about its layout, so these are errors instead of
+/// [`SlotLayout::Call`]
+fn ensure_deployed<DB: revm::DatabaseRef>(db: &DB, token: Address) -> eyre::Result<()>
+where
+    <DB as revm::DatabaseRef>::Error: Debug
+{
+    match db.basic_ref(token) {
+        Ok(Some(info)) if !info.is_empty_code_hash() => Ok(()),
+        Ok(_) => eyre::bail!("token {token} is not deployed"),
+        Err(e) => eyre::bail!("failed to load token {token}: {e:?}")
+    }
+}
+
+/// `balanceOf(user)` of the token, with the given storage overrides applied
+pub fn call_balance_of<DB: revm::DatabaseRef>(
+    db: &DB,
+    token: Address,
+    user: Address,
+    overrides: &HashMap<Address, HashMap<U256, U256>>
+) -> eyre::Result<U256>
 where
     <DB as revm::DatabaseRef>::Error: Debug
 {
-    let probe_address = Address::random();
+    let db = with_overrides(db, overrides)?;
+    Ok(call_token(&db, token, balanceOfCall::new((user,)), None)?._0)
+}
+
+/// `allowance(user, contract)` of the token, with the given storage overrides
+/// applied
+pub fn call_allowance<DB: revm::DatabaseRef>(
+    db: &DB,
+    token: Address,
+    user: Address,
+    contract: Address,
+    overrides: &HashMap<Address, HashMap<U256, U256>>
+) -> eyre::Result<U256>
+where
+    <DB as revm::DatabaseRef>::Error: Debug
+{
+    let db = with_overrides(db, overrides)?;
+    Ok(call_token(&db, token, allowanceCall::new((user, contract)), None)?._0)
+}
 
+fn with_overrides<'a, DB: revm::DatabaseRef>(
+    db: &'a DB,
+    overrides: &HashMap<Address, HashMap<U256, U256>>
+) -> eyre::Result<CacheDB<&'a DB>>
+where
+    <DB as revm::DatabaseRef>::Error: Debug
+{
     let mut db = CacheDB::new(db);
-    let evm_handler = EnvWithHandlerCfg::default();
+    for (address, slots) in overrides {
+        for (slot, value) in slots {
+            db.insert_account_storage(*address, *slot, *value)
+                .map_err(|e| eyre::eyre!("failed to override storage of {address}: {e:?}"))?;
+        }
+    }
 
-    // check the first 100 offsets
-    for offset in 0..100 {
-        // set balance
-        let balance_slot = keccak256((probe_address, offset as u64).abi_encode());
-        db.insert_account_storage(token_address, balance_slot.into(), U256::from(123456789))
-            .unwrap();
-        // execute revm to see if we hit the slot
-        let mut evm = revm::Evm::builder()
-            .with_ref_db(db.clone())
-            .with_env_with_handler_cfg(evm_handler.clone())
-            .modify_env(|env| {
-                env.cfg.disable_balance_check = true;
-            })
-            .modify_tx_env(|tx| {
-                tx.caller = probe_address;
-                tx.transact_to = TxKind::Call(token_address);
-                tx.data = balanceOfCall::new((probe_address,)).abi_encode().into();
-                tx.value = U256::from(0);
-                tx.nonce = None;
-            })
-            .build();
-
-        let output = evm.transact().unwrap().result.output().unwrap().to_vec();
-        let return_data = balanceOfCall::abi_decode_returns(&output, false).unwrap();
-        if return_data._0 == U256::from(123456789) {
-            return offset as u64
+    Ok(db)
+}
+
+/// checks that the token still reads balances from the given slot, e.g. after
+/// it was upgraded since the slot was found
+pub fn verify_balance_slot<DB: revm::DatabaseRef>(
+    db: &DB,
+    token: Address,
+    slot: &TokenBalanceSlot
+) -> bool
+where
+    <DB as revm::DatabaseRef>::Error: Debug
+{
+    reads_balance_from(db, token, slot, Address::random())
+}
+
+/// checks that the token still reads allowances from the given slot
+pub fn verify_approval_slot<DB: revm::DatabaseRef>(
+    db: &DB,
+    token: Address,
+    slot: &TokenApprovalSlot
+) -> bool
+where
+    <DB as revm::DatabaseRef>::Error: Debug
+{
+    reads_allowance_from(db, token, slot, Address::random(), Address::random())
+}
+
+/// overrides the probe's balance slot with the sentinel and checks that
+/// `balanceOf` returns it
+fn reads_balance_from<DB: revm::DatabaseRef>(
+    db: &DB,
+    token: Address,
+    slot: &TokenBalanceSlot,
+    probe: Address
+) -> bool
+where
+    <DB as revm::DatabaseRef>::Error: Debug
+{
+    let overrides =
+        HashMap::from([(token, HashMap::from([(slot.generate_slot(probe), SENTINEL)]))]);
+    call_balance_of(db, token, probe, &overrides).is_ok_and(|balance| balance == SENTINEL)
+}
+
+/// overrides the probe's allowance slot with the sentinel and checks that
+/// `allowance` returns it
+fn reads_allowance_from<DB: revm::DatabaseRef>(
+    db: &DB,
+    token: Address,
+    slot: &TokenApprovalSlot,
+    probe_user: Address,
+    probe_contract: Address
+) -> bool
+where
+    <DB as revm::DatabaseRef>::Error: Debug
+{
+    let overrides = HashMap::from([(
+        token,
+        HashMap::from([(slot.generate_slot(probe_user, probe_contract), SENTINEL)])
+    )]);
+    call_allowance(db, token, probe_user, probe_contract, &overrides)
+        .is_ok_and(|allowance| allowance == SENTINEL)
+}
+
+/// Finds where the token stores balances by tracing `balanceOf`. Falls back
+/// to calling the token if no plain mapping slot can be found. Errors if the
+/// token couldn't be traced at all
+pub fn find_balance_slot<DB: revm::DatabaseRef>(
+    db: &DB,
+    token: Address
+) -> eyre::Result<SlotLayout<TokenBalanceSlot>>
+where
+    <DB as revm::DatabaseRef>::Error: Debug
+{
+    ensure_deployed(db, token)?;
+    let probe = Address::random();
+
+    let mut inspector = StorageReadInspector::default();
+    match call_token(db, token, balanceOfCall::new((probe,)), Some(&mut inspector)) {
+        Ok(_) => {}
+        Err(e @ TokenCallError::Execution(..)) => return Err(e.into()),
+        Err(e) => {
+            tracing::warn!(?token, %e, "failed to trace balanceOf, reading balances through calls");
+            return Ok(SlotLayout::Call)
         }
     }
 
-    panic!("was not able to find balance offset");
+    let found = inspector
+        .reads
+        .iter()
+        .filter(|(account, _)| *account == token)
+        .filter_map(|(_, slot)| inspector.mapping_key(*slot, probe.into_word()))
+        .map(|(hash_method, slot_index)| TokenBalanceSlot::new(token, hash_method, slot_index))
+        .find(|candidate| reads_balance_from(db, token, candidate, probe));
+
+    Ok(match found {
+        Some(slot) => SlotLayout::Mapping(slot),
+        None => {
+            tracing::info!(?token, "no balance mapping found, reading balances through calls");
+            SlotLayout::Call
+        }
+    })
 }
 
-/// panics if we cannot prove the slot for the given token
-pub fn find_slot_offset_for_approval<DB: revm::DatabaseRef>(db: &DB, token_address: Address) -> u64
+/// Finds where the token stores allowances by tracing `allowance`. Falls back
+/// to calling the token if no plain nested mapping slot can be found. Errors if
+/// the token couldn't be traced at all
+pub fn find_approval_slot<DB: revm::DatabaseRef>(
+    db: &DB,
+    token: Address
+) -> eyre::Result<SlotLayout<TokenApprovalSlot>>
 where
     <DB as revm::DatabaseRef>::Error: Debug
 {
-    let probe_user_address = Address::random();
-    let probe_contract_address = Address::random();
+    ensure_deployed(db, token)?;
+    let probe_user = Address::random();
+    let probe_contract = Address::random();
 
-    let mut db = CacheDB::new(db);
-    let evm_handler = EnvWithHandlerCfg::default();
-
-    // check the first 100 offsets
-    for offset in 0..100 {
-        // set approval
-        let approval_slot = keccak256(
-            (probe_contract_address, keccak256((probe_user_address, offset as u64).abi_encode()))
-                .abi_encode()
+    let mut inspector = StorageReadInspector::default();
+    match call_token(
+        db,
+        token,
+        allowanceCall::new((probe_user, probe_contract)),
+        Some(&mut inspector)
+    ) {
+        Ok(_) => {}
+        Err(e @ TokenCallError::Execution(..)) => return Err(e.into()),
+        Err(e) => {
+            tracing::warn!(?token, %e, "failed to trace allowance, reading allowances through calls");
+            return Ok(SlotLayout::Call)
+        }
+    }
+
+    let found = inspector
+        .reads
+        .iter()
+        .filter(|(account, _)| *account == token)
+        .filter_map(|(_, slot)| {
+            // `allowance[user][contract]`, the outer mapping is keyed by the contract
+            let (outer_method, inner) = inspector.mapping_key(*slot, probe_contract.into_word())?;
+            let (inner_method, slot_index) =
+                inspector.mapping_key(inner, probe_user.into_word())?;
+
+            (outer_method == inner_method).then_some((outer_method, slot_index))
+        })
+        .map(|(hash_method, slot_index)| TokenApprovalSlot::new(token, hash_method, slot_index))
+        .find(|candidate| reads_allowance_from(db, token, candidate, probe_user, probe_contract));
+
+    Ok(match found {
+        Some(slot) => SlotLayout::Mapping(slot),
+        None => {
+            tracing::info!(?token, "no allowance mapping found, reading allowances through calls");
+            SlotLayout::Call
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{hex, keccak256, Bytes};
+    use revm::{
+        db::EmptyDB,
+        primitives::{AccountInfo, Bytecode}
+    };
+
+    use super::*;
+
+    /// `balanceOf(a)` returning `sload(keccak256(3 . a))`, how vyper lays out
+    /// `balanceOf: HashMap[address, uint256]` at slot 3
+    const VYPER_BALANCE: &[u8] = &hex!(
+        "6003600052" // mstore(0, 3)
+        "600435602052" // mstore(32, calldataload(4))
+        "6040600020" // keccak256(0, 64)
+        "54" // sload
+        "60005260206000f3" // return the word
+    );
+
+    /// `allowance(o, s)` returning `sload(keccak256(s . keccak256(o . 1)))`,
+    /// solidity's `mapping(address => mapping(address => uint256))` at slot 1
+    const SOLIDITY_ALLOWANCE: &[u8] = &hex!(
+        "600435600052" // mstore(0, calldataload(4))
+        "6001602052" // mstore(32, 1)
+        "6040600020602052" // mstore(32, keccak256(0, 64))
+        "602435600052" // mstore(0, calldataload(36))
+        "6040600020" // keccak256(0, 64)
+        "54" // sload
+        "60005260206000f3" // return the word
+    );
+
+    /// like [`VYPER_BALANCE`] but halves the stored value, the way a rebasing
+    /// token converts shares
+    const SHARES_BALANCE: &[u8] = &hex!(
+        "6003600052"
+        "600435602052"
+        "6040600020"
+        "54"
+        "60029004" // div(sload(..), 2)
+        "60005260206000f3"
+    );
+
+    fn deploy(code: &'static [u8]) -> (CacheDB<EmptyDB>, Address) {
+        let token = Address::random();
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            token,
+            AccountInfo {
+                code_hash: keccak256(code),
+                code: Some(Bytecode::new_raw(Bytes::from_static(code))),
+                ..Default::default()
+            }
         );
 
-        db.insert_account_storage(token_address, approval_slot.into(), U256::from(123456789))
+        (db, token)
+    }
+
+    #[test]
+    fn finds_vyper_balance_slot() {
+        let (mut db, token) = deploy(VYPER_BALANCE);
+        let Ok(SlotLayout::Mapping(slot)) = find_balance_slot(&db, token) else {
+            panic!("balance slot not found")
+        };
+        assert_eq!(slot.hash_method, HashMethod::Vyper);
+        assert_eq!(slot.slot_index, U256::from(3));
+
+        let user = Address::random();
+        db.insert_account_storage(token, slot.generate_slot(user), U256::from(42))
             .unwrap();
-        // execute revm to see if we hit the slot
-        let mut evm = revm::Evm::builder()
-            .with_ref_db(db.clone())
-            .with_env_with_handler_cfg(evm_handler.clone())
-            .modify_env(|env| {
-                env.cfg.disable_balance_check = true;
-            })
-            .modify_tx_env(|tx| {
-                tx.caller = probe_user_address;
-                tx.transact_to = TxKind::Call(token_address);
-                tx.data = allowanceCall::new((probe_user_address, probe_contract_address))
-                    .abi_encode()
-                    .into();
-                tx.value = U256::from(0);
-                tx.nonce = None;
-            })
-            .build();
-
-        let output = evm.transact().unwrap().result.output().unwrap().to_vec();
-        let return_data = allowanceCall::abi_decode_returns(&output, false).unwrap();
-        if return_data._0 == U256::from(123456789) {
-            return offset as u64
-        }
+        assert_eq!(slot.load_balance(user, &db).unwrap(), U256::from(42));
+        assert_eq!(call_balance_of(&db, token, user, &HashMap::new()).unwrap(), U256::from(42));
     }
 
-    panic!("was not able to find approval offset");
+    #[test]
+    fn finds_nested_approval_slot() {
+        let (db, token) = deploy(SOLIDITY_ALLOWANCE);
+        let Ok(SlotLayout::Mapping(slot)) = find_approval_slot(&db, token) else {
+            panic!("approval slot not found")
+        };
+        assert_eq!(slot.hash_method, HashMethod::Solidity);
+        assert_eq!(slot.slot_index, U256::from(1));
+
+        let (user, contract) = (Address::random(), Address::random());
+        let overrides = HashMap::from([(
+            token,
+            HashMap::from([(slot.generate_slot(user, contract), U256::from(7))])
+        )]);
+        assert_eq!(call_allowance(&db, token, user, contract, &overrides).unwrap(), U256::from(7));
+        // the other way around is a different allowance
+        assert_eq!(call_allowance(&db, token, contract, user, &overrides).unwrap(), U256::ZERO);
+    }
+
+    #[test]
+    fn verifies_balance_slot_against_current_code() {
+        let (mut db, token) = deploy(VYPER_BALANCE);
+        let slot = TokenBalanceSlot::new(token, HashMethod::Vyper, U256::from(3));
+        assert!(verify_balance_slot(&db, token, &slot));
+        assert!(!verify_balance_slot(
+            &db,
+            token,
+            &TokenBalanceSlot::new(token, HashMethod::Solidity, U256::from(3))
+        ));
+
+        // the token got upgraded to compute balances
+        db.insert_account_info(
+            token,
+            AccountInfo {
+                code_hash: keccak256(SHARES_BALANCE),
+                code: Some(Bytecode::new_raw(Bytes::from_static(SHARES_BALANCE))),
+                ..Default::default()
+            }
+        );
+        assert!(!verify_balance_slot(&db, token, &slot));
+    }
+
+    #[test]
+    fn computed_balances_are_read_through_calls() {
+        let (mut db, token) = deploy(SHARES_BALANCE);
+        assert_eq!(find_balance_slot(&db, token).unwrap(), SlotLayout::Call);
+
+        let user = Address::random();
+        let shares_slot = HashMethod::Vyper.mapping_slot(user.into_word(), U256::from(3));
+        db.insert_account_storage(token, shares_slot, U256::from(100))
+            .unwrap();
+        assert_eq!(call_balance_of(&db, token, user, &HashMap::new()).unwrap(), U256::from(50));
+    }
+
+    #[test]
+    fn missing_token_has_no_layout() {
+        let db = CacheDB::new(EmptyDB::default());
+        // it may be deployed later, so this must not be taken for a computed balance
+        assert!(find_balance_slot(&db, Address::random()).is_err());
+        assert!(find_approval_slot(&db, Address::random()).is_err());
+    }
 }
@@ -0,0 +1,254 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::Arc
+};
+
+use alloy::primitives::Address;
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::order::state::config::{SlotLayout, TokenApprovalSlot, TokenBalanceSlot};
+
+#[derive(Debug, Serialize, Deserialize)]
+enum LayoutEntry {
+    Balance(Address, SlotLayout<TokenBalanceSlot>),
+    Approval(Address, SlotLayout<TokenApprovalSlot>)
+}
+
+/// The balance and approval layouts of the tokens we have seen so far. When
+/// backed by a file, discovered layouts are appended to it so that they don't
+/// have to be traced again after a restart.
+///
+/// A token can be upgraded while we are down, so layouts loaded from the file
+/// are checked again the first time they are used. A mapping slot that no
+/// longer holds the balance and every `Call` layout are discovered again.
+#[derive(Debug, Clone, Default)]
+pub struct TokenLayoutStore {
+    balances:            Arc<DashMap<Address, SlotLayout<TokenBalanceSlot>>>,
+    approvals:           Arc<DashMap<Address, SlotLayout<TokenApprovalSlot>>>,
+    /// layouts loaded from the file that weren't checked yet
+    persisted_balances:  Arc<DashMap<Address, SlotLayout<TokenBalanceSlot>>>,
+    persisted_approvals: Arc<DashMap<Address, SlotLayout<TokenApprovalSlot>>>,
+    file:                Arc<Mutex<Option<(PathBuf, File)>>>
+}
+
+impl TokenLayoutStore {
+    /// opens the store at the given path, creating it if it doesn't exist
+    pub fn open(path: PathBuf) -> eyre::Result<Self> {
+        let this = Self::default();
+        if path.exists() {
+            for entry in Self::read_entries(&path)? {
+                match entry {
+                    LayoutEntry::Balance(token, layout) => {
+                        this.persisted_balances.insert(token, layout);
+                    }
+                    LayoutEntry::Approval(token, layout) => {
+                        this.persisted_approvals.insert(token, layout);
+                    }
+                }
+            }
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        tracing::info!(
+            ?path,
+            balances = this.persisted_balances.len(),
+            approvals = this.persisted_approvals.len(),
+            "opened token layouts"
+        );
+        *this.file.lock() = Some((path, file));
+
+        Ok(this)
+    }
+
+    /// the balance layout of the token, running `discover` until it succeeds
+    /// for the token. A mapping slot loaded from the file is only used if
+    /// `verify` confirms the token still reads balances from it
+    pub fn balance_layout(
+        &self,
+        token: Address,
+        verify: impl FnOnce(&TokenBalanceSlot) -> bool,
+        discover: impl FnOnce() -> eyre::Result<SlotLayout<TokenBalanceSlot>>
+    ) -> eyre::Result<SlotLayout<TokenBalanceSlot>> {
+        Self::layout(&self.balances, &self.persisted_balances, token, verify, discover, |layout| {
+            self.append(LayoutEntry::Balance(token, layout))
+        })
+    }
+
+    /// the approval layout of the token, running `discover` until it succeeds
+    /// for the token. A mapping slot loaded from the file is only used if
+    /// `verify` confirms the token still reads allowances from it
+    pub fn approval_layout(
+        &self,
+        token: Address,
+        verify: impl FnOnce(&TokenApprovalSlot) -> bool,
+        discover: impl FnOnce() -> eyre::Result<SlotLayout<TokenApprovalSlot>>
+    ) -> eyre::Result<SlotLayout<TokenApprovalSlot>> {
+        Self::layout(
+            &self.approvals,
+            &self.persisted_approvals,
+            token,
+            verify,
+            discover,
+            |layout| self.append(LayoutEntry::Approval(token, layout))
+        )
+    }
+
+    fn layout<S: Clone>(
+        layouts: &DashMap<Address, SlotLayout<S>>,
+        persisted: &DashMap<Address, SlotLayout<S>>,
+        token: Address,
+        verify: impl FnOnce(&S) -> bool,
+        discover: impl FnOnce() -> eyre::Result<SlotLayout<S>>,
+        record: impl FnOnce(SlotLayout<S>)
+    ) -> eyre::Result<SlotLayout<S>> {
+        if let Some(layout) = layouts.get(&token) {
+            return Ok(layout.clone())
+        }
+
+        if let Some((_, layout)) = persisted.remove(&token) {
+            match layout {
+                SlotLayout::Mapping(slot) if verify(&slot) => {
+                    let layout = SlotLayout::Mapping(slot);
+                    layouts.insert(token, layout.clone());
+                    return Ok(layout)
+                }
+                SlotLayout::Mapping(_) => {
+                    tracing::info!(?token, "stored token layout is stale, discovering it again");
+                }
+                // the token may have moved to a plain mapping since
+                SlotLayout::Call => {}
+            }
+        }
+
+        let layout = discover()?;
+        layouts.insert(token, layout.clone());
+        record(layout.clone());
+
+        Ok(layout)
+    }
+
+    fn append(&self, entry: LayoutEntry) {
+        let mut file = self.file.lock();
+        let Some((path, file)) = file.as_mut() else { return };
+
+        if let Err(e) = Self::write_entry(file, &entry) {
+            tracing::error!(%e, ?path, "failed to write to token layouts");
+        }
+    }
+
+    fn write_entry(file: &mut File, entry: &LayoutEntry) -> eyre::Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        file.write_all(line.as_bytes())?;
+
+        Ok(())
+    }
+
+    fn read_entries(path: &Path) -> eyre::Result<Vec<LayoutEntry>> {
+        let mut entries = Vec::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            // a crash can leave a partially written last line
+            let Ok(entry) = serde_json::from_str::<LayoutEntry>(&line) else {
+                tracing::warn!(?path, "skipping corrupt token layout entry");
+                continue
+            };
+            entries.push(entry);
+        }
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::U256;
+
+    use super::*;
+    use crate::order::state::config::HashMethod;
+
+    fn rediscovered<T>() -> eyre::Result<T> {
+        panic!("layout was discovered again")
+    }
+
+    fn unverified<S>(_: &S) -> bool {
+        panic!("layout was verified")
+    }
+
+    #[test]
+    fn test_layouts_survive_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("layouts.jsonl");
+        let (token, rebasing) = (Address::random(), Address::random());
+        let slot = TokenBalanceSlot::new(token, HashMethod::Vyper, U256::from(3));
+
+        let store = TokenLayoutStore::open(path.clone()).unwrap();
+        store
+            .balance_layout(token, unverified, || Ok(SlotLayout::Mapping(slot.clone())))
+            .unwrap();
+        store
+            .balance_layout(rebasing, unverified, || Ok(SlotLayout::Call))
+            .unwrap();
+        drop(store);
+
+        let reopened = TokenLayoutStore::open(path.clone()).unwrap();
+        // a slot that still holds the balance is checked once and kept
+        assert_eq!(
+            reopened
+                .balance_layout(token, |_| true, rediscovered)
+                .unwrap(),
+            SlotLayout::Mapping(slot.clone())
+        );
+        assert_eq!(
+            reopened
+                .balance_layout(token, unverified, rediscovered)
+                .unwrap(),
+            SlotLayout::Mapping(slot.clone())
+        );
+        // computed balances are traced again, the token may have changed
+        assert_eq!(
+            reopened
+                .balance_layout(rebasing, unverified, || Ok(SlotLayout::Mapping(slot.clone())))
+                .unwrap(),
+            SlotLayout::Mapping(slot.clone())
+        );
+        drop(reopened);
+
+        // the token got upgraded and no longer reads the slot
+        let reopened = TokenLayoutStore::open(path).unwrap();
+        assert_eq!(
+            reopened
+                .balance_layout(token, |_| false, || Ok(SlotLayout::Call))
+                .unwrap(),
+            SlotLayout::Call
+        );
+        // the rediscovered layout replaced the stored one
+        assert_eq!(
+            reopened
+                .balance_layout(rebasing, |_| true, rediscovered)
+                .unwrap(),
+            SlotLayout::Mapping(slot)
+        );
+    }
+
+    #[test]
+    fn test_failed_discovery_is_retried() {
+        let store = TokenLayoutStore::default();
+        let token = Address::random();
+
+        assert!(store
+            .balance_layout(token, || Err(eyre::eyre!("token is not deployed")))
+            .is_err());
+        // once it is deployed we find out what it really is
+        assert_eq!(
+            store
+                .balance_layout(token, || Ok(SlotLayout::Call))
+                .unwrap(),
+            SlotLayout::Call
+        );
+    }
+}
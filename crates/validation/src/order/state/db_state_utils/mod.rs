@@ -3,13 +3,14 @@ pub mod balances;
 pub mod nonces;
 
 pub mod finders;
+pub mod layouts;
 
 use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
 use alloy::primitives::{Address, U256};
 use angstrom_metrics::validation::ValidationMetrics;
 
-use self::{approvals::Approvals, balances::Balances, nonces::Nonces};
+use self::{approvals::Approvals, balances::Balances, layouts::TokenLayoutStore, nonces::Nonces};
 
 pub trait StateFetchUtils: Clone + Send + Unpin {
     fn is_valid_nonce(&self, user: Address, nonce: u64) -> bool;
@@ -112,10 +113,10 @@ where
 }
 
 impl<DB: revm::DatabaseRef> FetchUtils<DB> {
-    pub fn new(angstrom_address: Address, db: Arc<DB>) -> Self {
+    pub fn new(angstrom_address: Address, db: Arc<DB>, token_layouts: TokenLayoutStore) -> Self {
         Self {
-            approvals: Approvals::new(angstrom_address),
-            balances: Balances::new(angstrom_address),
+            approvals: Approvals::new(angstrom_address, token_layouts.clone()),
+            balances: Balances::new(angstrom_address, token_layouts),
             nonces: Nonces::new(angstrom_address),
             db,
             metrics: ValidationMetrics::new()
@@ -3,8 +3,9 @@ use std::sync::Arc;
 use account::UserAccountProcessor;
 use alloy::primitives::{Address, B256};
 use angstrom_metrics::validation::ValidationMetrics;
-use angstrom_types::sol_bindings::{
-    ext::RawPoolOrder, grouped_orders::AllOrders, rpc_orders::TopOfBlockOrder
+use angstrom_types::{
+    primitive::OrderRejectionReason,
+    sol_bindings::{ext::RawPoolOrder, grouped_orders::AllOrders, rpc_orders::TopOfBlockOrder}
 };
 use db_state_utils::StateFetchUtils;
 use parking_lot::RwLock;
@@ -71,12 +72,18 @@ impl<Pools: PoolsTracker, Fetch: StateFetchUtils> StateValidation<Pools, Fetch>
             let order_hash = order.order_hash();
             if !order.is_valid_signature() {
                 tracing::debug!("order had invalid hash");
-                return OrderValidationResults::Invalid(order_hash)
+                return OrderValidationResults::Invalid(
+                    order_hash,
+                    OrderRejectionReason::InvalidSignature
+                )
             }
 
             let Some(pool_info) = self.pool_tacker.read().fetch_pool_info_for_order(&order) else {
                 tracing::debug!("order requested a invalid pool");
-                return OrderValidationResults::Invalid(order_hash);
+                return OrderValidationResults::Invalid(
+                    order_hash,
+                    OrderRejectionReason::UnknownPool
+                );
             };
 
             self.user_account_tracker
@@ -88,7 +95,7 @@ impl<Pools: PoolsTracker, Fetch: StateFetchUtils> StateValidation<Pools, Fetch>
                 })
                 .unwrap_or_else(|e| {
                     tracing::debug!(%e,"user acount tracker failed to validate order");
-                    OrderValidationResults::Invalid(order_hash)
+                    OrderValidationResults::Invalid(order_hash, e.rejection_reason())
                 })
         })
     }
@@ -110,6 +117,14 @@ impl<Pools: PoolsTracker, Fetch: StateFetchUtils> StateValidation<Pools, Fetch>
                 })
                 .expect("should be unreachable");
             let pool_address = order_with_storage.pool_id;
+            // pools configured at runtime only get synced a little after they are
+            // configured on chain
+            if !self.uniswap_pools.contains_key(&pool_address) {
+                return OrderValidationResults::Invalid(
+                    order_with_storage.order_id.hash,
+                    OrderRejectionReason::UnknownPool
+                )
+            }
             let rewards = self
                 .uniswap_pools
                 .calculate_rewards(pool_address, &tob_order)
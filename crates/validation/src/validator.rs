@@ -1,7 +1,11 @@
-use std::{fmt::Debug, task::Poll};
+use std::{collections::VecDeque, fmt::Debug, task::Poll};
 
 use alloy::primitives::{Address, B256};
-use angstrom_types::contract_payloads::angstrom::{AngstromBundle, BundleGasDetails};
+use angstrom_types::{
+    contract_payloads::angstrom::{AngstromBundle, BundleGasDetails},
+    pair_with_price::PairsWithPrice,
+    primitive::PoolId
+};
 use futures_util::{Future, FutureExt};
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
@@ -29,6 +33,17 @@ pub enum ValidationRequest {
         block_number: u64,
         orders:       Vec<B256>,
         addresses:    Vec<Address>
+    },
+    /// a pool was configured after startup, `prices` seeds the history its gas
+    /// conversion price is averaged over
+    NewPool {
+        pool_id: PoolId,
+        token0:  Address,
+        token1:  Address,
+        prices:  VecDeque<PairsWithPrice>
+    },
+    RemovedPool {
+        pool_id: PoolId
     }
 }
 
@@ -91,6 +106,15 @@ where
                     .send(OrderValidationResults::TransitionedToBlock)
                     .unwrap();
             }
+            ValidationRequest::NewPool { pool_id, token0, token1, prices } => {
+                tracing::debug!(?pool_id, "pricing gas for new pool");
+                self.utils
+                    .token_pricing
+                    .add_pool(pool_id, token0, token1, prices);
+            }
+            ValidationRequest::RemovedPool { pool_id } => {
+                self.utils.token_pricing.remove_pool(pool_id);
+            }
         }
     }
 }
@@ -197,7 +197,7 @@ mod tests {
         contract_payloads::angstrom::{AngstromBundle, BundleGasDetails, UserOrder},
         matching::{uniswap::LiqRange, SqrtPriceX96},
         orders::{OrderFillState, OrderOutcome},
-        primitive::{AngstromSigner, ANGSTROM_DOMAIN},
+        primitive::{angstrom_domain, AngstromSigner},
         sol_bindings::{
             grouped_orders::{GroupedVanillaOrder, OrderWithStorageData, StandingVariants},
             rpc_orders::OmitOrderMeta
@@ -251,7 +251,7 @@ mod tests {
             ),
             meta:                 Default::default()
         };
-        let hash = default.no_meta_eip712_signing_hash(&ANGSTROM_DOMAIN);
+        let hash = default.no_meta_eip712_signing_hash(&angstrom_domain());
         let sig = user.sign_hash_sync(&hash).unwrap();
         default.meta.isEcdsa = true;
         default.meta.from = address;
@@ -1,9 +1,19 @@
-use std::{collections::HashSet, pin::Pin};
+use std::{collections::HashSet, pin::Pin, time::Duration};
 
-use alloy::providers::ext::AnvilApi;
+use alloy::{eips::BlockId, providers::ext::AnvilApi};
 use alloy_primitives::U256;
-use angstrom_types::{block_sync::GlobalBlockSync, testnet::InitialTestnetState};
+use angstrom_types::{
+    block_sync::GlobalBlockSync,
+    contract_payloads::angstrom::AngstromPoolConfigStore,
+    matching::Ray,
+    orders::{OrderOrigin, OrderStatus},
+    primitive::{AngstromSigner, PoolId},
+    sol_bindings::grouped_orders::AllOrders,
+    testnet::InitialTestnetState
+};
+use consensus::ETH_BLOCK_TIME;
 use futures::Future;
+use order_pool::OrderPoolHandle;
 use reth_chainspec::Hardforks;
 use reth_provider::{BlockReader, ChainSpecProvider, HeaderProvider, ReceiptProvider};
 
@@ -12,12 +22,21 @@ use crate::{
     agents::AgentConfig,
     controllers::{enviroments::DevnetStateMachine, strom::TestnetNode},
     providers::{AnvilInitializer, AnvilProvider, TestnetBlockProvider, WalletProvider},
+    type_generator::orders::UserOrderBuilder,
     types::{
         config::{DevnetConfig, TestingNodeConfig},
+        initial_state::PartialConfigPoolKey,
         GlobalTestingConfig
     }
 };
 
+/// what the controller gets of both tokens of a pool configured mid run
+const NEW_POOL_CONTROLLER_FUNDS: u128 = 1_000_000_000_000_000_000_000;
+/// the amount of token0 the controller sells on a pool configured mid run
+const NEW_POOL_ORDER_AMOUNT: u128 = 1_000_000_000_000_000;
+/// how often we check for the ticks of a new pool, 500ms apart
+const MAX_POOL_SYNC_ATTEMPTS: usize = 40;
+
 impl<C> AngstromTestnet<C, DevnetConfig, WalletProvider>
 where
     C: BlockReader<Block = reth_primitives::Block>
@@ -37,7 +56,8 @@ where
             current_max_peer_id: 0,
             config: config.clone(),
             block_provider,
-            _anvil_instance: None
+            _anvil_instance: None,
+            angstrom_state: None
         };
 
         tracing::info!("initializing devnet with {} nodes", config.node_count());
@@ -83,6 +103,7 @@ where
                 .await?;
                 let provider = initializer.provider_mut().provider_mut();
                 let initial_state = provider.initialize_state().await?;
+                self.angstrom_state = Some(initial_state.clone());
                 initial_angstrom_state = Some(initial_state);
 
                 initializer
@@ -133,6 +154,124 @@ where
 
         Ok(())
     }
+
+    /// configures a new pool through the controller on the leader's anvil. The
+    /// configuration lands in a block that gets sent to every node, so the
+    /// nodes have to pick the pool up at runtime
+    pub(crate) async fn configure_pool(&mut self, key: PartialConfigPoolKey) -> eyre::Result<()> {
+        let state = self
+            .angstrom_state
+            .clone()
+            .expect("devnet has no angstrom state");
+        let leader = self.get_peer(0).state_provider();
+        let rpc = leader.rpc_provider();
+        let mut initializer = AnvilInitializer::attach(leader.wallet_provider(), &state);
+
+        // none of the nodes care about the tokens, so these are mined as usual
+        let (currency0, currency1) = initializer.deploy_currencies(&key).await?;
+        initializer
+            .fund_controller(currency0, currency1, U256::from(NEW_POOL_CONTROLLER_FUNDS))
+            .await?;
+        initializer.finalize_pending_txs().await?;
+
+        let store_index =
+            AngstromPoolConfigStore::load_from_chain(state.angstrom_addr, BlockId::latest(), &rpc)
+                .await
+                .map_err(|e| eyre::eyre!("{e}"))?
+                .length();
+
+        // from here on every block has to go through the block provider, otherwise
+        // the nodes never see the pool being configured or orders being filled
+        rpc.anvil_set_auto_mine(false).await?;
+
+        let pool_key = key.make_pool_key(state.angstrom_addr, currency0, currency1);
+        initializer
+            .deploy_pool_full(
+                pool_key.clone(),
+                key.initial_liquidity(),
+                key.sqrt_price(),
+                U256::from(store_index)
+            )
+            .await?;
+        self.all_peers_update_state(0).await?;
+        initializer.finalize_pending_txs().await?;
+
+        tracing::info!(?pool_key, "configured new pool");
+        if let Some(state) = self.angstrom_state.as_mut() {
+            state.pool_keys.push(pool_key);
+        }
+
+        Ok(())
+    }
+
+    /// has the leader's controller send a standing order on the last pool that
+    /// was configured and advances blocks until the order was filled
+    pub(crate) async fn clear_order_on_last_pool(&self, max_blocks: u64) -> eyre::Result<()> {
+        let pool_key = self
+            .angstrom_state
+            .as_ref()
+            .and_then(|state| state.pool_keys.last().cloned())
+            .expect("no pool was configured");
+        let pool_id = PoolId::from(pool_key.clone());
+        let leader = self.get_peer(0);
+
+        // ticks of the new pool are loaded in the background
+        let mut attempts = 0;
+        while !leader.uniswap_pools().contains_key(&pool_id) {
+            if attempts == MAX_POOL_SYNC_ATTEMPTS {
+                eyre::bail!("new pool {pool_id:?} was never synced")
+            }
+            attempts += 1;
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+
+        self.peers
+            .values()
+            .for_each(|peer| peer.start_network_and_consensus_and_validation());
+
+        let signer = AngstromSigner::new(
+            leader
+                .state_provider()
+                .wallet_provider()
+                .controller_secret_key
+        );
+        let order: AllOrders = UserOrderBuilder::new()
+            .standing()
+            .exact()
+            .exact_in(true)
+            .asset_in(pool_key.currency0)
+            .asset_out(pool_key.currency1)
+            .amount(NEW_POOL_ORDER_AMOUNT)
+            .min_price(Ray::from(U256::from(1)))
+            .deadline(U256::from(u32::MAX))
+            .recipient(signer.address())
+            .signing_key(Some(signer))
+            .build()
+            .into();
+        let order_hash = order.order_hash();
+
+        let res = leader
+            .pool_handle()
+            .new_order(OrderOrigin::External, order)
+            .await;
+        if !res.is_valid() {
+            eyre::bail!("order {order_hash:?} on new pool was rejected: {res:?}")
+        }
+
+        for _ in 0..max_blocks {
+            // give consensus a round to get the bundle to the leader's anvil
+            tokio::time::sleep(ETH_BLOCK_TIME).await;
+            self.all_peers_update_state(0).await?;
+
+            if let Some(OrderStatus::Filled) =
+                leader.pool_handle().fetch_order_status(order_hash).await
+            {
+                return Ok(())
+            }
+        }
+
+        eyre::bail!("order {order_hash:?} was not filled within {max_blocks} blocks")
+    }
 }
 
 fn a<'a>(
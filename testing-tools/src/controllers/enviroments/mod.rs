@@ -10,7 +10,7 @@ use alloy::{node_bindings::AnvilInstance, providers::Provider};
 use angstrom_network::{
     manager::StromConsensusEvent, NetworkOrderEvent, StromMessage, StromNetworkManager
 };
-use angstrom_types::sol_bindings::grouped_orders::AllOrders;
+use angstrom_types::{sol_bindings::grouped_orders::AllOrders, testnet::InitialTestnetState};
 use futures::TryFutureExt;
 use rand::Rng;
 use reth_chainspec::Hardforks;
@@ -35,7 +35,9 @@ pub struct AngstromTestnet<C, G, P> {
     _disconnected_peers: HashSet<u64>,
     _dropped_peers:      HashSet<u64>,
     current_max_peer_id: u64,
-    config:              G
+    config:              G,
+    /// the deployed contracts and the pools configured on them
+    angstrom_state:      Option<InitialTestnetState>
 }
 
 impl<C, G, P> AngstromTestnet<C, G, P>
@@ -48,7 +48,8 @@ where
             current_max_peer_id: 0,
             config: config.clone(),
             block_provider,
-            _anvil_instance: None
+            _anvil_instance: None,
+            angstrom_state: None
         };
 
         tracing::info!("initializing testnet with {} nodes", config.node_count());
@@ -188,6 +189,7 @@ where
         let (p, initial_state) = self
             .leader_initialization(node_config.clone(), block_sync.clone(), node_addresses)
             .await?;
+        self.angstrom_state = Some(initial_state.clone());
         *initial_angstrom_state = Some(initial_state);
         Ok(p)
     }
@@ -1,5 +1,6 @@
 use std::{pin::Pin, sync::Arc};
 
+use alloy::providers::Provider;
 use alloy_rpc_types::{BlockId, Transaction};
 use angstrom::components::StromHandles;
 use angstrom_eth::handle::Eth;
@@ -14,17 +15,25 @@ use angstrom_types::{
     sol_bindings::testnet::TestnetHub,
     testnet::InitialTestnetState
 };
-use consensus::{AngstromValidator, ConsensusManager, ManagerNetworkDeps};
+use consensus::{
+    AngstromValidator, ConsensusManager, ConsensusTimingConfig, EvidenceStore, ManagerNetworkDeps,
+    ManagerRpcDeps
+};
 use futures::{Future, Stream, StreamExt, TryStreamExt};
 use jsonrpsee::server::ServerBuilder;
-use matching_engine::{configure_uniswap_manager, manager::MatcherHandle, MatchingManager};
+use matching_engine::{
+    configure_uniswap_manager, manager::MatcherHandle, strategy::StrategyKind, MatchingManager,
+    DEFAULT_INITIAL_TICKS_PER_SIDE
+};
 use order_pool::{order_storage::OrderStorage, PoolConfig};
 use reth_provider::{BlockNumReader, CanonStateSubscriptions};
 use reth_tasks::TokioTaskExecutor;
 use tokio_stream::wrappers::BroadcastStream;
 use tracing::{span, Instrument};
+use uniswap_v4::uniswap::pool_manager::SyncedUniswapPools;
 use validation::{
-    common::TokenPriceGenerator, order::state::pools::AngstromPoolsTracker,
+    common::{sync_configured_pool_prices, TokenPriceGenerator, WETH_ADDRESS},
+    order::state::pools::AngstromPoolsTracker,
     validator::ValidationClient
 };
 
@@ -47,7 +56,8 @@ pub struct AngstromDevnetNodeInternals<P> {
     pub order_storage:    Arc<OrderStorage>,
     pub pool_handle:      PoolHandle,
     pub tx_strom_handles: SendingStromHandles,
-    pub testnet_hub:      StromContractInstance
+    pub testnet_hub:      StromContractInstance,
+    pub uniswap_pools:    SyncedUniswapPools
 }
 
 impl<P: WithWalletProvider> AngstromDevnetNodeInternals<P> {
@@ -78,7 +88,11 @@ impl<P: WithWalletProvider> AngstromDevnetNodeInternals<P> {
         let tx_strom_handles = (&strom_handles).into();
 
         let validation_client = ValidationClient(strom_handles.validator_tx);
-        let matching_handle = MatchingManager::spawn(executor.clone(), validation_client.clone());
+        let matching_handle = MatchingManager::spawn(
+            executor.clone(),
+            validation_client.clone(),
+            StrategyKind::default()
+        );
 
         let order_api = OrderApi::new(pool.clone(), executor.clone(), validation_client.clone());
 
@@ -93,10 +107,23 @@ impl<P: WithWalletProvider> AngstromDevnetNodeInternals<P> {
         let block_number = BlockNumReader::best_block_number(&state_provider.state_provider())?;
         block_sync.set_block(block_number);
 
+        let pool_config_store = Arc::new(
+            AngstromPoolConfigStore::load_from_chain(
+                inital_angstrom_state.angstrom_addr,
+                BlockId::latest(),
+                &state_provider.rpc_provider()
+            )
+            .await
+            .map_err(|e| eyre::eyre!("{e}"))?
+        );
+
         let eth_handle = AnvilEthDataCleanser::spawn(
             node_config.node_id,
             executor.clone(),
             inital_angstrom_state.angstrom_addr,
+            inital_angstrom_state.controller_addr,
+            pool_config_store.clone(),
+            inital_angstrom_state.pool_keys.clone(),
             strom_handles.eth_tx,
             strom_handles.eth_rx,
             block_subscription,
@@ -121,16 +148,6 @@ impl<P: WithWalletProvider> AngstromDevnetNodeInternals<P> {
 
         let uniswap_registry: UniswapPoolRegistry = inital_angstrom_state.pool_keys.clone().into();
 
-        let pool_config_store = Arc::new(
-            AngstromPoolConfigStore::load_from_chain(
-                inital_angstrom_state.angstrom_addr,
-                BlockId::latest(),
-                &state_provider.rpc_provider()
-            )
-            .await
-            .map_err(|e| eyre::eyre!("{e}"))?
-        );
-
         let uniswap_pool_manager = configure_uniswap_manager(
             state_provider.rpc_provider().into(),
             state_provider
@@ -139,7 +156,9 @@ impl<P: WithWalletProvider> AngstromDevnetNodeInternals<P> {
             uniswap_registry.clone(),
             block_number,
             block_sync.clone(),
-            inital_angstrom_state.pool_manager_addr
+            inital_angstrom_state.pool_manager_addr,
+            DEFAULT_INITIAL_TICKS_PER_SIDE,
+            eth_handle.subscribe_network()
         )
         .await;
 
@@ -154,10 +173,18 @@ impl<P: WithWalletProvider> AngstromDevnetNodeInternals<P> {
             Arc::new(state_provider.rpc_provider()),
             block_number,
             uniswap_pools.clone(),
-            Some(1)
+            Some(1),
+            WETH_ADDRESS
         )
         .await
         .expect("failed to start price generator");
+        tokio::spawn(sync_configured_pool_prices(
+            Arc::new(state_provider.rpc_provider()),
+            inital_angstrom_state.pool_manager_addr,
+            Some(1),
+            eth_handle.subscribe_network(),
+            validation_client.clone()
+        ));
 
         let token_price_update_stream = state_provider.state_provider().canonical_state_stream();
         let token_price_update_stream = Box::pin(PairsWithPrice::into_price_update_stream(
@@ -232,7 +259,8 @@ impl<P: WithWalletProvider> AngstromDevnetNodeInternals<P> {
 
         let mev_boost_provider = MevBoostProvider::new_from_raw(
             Arc::new(state_provider.rpc_provider()),
-            vec![Arc::new(Box::new(anvil) as Box<dyn SubmitTx>)]
+            vec![Arc::new(Box::new(anvil) as Box<dyn SubmitTx>)],
+            state_provider.rpc_provider().get_chain_id().await?
         );
 
         tracing::debug!("created mev boost provider");
@@ -243,7 +271,8 @@ impl<P: WithWalletProvider> AngstromDevnetNodeInternals<P> {
                 state_provider
                     .state_provider()
                     .subscribe_to_canonical_state(),
-                strom_handles.consensus_rx_op
+                strom_handles.consensus_rx_op,
+                eth_handle.subscribe_network()
             ),
             node_config.angstrom_signer(),
             initial_validators,
@@ -254,15 +283,22 @@ impl<P: WithWalletProvider> AngstromDevnetNodeInternals<P> {
             uniswap_pools.clone(),
             mev_boost_provider,
             matching_handle,
-            block_sync.clone()
+            block_sync.clone(),
+            EvidenceStore::in_memory(),
+            ConsensusTimingConfig::default(),
+            ManagerRpcDeps::new(
+                strom_handles.gas_estimate_tx,
+                strom_handles.consensus_events_tx,
+                strom_handles.consensus_command_rx
+            )
         );
 
         // init agents
         let agent_config = AgentConfig {
-            uniswap_pools,
-            agent_id: node_config.node_id,
-            rpc_address: addr,
-            current_block: block_number,
+            uniswap_pools:  uniswap_pools.clone(),
+            agent_id:       node_config.node_id,
+            rpc_address:    addr,
+            current_block:  block_number,
             state_provider: state_provider.state_provider()
         };
 
@@ -284,7 +320,8 @@ impl<P: WithWalletProvider> AngstromDevnetNodeInternals<P> {
                 order_storage,
                 pool_handle,
                 tx_strom_handles,
-                testnet_hub
+                testnet_hub,
+                uniswap_pools
             },
             consensus,
             validator
@@ -9,7 +9,8 @@ use std::{
 use alloy_primitives::Address;
 use angstrom::components::initialize_strom_handles;
 use angstrom_network::{
-    NetworkOrderEvent, StromNetworkEvent, StromNetworkHandle, StromNetworkManager
+    pool_manager::PoolHandle, NetworkOrderEvent, StromNetworkEvent, StromNetworkHandle,
+    StromNetworkManager
 };
 use angstrom_types::{
     block_sync::GlobalBlockSync,
@@ -30,6 +31,7 @@ use reth_network::{
 use reth_provider::{BlockReader, ChainSpecProvider, HeaderProvider, ReceiptProvider};
 use tokio_stream::wrappers::{BroadcastStream, UnboundedReceiverStream};
 use tracing::instrument;
+use uniswap_v4::uniswap::pool_manager::SyncedUniswapPools;
 
 use super::internals::AngstromDevnetNodeInternals;
 use crate::{
@@ -134,6 +136,14 @@ where
         &self.strom.state_provider
     }
 
+    pub fn pool_handle(&self) -> &PoolHandle {
+        &self.strom.pool_handle
+    }
+
+    pub fn uniswap_pools(&self) -> &SyncedUniswapPools {
+        &self.strom.uniswap_pools
+    }
+
     /// Eth
     /// -------------------------------------
     pub fn eth_peer_handle(&self) -> &PeerHandle<EthPeerPool> {
@@ -0,0 +1,93 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use alloy::{
+    consensus::TxEnvelope,
+    eips::eip2718::Decodable2718,
+    primitives::{keccak256, BlockNumber, TxHash},
+    transports::http::reqwest::Url
+};
+use angstrom_types::mev_boost::{SendBundleRequest, SendBundleResponse};
+use jsonrpsee::{
+    core::RpcResult,
+    proc_macros::rpc,
+    server::{ServerBuilder, ServerHandle},
+    types::ErrorObjectOwned
+};
+use parking_lot::Mutex;
+
+#[rpc(server, namespace = "eth")]
+pub trait MockBuilderApi {
+    #[method(name = "sendBundle")]
+    fn send_bundle(&self, bundle: SendBundleRequest) -> RpcResult<SendBundleResponse>;
+}
+
+/// A bundle as it was received by the mock builder
+#[derive(Debug, Clone)]
+pub struct ReceivedBundle {
+    pub target_block:        BlockNumber,
+    pub tx_hashes:           Vec<TxHash>,
+    pub reverting_tx_hashes: Vec<TxHash>
+}
+
+/// Stand-in for a block builder that accepts `eth_sendBundle` and records what
+/// it was sent, so that submission can be tested without a network connection
+#[derive(Clone, Default)]
+pub struct MockBuilder {
+    received: Arc<Mutex<Vec<ReceivedBundle>>>,
+    reject:   bool
+}
+
+impl MockBuilder {
+    /// A builder that accepts every bundle
+    pub fn accepting() -> Self {
+        Self::default()
+    }
+
+    /// A builder that rejects every bundle with an rpc error
+    pub fn rejecting() -> Self {
+        Self { reject: true, ..Default::default() }
+    }
+
+    pub fn received(&self) -> Vec<ReceivedBundle> {
+        self.received.lock().clone()
+    }
+
+    /// Starts serving on a random local port, returning the url to submit to.
+    /// The server stops once the handle is dropped
+    pub async fn spawn(&self) -> eyre::Result<(Url, ServerHandle)> {
+        let server = ServerBuilder::default()
+            .build(SocketAddr::from(([127, 0, 0, 1], 0)))
+            .await?;
+        let url = Url::parse(&format!("http://{}", server.local_addr()?))?;
+        let handle = server.start(self.clone().into_rpc());
+
+        Ok((url, handle))
+    }
+}
+
+impl MockBuilderApiServer for MockBuilder {
+    fn send_bundle(&self, bundle: SendBundleRequest) -> RpcResult<SendBundleResponse> {
+        if self.reject {
+            return Err(ErrorObjectOwned::owned(-32000, "bundle rejected", None::<()>))
+        }
+
+        let tx_hashes = bundle
+            .txs
+            .iter()
+            .map(|raw| {
+                TxEnvelope::decode_2718(&mut raw.as_ref())
+                    .map(|tx| *tx.tx_hash())
+                    .map_err(|e| ErrorObjectOwned::owned(-32602, e.to_string(), None::<()>))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let bundle_hash = keccak256(tx_hashes.iter().flat_map(|hash| hash.0).collect::<Vec<_>>());
+        self.received.lock().push(ReceivedBundle {
+            target_block: bundle.block_number.to(),
+            tx_hashes,
+            reverting_tx_hashes: bundle.reverting_tx_hashes
+        });
+
+        Ok(SendBundleResponse { bundle_hash })
+    }
+}
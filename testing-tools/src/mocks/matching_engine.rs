@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use alloy::primitives::Address;
 use angstrom_types::{
     contract_payloads::angstrom::BundleGasDetails,
-    matching::uniswap::PoolSnapshot,
+    matching::{match_estimate_response::BundleEstimate, uniswap::PoolSnapshot},
     orders::PoolSolution,
     primitive::PoolId,
     sol_bindings::{grouped_orders::OrderWithStorageData, rpc_orders::TopOfBlockOrder}
@@ -23,4 +23,13 @@ impl MatchingEngineHandle for MockMatchingEngine {
     ) -> BoxFuture<eyre::Result<(Vec<PoolSolution>, BundleGasDetails)>> {
         async move { Ok((vec![], BundleGasDetails::default())) }.boxed()
     }
+
+    fn estimate_gas_per_pool(
+        &self,
+        _: Vec<BookOrder>,
+        _: Vec<OrderWithStorageData<TopOfBlockOrder>>,
+        _: HashMap<PoolId, (Address, Address, PoolSnapshot, u16)>
+    ) -> BoxFuture<eyre::Result<BundleEstimate>> {
+        async move { Ok(BundleEstimate::default()) }.boxed()
+    }
 }
@@ -1,3 +1,4 @@
+pub mod builder;
 pub mod canon_state;
 pub mod consensus;
 pub mod eth_events;
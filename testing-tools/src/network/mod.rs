@@ -6,7 +6,8 @@ use alloy_chains::Chain;
 use angstrom_eth::manager::EthEvent;
 use angstrom_network::{
     manager::StromConsensusEvent, state::StromState, NetworkOrderEvent, StatusState,
-    StromNetworkManager, StromProtocolHandler, StromSessionManager, Swarm, VerificationSidecar
+    StromNetworkManager, StromProtocolHandler, StromSessionManager, StromVersion, Swarm,
+    VerificationSidecar
 };
 pub use eth_peer::*;
 use parking_lot::RwLock;
@@ -57,7 +58,7 @@ impl TestnetNodeNetwork {
 
         let peer_id = pk2id(&node_config.pub_key);
         let state = StatusState {
-            version:   0,
+            version:   StromVersion::LATEST.into(),
             chain:     Chain::mainnet().id(),
             peer:      peer_id,
             timestamp: 0
@@ -28,9 +28,7 @@ impl OrderGenerator {
     ) -> Self {
         let pools = pool_data
             .iter()
-            .map(|(pool_id, pool_data)| {
-                PoolOrderGenerator::new(*pool_id, pool_data.clone(), block_number)
-            })
+            .map(|(pool_id, pool_data)| PoolOrderGenerator::new(pool_id, pool_data, block_number))
             .collect::<Vec<_>>();
 
         Self { pools, order_amt_range, partial_pct_range }
@@ -1,6 +1,14 @@
-use std::task::{Context, Poll};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    task::{Context, Poll}
+};
 
-use alloy::{primitives::Address, rpc::types::Transaction, sol_types::SolCall};
+use alloy::{
+    primitives::{aliases::I24, Address},
+    rpc::types::Transaction,
+    sol_types::SolCall
+};
 use alloy_rpc_types::TransactionTrait;
 use angstrom_eth::{
     handle::{EthCommand, EthHandle},
@@ -8,7 +16,8 @@ use angstrom_eth::{
 };
 use angstrom_types::{
     block_sync::{BlockSyncProducer, GlobalBlockSync},
-    contract_payloads::angstrom::AngstromBundle,
+    contract_bindings::{angstrom::Angstrom::PoolKey, controller_v_1::ControllerV1},
+    contract_payloads::angstrom::{AngPoolConfigEntry, AngstromBundle, AngstromPoolConfigStore},
     sol_bindings::testnet::TestnetHub
 };
 use futures::{Future, Stream, StreamExt};
@@ -21,6 +30,10 @@ use tracing::{span, Instrument, Level};
 pub struct AnvilEthDataCleanser<S: Stream<Item = (u64, Vec<Transaction>)>> {
     testnet_node_id:             u64,
     angstrom_contract:           Address,
+    controller_contract:         Address,
+    pool_store:                  Arc<AngstromPoolConfigStore>,
+    /// pools configured through the controller, by their sorted assets
+    configured_pools:            HashMap<(Address, Address), PoolKey>,
     /// our command receiver
     commander:                   ReceiverStream<EthCommand>,
     /// people listening to events
@@ -35,6 +48,9 @@ impl<S: Stream<Item = (u64, Vec<Transaction>)> + Unpin + Send + 'static> AnvilEt
         testnet_node_id: u64,
         tp: TP,
         angstrom_contract: Address,
+        controller_contract: Address,
+        pool_store: Arc<AngstromPoolConfigStore>,
+        pool_keys: Vec<PoolKey>,
         tx: Sender<EthCommand>,
         rx: Receiver<EthCommand>,
         block_subscription: S,
@@ -48,6 +64,12 @@ impl<S: Stream<Item = (u64, Vec<Transaction>)> + Unpin + Send + 'static> AnvilEt
             event_listeners: Vec::new(),
             block_subscription,
             angstrom_contract,
+            controller_contract,
+            pool_store,
+            configured_pools: pool_keys
+                .into_iter()
+                .map(|key| ((key.currency0, key.currency1), key))
+                .collect(),
             block_finalization_lookback,
             block_sync
         };
@@ -78,6 +100,48 @@ impl<S: Stream<Item = (u64, Vec<Transaction>)> + Unpin + Send + 'static> AnvilEt
         }
     }
 
+    /// anvil doesn't give us logs, so we decode the controller calls instead
+    fn on_controller_tx(&mut self, tx: &Transaction) {
+        let input = tx.input();
+
+        if let Ok(configure) = ControllerV1::configurePoolCall::abi_decode(input, false) {
+            let (asset0, asset1) = if configure.asset0 < configure.asset1 {
+                (configure.asset0, configure.asset1)
+            } else {
+                (configure.asset1, configure.asset0)
+            };
+            let entry = AngPoolConfigEntry {
+                pool_partial_key: AngstromPoolConfigStore::derive_store_key(asset0, asset1),
+                tick_spacing:     configure.tickSpacing,
+                fee_in_e6:        configure.bundleFee.to(),
+                store_index:      self.pool_store.length()
+            };
+            let pool_key = PoolKey {
+                currency0:   asset0,
+                currency1:   asset1,
+                fee:         configure.bundleFee,
+                tickSpacing: I24::unchecked_from(configure.tickSpacing),
+                hooks:       self.angstrom_contract
+            };
+
+            self.pool_store.new_pool(asset0, asset1, entry);
+            self.configured_pools
+                .insert((asset0, asset1), pool_key.clone());
+            self.send_events(EthEvent::NewPool { pool: pool_key });
+        } else if let Ok(remove) = ControllerV1::removePoolCall::abi_decode(input, false) {
+            let assets = if remove.asset0 < remove.asset1 {
+                (remove.asset0, remove.asset1)
+            } else {
+                (remove.asset1, remove.asset0)
+            };
+            self.pool_store.remove_pair(assets.0, assets.1);
+
+            if let Some(pool_key) = self.configured_pools.remove(&assets) {
+                self.send_events(EthEvent::RemovedPool { pool: pool_key });
+            }
+        }
+    }
+
     fn on_new_block(&mut self, block: (u64, Vec<Transaction>)) {
         let (bn, txes) = block;
         self.block_sync.new_block(bn);
@@ -88,6 +152,10 @@ impl<S: Stream<Item = (u64, Vec<Transaction>)> + Unpin + Send + 'static> AnvilEt
             self.send_events(EthEvent::FinalizedBlock(bn - self.block_finalization_lookback));
         }
 
+        txes.iter()
+            .filter(|tx| tx.to() == Some(self.controller_contract))
+            .for_each(|tx| self.on_controller_tx(tx));
+
         // find angstrom tx
         let Some(angstrom_tx) = txes
             .into_iter()
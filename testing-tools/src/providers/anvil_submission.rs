@@ -1,12 +1,11 @@
 use std::pin::Pin;
 
 use alloy::{
+    consensus::{Transaction, TxEnvelope},
     eips::eip2718::Encodable2718,
-    network::TransactionBuilder,
-    primitives::TxHash,
+    primitives::BlockNumber,
     providers::{ext::AnvilApi, Provider}
 };
-use alloy_rpc_types::TransactionRequest;
 use alloy_sol_types::SolCall;
 use angstrom_types::{
     contract_bindings::angstrom::Angstrom, contract_payloads::angstrom::AngstromBundle,
@@ -22,17 +21,22 @@ pub struct AnvilSubmissionProvider {
 }
 
 impl SubmitTx for AnvilSubmissionProvider {
+    fn endpoint(&self) -> String {
+        "anvil".to_string()
+    }
+
     fn submit_transaction<'a>(
         &'a self,
-        signer: &'a AngstromSigner,
-        tx: TransactionRequest
-    ) -> Pin<Box<dyn Future<Output = (TxHash, bool)> + Send + 'a>> {
+        _: &'a AngstromSigner,
+        tx: &'a TxEnvelope,
+        target_block: BlockNumber
+    ) -> Pin<Box<dyn Future<Output = eyre::Result<()>> + Send + 'a>> {
         async move {
             tracing::debug!(?tx);
             // decoded encoded payload, then apply all mock approvals + balances for the
             // given token
 
-            let data_vec = tx.input.input.clone().unwrap().to_vec();
+            let data_vec = tx.input().to_vec();
             let slice = data_vec.as_slice();
             // problem is we have abi enocded as bytes so we need to unabi incode
             let bytes = Angstrom::executeCall::abi_decode(slice, true)
@@ -42,9 +46,8 @@ impl SubmitTx for AnvilSubmissionProvider {
             let mut slice = vecd.as_slice();
 
             let bundle = AngstromBundle::pade_decode(&mut slice, None).unwrap();
-            let block = self.provider.get_block_number().await.unwrap() + 1;
-            let order_overrides = bundle.fetch_needed_overrides(block);
-            let angstrom_address = *tx.to.as_ref().unwrap().to().unwrap();
+            let order_overrides = bundle.fetch_needed_overrides(target_block);
+            let angstrom_address = tx.to().unwrap();
 
             let _ =
                 futures::stream::iter(order_overrides.into_slots_with_overrides(angstrom_address))
@@ -57,13 +60,10 @@ impl SubmitTx for AnvilSubmissionProvider {
                     .collect::<Vec<_>>()
                     .await;
 
-            let tx = tx.build(&signer).await.unwrap();
-
-            let hash = *tx.tx_hash();
-            let encoded = tx.encoded_2718();
-
-            let submitted = self.provider.send_raw_transaction(&encoded).await.is_ok();
-            (hash, submitted)
+            self.provider
+                .send_raw_transaction(&tx.encoded_2718())
+                .await?;
+            Ok(())
         }
         .boxed()
     }
@@ -30,7 +30,8 @@ use crate::{
             angstrom::AngstromEnv,
             uniswap::{TestUniswapEnv, UniswapEnv},
             TestAnvilEnvironment
-        }
+        },
+        DebugTransaction
     },
     types::{
         config::TestingNodeConfig,
@@ -41,7 +42,7 @@ use crate::{
 
 pub struct AnvilInitializer {
     provider:      WalletProvider,
-    angstrom_env:  AngstromEnv<UniswapEnv<WalletProvider>>,
+    pool_manager:  Address,
     controller_v1: ControllerV1Instance<BoxTransport, WalletProviderRpc>,
     angstrom:      AngstromInstance<BoxTransport, WalletProviderRpc>,
     pool_gate:     PoolGateInstance<BoxTransport, WalletProviderRpc>,
@@ -76,12 +77,33 @@ impl AnvilInitializer {
 
         let pending_state = PendingDeployedPools::new();
 
-        let this =
-            Self { provider, controller_v1, angstrom_env, angstrom, pending_state, pool_gate };
+        let this = Self {
+            provider,
+            pool_manager: angstrom_env.pool_manager(),
+            controller_v1,
+            angstrom,
+            pending_state,
+            pool_gate
+        };
 
         Ok((this, anvil))
     }
 
+    /// attaches to contracts that have already been deployed, used to
+    /// configure pools on a testnet that is already running
+    pub fn attach(provider: WalletProvider, state: &InitialTestnetState) -> Self {
+        let rpc = provider.provider();
+
+        Self {
+            angstrom: AngstromInstance::new(state.angstrom_addr, rpc.clone()),
+            pool_gate: PoolGateInstance::new(state.pool_gate_addr, rpc.clone()),
+            controller_v1: ControllerV1Instance::new(state.controller_addr, rpc),
+            pool_manager: state.pool_manager_addr,
+            pending_state: PendingDeployedPools::new(),
+            provider
+        }
+    }
+
     /// deploys multiple pools (pool key, liquidity, sqrt price)
     pub async fn deploy_pool_fulls(
         &mut self,
@@ -195,7 +217,7 @@ impl AnvilInitializer {
     }
 
     /// deploys tokens, a uniV4 pool, angstrom pool
-    async fn deploy_pool_full(
+    pub async fn deploy_pool_full(
         &mut self,
         pool_key: PoolKey,
         liquidity: u128,
@@ -279,13 +301,43 @@ impl AnvilInitializer {
         Ok(())
     }
 
+    /// mints `amount` of both tokens to the controller and approves angstrom to
+    /// pull them, so that the controller can trade on the pool
+    pub async fn fund_controller(
+        &self,
+        currency0: Address,
+        currency1: Address,
+        amount: U256
+    ) -> eyre::Result<()> {
+        let controller = self.provider.controller();
+        for currency in [currency0, currency1] {
+            let token = MintableMockERC20::new(currency, self.provider.provider());
+            token.mint(controller, amount).run_safe().await?;
+            token
+                .approve(*self.angstrom.address(), amount)
+                .from(controller)
+                .run_safe()
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// waits for all the transactions that have been sent to land
+    pub async fn finalize_pending_txs(&mut self) -> eyre::Result<Vec<PoolKey>> {
+        let (pool_keys, _) = self.pending_state.finalize_pending_txs().await?;
+        Ok(pool_keys)
+    }
+
     pub async fn initialize_state(&mut self) -> eyre::Result<InitialTestnetState> {
         let (pool_keys, _) = self.pending_state.finalize_pending_txs().await?;
 
         let state_bytes = self.provider.provider_ref().anvil_dump_state().await?;
         let state = InitialTestnetState::new(
-            self.angstrom_env.angstrom(),
-            self.angstrom_env.pool_manager(),
+            *self.angstrom.address(),
+            self.pool_manager,
+            *self.controller_v1.address(),
+            *self.pool_gate.address(),
             Some(state_bytes),
             pool_keys.clone()
         );
@@ -312,8 +364,10 @@ impl AnvilInitializer {
         }
 
         let state = InitialTestnetState::new(
-            self.angstrom_env.angstrom(),
-            self.angstrom_env.pool_manager(),
+            *self.angstrom.address(),
+            self.pool_manager,
+            *self.controller_v1.address(),
+            *self.pool_gate.address(),
             None,
             pool_keys.clone()
         );
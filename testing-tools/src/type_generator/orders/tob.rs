@@ -1,6 +1,6 @@
 use alloy::{primitives::Address, signers::SignerSync};
 use angstrom_types::{
-    primitive::{AngstromSigner, ANGSTROM_DOMAIN},
+    primitive::{angstrom_domain, AngstromSigner},
     sol_bindings::rpc_orders::{OmitOrderMeta, OrderMeta, TopOfBlockOrder}
 };
 use pade::PadeEncode;
@@ -61,7 +61,7 @@ impl ToBOrderBuilder {
             ..Default::default()
         };
         if let Some(signer) = self.signing_key {
-            let hash = order.no_meta_eip712_signing_hash(&ANGSTROM_DOMAIN);
+            let hash = order.no_meta_eip712_signing_hash(&angstrom_domain());
             let sig = signer.sign_hash_sync(&hash).unwrap();
             order.meta = OrderMeta {
                 isEcdsa:   true,
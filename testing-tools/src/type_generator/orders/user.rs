@@ -5,7 +5,7 @@ use alloy::{
 use alloy_primitives::aliases::U40;
 use angstrom_types::{
     matching::Ray,
-    primitive::{AngstromSigner, ANGSTROM_DOMAIN},
+    primitive::{angstrom_domain, AngstromSigner},
     sol_bindings::{
         grouped_orders::{FlashVariants, GroupedVanillaOrder, StandingVariants},
         rpc_orders::{
@@ -153,7 +153,7 @@ impl UserOrderBuilder {
                     ..Default::default()
                 };
                 if let Some(signer) = self.signing_key {
-                    let hash = order.no_meta_eip712_signing_hash(&ANGSTROM_DOMAIN);
+                    let hash = order.no_meta_eip712_signing_hash(&angstrom_domain());
                     let sig = signer.sign_hash_sync(&hash).unwrap();
                     order.meta = OrderMeta {
                         isEcdsa:   true,
@@ -176,7 +176,7 @@ impl UserOrderBuilder {
                     ..Default::default()
                 };
                 if let Some(signer) = self.signing_key {
-                    let hash = order.no_meta_eip712_signing_hash(&ANGSTROM_DOMAIN);
+                    let hash = order.no_meta_eip712_signing_hash(&angstrom_domain());
                     let sig = signer.sign_hash_sync(&hash).unwrap();
                     order.meta = OrderMeta {
                         isEcdsa:   true,
@@ -206,7 +206,7 @@ impl UserOrderBuilder {
                     ..Default::default()
                 };
                 if let Some(signer) = self.signing_key {
-                    let hash = order.no_meta_eip712_signing_hash(&ANGSTROM_DOMAIN);
+                    let hash = order.no_meta_eip712_signing_hash(&angstrom_domain());
                     let sig = signer.sign_hash_sync(&hash).unwrap();
                     order.meta = OrderMeta {
                         isEcdsa:   true,
@@ -228,7 +228,7 @@ impl UserOrderBuilder {
                     ..Default::default()
                 };
                 if let Some(signer) = self.signing_key {
-                    let hash = order.no_meta_eip712_signing_hash(&ANGSTROM_DOMAIN);
+                    let hash = order.no_meta_eip712_signing_hash(&angstrom_domain());
                     let sig = signer.sign_hash_sync(&hash).unwrap();
                     order.meta = OrderMeta {
                         isEcdsa:   true,
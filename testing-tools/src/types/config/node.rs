@@ -65,8 +65,7 @@ impl<C: GlobalTestingConfig> TestingNodeConfig<C> {
     }
 
     pub fn angstrom_validator(&self) -> AngstromValidator {
-        let id = self.angstrom_signer().id();
-        AngstromValidator::new(id, self.voting_power)
+        AngstromValidator::new(self.address(), self.voting_power)
     }
 
     pub fn address(&self) -> Address {
@@ -6,7 +6,7 @@ use reth_provider::{BlockReader, ChainSpecProvider, HeaderProvider, ReceiptProvi
 use crate::{
     controllers::enviroments::{AngstromTestnet, DevnetStateMachine},
     providers::WalletProvider,
-    types::{config::DevnetConfig, StateMachineActionHookFn}
+    types::{config::DevnetConfig, initial_state::PartialConfigPoolKey, StateMachineActionHookFn}
 };
 
 pub trait WithAction<'a, C>
@@ -22,6 +22,13 @@ where
     type FunctionOutput = StateMachineActionHookFn<'a, C>;
 
     fn advance_block(&mut self);
+
+    /// configures a new pool through the controller while the nodes are running
+    fn configure_pool(&mut self, pool_key: PartialConfigPoolKey);
+
+    /// sends an order on the pool that was configured last and advances up to
+    /// `max_blocks` blocks until it is filled
+    fn clear_order_on_new_pool(&mut self, max_blocks: u64);
 }
 
 impl<'a, C> WithAction<'a, C> for DevnetStateMachine<'a, C>
@@ -40,6 +47,20 @@ where
         };
         self.add_action("advance block", f);
     }
+
+    fn configure_pool(&mut self, pool_key: PartialConfigPoolKey) {
+        let f = move |testnet: &'a mut AngstromTestnet<C, DevnetConfig, WalletProvider>| {
+            pin_action(testnet.configure_pool(pool_key))
+        };
+        self.add_action("configure pool", f);
+    }
+
+    fn clear_order_on_new_pool(&mut self, max_blocks: u64) {
+        let f = move |testnet: &'a mut AngstromTestnet<C, DevnetConfig, WalletProvider>| {
+            pin_action(testnet.clear_order_on_last_pool(max_blocks))
+        };
+        self.add_action("clear order on new pool", f);
+    }
 }
 
 fn pin_action<'a, F>(fut: F) -> Pin<Box<dyn Future<Output = eyre::Result<()>> + Send + 'a>>
@@ -22,7 +22,7 @@ use validation::{
         order_validator::OrderValidator,
         sim::SimValidation,
         state::{
-            db_state_utils::{nonces::Nonces, AutoMaxFetchUtils},
+            db_state_utils::{layouts::TokenLayoutStore, nonces::Nonces, AutoMaxFetchUtils},
             pools::AngstromPoolsTracker
         }
     },
@@ -70,7 +70,12 @@ where
 
         let handle = tokio::runtime::Handle::current();
         let thread_pool = KeySplitThreadpool::new(handle, 3);
-        let sim = SimValidation::new(db.clone(), angstrom_address, node_address);
+        let sim = SimValidation::new(
+            db.clone(),
+            angstrom_address,
+            node_address,
+            TokenLayoutStore::default()
+        );
 
         let order_validator =
             OrderValidator::new(sim, current_block, pool_storage, fetch, uniswap_pools).await;